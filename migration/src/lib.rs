@@ -15,6 +15,39 @@ mod m20241220_012355_fees;
 mod m20241220_012613_order_fees;
 mod m20250103_173848_processes_fees;
 mod m20250324_184801_parties;
+mod m20250407_140512_credit_notes;
+mod m20250409_093021_refunds;
+mod m20250414_171245_expenses;
+mod m20250422_101530_billing_plans;
+mod m20250422_101842_billing_plan_prices;
+mod m20250422_102205_add_billing_plan_id_to_payments;
+mod m20250422_102530_add_period_date_to_payments;
+mod m20250428_143310_hourly_rates;
+mod m20250428_144027_time_entries;
+mod m20250505_112040_documents;
+mod m20250505_113012_document_counters;
+mod m20250512_093415_add_nosso_numero_to_payments;
+mod m20250519_150230_bank_transactions;
+mod m20250526_104512_payment_charges;
+mod m20250526_105130_provider_events;
+mod m20250602_091204_ledger_accounts;
+mod m20250602_092617_journal_entries;
+mod m20250602_093048_journal_lines;
+mod m20250609_140322_accounting_periods;
+mod m20250616_101530_add_document_to_clients;
+mod m20250623_093512_client_contacts;
+mod m20250623_094108_client_addresses;
+mod m20250623_095233_move_client_contacts;
+mod m20250630_101204_client_merges;
+mod m20250707_094512_search_index;
+mod m20250714_091203_lawsuits;
+mod m20250714_093410_search_lawsuits;
+mod m20250721_100512_calendar_events;
+mod m20250721_101130_calendar_feeds;
+mod m20250728_090215_notes;
+mod m20250728_093012_status_changes;
+mod m20250804_101530_attachments;
+mod m20250811_094020_contracts;
 pub struct Migrator;
 
 #[async_trait::async_trait]
@@ -33,6 +66,39 @@ impl MigratorTrait for Migrator {
             Box::new(m20241220_012613_order_fees::Migration),
             Box::new(m20250103_173848_processes_fees::Migration),
             Box::new(m20250324_184801_parties::Migration),
+            Box::new(m20250407_140512_credit_notes::Migration),
+            Box::new(m20250409_093021_refunds::Migration),
+            Box::new(m20250414_171245_expenses::Migration),
+            Box::new(m20250422_101530_billing_plans::Migration),
+            Box::new(m20250422_101842_billing_plan_prices::Migration),
+            Box::new(m20250422_102205_add_billing_plan_id_to_payments::Migration),
+            Box::new(m20250422_102530_add_period_date_to_payments::Migration),
+            Box::new(m20250428_143310_hourly_rates::Migration),
+            Box::new(m20250428_144027_time_entries::Migration),
+            Box::new(m20250505_112040_documents::Migration),
+            Box::new(m20250505_113012_document_counters::Migration),
+            Box::new(m20250512_093415_add_nosso_numero_to_payments::Migration),
+            Box::new(m20250519_150230_bank_transactions::Migration),
+            Box::new(m20250526_104512_payment_charges::Migration),
+            Box::new(m20250526_105130_provider_events::Migration),
+            Box::new(m20250602_091204_ledger_accounts::Migration),
+            Box::new(m20250602_092617_journal_entries::Migration),
+            Box::new(m20250602_093048_journal_lines::Migration),
+            Box::new(m20250609_140322_accounting_periods::Migration),
+            Box::new(m20250616_101530_add_document_to_clients::Migration),
+            Box::new(m20250623_093512_client_contacts::Migration),
+            Box::new(m20250623_094108_client_addresses::Migration),
+            Box::new(m20250623_095233_move_client_contacts::Migration),
+            Box::new(m20250630_101204_client_merges::Migration),
+            Box::new(m20250707_094512_search_index::Migration),
+            Box::new(m20250714_091203_lawsuits::Migration),
+            Box::new(m20250714_093410_search_lawsuits::Migration),
+            Box::new(m20250721_100512_calendar_events::Migration),
+            Box::new(m20250721_101130_calendar_feeds::Migration),
+            Box::new(m20250728_090215_notes::Migration),
+            Box::new(m20250728_093012_status_changes::Migration),
+            Box::new(m20250804_101530_attachments::Migration),
+            Box::new(m20250811_094020_contracts::Migration),
             // inject-above (do not remove this comment)
         ]
     }
@@ -0,0 +1,86 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(CreditNotes::Table)
+                    .col(pk_auto(CreditNotes::Id))
+                    .col(uuid_uniq(CreditNotes::Pid))
+                    .col(integer(CreditNotes::OrderId))
+                    .col(integer_null(CreditNotes::OrderFeeId))
+                    .col(integer_null(CreditNotes::PaymentId))
+                    .col(float(CreditNotes::Value))
+                    .col(string(CreditNotes::Reason))
+                    .col(date(CreditNotes::CreditDate))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-credit_notes-order_ids")
+                            .from(CreditNotes::Table, CreditNotes::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-credit_notes-order_fee_ids")
+                            .from(CreditNotes::Table, CreditNotes::OrderFeeId)
+                            .to(OrderFees::Table, OrderFees::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-credit_notes-payment_ids")
+                            .from(CreditNotes::Table, CreditNotes::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CreditNotes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CreditNotes {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    OrderFeeId,
+    PaymentId,
+    Value,
+    Reason,
+    CreditDate,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum OrderFees {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
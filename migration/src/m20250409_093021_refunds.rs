@@ -0,0 +1,74 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(Refunds::Table)
+                    .col(pk_auto(Refunds::Id))
+                    .col(uuid_uniq(Refunds::Pid))
+                    .col(integer(Refunds::OrderId))
+                    .col(integer_null(Refunds::PaymentId))
+                    .col(float(Refunds::Value))
+                    .col(date(Refunds::RefundDate))
+                    .col(string_null(Refunds::RefundMethod))
+                    .col(string(Refunds::Reason))
+                    .col(boolean(Refunds::Chargeback))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-refunds-order_ids")
+                            .from(Refunds::Table, Refunds::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-refunds-payment_ids")
+                            .from(Refunds::Table, Refunds::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Refunds::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Refunds {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    PaymentId,
+    Value,
+    RefundDate,
+    RefundMethod,
+    Reason,
+    Chargeback,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
@@ -0,0 +1,76 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(Expenses::Table)
+                    .col(pk_auto(Expenses::Id))
+                    .col(uuid_uniq(Expenses::Pid))
+                    .col(integer(Expenses::OrderId))
+                    .col(string(Expenses::Category))
+                    .col(float(Expenses::Value))
+                    .col(date(Expenses::ExpenseDate))
+                    .col(string_null(Expenses::Description))
+                    .col(string_null(Expenses::Receipt))
+                    .col(boolean(Expenses::Reimbursable))
+                    .col(integer_null(Expenses::PaymentId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-expenses-order_ids")
+                            .from(Expenses::Table, Expenses::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-expenses-payment_ids")
+                            .from(Expenses::Table, Expenses::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Expenses::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Expenses {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    Category,
+    Value,
+    ExpenseDate,
+    Description,
+    Receipt,
+    Reimbursable,
+    PaymentId,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
@@ -0,0 +1,62 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(BillingPlans::Table)
+                    .col(pk_auto(BillingPlans::Id))
+                    .col(uuid_uniq(BillingPlans::Pid))
+                    .col(integer(BillingPlans::OrderId))
+                    .col(float(BillingPlans::Value))
+                    .col(integer(BillingPlans::DayOfMonth))
+                    .col(date(BillingPlans::StartDate))
+                    .col(date_null(BillingPlans::EndDate))
+                    .col(boolean(BillingPlans::Paused))
+                    .col(date_null(BillingPlans::CancelledAt))
+                    .col(date_null(BillingPlans::BilledUntil))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-billing_plans-order_ids")
+                            .from(BillingPlans::Table, BillingPlans::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BillingPlans::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BillingPlans {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    Value,
+    DayOfMonth,
+    StartDate,
+    EndDate,
+    Paused,
+    CancelledAt,
+    BilledUntil,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
@@ -0,0 +1,52 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(BillingPlanPrices::Table)
+                    .col(pk_auto(BillingPlanPrices::Id))
+                    .col(uuid_uniq(BillingPlanPrices::Pid))
+                    .col(integer(BillingPlanPrices::BillingPlanId))
+                    .col(float(BillingPlanPrices::Value))
+                    .col(date(BillingPlanPrices::EffectiveDate))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-billing_plan_prices-billing_plan_ids")
+                            .from(BillingPlanPrices::Table, BillingPlanPrices::BillingPlanId)
+                            .to(BillingPlans::Table, BillingPlans::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BillingPlanPrices::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BillingPlanPrices {
+    Table,
+    Id,
+    Pid,
+    BillingPlanId,
+    Value,
+    EffectiveDate,
+}
+
+#[derive(DeriveIden)]
+enum BillingPlans {
+    Table,
+    Id,
+}
@@ -0,0 +1,66 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(HourlyRates::Table)
+                    .col(pk_auto(HourlyRates::Id))
+                    .col(uuid_uniq(HourlyRates::Pid))
+                    .col(integer_null(HourlyRates::UserId))
+                    .col(integer_null(HourlyRates::OrderId))
+                    .col(float(HourlyRates::Value))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-hourly_rates-user_ids")
+                            .from(HourlyRates::Table, HourlyRates::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-hourly_rates-order_ids")
+                            .from(HourlyRates::Table, HourlyRates::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(HourlyRates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum HourlyRates {
+    Table,
+    Id,
+    Pid,
+    UserId,
+    OrderId,
+    Value,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
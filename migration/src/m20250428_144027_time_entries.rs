@@ -0,0 +1,90 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(TimeEntries::Table)
+                    .col(pk_auto(TimeEntries::Id))
+                    .col(uuid_uniq(TimeEntries::Pid))
+                    .col(integer(TimeEntries::OrderId))
+                    .col(integer(TimeEntries::UserId))
+                    .col(date(TimeEntries::EntryDate))
+                    .col(integer(TimeEntries::Minutes))
+                    .col(string(TimeEntries::Description))
+                    .col(float(TimeEntries::HourlyRate))
+                    .col(boolean(TimeEntries::Billed))
+                    .col(integer_null(TimeEntries::PaymentId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-time_entries-order_ids")
+                            .from(TimeEntries::Table, TimeEntries::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-time_entries-user_ids")
+                            .from(TimeEntries::Table, TimeEntries::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-time_entries-payment_ids")
+                            .from(TimeEntries::Table, TimeEntries::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TimeEntries::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TimeEntries {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    UserId,
+    EntryDate,
+    Minutes,
+    Description,
+    HourlyRate,
+    Billed,
+    PaymentId,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
@@ -0,0 +1,92 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(Documents::Table)
+                    .col(pk_auto(Documents::Id))
+                    .col(uuid_uniq(Documents::Pid))
+                    .col(string(Documents::Kind))
+                    .col(integer(Documents::Number))
+                    .col(integer(Documents::OrderId))
+                    .col(integer_null(Documents::PaymentId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-documents-order_ids")
+                            .from(Documents::Table, Documents::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-documents-payment_ids")
+                            .from(Documents::Table, Documents::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-documents-kind-number")
+                    .table(Documents::Table)
+                    .col(Documents::Kind)
+                    .col(Documents::Number)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        // a payment has one receipt; statements have no payment and, with
+        // nulls distinct, any number of them
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-documents-kind-payment_id")
+                    .table(Documents::Table)
+                    .col(Documents::Kind)
+                    .col(Documents::PaymentId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Documents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Documents {
+    Table,
+    Id,
+    Pid,
+    Kind,
+    Number,
+    OrderId,
+    PaymentId,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
@@ -0,0 +1,83 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::sea_orm::prelude::Uuid;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// The last number given to each kind of document, locked while the next
+/// one is issued so numbers are never given twice, starting from the
+/// documents already issued
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(DocumentCounters::Table)
+                    .col(pk_auto(DocumentCounters::Id))
+                    .col(uuid_uniq(DocumentCounters::Pid))
+                    .col(string_uniq(DocumentCounters::Kind))
+                    .col(integer(DocumentCounters::LastNumber))
+                    .to_owned(),
+            )
+            .await?;
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        for kind in ["receipt", "statement"] {
+            let last = db
+                .query_one(
+                    backend.build(
+                        Query::select()
+                            .expr_as(
+                                Func::coalesce([
+                                    Expr::col(Documents::Number).max(),
+                                    Expr::val(0).into(),
+                                ]),
+                                Alias::new("last_number"),
+                            )
+                            .from(Documents::Table)
+                            .and_where(Expr::col(Documents::Kind).eq(kind)),
+                    ),
+                )
+                .await?;
+            let last_number: i32 = match last {
+                Some(row) => row.try_get("", "last_number")?,
+                None => 0,
+            };
+            let insert = Query::insert()
+                .into_table(DocumentCounters::Table)
+                .columns([
+                    DocumentCounters::Pid,
+                    DocumentCounters::Kind,
+                    DocumentCounters::LastNumber,
+                ])
+                .values_panic([Uuid::new_v4().into(), kind.into(), last_number.into()])
+                .to_owned();
+            db.execute(backend.build(&insert)).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DocumentCounters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentCounters {
+    Table,
+    Id,
+    Pid,
+    Kind,
+    LastNumber,
+}
+
+#[derive(DeriveIden)]
+enum Documents {
+    Table,
+    Kind,
+    Number,
+}
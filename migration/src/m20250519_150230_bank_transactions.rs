@@ -0,0 +1,75 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(BankTransactions::Table)
+                    .col(pk_auto(BankTransactions::Id))
+                    .col(uuid_uniq(BankTransactions::Pid))
+                    .col(string(BankTransactions::Source))
+                    .col(string(BankTransactions::ExternalId))
+                    .col(date(BankTransactions::TransactionDate))
+                    .col(float(BankTransactions::Value))
+                    .col(string(BankTransactions::Description))
+                    .col(string_null(BankTransactions::NossoNumero))
+                    .col(string(BankTransactions::Status))
+                    .col(string_null(BankTransactions::MatchRule))
+                    .col(integer_null(BankTransactions::PaymentId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-bank_transactions-payment_ids")
+                            .from(BankTransactions::Table, BankTransactions::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-bank_transactions-source-external_id")
+                    .table(BankTransactions::Table)
+                    .col(BankTransactions::Source)
+                    .col(BankTransactions::ExternalId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BankTransactions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BankTransactions {
+    Table,
+    Id,
+    Pid,
+    Source,
+    ExternalId,
+    TransactionDate,
+    Value,
+    Description,
+    NossoNumero,
+    Status,
+    MatchRule,
+    PaymentId,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
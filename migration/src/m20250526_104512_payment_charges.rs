@@ -0,0 +1,71 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(PaymentCharges::Table)
+                    .col(pk_auto(PaymentCharges::Id))
+                    .col(uuid_uniq(PaymentCharges::Pid))
+                    .col(integer(PaymentCharges::PaymentId))
+                    .col(string(PaymentCharges::Provider))
+                    .col(string(PaymentCharges::ExternalId))
+                    .col(string_null(PaymentCharges::CheckoutUrl))
+                    .col(float(PaymentCharges::Value))
+                    .col(string(PaymentCharges::Status))
+                    .col(date_null(PaymentCharges::PaidOn))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-payment_charges-payment_ids")
+                            .from(PaymentCharges::Table, PaymentCharges::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-payment_charges-provider-external_id")
+                    .table(PaymentCharges::Table)
+                    .col(PaymentCharges::Provider)
+                    .col(PaymentCharges::ExternalId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaymentCharges::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PaymentCharges {
+    Table,
+    Id,
+    Pid,
+    PaymentId,
+    Provider,
+    ExternalId,
+    CheckoutUrl,
+    Value,
+    Status,
+    PaidOn,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
@@ -0,0 +1,67 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(ProviderEvents::Table)
+                    .col(pk_auto(ProviderEvents::Id))
+                    .col(uuid_uniq(ProviderEvents::Pid))
+                    .col(string(ProviderEvents::Provider))
+                    .col(string(ProviderEvents::EventId))
+                    .col(integer_null(ProviderEvents::PaymentChargeId))
+                    .col(string(ProviderEvents::Status))
+                    .col(date_null(ProviderEvents::PaidOn))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-provider_events-payment_charge_ids")
+                            .from(ProviderEvents::Table, ProviderEvents::PaymentChargeId)
+                            .to(PaymentCharges::Table, PaymentCharges::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-provider_events-provider-event_id")
+                    .table(ProviderEvents::Table)
+                    .col(ProviderEvents::Provider)
+                    .col(ProviderEvents::EventId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProviderEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProviderEvents {
+    Table,
+    Id,
+    Pid,
+    Provider,
+    EventId,
+    PaymentChargeId,
+    Status,
+    PaidOn,
+}
+
+#[derive(DeriveIden)]
+enum PaymentCharges {
+    Table,
+    Id,
+}
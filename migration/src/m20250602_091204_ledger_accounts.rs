@@ -0,0 +1,38 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(LedgerAccounts::Table)
+                    .col(pk_auto(LedgerAccounts::Id))
+                    .col(uuid_uniq(LedgerAccounts::Pid))
+                    .col(string_uniq(LedgerAccounts::Code))
+                    .col(string(LedgerAccounts::Name))
+                    .col(string(LedgerAccounts::Kind))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LedgerAccounts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LedgerAccounts {
+    Table,
+    Id,
+    Pid,
+    Code,
+    Name,
+    Kind,
+}
@@ -0,0 +1,61 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // entries keep the id of their order without a foreign key, so the
+        // books still hold the reversals of an order after it is deleted
+        manager
+            .create_table(
+                table_auto_tz(JournalEntries::Table)
+                    .col(pk_auto(JournalEntries::Id))
+                    .col(uuid_uniq(JournalEntries::Pid))
+                    .col(integer_null(JournalEntries::OrderId))
+                    .col(string(JournalEntries::Source))
+                    .col(date(JournalEntries::EntryDate))
+                    .col(string(JournalEntries::Description))
+                    .col(integer_null(JournalEntries::ReversesId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-journal_entries-reverses_ids")
+                            .from(JournalEntries::Table, JournalEntries::ReversesId)
+                            .to(JournalEntries::Table, JournalEntries::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-journal_entries-order_id")
+                    .table(JournalEntries::Table)
+                    .col(JournalEntries::OrderId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JournalEntries::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JournalEntries {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    Source,
+    EntryDate,
+    Description,
+    ReversesId,
+}
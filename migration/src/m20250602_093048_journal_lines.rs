@@ -0,0 +1,68 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(JournalLines::Table)
+                    .col(pk_auto(JournalLines::Id))
+                    .col(uuid_uniq(JournalLines::Pid))
+                    .col(integer(JournalLines::JournalEntryId))
+                    .col(integer(JournalLines::LedgerAccountId))
+                    .col(float(JournalLines::Debit))
+                    .col(float(JournalLines::Credit))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-journal_lines-journal_entry_ids")
+                            .from(JournalLines::Table, JournalLines::JournalEntryId)
+                            .to(JournalEntries::Table, JournalEntries::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-journal_lines-ledger_account_ids")
+                            .from(JournalLines::Table, JournalLines::LedgerAccountId)
+                            .to(LedgerAccounts::Table, LedgerAccounts::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JournalLines::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum JournalLines {
+    Table,
+    Id,
+    Pid,
+    JournalEntryId,
+    LedgerAccountId,
+    Debit,
+    Credit,
+}
+
+#[derive(DeriveIden)]
+enum JournalEntries {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum LedgerAccounts {
+    Table,
+    Id,
+}
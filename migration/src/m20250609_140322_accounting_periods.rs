@@ -0,0 +1,36 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(AccountingPeriods::Table)
+                    .col(pk_auto(AccountingPeriods::Id))
+                    .col(uuid_uniq(AccountingPeriods::Pid))
+                    .col(date_uniq(AccountingPeriods::StartsOn))
+                    .col(date(AccountingPeriods::EndsOn))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AccountingPeriods::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AccountingPeriods {
+    Table,
+    Id,
+    Pid,
+    StartsOn,
+    EndsOn,
+}
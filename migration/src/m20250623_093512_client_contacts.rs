@@ -0,0 +1,67 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(ClientContacts::Table)
+                    .col(pk_auto(ClientContacts::Id))
+                    .col(uuid_uniq(ClientContacts::Pid))
+                    .col(integer(ClientContacts::ClientId))
+                    .col(string(ClientContacts::Kind))
+                    .col(string(ClientContacts::Value))
+                    .col(boolean(ClientContacts::Primary))
+                    .col(boolean(ClientContacts::Whatsapp))
+                    .col(text_null(ClientContacts::Notes))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-client_contacts-client_ids")
+                            .from(ClientContacts::Table, ClientContacts::ClientId)
+                            .to(Clients::Table, Clients::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-client_contacts-client_id")
+                    .table(ClientContacts::Table)
+                    .col(ClientContacts::ClientId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ClientContacts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ClientContacts {
+    Table,
+    Id,
+    Pid,
+    ClientId,
+    Kind,
+    Value,
+    Primary,
+    Whatsapp,
+    Notes,
+}
+
+#[derive(DeriveIden)]
+enum Clients {
+    Table,
+    Id,
+}
@@ -0,0 +1,73 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(ClientAddresses::Table)
+                    .col(pk_auto(ClientAddresses::Id))
+                    .col(uuid_uniq(ClientAddresses::Pid))
+                    .col(integer(ClientAddresses::ClientId))
+                    .col(string(ClientAddresses::Cep))
+                    .col(string(ClientAddresses::Street))
+                    .col(string(ClientAddresses::Number))
+                    .col(string_null(ClientAddresses::Complement))
+                    .col(string_null(ClientAddresses::District))
+                    .col(string(ClientAddresses::City))
+                    .col(string(ClientAddresses::State))
+                    .col(boolean(ClientAddresses::Primary))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-client_addresses-client_ids")
+                            .from(ClientAddresses::Table, ClientAddresses::ClientId)
+                            .to(Clients::Table, Clients::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-client_addresses-client_id")
+                    .table(ClientAddresses::Table)
+                    .col(ClientAddresses::ClientId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ClientAddresses::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ClientAddresses {
+    Table,
+    Id,
+    Pid,
+    ClientId,
+    Cep,
+    Street,
+    Number,
+    Complement,
+    District,
+    City,
+    State,
+    Primary,
+}
+
+#[derive(DeriveIden)]
+enum Clients {
+    Table,
+    Id,
+}
@@ -0,0 +1,237 @@
+use sea_orm_migration::sea_orm::prelude::Uuid;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Moves the phones and the email of every client to its contacts, the first
+/// phone and the email as the primary ones, and drops the flat columns. The
+/// values are stored the way new contacts are, the digits of a phone and an
+/// email in lowercase; one that is not a phone or an email is kept unchanged
+/// in the notes of the first contact moved, or in the contact of the client
+/// when none was.
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let clients = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([
+                            Clients::Id,
+                            Clients::Contact,
+                            Clients::Phone,
+                            Clients::Phone2,
+                            Clients::Email,
+                        ])
+                        .from(Clients::Table),
+                ),
+            )
+            .await?;
+        for client in clients {
+            let client_id: i32 = client.try_get("", "id")?;
+            let contacts = [
+                (
+                    "phone",
+                    client.try_get::<Option<String>>("", "phone")?,
+                    true,
+                ),
+                (
+                    "phone",
+                    client.try_get::<Option<String>>("", "phone2")?,
+                    false,
+                ),
+                (
+                    "email",
+                    client.try_get::<Option<String>>("", "email")?,
+                    true,
+                ),
+            ];
+            let mut moved: Vec<(&str, String, bool)> = vec![];
+            let mut not_recognised = vec![];
+            for (kind, value, primary) in contacts {
+                let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+                    continue;
+                };
+                match normalize_contact(kind, &value) {
+                    Some(normalized) => moved.push((kind, normalized, primary)),
+                    None => not_recognised.push(format!("{kind}: {value}")),
+                }
+            }
+            // the second phone is the primary one when the first was not
+            // recognised
+            if !moved
+                .iter()
+                .any(|(kind, _, primary)| *kind == "phone" && *primary)
+            {
+                if let Some(first_phone) = moved.iter_mut().find(|(kind, _, _)| *kind == "phone") {
+                    first_phone.2 = true;
+                }
+            }
+            let notes = (!not_recognised.is_empty())
+                .then(|| format!("Not recognised when moved: {}", not_recognised.join("; ")));
+            if moved.is_empty() {
+                if let Some(notes) = notes.clone() {
+                    let contact: String = client.try_get("", "contact")?;
+                    let update = Query::update()
+                        .table(Clients::Table)
+                        .value(Clients::Contact, format!("{contact} ({notes})").trim())
+                        .and_where(Expr::col(Clients::Id).eq(client_id))
+                        .to_owned();
+                    db.execute(backend.build(&update)).await?;
+                }
+            }
+            for (index, (kind, value, primary)) in moved.into_iter().enumerate() {
+                let insert = Query::insert()
+                    .into_table(ClientContacts::Table)
+                    .columns([
+                        ClientContacts::Pid,
+                        ClientContacts::ClientId,
+                        ClientContacts::Kind,
+                        ClientContacts::Value,
+                        ClientContacts::Primary,
+                        ClientContacts::Whatsapp,
+                        ClientContacts::Notes,
+                    ])
+                    .values_panic([
+                        Uuid::new_v4().into(),
+                        client_id.into(),
+                        kind.into(),
+                        value.into(),
+                        primary.into(),
+                        false.into(),
+                        notes.clone().filter(|_| index == 0).into(),
+                    ])
+                    .to_owned();
+                db.execute(backend.build(&insert)).await?;
+            }
+        }
+        for column in [Clients::Phone, Clients::Phone2, Clients::Email] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Clients::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Clients::Table)
+                    .add_column(string(Clients::Phone).default(""))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Clients::Table)
+                    .add_column(string_null(Clients::Phone2))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Clients::Table)
+                    .add_column(string(Clients::Email).default(""))
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        let backend = db.get_database_backend();
+        let contacts = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([
+                            ClientContacts::ClientId,
+                            ClientContacts::Kind,
+                            ClientContacts::Value,
+                            ClientContacts::Primary,
+                        ])
+                        .from(ClientContacts::Table)
+                        .order_by(ClientContacts::Id, Order::Asc),
+                ),
+            )
+            .await?;
+        for contact in contacts {
+            let client_id: i32 = contact.try_get("", "client_id")?;
+            let kind: String = contact.try_get("", "kind")?;
+            let value: String = contact.try_get("", "value")?;
+            let primary: bool = contact.try_get("", "primary")?;
+            let column = match (kind.as_str(), primary) {
+                ("phone", true) => Clients::Phone,
+                ("phone", false) => Clients::Phone2,
+                ("email", true) => Clients::Email,
+                _ => continue,
+            };
+            let update = Query::update()
+                .table(Clients::Table)
+                .value(column, value)
+                .and_where(Expr::col(Clients::Id).eq(client_id))
+                .to_owned();
+            db.execute(backend.build(&update)).await?;
+        }
+        Ok(())
+    }
+}
+
+/// A phone or an email the way contacts are stored, as checked by the
+/// clients model when they are created
+fn normalize_contact(kind: &str, value: &str) -> Option<String> {
+    let value = value.trim();
+    if kind == "phone" {
+        if !value
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '+' | '(' | ')' | '-' | '.' | ' '))
+        {
+            return None;
+        }
+        let digits = value
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+        return (8..=13).contains(&digits.len()).then_some(digits);
+    }
+    let (user, domain) = value.split_once('@')?;
+    let valid = !user.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !value.contains(char::is_whitespace);
+    valid.then(|| value.to_lowercase())
+}
+
+#[derive(DeriveIden)]
+enum Clients {
+    Table,
+    Id,
+    Contact,
+    Phone,
+    Phone2,
+    Email,
+}
+
+#[derive(DeriveIden)]
+enum ClientContacts {
+    Table,
+    Id,
+    ClientId,
+    Pid,
+    Kind,
+    Value,
+    Primary,
+    Whatsapp,
+    Notes,
+}
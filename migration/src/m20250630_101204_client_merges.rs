@@ -0,0 +1,72 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(ClientMerges::Table)
+                    .col(pk_auto(ClientMerges::Id))
+                    .col(uuid_uniq(ClientMerges::Pid))
+                    .col(integer(ClientMerges::PrimaryClientId))
+                    .col(uuid(ClientMerges::SecondaryClientPid))
+                    .col(string(ClientMerges::SecondaryName))
+                    .col(text(ClientMerges::Snapshot))
+                    .col(integer(ClientMerges::MovedOrders))
+                    .col(integer_null(ClientMerges::UserId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-client_merges-primary_client_ids")
+                            .from(ClientMerges::Table, ClientMerges::PrimaryClientId)
+                            .to(Clients::Table, Clients::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-client_merges-user_ids")
+                            .from(ClientMerges::Table, ClientMerges::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ClientMerges::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ClientMerges {
+    Table,
+    Id,
+    Pid,
+    PrimaryClientId,
+    SecondaryClientPid,
+    SecondaryName,
+    Snapshot,
+    MovedOrders,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Clients {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
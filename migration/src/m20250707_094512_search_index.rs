@@ -0,0 +1,170 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// What is searched: the table, the kind of result a row leads to, the pid
+/// of that result, the name of the field and the text to search, written
+/// over the row as `row`
+const SOURCES: [(&str, &str, &str, &str, &str); 6] = [
+    ("clients", "client", "row.pid", "'name'", "row.name"),
+    (
+        "client_contacts",
+        "client",
+        "(SELECT pid FROM clients WHERE clients.id = row.client_id)",
+        "row.kind",
+        "row.value",
+    ),
+    ("partners", "partner", "row.pid", "'name'", "row.name"),
+    ("sellers", "seller", "row.pid", "'name'", "row.name"),
+    (
+        "processes",
+        "process",
+        "row.pid",
+        "'case_type'",
+        "row.case_type",
+    ),
+    (
+        "order_fees",
+        "order",
+        "(SELECT pid FROM orders WHERE orders.id = row.order_id)",
+        "'fee_info'",
+        "row.info",
+    ),
+];
+
+/// The columns indexed for search on Postgres
+const POSTGRES_INDEXES: [(&str, &str); 6] = [
+    ("clients", "name"),
+    ("client_contacts", "value"),
+    ("partners", "name"),
+    ("sellers", "name"),
+    ("processes", "case_type"),
+    ("order_fees", "info"),
+];
+
+/// Postgres searches the tables themselves, through expression indexes on
+/// their text without accents. `unaccent` is not immutable, as it depends on
+/// its dictionary, so it is wrapped to be usable in an index.
+async fn up_postgres(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS unaccent")
+        .await?;
+    db.execute_unprepared(
+        "CREATE OR REPLACE FUNCTION immutable_unaccent(text) RETURNS text \
+         LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT AS \
+         $$ SELECT public.unaccent('public.unaccent'::regdictionary, $1) $$",
+    )
+    .await?;
+    for (table, column) in POSTGRES_INDEXES {
+        db.execute_unprepared(&format!(
+            "CREATE INDEX IF NOT EXISTS \"idx-{table}-{column}-search\" ON {table} \
+             USING gin (to_tsvector('simple', immutable_unaccent(coalesce({column}, ''))))"
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+async fn down_postgres(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    for (table, column) in POSTGRES_INDEXES {
+        db.execute_unprepared(&format!(
+            "DROP INDEX IF EXISTS \"idx-{table}-{column}-search\""
+        ))
+        .await?;
+    }
+    db.execute_unprepared("DROP FUNCTION IF EXISTS immutable_unaccent(text)")
+        .await?;
+    Ok(())
+}
+
+/// SQLite searches an FTS5 index of its own, kept up to date by triggers on
+/// the tables it indexes and filled with what they already hold
+async fn up_sqlite(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    db.execute_unprepared(
+        "CREATE VIRTUAL TABLE search_index USING fts5(\
+         kind UNINDEXED, pid UNINDEXED, field UNINDEXED, source UNINDEXED, \
+         source_id UNINDEXED, text, tokenize = 'unicode61 remove_diacritics 2')",
+    )
+    .await?;
+    for (table, kind, pid, field, text) in SOURCES {
+        let insert = |row: &str| {
+            format!(
+                "INSERT INTO search_index (kind, pid, field, source, source_id, text) \
+                 SELECT '{kind}', {}, {}, '{table}', {row}.id, {} \
+                 WHERE {} IS NOT NULL",
+                pid.replace("row.", &format!("{row}.")),
+                field.replace("row.", &format!("{row}.")),
+                text.replace("row.", &format!("{row}.")),
+                text.replace("row.", &format!("{row}.")),
+            )
+        };
+        let delete =
+            format!("DELETE FROM search_index WHERE source = '{table}' AND source_id = OLD.id");
+        db.execute_unprepared(&format!(
+            "CREATE TRIGGER search_index_{table}_insert AFTER INSERT ON {table} \
+             BEGIN {}; END",
+            insert("NEW")
+        ))
+        .await?;
+        db.execute_unprepared(&format!(
+            "CREATE TRIGGER search_index_{table}_update AFTER UPDATE ON {table} \
+             BEGIN {delete}; {}; END",
+            insert("NEW")
+        ))
+        .await?;
+        db.execute_unprepared(&format!(
+            "CREATE TRIGGER search_index_{table}_delete AFTER DELETE ON {table} \
+             BEGIN {delete}; END"
+        ))
+        .await?;
+        db.execute_unprepared(&format!(
+            "INSERT INTO search_index (kind, pid, field, source, source_id, text) \
+             SELECT '{kind}', {}, {}, '{table}', existing.id, {} FROM {table} AS existing \
+             WHERE {} IS NOT NULL",
+            pid.replace("row.", "existing."),
+            field.replace("row.", "existing."),
+            text.replace("row.", "existing."),
+            text.replace("row.", "existing."),
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+async fn down_sqlite(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    for (table, ..) in SOURCES {
+        for event in ["insert", "update", "delete"] {
+            db.execute_unprepared(&format!(
+                "DROP TRIGGER IF EXISTS search_index_{table}_{event}"
+            ))
+            .await?;
+        }
+    }
+    db.execute_unprepared("DROP TABLE IF EXISTS search_index")
+        .await?;
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        match manager.get_database_backend() {
+            DbBackend::Postgres => up_postgres(manager).await,
+            DbBackend::Sqlite => up_sqlite(manager).await,
+            DbBackend::MySql => Ok(()),
+        }
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        match manager.get_database_backend() {
+            DbBackend::Postgres => down_postgres(manager).await,
+            DbBackend::Sqlite => down_sqlite(manager).await,
+            DbBackend::MySql => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(Lawsuits::Table)
+                    .col(pk_auto(Lawsuits::Id))
+                    .col(uuid_uniq(Lawsuits::Pid))
+                    .col(integer(Lawsuits::OrderId))
+                    .col(string(Lawsuits::CnjNumber))
+                    .col(string_null(Lawsuits::Court))
+                    .col(string_null(Lawsuits::District))
+                    .col(string_null(Lawsuits::OpposingParty))
+                    .col(date_null(Lawsuits::FilingDate))
+                    .col(string(Lawsuits::Status))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-lawsuits-order_ids")
+                            .from(Lawsuits::Table, Lawsuits::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-lawsuits-order_id")
+                    .table(Lawsuits::Table)
+                    .col(Lawsuits::OrderId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-lawsuits-cnj_number")
+                    .table(Lawsuits::Table)
+                    .col(Lawsuits::CnjNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Lawsuits::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Lawsuits {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    CnjNumber,
+    Court,
+    District,
+    OpposingParty,
+    FilingDate,
+    Status,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
@@ -0,0 +1,90 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// The fields of a lawsuit searched for its order
+const FIELDS: [&str; 3] = ["cnj_number", "opposing_party", "court"];
+
+/// Lawsuits are searched like the tables of the search index migration: by
+/// expression indexes on Postgres and through `search_index` on SQLite
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        match manager.get_database_backend() {
+            DbBackend::Postgres => {
+                for column in FIELDS {
+                    db.execute_unprepared(&format!(
+                        "CREATE INDEX IF NOT EXISTS \"idx-lawsuits-{column}-search\" ON lawsuits \
+                         USING gin (to_tsvector('simple', immutable_unaccent(coalesce({column}, ''))))"
+                    ))
+                    .await?;
+                }
+            }
+            DbBackend::Sqlite => {
+                let inserts = |row: &str| {
+                    FIELDS
+                        .iter()
+                        .map(|column| {
+                            format!(
+                                "INSERT INTO search_index (kind, pid, field, source, source_id, text) \
+                                 SELECT 'order', (SELECT pid FROM orders WHERE orders.id = {row}.order_id), \
+                                 '{column}', 'lawsuits', {row}.id, {row}.{column} \
+                                 WHERE {row}.{column} IS NOT NULL"
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                };
+                let delete =
+                    "DELETE FROM search_index WHERE source = 'lawsuits' AND source_id = OLD.id";
+                db.execute_unprepared(&format!(
+                    "CREATE TRIGGER search_index_lawsuits_insert AFTER INSERT ON lawsuits \
+                     BEGIN {}; END",
+                    inserts("NEW").join("; ")
+                ))
+                .await?;
+                db.execute_unprepared(&format!(
+                    "CREATE TRIGGER search_index_lawsuits_update AFTER UPDATE ON lawsuits \
+                     BEGIN {delete}; {}; END",
+                    inserts("NEW").join("; ")
+                ))
+                .await?;
+                db.execute_unprepared(&format!(
+                    "CREATE TRIGGER search_index_lawsuits_delete AFTER DELETE ON lawsuits \
+                     BEGIN {delete}; END"
+                ))
+                .await?;
+            }
+            DbBackend::MySql => {}
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        match manager.get_database_backend() {
+            DbBackend::Postgres => {
+                for column in FIELDS {
+                    db.execute_unprepared(&format!(
+                        "DROP INDEX IF EXISTS \"idx-lawsuits-{column}-search\""
+                    ))
+                    .await?;
+                }
+            }
+            DbBackend::Sqlite => {
+                for event in ["insert", "update", "delete"] {
+                    db.execute_unprepared(&format!(
+                        "DROP TRIGGER IF EXISTS search_index_lawsuits_{event}"
+                    ))
+                    .await?;
+                }
+                db.execute_unprepared("DELETE FROM search_index WHERE source = 'lawsuits'")
+                    .await?;
+            }
+            DbBackend::MySql => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(CalendarEvents::Table)
+                    .col(pk_auto(CalendarEvents::Id))
+                    .col(uuid_uniq(CalendarEvents::Pid))
+                    .col(string(CalendarEvents::Kind))
+                    .col(string(CalendarEvents::Title))
+                    .col(timestamp_with_time_zone(CalendarEvents::StartsAt))
+                    .col(timestamp_with_time_zone_null(CalendarEvents::EndsAt))
+                    .col(string_null(CalendarEvents::Location))
+                    .col(text_null(CalendarEvents::Description))
+                    .col(boolean(CalendarEvents::Completed))
+                    .col(integer_null(CalendarEvents::OrderId))
+                    .col(integer_null(CalendarEvents::LawsuitId))
+                    .col(integer_null(CalendarEvents::UserId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-calendar_events-order_ids")
+                            .from(CalendarEvents::Table, CalendarEvents::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-calendar_events-lawsuit_ids")
+                            .from(CalendarEvents::Table, CalendarEvents::LawsuitId)
+                            .to(Lawsuits::Table, Lawsuits::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-calendar_events-user_ids")
+                            .from(CalendarEvents::Table, CalendarEvents::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-calendar_events-starts_at")
+                    .table(CalendarEvents::Table)
+                    .col(CalendarEvents::StartsAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CalendarEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CalendarEvents {
+    Table,
+    Id,
+    Pid,
+    Kind,
+    Title,
+    StartsAt,
+    EndsAt,
+    Location,
+    Description,
+    Completed,
+    OrderId,
+    LawsuitId,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Lawsuits {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,52 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(CalendarFeeds::Table)
+                    .col(pk_auto(CalendarFeeds::Id))
+                    .col(uuid_uniq(CalendarFeeds::Pid))
+                    .col(string_uniq(CalendarFeeds::Token))
+                    .col(integer(CalendarFeeds::UserId))
+                    .col(boolean(CalendarFeeds::Firm))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-calendar_feeds-user_ids")
+                            .from(CalendarFeeds::Table, CalendarFeeds::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CalendarFeeds::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CalendarFeeds {
+    Table,
+    Id,
+    Pid,
+    Token,
+    UserId,
+    Firm,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
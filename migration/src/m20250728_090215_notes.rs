@@ -0,0 +1,142 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(Notes::Table)
+                    .col(pk_auto(Notes::Id))
+                    .col(uuid_uniq(Notes::Pid))
+                    .col(text(Notes::Body))
+                    .col(boolean(Notes::Pinned))
+                    .col(integer_null(Notes::UserId))
+                    .col(integer_null(Notes::OrderId))
+                    .col(integer_null(Notes::ClientId))
+                    .col(integer_null(Notes::PaymentId))
+                    .col(timestamp_with_time_zone_null(Notes::DeletedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notes-user_ids")
+                            .from(Notes::Table, Notes::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notes-order_ids")
+                            .from(Notes::Table, Notes::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notes-client_ids")
+                            .from(Notes::Table, Notes::ClientId)
+                            .to(Clients::Table, Clients::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notes-payment_ids")
+                            .from(Notes::Table, Notes::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                table_auto_tz(NoteRevisions::Table)
+                    .col(pk_auto(NoteRevisions::Id))
+                    .col(uuid_uniq(NoteRevisions::Pid))
+                    .col(integer(NoteRevisions::NoteId))
+                    .col(text(NoteRevisions::Body))
+                    .col(integer_null(NoteRevisions::UserId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-note_revisions-note_ids")
+                            .from(NoteRevisions::Table, NoteRevisions::NoteId)
+                            .to(Notes::Table, Notes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-note_revisions-user_ids")
+                            .from(NoteRevisions::Table, NoteRevisions::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NoteRevisions::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Notes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Notes {
+    Table,
+    Id,
+    Pid,
+    Body,
+    Pinned,
+    UserId,
+    OrderId,
+    ClientId,
+    PaymentId,
+    DeletedAt,
+}
+
+#[derive(DeriveIden)]
+enum NoteRevisions {
+    Table,
+    Id,
+    Pid,
+    NoteId,
+    Body,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Clients {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
@@ -0,0 +1,54 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(StatusChanges::Table)
+                    .col(pk_auto(StatusChanges::Id))
+                    .col(uuid_uniq(StatusChanges::Pid))
+                    .col(integer(StatusChanges::OrderId))
+                    .col(string(StatusChanges::Subject))
+                    .col(string(StatusChanges::FromStatus))
+                    .col(string(StatusChanges::ToStatus))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-status_changes-order_ids")
+                            .from(StatusChanges::Table, StatusChanges::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StatusChanges::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StatusChanges {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    Subject,
+    FromStatus,
+    ToStatus,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
@@ -0,0 +1,124 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(Attachments::Table)
+                    .col(pk_auto(Attachments::Id))
+                    .col(uuid_uniq(Attachments::Pid))
+                    .col(string(Attachments::Category))
+                    .col(string(Attachments::FileName))
+                    .col(string(Attachments::ContentType))
+                    .col(big_integer(Attachments::Size))
+                    .col(string(Attachments::Checksum))
+                    .col(string_uniq(Attachments::StorageKey))
+                    .col(integer_null(Attachments::UserId))
+                    .col(integer_null(Attachments::ClientId))
+                    .col(integer_null(Attachments::OrderId))
+                    .col(integer_null(Attachments::PaymentId))
+                    .col(integer_null(Attachments::ExpenseId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-attachments-user_ids")
+                            .from(Attachments::Table, Attachments::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-attachments-client_ids")
+                            .from(Attachments::Table, Attachments::ClientId)
+                            .to(Clients::Table, Clients::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-attachments-order_ids")
+                            .from(Attachments::Table, Attachments::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-attachments-payment_ids")
+                            .from(Attachments::Table, Attachments::PaymentId)
+                            .to(Payments::Table, Payments::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-attachments-expense_ids")
+                            .from(Attachments::Table, Attachments::ExpenseId)
+                            .to(Expenses::Table, Expenses::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Attachments::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Attachments {
+    Table,
+    Id,
+    Pid,
+    Category,
+    FileName,
+    ContentType,
+    Size,
+    Checksum,
+    StorageKey,
+    UserId,
+    ClientId,
+    OrderId,
+    PaymentId,
+    ExpenseId,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Clients {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Expenses {
+    Table,
+    Id,
+}
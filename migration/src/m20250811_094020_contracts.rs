@@ -0,0 +1,153 @@
+use loco_rs::schema::table_auto_tz;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                table_auto_tz(ContractTemplates::Table)
+                    .col(pk_auto(ContractTemplates::Id))
+                    .col(uuid_uniq(ContractTemplates::Pid))
+                    .col(integer_uniq(ContractTemplates::ProcessId))
+                    .col(string(ContractTemplates::Name))
+                    .col(text(ContractTemplates::Body))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contract_templates-process_ids")
+                            .from(ContractTemplates::Table, ContractTemplates::ProcessId)
+                            .to(Processes::Table, Processes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                table_auto_tz(Contracts::Table)
+                    .col(pk_auto(Contracts::Id))
+                    .col(uuid_uniq(Contracts::Pid))
+                    .col(integer(Contracts::OrderId))
+                    .col(integer(Contracts::Version))
+                    .col(string(Contracts::Fingerprint))
+                    .col(integer_null(Contracts::TemplateId))
+                    .col(integer_null(Contracts::PdfAttachmentId))
+                    .col(integer_null(Contracts::DocxAttachmentId))
+                    .col(integer_null(Contracts::UserId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contracts-order_ids")
+                            .from(Contracts::Table, Contracts::OrderId)
+                            .to(Orders::Table, Orders::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contracts-template_ids")
+                            .from(Contracts::Table, Contracts::TemplateId)
+                            .to(ContractTemplates::Table, ContractTemplates::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contracts-pdf_attachment_ids")
+                            .from(Contracts::Table, Contracts::PdfAttachmentId)
+                            .to(Attachments::Table, Attachments::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contracts-docx_attachment_ids")
+                            .from(Contracts::Table, Contracts::DocxAttachmentId)
+                            .to(Attachments::Table, Attachments::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-contracts-user_ids")
+                            .from(Contracts::Table, Contracts::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-contracts-order_id-version")
+                    .table(Contracts::Table)
+                    .col(Contracts::OrderId)
+                    .col(Contracts::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Contracts::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ContractTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ContractTemplates {
+    Table,
+    Id,
+    Pid,
+    ProcessId,
+    Name,
+    Body,
+}
+
+#[derive(DeriveIden)]
+enum Contracts {
+    Table,
+    Id,
+    Pid,
+    OrderId,
+    Version,
+    Fingerprint,
+    TemplateId,
+    PdfAttachmentId,
+    DocxAttachmentId,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Processes {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Orders {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Attachments {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
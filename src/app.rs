@@ -13,7 +13,10 @@ use migration::Migrator;
 use sea_orm::DatabaseConnection;
 use std::path::Path;
 
-use crate::{controllers, models::_entities::users, tasks, workers::downloader::DownloadWorker};
+use crate::{
+    controllers, file_storage::StorageSettings, models::_entities::users, tasks,
+    workers::downloader::DownloadWorker,
+};
 
 pub struct App;
 #[async_trait]
@@ -36,6 +39,13 @@ impl Hooks for App {
         create_app::<Self, Migrator>(mode, environment).await
     }
 
+    async fn after_context(mut ctx: AppContext) -> Result<AppContext> {
+        ctx.storage = StorageSettings::from_settings(ctx.config.settings.as_ref())
+            .storage()?
+            .into();
+        Ok(ctx)
+    }
+
     async fn initializers(_ctx: &AppContext) -> Result<Vec<Box<dyn Initializer>>> {
         Ok(vec![])
     }
@@ -49,6 +59,29 @@ impl Hooks for App {
             .add_route(controllers::partners::routes())
             .add_route(controllers::processes::routes())
             .add_route(controllers::fees::routes())
+            .add_route(controllers::credit_notes::routes())
+            .add_route(controllers::refunds::routes())
+            .add_route(controllers::expenses::routes())
+            .add_route(controllers::billing_plans::routes())
+            .add_route(controllers::time_entries::routes())
+            .add_route(controllers::hourly_rates::routes())
+            .add_route(controllers::documents::routes())
+            .add_route(controllers::pix::routes())
+            .add_route(controllers::boletos::routes())
+            .add_route(controllers::bank_transactions::routes())
+            .add_route(controllers::payment_charges::routes())
+            .add_route(controllers::webhooks::routes())
+            .add_route(controllers::ledger::routes())
+            .add_route(controllers::accounting::routes())
+            .add_route(controllers::reports::routes())
+            .add_route(controllers::search::routes())
+            .add_route(controllers::lawsuits::routes())
+            .add_route(controllers::calendar::routes())
+            .add_route(controllers::business_days::routes())
+            .add_route(controllers::notes::routes())
+            .add_route(controllers::attachments::routes())
+            .add_route(controllers::contract_templates::routes())
+            .add_route(controllers::contracts::routes())
     }
     async fn connect_workers(ctx: &AppContext, queue: &Queue) -> Result<()> {
         queue.register(DownloadWorker::build(ctx)).await?;
@@ -56,6 +89,11 @@ impl Hooks for App {
     }
     fn register_tasks(tasks: &mut Tasks) {
         tasks.register(tasks::seed::SeedData);
+        tasks.register(tasks::generate_retainer_payments::GenerateRetainerPayments);
+        tasks.register(tasks::check_ledger::CheckLedger);
+        tasks.register(tasks::export_journal::ExportJournal);
+        tasks.register(tasks::find_duplicate_clients::FindDuplicateClients);
+        tasks.register(tasks::refresh_contracts::RefreshContracts);
         // tasks-inject (do not remove)
     }
     async fn truncate(db: &DatabaseConnection) -> Result<()> {
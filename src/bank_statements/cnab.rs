@@ -0,0 +1,93 @@
+//! CNAB return files, where the bank reports the boletos it received.
+//!
+//! Positions follow the Banco do Brasil layouts for agreements with seven
+//! digits, the ones boletos are issued under: CBR643 for CNAB 400 and the
+//! FEBRABAN segments T and U for CNAB 240.
+
+use super::StatementLine;
+
+/// occurrences that mean the boleto was paid: settled, and settled after
+/// being written off
+const SETTLED_OCCURRENCES: [&str; 2] = ["06", "17"];
+
+/// Reads the boletos settled in a CNAB 400 return file
+#[must_use]
+pub fn parse_400(content: &str) -> Vec<StatementLine> {
+    content
+        .lines()
+        .map(|line| line.trim_end_matches('\r').chars().collect::<Vec<_>>())
+        .filter(|record| field(record, 1, 1) == "7")
+        .filter(|record| SETTLED_OCCURRENCES.contains(&field(record, 109, 110).as_str()))
+        .filter_map(|record| {
+            let nosso_numero = field(&record, 64, 80);
+            let date = date(&field(&record, 111, 116), "%d%m%y")?;
+            let value = cents(&field(&record, 254, 266))?;
+            Some(settled_boleto(nosso_numero, date, value))
+        })
+        .collect()
+}
+
+/// Reads the boletos settled in a CNAB 240 return file, where every boleto
+/// takes a segment T with its "nosso número" followed by a segment U with
+/// what was paid
+#[must_use]
+pub fn parse_240(content: &str) -> Vec<StatementLine> {
+    let mut lines = vec![];
+    let mut nosso_numero = None;
+    for record in content
+        .lines()
+        .map(|line| line.trim_end_matches('\r').chars().collect::<Vec<_>>())
+        .filter(|record| field(record, 8, 8) == "3")
+    {
+        let settled = SETTLED_OCCURRENCES.contains(&field(&record, 16, 17).as_str());
+        match field(&record, 14, 14).as_str() {
+            "T" => nosso_numero = settled.then(|| field(&record, 38, 57)),
+            "U" => {
+                let Some(nosso_numero) = nosso_numero.take() else {
+                    continue;
+                };
+                let (Some(date), Some(value)) = (
+                    date(&field(&record, 138, 145), "%d%m%Y"),
+                    cents(&field(&record, 78, 92)),
+                ) else {
+                    continue;
+                };
+                lines.push(settled_boleto(nosso_numero, date, value));
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+fn settled_boleto(nosso_numero: String, date: chrono::NaiveDate, value: f32) -> StatementLine {
+    StatementLine {
+        external_id: format!("{nosso_numero}-{}", date.format("%Y%m%d")),
+        date,
+        value,
+        description: format!("Liquidação do boleto {nosso_numero}"),
+        nosso_numero: Some(nosso_numero),
+    }
+}
+
+/// the field between two positions of a record, counted from 1 and
+/// inclusive as in the layout manuals
+fn field(record: &[char], start: usize, end: usize) -> String {
+    record
+        .get(start - 1..end.min(record.len()))
+        .unwrap_or_default()
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn date(text: &str, format: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(text, format).ok()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn cents(text: &str) -> Option<f32> {
+    let cents = text.parse::<i64>().ok()?;
+    (cents > 0).then(|| cents as f32 / 100.0)
+}
@@ -0,0 +1,69 @@
+//! Bank statements and boleto return files uploaded for reconciliation,
+//! read into the credits they report.
+
+pub mod cnab;
+pub mod ofx;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementFormat {
+    Ofx,
+    Cnab240,
+    Cnab400,
+}
+
+impl StatementFormat {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ofx => "ofx",
+            Self::Cnab240 => "cnab240",
+            Self::Cnab400 => "cnab400",
+        }
+    }
+
+    /// tells the format of a file by its content: OFX has its own header,
+    /// while CNAB files are told apart by the width of their records
+    #[must_use]
+    pub fn detect(content: &str) -> Option<Self> {
+        if content.contains("<OFX>") {
+            return Some(Self::Ofx);
+        }
+        let width = content
+            .lines()
+            .map(|line| line.trim_end_matches('\r').chars().count())
+            .max()?;
+        match width {
+            400 => Some(Self::Cnab400),
+            240 => Some(Self::Cnab240),
+            _ => None,
+        }
+    }
+}
+
+/// A credit reported by the bank
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StatementLine {
+    /// what identifies the line in the bank's files, so importing the same
+    /// file twice does not record it twice
+    pub external_id: String,
+    pub date: chrono::NaiveDate,
+    pub value: f32,
+    pub description: String,
+    pub nosso_numero: Option<String>,
+}
+
+/// Reads the credits of a bank statement or return file, or `None` when its
+/// format is not known
+#[must_use]
+pub fn parse(content: &str) -> Option<(StatementFormat, Vec<StatementLine>)> {
+    let format = StatementFormat::detect(content)?;
+    let lines = match format {
+        StatementFormat::Ofx => ofx::parse(content),
+        StatementFormat::Cnab240 => cnab::parse_240(content),
+        StatementFormat::Cnab400 => cnab::parse_400(content),
+    };
+    Some((format, lines))
+}
@@ -0,0 +1,49 @@
+//! OFX statements as exported by the internet banking of Brazilian banks,
+//! either in the SGML flavour of version 1 or in XML.
+
+use super::StatementLine;
+
+/// Reads the credits of an OFX statement, leaving debits out
+#[must_use]
+pub fn parse(content: &str) -> Vec<StatementLine> {
+    content
+        .split("<STMTTRN>")
+        .skip(1)
+        .filter_map(|block| {
+            let block = block.split("</STMTTRN>").next().unwrap_or(block);
+            let value = tag(block, "TRNAMT")?
+                .replace(',', ".")
+                .parse::<f32>()
+                .ok()?;
+            if value <= 0.0 {
+                return None;
+            }
+            let posted = tag(block, "DTPOSTED")?;
+            let date = chrono::NaiveDate::parse_from_str(posted.get(..8)?, "%Y%m%d").ok()?;
+            let description = [tag(block, "NAME"), tag(block, "MEMO")]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" - ");
+            Some(StatementLine {
+                external_id: tag(block, "FITID")?.to_string(),
+                date,
+                value,
+                description,
+                nosso_numero: None,
+            })
+        })
+        .collect()
+}
+
+/// the value of a tag, which in SGML runs until the end of the line or the
+/// next tag
+fn tag<'a>(block: &'a str, name: &str) -> Option<&'a str> {
+    let start = block.find(&format!("<{name}>"))? + name.len() + 2;
+    let value = block[start..]
+        .split(['<', '\n', '\r'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    (!value.is_empty()).then_some(value)
+}
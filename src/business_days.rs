@@ -0,0 +1,164 @@
+//! Business days, on which instalments can be paid.
+//!
+//! An instalment that falls due on a weekend or a holiday is payable on the
+//! next business day without being late. The national holidays are worked
+//! out for each year, the ones that move with Easter included, and the local
+//! holidays of the firm's city and state come from the `business_days`
+//! section of the settings.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// how far ahead the next business day is looked for
+const MAX_DAYS_AHEAD: usize = 366;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+    /// a holiday of the whole country rather than a local one
+    pub national: bool,
+}
+
+/// Easter Sunday of a year of the Gregorian calendar
+#[must_use]
+pub fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, u32::try_from(month).ok()?, u32::try_from(day).ok()?)
+}
+
+/// The national holidays of a year, in the order they happen, with Carnival
+/// and Corpus Christi, on which banks do not open either
+#[must_use]
+pub fn national_holidays(year: i32) -> Vec<Holiday> {
+    let mut holidays = [
+        (1, 1, "Confraternização Universal"),
+        (4, 21, "Tiradentes"),
+        (5, 1, "Dia do Trabalho"),
+        (9, 7, "Independência do Brasil"),
+        (10, 12, "Nossa Senhora Aparecida"),
+        (11, 2, "Finados"),
+        (11, 15, "Proclamação da República"),
+        (11, 20, "Dia Nacional de Zumbi e da Consciência Negra"),
+        (12, 25, "Natal"),
+    ]
+    .into_iter()
+    // Consciência Negra is a national holiday since 2024
+    .filter(|(month, day, _)| (*month, *day) != (11, 20) || year >= 2024)
+    .filter_map(|(month, day, name)| {
+        NaiveDate::from_ymd_opt(year, month, day).map(|date| (date, name))
+    })
+    .collect::<Vec<_>>();
+    if let Some(easter) = easter(year) {
+        holidays.extend(
+            [
+                (-48, "Carnaval"),
+                (-47, "Carnaval"),
+                (-2, "Paixão de Cristo"),
+                (60, "Corpus Christi"),
+            ]
+            .into_iter()
+            .map(|(days, name)| (easter + chrono::Duration::days(days), name)),
+        );
+    }
+    holidays.sort_by_key(|(date, _)| *date);
+    holidays
+        .into_iter()
+        .map(|(date, name)| Holiday {
+            date,
+            name: name.to_string(),
+            national: true,
+        })
+        .collect()
+}
+
+/// A holiday of the firm's city or state, on `MM-DD` every year or on a
+/// single `YYYY-MM-DD`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LocalHoliday {
+    pub date: String,
+    pub name: String,
+}
+
+impl LocalHoliday {
+    /// the day of the holiday in the given year, if it has one that year
+    #[must_use]
+    pub fn on(&self, year: i32) -> Option<NaiveDate> {
+        if let Ok(date) = NaiveDate::parse_from_str(&self.date, "%Y-%m-%d") {
+            return (date.year() == year).then_some(date);
+        }
+        let (month, day) = self.date.split_once('-')?;
+        NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+    }
+}
+
+/// The days instalments can be paid on: weekdays that are neither national
+/// nor local holidays
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BusinessCalendar {
+    #[serde(default)]
+    pub holidays: Vec<LocalHoliday>,
+}
+
+impl BusinessCalendar {
+    /// reads the local holidays from the app settings, having only the
+    /// national ones when they are not configured
+    #[must_use]
+    pub fn from_settings(settings: Option<&serde_json::Value>) -> Self {
+        settings
+            .and_then(|settings| settings.get("business_days"))
+            .and_then(|business_days| serde_json::from_value(business_days.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// the national and local holidays of a year, in the order they happen
+    #[must_use]
+    pub fn holidays(&self, year: i32) -> Vec<Holiday> {
+        let mut holidays = national_holidays(year);
+        holidays.extend(self.holidays.iter().filter_map(|holiday| {
+            holiday.on(year).map(|date| Holiday {
+                date,
+                name: holiday.name.clone(),
+                national: false,
+            })
+        }));
+        holidays.sort_by_key(|holiday| (holiday.date, !holiday.national));
+        holidays
+    }
+
+    /// the holiday on a date, the national one when there are both
+    #[must_use]
+    pub fn holiday(&self, date: NaiveDate) -> Option<Holiday> {
+        self.holidays(date.year())
+            .into_iter()
+            .find(|holiday| holiday.date == date)
+    }
+
+    #[must_use]
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && self.holiday(date).is_none()
+    }
+
+    /// the date itself when it is a business day, the first business day
+    /// after it otherwise
+    #[must_use]
+    pub fn next_business_day(&self, date: NaiveDate) -> NaiveDate {
+        date.iter_days()
+            .take(MAX_DAYS_AHEAD)
+            .find(|date| self.is_business_day(*date))
+            .unwrap_or(date)
+    }
+}
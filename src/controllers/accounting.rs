@@ -0,0 +1,62 @@
+use crate::documents::journal::ChartOfAccounts;
+use crate::models::_entities::accounting_periods;
+use crate::models::accounting_periods::ExportJournal;
+use crate::views::accounting_periods::AccountingPeriodView;
+use axum::body::Body;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::http::header;
+use axum::response::Response;
+use loco_rs::prelude::*;
+
+/// Exports the journal of a month as CSV for the accountant, locking the
+/// month against further changes
+///
+/// # Errors
+///
+/// When could not export the journal or DB query error
+#[debug_handler]
+pub async fn export(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<ExportJournal>,
+) -> Result<Response> {
+    let chart = ChartOfAccounts::from_settings(ctx.config.settings.as_ref());
+    let response = accounting_periods::Model::export(&ctx.db, &req_body.month, &chart).await;
+
+    let csv = match response {
+        Ok(csv) => csv,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not export journal",);
+            return format::json(());
+        }
+    };
+
+    let response = format::render()
+        .response()
+        .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"journal-{}.csv\"", req_body.month),
+        )
+        .body(Body::from(csv))?;
+    Ok(response)
+}
+
+/// Gets the months that were exported and are locked
+///
+/// # Errors
+///
+/// When could not find accounting periods or DB query error
+#[debug_handler]
+pub async fn get_periods(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let accounting_periods = accounting_periods::Model::find_all(&ctx.db).await?;
+    format::json(AccountingPeriodView::from_model(accounting_periods))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/accounting")
+        .add("/export", post(export))
+        .add("/periods", get(get_periods))
+}
@@ -0,0 +1,311 @@
+use crate::models::_entities::{attachments, users};
+use crate::models::attachments::{AttachmentCategory, AttachmentRules, AttachmentTarget, Upload};
+use crate::views::attachments::AttachmentView;
+use axum::body::Body;
+use axum::debug_handler;
+use axum::extract::{DefaultBodyLimit, Json, Multipart, Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::Response;
+use bytes::BytesMut;
+use loco_rs::controller::ErrorDetail;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// the largest upload request accepted, above the `limit_payload` of the
+/// server so a file up to the `max_size` of the attachments fits in it
+const UPLOAD_BODY_LIMIT: usize = 25 * 1024 * 1024;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteAttachment {
+    pub pid: Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AttachmentsParams {
+    pub client_pid: Option<Uuid>,
+    pub order_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub expense_pid: Option<Uuid>,
+}
+
+/// The fields of an upload form
+#[derive(Debug, Default)]
+struct UploadForm {
+    params: AttachmentsParams,
+    category: Option<AttachmentCategory>,
+    checksum: Option<String>,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    content: BytesMut,
+}
+
+/// the response to an upload that is not accepted, telling why
+fn invalid(status: StatusCode, reason: String) -> Error {
+    Error::CustomError(
+        status,
+        ErrorDetail::new("invalid_attachment".to_string(), reason),
+    )
+}
+
+fn parse_pid(value: &str) -> Result<Option<Uuid>> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    Uuid::parse_str(value.trim())
+        .map(Some)
+        .map_err(|err| invalid(StatusCode::BAD_REQUEST, err.to_string()))
+}
+
+/// reads the fields of an upload form, the file chunk by chunk so a file
+/// larger than allowed is refused without reading it all
+///
+/// # Errors
+///
+/// When the form is not valid multipart or the file is too large
+async fn read_form(mut multipart: Multipart, max_size: u64) -> Result<UploadForm> {
+    let mut form = UploadForm::default();
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| invalid(StatusCode::BAD_REQUEST, err.to_string()))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" {
+            form.file_name = field.file_name().map(str::to_string);
+            form.content_type = field.content_type().map(str::to_string);
+            while let Some(chunk) = field
+                .chunk()
+                .await
+                .map_err(|err| invalid(StatusCode::BAD_REQUEST, err.to_string()))?
+            {
+                if (form.content.len() + chunk.len()) as u64 > max_size {
+                    return Err(invalid(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        format!("the file is larger than {max_size} bytes"),
+                    ));
+                }
+                form.content.extend_from_slice(&chunk);
+            }
+            continue;
+        }
+        let value = field
+            .text()
+            .await
+            .map_err(|err| invalid(StatusCode::BAD_REQUEST, err.to_string()))?;
+        match name.as_str() {
+            "category" => {
+                form.category = Some(AttachmentCategory::parse(value.trim()).ok_or_else(|| {
+                    invalid(
+                        StatusCode::BAD_REQUEST,
+                        format!("unknown attachment category {value}"),
+                    )
+                })?);
+            }
+            "checksum" => form.checksum = Some(value),
+            "client_pid" => form.params.client_pid = parse_pid(&value)?,
+            "order_pid" => form.params.order_pid = parse_pid(&value)?,
+            "payment_pid" => form.params.payment_pid = parse_pid(&value)?,
+            "expense_pid" => form.params.expense_pid = parse_pid(&value)?,
+            _ => {}
+        }
+    }
+    Ok(form)
+}
+
+/// the `Content-Disposition` of a download, with a plain ASCII name for old
+/// clients and the full name encoded as RFC 5987 asks
+fn content_disposition(file_name: &str) -> String {
+    let ascii_name = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let encoded_name = file_name
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect::<String>();
+    format!("attachment; filename=\"{ascii_name}\"; filename*=UTF-8''{encoded_name}")
+}
+
+/// the attachments with who uploaded them and what they are kept with
+async fn attachments_response(
+    ctx: &AppContext,
+    attachments: Vec<attachments::Model>,
+) -> Result<Response> {
+    let links = attachments::Model::find_links(&ctx.db, &attachments).await?;
+    format::json(AttachmentView::from_model(attachments, &links))
+}
+
+/// Uploads a file to keep with a client, an order, a payment or an expense,
+/// given as multipart form with the `file`, its `category`, the pid of
+/// exactly one record and optionally the SHA-256 `checksum` of the file
+///
+/// # Errors
+///
+/// When the form or the file is not accepted, with the reason, could not
+/// find the user, could not write to the storage or DB query error
+#[debug_handler]
+pub async fn upload(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    multipart: Multipart,
+) -> Result<Response> {
+    let rules = AttachmentRules::from_settings(ctx.config.settings.as_ref());
+    let form = read_form(multipart, rules.max_size).await?;
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let target = match AttachmentTarget::find(
+        &ctx.db,
+        form.params.client_pid,
+        form.params.order_pid,
+        form.params.payment_pid,
+        form.params.expense_pid,
+    )
+    .await
+    {
+        Ok(target) => target,
+        Err(err) => {
+            tracing::info!(
+                message = err.to_string(),
+                "could not find what to attach to",
+            );
+            return format::json(());
+        }
+    };
+    let upload = Upload {
+        category: form.category.unwrap_or(AttachmentCategory::Other),
+        file_name: form.file_name.unwrap_or_default(),
+        content_type: form
+            .content_type
+            .unwrap_or_else(|| "application/octet-stream".to_string()),
+        content: form.content.freeze(),
+        checksum: form.checksum,
+    };
+    let response =
+        attachments::Model::create(&ctx.db, &ctx.storage, &rules, Some(user.id), target, upload)
+            .await;
+
+    let attachment = match response {
+        Ok(attachment) => attachment,
+        Err(ModelError::ModelValidation { errors }) => {
+            return Err(invalid(
+                StatusCode::BAD_REQUEST,
+                errors.message.unwrap_or(errors.code),
+            ));
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    attachments_response(&ctx, vec![attachment]).await
+}
+
+/// Gets the attachments of a client, an order, a payment or an expense, the
+/// latest first
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn get_all(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<AttachmentsParams>,
+) -> Result<Response> {
+    let target = match AttachmentTarget::find(
+        &ctx.db,
+        params.client_pid,
+        params.order_pid,
+        params.payment_pid,
+        params.expense_pid,
+    )
+    .await
+    {
+        Ok(target) => target,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find attachments",);
+            return format::json(());
+        }
+    };
+    let attachments = attachments::Model::find_by_target(&ctx.db, target).await?;
+    attachments_response(&ctx, attachments).await
+}
+
+/// Downloads the file of an attachment, streamed from the storage
+///
+/// # Errors
+///
+/// When could not find the attachment, could not read its file or DB query
+/// error
+#[debug_handler]
+pub async fn download(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    let attachment = match attachments::Model::find_by_pid(&ctx.db, pid).await {
+        Ok(attachment) => attachment,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find attachment",);
+            return format::json(());
+        }
+    };
+    let file = attachment.open(&ctx.storage).await?;
+
+    let response = format::render()
+        .response()
+        .header(header::CONTENT_TYPE, &attachment.content_type)
+        .header(header::CONTENT_LENGTH, attachment.size)
+        .header(
+            header::CONTENT_DISPOSITION,
+            content_disposition(&attachment.file_name),
+        )
+        .header(header::ETAG, format!("\"{}\"", attachment.checksum))
+        .body(Body::from_stream(file.into_stream()))?;
+    Ok(response)
+}
+
+/// Deletes an attachment with its file
+///
+/// # Errors
+///
+/// When could not find attachment by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteAttachment>,
+) -> Result<Response> {
+    let response = attachments::Model::delete(&ctx.db, &ctx.storage, req_body.pid).await;
+
+    let attachment = match response {
+        Ok(attachment) => attachment,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete attachment",);
+            return format::json(());
+        }
+    };
+
+    attachments_response(&ctx, vec![attachment]).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/attachments")
+        .add(
+            "/upload",
+            post(upload).layer(DefaultBodyLimit::max(UPLOAD_BODY_LIMIT)),
+        )
+        .add("/all", get(get_all))
+        .add("/delete", delete(del))
+        .add("/:pid/download", get(download))
+}
@@ -0,0 +1,186 @@
+use crate::models::_entities::{bank_transactions, orders, payments};
+use crate::models::bank_transactions::ImportBankStatement;
+use crate::views::bank_transactions::BankTransactionView;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfirmBankTransaction {
+    pub pid: Uuid,
+    pub payment_pid: Option<Uuid>,
+    /// settles the instalment even when the credit is not for its value
+    #[serde(default)]
+    pub accept_difference: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReviewBankTransaction {
+    pub pid: Uuid,
+}
+
+/// Responds with the given bank transactions and the instalments they are
+/// matched to
+///
+/// # Errors
+///
+/// When DB query error
+async fn bank_transactions_response(
+    ctx: &AppContext,
+    bank_transactions: Vec<bank_transactions::Model>,
+) -> Result<Response> {
+    let payments = payments::Entity::find().all(&ctx.db).await?;
+    let orders = orders::Entity::find().all(&ctx.db).await?;
+    format::json(BankTransactionView::from_model(
+        bank_transactions,
+        &payments,
+        &orders,
+    ))
+}
+
+/// Responds with the bank transactions waiting for review
+///
+/// # Errors
+///
+/// When could not find bank transactions or DB query error
+async fn review_queue_response(ctx: &AppContext) -> Result<Response> {
+    let bank_transactions = bank_transactions::Model::find_review_queue(&ctx.db).await?;
+    bank_transactions_response(ctx, bank_transactions).await
+}
+
+/// Imports an OFX statement or a CNAB return file, responding with the
+/// transactions it added
+///
+/// # Errors
+///
+/// When could not import the file or DB query error
+#[debug_handler]
+pub async fn import(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<ImportBankStatement>,
+) -> Result<Response> {
+    let response = bank_transactions::Model::import(&ctx.db, &req_body.content).await;
+
+    let imported = match response {
+        Ok(imported) => imported,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not import bank statement",);
+            return format::json(());
+        }
+    };
+
+    bank_transactions_response(&ctx, imported).await
+}
+
+/// Gets all bank transactions
+///
+/// # Errors
+///
+/// When could not find bank transactions or DB query error
+#[debug_handler]
+pub async fn get_all(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let bank_transactions = bank_transactions::Model::find_all(&ctx.db).await?;
+    bank_transactions_response(&ctx, bank_transactions).await
+}
+
+/// Gets the bank transactions waiting for review
+///
+/// # Errors
+///
+/// When could not find bank transactions or DB query error
+#[debug_handler]
+pub async fn get_review(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    review_queue_response(&ctx).await
+}
+
+/// Confirms a bank transaction, settling the instalment it pays
+///
+/// # Errors
+///
+/// When could not confirm the bank transaction or DB query error
+#[debug_handler]
+pub async fn confirm(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<ConfirmBankTransaction>,
+) -> Result<Response> {
+    let response = bank_transactions::Model::confirm(
+        &ctx.db,
+        req_body.pid,
+        req_body.payment_pid,
+        req_body.accept_difference,
+    )
+    .await;
+
+    if let Err(err) = response {
+        tracing::info!(
+            message = err.to_string(),
+            "could not confirm bank transaction",
+        );
+        return format::json(());
+    }
+
+    review_queue_response(&ctx).await
+}
+
+/// Rejects the instalment proposed for a bank transaction
+///
+/// # Errors
+///
+/// When could not reject the proposal or DB query error
+#[debug_handler]
+pub async fn reject(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<ReviewBankTransaction>,
+) -> Result<Response> {
+    let response = bank_transactions::Model::reject(&ctx.db, req_body.pid).await;
+
+    if let Err(err) = response {
+        tracing::info!(
+            message = err.to_string(),
+            "could not reject bank transaction",
+        );
+        return format::json(());
+    }
+
+    review_queue_response(&ctx).await
+}
+
+/// Takes a bank transaction that pays no instalment out of the review queue
+///
+/// # Errors
+///
+/// When could not ignore the bank transaction or DB query error
+#[debug_handler]
+pub async fn ignore(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<ReviewBankTransaction>,
+) -> Result<Response> {
+    let response = bank_transactions::Model::ignore(&ctx.db, req_body.pid).await;
+
+    if let Err(err) = response {
+        tracing::info!(
+            message = err.to_string(),
+            "could not ignore bank transaction",
+        );
+        return format::json(());
+    }
+
+    review_queue_response(&ctx).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/bank_transactions")
+        .add("/import", post(import))
+        .add("/all", get(get_all))
+        .add("/review", get(get_review))
+        .add("/confirm", post(confirm))
+        .add("/reject", post(reject))
+        .add("/ignore", post(ignore))
+}
@@ -0,0 +1,235 @@
+use crate::business_days::BusinessCalendar;
+use crate::controllers::orders as OrdersController;
+use crate::models::_entities::{billing_plan_prices, billing_plans};
+use crate::models::billing_plans::{ChangeBillingPlanPrice, CreateNewBillingPlan, EditBillingPlan};
+use crate::views::billing_plans as BillingPlansView;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateBillingPlan {
+    pub pid: Uuid,
+    pub day_of_month: i32,
+    pub end_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BillingPlanPid {
+    pub pid: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CancelBillingPlan {
+    pub pid: Uuid,
+    pub cancelled_at: chrono::NaiveDate,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChangePrice {
+    pub pid: Uuid,
+    pub value: f32,
+    pub effective_date: chrono::NaiveDate,
+}
+
+/// Creates a new billing plan
+///
+/// # Errors
+///
+/// When could not create billing plan or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewBillingPlan>,
+) -> Result<Response> {
+    let response = billing_plans::Model::create(&ctx.db, req_body).await;
+
+    let billing_plan = match response {
+        Ok(billing_plan) => billing_plan,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create billing plan",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, billing_plan.order_id).await
+}
+
+/// Gets all billing plans
+///
+/// # Errors
+///
+/// When could not find billing plans or DB query error
+#[debug_handler]
+pub async fn get_all(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let billing_plans = billing_plans::Model::find_all(&ctx.db).await;
+
+    let billing_plans = match billing_plans {
+        Ok(billing_plans) => billing_plans,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find billing plans",);
+            return format::json(());
+        }
+    };
+
+    let prices = billing_plan_prices::Model::find_all(&ctx.db).await?;
+    format::json(BillingPlansView::BillingPlanView::from_model(
+        billing_plans,
+        &prices,
+    ))
+}
+
+/// Updates the day of month and end date of a billing plan
+///
+/// # Errors
+///
+/// When could not find billing plan by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateBillingPlan>,
+) -> Result<Response> {
+    let update_billing_plan_params = EditBillingPlan {
+        day_of_month: req_body.day_of_month,
+        end_date: req_body.end_date,
+    };
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let response =
+        billing_plans::Model::update(&ctx.db, req_body.pid, update_billing_plan_params, &calendar)
+            .await;
+
+    let billing_plan = match response {
+        Ok(billing_plan) => billing_plan,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update billing plan",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, billing_plan.order_id).await
+}
+
+/// Pauses a billing plan from today
+///
+/// # Errors
+///
+/// When could not find billing plan by the given pid or DB query error
+#[debug_handler]
+pub async fn pause(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<BillingPlanPid>,
+) -> Result<Response> {
+    let today = chrono::Utc::now().date_naive();
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let response = billing_plans::Model::pause(&ctx.db, req_body.pid, today, &calendar).await;
+
+    let billing_plan = match response {
+        Ok(billing_plan) => billing_plan,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not pause billing plan",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, billing_plan.order_id).await
+}
+
+/// Resumes a paused billing plan from today
+///
+/// # Errors
+///
+/// When could not find billing plan by the given pid or DB query error
+#[debug_handler]
+pub async fn resume(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<BillingPlanPid>,
+) -> Result<Response> {
+    let today = chrono::Utc::now().date_naive();
+    let response = billing_plans::Model::resume(&ctx.db, req_body.pid, today).await;
+
+    let billing_plan = match response {
+        Ok(billing_plan) => billing_plan,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not resume billing plan",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, billing_plan.order_id).await
+}
+
+/// Cancels a billing plan
+///
+/// # Errors
+///
+/// When could not find billing plan by the given pid or DB query error
+#[debug_handler]
+pub async fn cancel(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CancelBillingPlan>,
+) -> Result<Response> {
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let response =
+        billing_plans::Model::cancel(&ctx.db, req_body.pid, req_body.cancelled_at, &calendar).await;
+
+    let billing_plan = match response {
+        Ok(billing_plan) => billing_plan,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not cancel billing plan",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, billing_plan.order_id).await
+}
+
+/// Changes the amount of a billing plan from an effective date
+///
+/// # Errors
+///
+/// When could not find billing plan by the given pid or DB query error
+#[debug_handler]
+pub async fn change_price(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<ChangePrice>,
+) -> Result<Response> {
+    let change_price_params = ChangeBillingPlanPrice {
+        value: req_body.value,
+        effective_date: req_body.effective_date,
+    };
+    let response =
+        billing_plans::Model::change_price(&ctx.db, req_body.pid, change_price_params).await;
+
+    let billing_plan = match response {
+        Ok(billing_plan) => billing_plan,
+        Err(err) => {
+            tracing::info!(
+                message = err.to_string(),
+                "could not change billing plan price",
+            );
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, billing_plan.order_id).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/billing_plans")
+        .add("/create", post(create_new))
+        .add("/all", get(get_all))
+        .add("/edit", put(update))
+        .add("/pause", post(pause))
+        .add("/resume", post(resume))
+        .add("/cancel", post(cancel))
+        .add("/change_price", post(change_price))
+}
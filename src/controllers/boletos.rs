@@ -0,0 +1,81 @@
+use crate::documents::boleto::BoletoSettings;
+use crate::models::_entities::payments;
+use crate::views::boletos::BoletoView;
+use axum::body::Body;
+use axum::debug_handler;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::Response;
+use loco_rs::prelude::*;
+
+/// Issues the boleto of an open instalment, assigning its "nosso número"
+/// the first time
+///
+/// # Errors
+///
+/// When DB query error
+async fn issue_boleto(ctx: &AppContext, pid: Uuid) -> Result<Option<BoletoView>> {
+    let Some(settings) = BoletoSettings::from_settings(ctx.config.settings.as_ref()) else {
+        tracing::info!("could not find the bank agreement for boletos in the settings");
+        return Ok(None);
+    };
+    let payment = match payments::Model::issue_nosso_numero(&ctx.db, pid, &settings).await {
+        Ok(payment) => payment,
+        Err(err) => {
+            tracing::info!(
+                message = err.to_string(),
+                payment_pid = pid.to_string(),
+                "could not issue boleto",
+            );
+            return Ok(None);
+        }
+    };
+    Ok(BoletoView::from(&payment, &settings))
+}
+
+/// Returns the barcode and digitable line of the boleto of an open
+/// instalment, along with the barcode as SVG
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn boleto(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    match issue_boleto(&ctx, pid).await? {
+        Some(boleto) => format::json(boleto),
+        None => format::json(()),
+    }
+}
+
+/// Returns the ITF barcode of the boleto of an open instalment as an SVG
+/// image
+///
+/// # Errors
+///
+/// When DB query error or the response could not be built
+#[debug_handler]
+pub async fn barcode(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    let Some(boleto) = issue_boleto(&ctx, pid).await? else {
+        return format::json(());
+    };
+    let response = format::render()
+        .response()
+        .header(header::CONTENT_TYPE, "image/svg+xml")
+        .body(Body::from(boleto.barcode_image))?;
+    Ok(response)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/boletos")
+        .add("/:pid", get(boleto))
+        .add("/:pid/barcode", get(barcode))
+}
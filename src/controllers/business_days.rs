@@ -0,0 +1,36 @@
+use crate::business_days::BusinessCalendar;
+use axum::debug_handler;
+use axum::extract::{Query, State};
+use axum::response::Response;
+use chrono::Datelike;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct HolidaysParams {
+    pub year: Option<i32>,
+}
+
+/// Gets the national and local holidays of the current year, or of `year`
+///
+/// # Errors
+///
+/// When could not render the holidays
+#[debug_handler]
+pub async fn holidays(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<HolidaysParams>,
+) -> Result<Response> {
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let year = params
+        .year
+        .unwrap_or_else(|| chrono::Utc::now().date_naive().year());
+    format::json(calendar.holidays(year))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/business_days")
+        .add("/holidays", get(holidays))
+}
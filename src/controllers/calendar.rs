@@ -0,0 +1,386 @@
+use crate::business_days::BusinessCalendar;
+use crate::documents::ics;
+use crate::models::_entities::{calendar_events, calendar_feeds, users};
+use crate::models::calendar_events::{firm_offset, CreateNewEvent, EventKind};
+use crate::models::calendar_feeds::CreateNewFeed;
+use crate::views::calendar_events::{CalendarEventView, CalendarFeedView, UpcomingItem};
+use axum::body::Body;
+use axum::debug_handler;
+use axum::extract::{Json, Path, Query, State};
+use axum::http::header;
+use axum::response::Response;
+use loco_rs::prelude::*;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+
+/// how many days back a feed starts, so recent events stay in calendars
+const FEED_DAYS_BACK: u64 = 90;
+/// how many days ahead the agenda goes when no `until` is given
+const UPCOMING_DAYS: u64 = 30;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateEvent {
+    pub pid: Uuid,
+    pub kind: EventKind,
+    pub title: String,
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: Option<DateTimeWithTimeZone>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub completed: bool,
+    pub order_pid: Option<Uuid>,
+    pub lawsuit_pid: Option<Uuid>,
+    pub user_pid: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CompleteEvent {
+    pub pid: Uuid,
+    #[serde(default = "completed")]
+    pub completed: bool,
+}
+
+const fn completed() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteEvent {
+    pub pid: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteFeed {
+    pub pid: Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UpcomingParams {
+    pub from: Option<chrono::NaiveDate>,
+    pub until: Option<chrono::NaiveDate>,
+    /// only the events of this user
+    pub user_pid: Option<Uuid>,
+    /// only the events of the signed in user
+    #[serde(default)]
+    pub mine: bool,
+    /// with the open instalments falling due
+    #[serde(default)]
+    pub instalments: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct FeedParams {
+    #[serde(default)]
+    pub instalments: bool,
+}
+
+/// the event with what it is tied to
+async fn event_response(ctx: &AppContext, event: calendar_events::Model) -> Result<Response> {
+    let events = vec![event];
+    let (orders, lawsuits, users) = calendar_events::Model::find_links(&ctx.db, &events).await?;
+    format::json(CalendarEventView::from_model(
+        events, &orders, &lawsuits, &users,
+    ))
+}
+
+/// Creates a new event
+///
+/// # Errors
+///
+/// When could not create event or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewEvent>,
+) -> Result<Response> {
+    let response = calendar_events::Model::create(&ctx.db, req_body).await;
+
+    let event = match response {
+        Ok(event) => event,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create event",);
+            return format::json(());
+        }
+    };
+
+    event_response(&ctx, event).await
+}
+
+/// Updates an event
+///
+/// # Errors
+///
+/// When could not find event by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateEvent>,
+) -> Result<Response> {
+    let update_event_params = CreateNewEvent {
+        kind: req_body.kind,
+        title: req_body.title.clone(),
+        starts_at: req_body.starts_at,
+        ends_at: req_body.ends_at,
+        location: req_body.location.clone(),
+        description: req_body.description.clone(),
+        completed: req_body.completed,
+        order_pid: req_body.order_pid,
+        lawsuit_pid: req_body.lawsuit_pid,
+        user_pid: req_body.user_pid,
+    };
+    let response = calendar_events::Model::update(&ctx.db, req_body.pid, update_event_params).await;
+
+    let event = match response {
+        Ok(event) => event,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update event",);
+            return format::json(());
+        }
+    };
+
+    event_response(&ctx, event).await
+}
+
+/// Marks an event done, or not done with `completed` false
+///
+/// # Errors
+///
+/// When could not find event by the given pid or DB query error
+#[debug_handler]
+pub async fn complete(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CompleteEvent>,
+) -> Result<Response> {
+    let response =
+        calendar_events::Model::complete(&ctx.db, req_body.pid, req_body.completed).await;
+
+    let event = match response {
+        Ok(event) => event,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not complete event",);
+            return format::json(());
+        }
+    };
+
+    event_response(&ctx, event).await
+}
+
+/// Deletes an event
+///
+/// # Errors
+///
+/// When could not find event by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteEvent>,
+) -> Result<Response> {
+    let response = calendar_events::Model::delete(&ctx.db, req_body.pid).await;
+
+    let event = match response {
+        Ok(event) => event,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete event",);
+            return format::json(());
+        }
+    };
+
+    event_response(&ctx, event).await
+}
+
+/// Gets the agenda from today, or `from`, to thirty days ahead, or `until`,
+/// of everyone or of a single user, with the instalments falling due when
+/// `instalments` is set
+///
+/// # Errors
+///
+/// When could not find the user or DB query error
+#[debug_handler]
+pub async fn upcoming(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<UpcomingParams>,
+) -> Result<Response> {
+    let offset = firm_offset(ctx.config.settings.as_ref());
+    let from = params
+        .from
+        .unwrap_or_else(|| chrono::Utc::now().with_timezone(&offset).date_naive());
+    let until = params
+        .until
+        .or_else(|| from.checked_add_days(chrono::Days::new(UPCOMING_DAYS)));
+    let user_id = if params.mine {
+        Some(
+            users::Model::find_by_pid(&ctx.db, &auth.claims.pid)
+                .await?
+                .id,
+        )
+    } else {
+        match params.user_pid {
+            Some(pid) => Some(
+                users::Model::find_by_pid(&ctx.db, &pid.to_string())
+                    .await?
+                    .id,
+            ),
+            None => None,
+        }
+    };
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let items = UpcomingItem::find(
+        &ctx.db,
+        from,
+        until,
+        user_id,
+        params.instalments,
+        offset,
+        &calendar,
+    )
+    .await?;
+    format::json(items)
+}
+
+/// Gets the calendar feeds of the signed in user
+///
+/// # Errors
+///
+/// When could not find the user or DB query error
+#[debug_handler]
+pub async fn get_feeds(auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let feeds = calendar_feeds::Model::find_by_user_id(&ctx.db, user.id).await?;
+    let server_url = ctx.config.server.full_url();
+    format::json(
+        feeds
+            .iter()
+            .map(|feed| CalendarFeedView::new(feed, &server_url))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Creates a calendar feed of the signed in user's events, or of the whole
+/// firm's with `firm`, returning the url to subscribe to
+///
+/// # Errors
+///
+/// When could not create feed or DB query error
+#[debug_handler]
+pub async fn create_feed(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewFeed>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let response = calendar_feeds::Model::create(&ctx.db, user.id, req_body).await;
+
+    let feed = match response {
+        Ok(feed) => feed,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create calendar feed",);
+            return format::json(());
+        }
+    };
+
+    format::json(CalendarFeedView::new(&feed, &ctx.config.server.full_url()))
+}
+
+/// Revokes a calendar feed of the signed in user
+///
+/// # Errors
+///
+/// When could not find feed by the given pid or DB query error
+#[debug_handler]
+pub async fn delete_feed(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteFeed>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let response = calendar_feeds::Model::delete(&ctx.db, req_body.pid, user.id).await;
+
+    let feed = match response {
+        Ok(feed) => feed,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete calendar feed",);
+            return format::json(());
+        }
+    };
+
+    format::json(CalendarFeedView::new(&feed, &ctx.config.server.full_url()))
+}
+
+/// Gets the iCalendar feed with the given token, which calendar apps fetch
+/// without signing in. It has the events from ninety days ago on, with the
+/// open instalments when `instalments` is set.
+///
+/// # Errors
+///
+/// When the token is not of a feed or DB query error
+#[debug_handler]
+pub async fn feed(
+    State(ctx): State<AppContext>,
+    Path(token): Path<String>,
+    Query(params): Query<FeedParams>,
+) -> Result<Response> {
+    let Ok(feed) = calendar_feeds::Model::find_by_token(&ctx.db, &token).await else {
+        return not_found();
+    };
+    let user = users::Entity::find_by_id(feed.user_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    let offset = firm_offset(ctx.config.settings.as_ref());
+    let now = chrono::Utc::now();
+    let today = now.with_timezone(&offset).date_naive();
+    let from = today
+        .checked_sub_days(chrono::Days::new(FEED_DAYS_BACK))
+        .unwrap_or(today);
+    let user_id = (!feed.firm).then_some(feed.user_id);
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let items = UpcomingItem::find(
+        &ctx.db,
+        from,
+        None,
+        user_id,
+        params.instalments,
+        offset,
+        &calendar,
+    )
+    .await?;
+    let name = if feed.firm {
+        "Johandler".to_string()
+    } else {
+        format!("Johandler - {}", user.name)
+    };
+    let events = items
+        .iter()
+        .map(|item| item.to_ics(now))
+        .collect::<Vec<_>>();
+
+    let response = format::render()
+        .response()
+        .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            "inline; filename=\"calendar.ics\"",
+        )
+        .body(Body::from(ics::calendar(&name, &events)))?;
+    Ok(response)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/calendar")
+        .add("/create", post(create_new))
+        .add("/edit", put(update))
+        .add("/complete", put(complete))
+        .add("/delete", delete(del))
+        .add("/upcoming", get(upcoming))
+        .add("/feeds", get(get_feeds))
+        .add("/feeds", post(create_feed))
+        .add("/feeds", delete(delete_feed))
+        .add("/feed/:token", get(feed))
+}
@@ -1,8 +1,16 @@
-use crate::models::_entities::clients;
+use crate::documents::{self, Letterhead};
+use crate::models::_entities::{client_merges, clients, users};
+use crate::models::client_addresses::NewAddress;
+use crate::models::client_contacts::NewContact;
+use crate::models::client_merges::{MergeClients, DEFAULT_MIN_SCORE};
 use crate::models::clients::CreateNewClient;
-use crate::views::clients::ClientViewResponse;
+use crate::views::client_merges::ClientMergeView;
+use crate::views::clients::{ClientStatementView, ClientViewResponse};
+use axum::body::Body;
 use axum::debug_handler;
-use axum::extract::State;
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::Response;
 use axum::Json;
 use loco_rs::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -12,10 +20,20 @@ pub struct UpdateClient {
     pub pid: Uuid,
     pub name: String,
     pub contact: String,
-    pub phone: String,
-    pub phone2: Option<String>,
-    pub email: String,
     pub partner_pid: Option<Uuid>,
+    pub document: Option<String>,
+    pub contacts: Option<Vec<NewContact>>,
+    pub addresses: Option<Vec<NewAddress>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DuplicatesParams {
+    pub min_score: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SearchParams {
+    pub document: Option<String>,
 }
 
 /// Gets all clients
@@ -39,6 +57,31 @@ pub async fn get_all(
     Ok(Json(client_views))
 }
 
+/// Finds the clients whose CPF or CNPJ holds the given `document` digits
+///
+/// # Errors
+///
+/// When could not find clients or DB query error
+#[debug_handler]
+pub async fn search(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<Vec<ClientViewResponse>>> {
+    let clients = match params.document {
+        Some(document) => clients::Model::find_by_document(&ctx.db, &document).await?,
+        None => vec![],
+    };
+    let mut client_views = Vec::new();
+
+    for client in clients {
+        let client_view = ClientViewResponse::from_model(&ctx.db, client).await?;
+        client_views.push(client_view);
+    }
+
+    Ok(Json(client_views))
+}
+
 /// Creates a new client
 ///
 /// # Errors
@@ -69,20 +112,124 @@ pub async fn update(
     let to_update_client = CreateNewClient {
         name: params.name.clone(),
         contact: params.contact.clone(),
-        phone: params.phone.clone(),
-        phone2: params.phone2.clone(),
-        email: params.email.clone(),
         partner_pid: params.partner_pid,
+        document: params.document.clone(),
+        contacts: params.contacts.clone(),
+        addresses: params.addresses.clone(),
     };
     let client_updated = clients::Model::update(&ctx.db, params.pid, to_update_client).await?;
     let client_view = ClientViewResponse::from_model(&ctx.db, client_updated).await?;
     Ok(Json(client_view))
 }
 
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StatementParams {
+    pub as_of: Option<chrono::NaiveDate>,
+    pub format: Option<String>,
+}
+
+/// Gets the account statement of a client as of a date, today unless
+/// `as_of` is given, as JSON or, with `format=pdf`, as a PDF to download
+///
+/// # Errors
+///
+/// When could not find the client or DB query error
+#[debug_handler]
+pub async fn statement(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+    Query(params): Query<StatementParams>,
+) -> Result<Response> {
+    let client = match clients::Model::find_by_pid(&ctx.db, pid).await {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find client",);
+            return format::json(());
+        }
+    };
+    let today = chrono::Utc::now().date_naive();
+    let as_of = params.as_of.unwrap_or(today);
+    let statement = ClientStatementView::from_model(&ctx.db, &client, as_of).await?;
+
+    if params.format.as_deref() == Some("pdf") {
+        let letterhead = Letterhead::from_settings(ctx.config.settings.as_ref());
+        let content = documents::client_statement(&letterhead, &statement, today);
+        let response = format::render()
+            .response()
+            .header(header::CONTENT_TYPE, "application/pdf")
+            .header(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"statement-{pid}-{as_of}.pdf\""),
+            )
+            .body(Body::from(content))?;
+        return Ok(response);
+    }
+    format::json(statement)
+}
+
+/// Gets the pairs of clients that are likely duplicates, scored by their
+/// names, emails, phones and documents
+///
+/// # Errors
+///
+/// When could not find clients or DB query error
+#[debug_handler]
+pub async fn duplicates(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<DuplicatesParams>,
+) -> Result<Response> {
+    let min_score = params.min_score.unwrap_or(DEFAULT_MIN_SCORE);
+    let candidates = client_merges::Model::find_duplicates(&ctx.db, min_score).await?;
+    format::json(candidates)
+}
+
+/// Merges a duplicate client into the one to keep, moving its orders and
+/// removing it
+///
+/// # Errors
+///
+/// When could not merge the clients or DB query error
+#[debug_handler]
+pub async fn merge(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(params): Json<MergeClients>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let client_merge = match client_merges::Model::merge(&ctx.db, &params, Some(user.id)).await {
+        Ok(client_merge) => client_merge,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not merge clients",);
+            return format::json(());
+        }
+    };
+    let clients = clients::Model::find_all(&ctx.db).await?;
+    format::json(ClientMergeView::from(client_merge, &clients))
+}
+
+/// Gets the audit trail of the merged clients
+///
+/// # Errors
+///
+/// When could not find merges or DB query error
+#[debug_handler]
+pub async fn get_merges(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let client_merges = client_merges::Model::find_all(&ctx.db).await?;
+    let clients = clients::Model::find_all(&ctx.db).await?;
+    format::json(ClientMergeView::from_model(client_merges, &clients))
+}
+
 pub fn routes() -> Routes {
     Routes::new()
         .prefix("/api/clients")
         .add("/all", get(get_all))
+        .add("/search", get(search))
+        .add("/duplicates", get(duplicates))
+        .add("/merge", post(merge))
+        .add("/merges", get(get_merges))
         .add("/create", post(create_new))
         .add("/edit", put(update))
+        .add("/:pid/statement", get(statement))
 }
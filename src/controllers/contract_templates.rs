@@ -0,0 +1,193 @@
+use crate::documents::contract::{DEFAULT_TEMPLATE, PLACEHOLDERS, TABLES};
+use crate::models::_entities::{contract_templates, processes};
+use crate::models::contract_templates::{CreateNewContractTemplate, UpdateContractTemplate};
+use crate::views::contract_templates::ContractTemplateView;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use loco_rs::controller::ErrorDetail;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditContractTemplate {
+    pub pid: Uuid,
+    pub name: String,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteContractTemplate {
+    pub pid: Uuid,
+}
+
+/// A placeholder or table a template can use, with what it is filled with
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlaceholderView {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlaceholdersView {
+    pub placeholders: Vec<PlaceholderView>,
+    pub tables: Vec<PlaceholderView>,
+    /// the template of the processes that do not have one
+    pub default_template: String,
+}
+
+/// the response to a template that cannot be rendered, telling why, or
+/// the error as it is
+fn template_error(err: ModelError) -> Error {
+    match err {
+        ModelError::ModelValidation { errors } => Error::CustomError(
+            StatusCode::BAD_REQUEST,
+            ErrorDetail::new(errors.code, errors.message.unwrap_or_default()),
+        ),
+        err => err.into(),
+    }
+}
+
+async fn templates_response(
+    ctx: &AppContext,
+    templates: Vec<contract_templates::Model>,
+) -> Result<Response> {
+    let processes = processes::Model::find_all(&ctx.db).await?;
+    format::json(ContractTemplateView::from_model(templates, &processes))
+}
+
+/// Creates the contract template of a process
+///
+/// # Errors
+///
+/// When the template has a placeholder that is not known or not closed,
+/// with what is wrong, or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewContractTemplate>,
+) -> Result<Response> {
+    let response = contract_templates::Model::create(&ctx.db, req_body).await;
+
+    let template = match response {
+        Ok(template) => template,
+        Err(err @ ModelError::ModelValidation { .. }) => return Err(template_error(err)),
+        Err(err) => {
+            tracing::info!(
+                message = err.to_string(),
+                "could not create contract template",
+            );
+            return format::json(());
+        }
+    };
+
+    templates_response(&ctx, vec![template]).await
+}
+
+/// Gets all contract templates
+///
+/// # Errors
+///
+/// When could not find contract templates or DB query error
+#[debug_handler]
+pub async fn get_all(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let templates = contract_templates::Model::find_all(&ctx.db).await?;
+    templates_response(&ctx, templates).await
+}
+
+/// Updates a contract template; the contracts written from it get a new
+/// version the next time they are generated
+///
+/// # Errors
+///
+/// When the template has a placeholder that is not known or not closed,
+/// with what is wrong, or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<EditContractTemplate>,
+) -> Result<Response> {
+    let update_template_params = UpdateContractTemplate {
+        name: req_body.name,
+        body: req_body.body,
+    };
+    let response =
+        contract_templates::Model::update(&ctx.db, req_body.pid, update_template_params).await;
+
+    let template = match response {
+        Ok(template) => template,
+        Err(err @ ModelError::ModelValidation { .. }) => return Err(template_error(err)),
+        Err(err) => {
+            tracing::info!(
+                message = err.to_string(),
+                "could not update contract template",
+            );
+            return format::json(());
+        }
+    };
+
+    templates_response(&ctx, vec![template]).await
+}
+
+/// Deletes a contract template, so its process uses the default one
+///
+/// # Errors
+///
+/// When could not find contract template by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteContractTemplate>,
+) -> Result<Response> {
+    let response = contract_templates::Model::delete(&ctx.db, req_body.pid).await;
+
+    let template = match response {
+        Ok(template) => template,
+        Err(err) => {
+            tracing::info!(
+                message = err.to_string(),
+                "could not delete contract template",
+            );
+            return format::json(());
+        }
+    };
+
+    templates_response(&ctx, vec![template]).await
+}
+
+/// Gets the placeholders and tables a template can use, and the default
+/// template
+///
+/// # Errors
+///
+/// When the response could not be built
+#[debug_handler]
+pub async fn placeholders(_auth: auth::JWT, State(_ctx): State<AppContext>) -> Result<Response> {
+    let view = |list: &[(&str, &str)]| {
+        list.iter()
+            .map(|(name, description)| PlaceholderView {
+                name: (*name).to_string(),
+                description: (*description).to_string(),
+            })
+            .collect()
+    };
+    format::json(PlaceholdersView {
+        placeholders: view(PLACEHOLDERS),
+        tables: view(TABLES),
+        default_template: DEFAULT_TEMPLATE.to_string(),
+    })
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/contract_templates")
+        .add("/create", post(create_new))
+        .add("/all", get(get_all))
+        .add("/edit", put(update))
+        .add("/delete", delete(del))
+        .add("/placeholders", get(placeholders))
+}
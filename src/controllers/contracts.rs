@@ -0,0 +1,125 @@
+use crate::controllers::orders as OrdersController;
+use crate::documents::Letterhead;
+use crate::models::_entities::{contracts, orders, users};
+use crate::views::contracts::ContractView;
+use axum::debug_handler;
+use axum::extract::{Json, Query, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GenerateContract {
+    pub order_pid: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ContractsParams {
+    pub order_pid: Uuid,
+}
+
+/// the contract versions of an order, telling which one is written from what
+/// the order and its template are now
+async fn contracts_response(
+    ctx: &AppContext,
+    letterhead: &Letterhead,
+    order_id: i32,
+    contracts: Vec<contracts::Model>,
+) -> Result<Response> {
+    let links = contracts::Model::find_links(&ctx.db, &contracts).await?;
+    let fingerprint = contracts::Model::current_fingerprint(&ctx.db, letterhead, order_id).await?;
+    format::json(
+        contracts
+            .into_iter()
+            .map(|contract| {
+                let current = contract.fingerprint == fingerprint;
+                ContractView::from(contract, &links, current)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Writes the contract of an order as PDF and DOCX attachments of the order,
+/// as a new version when the order or the template of its process changed
+/// since the latest one, which is given back otherwise
+///
+/// # Errors
+///
+/// When could not find the user, could not write to the storage or DB query
+/// error
+#[debug_handler]
+pub async fn generate(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<GenerateContract>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let letterhead = Letterhead::from_settings(ctx.config.settings.as_ref());
+    let response = contracts::Model::generate(
+        &ctx.db,
+        &ctx.storage,
+        &letterhead,
+        Some(user.id),
+        req_body.order_pid,
+        chrono::Utc::now().date_naive(),
+    )
+    .await;
+
+    let contract = match response {
+        Ok(contract) => contract,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not generate contract",);
+            return format::json(());
+        }
+    };
+
+    let order_id = contract.order_id;
+    contracts_response(&ctx, &letterhead, order_id, vec![contract]).await
+}
+
+/// Gets the contract versions of an order, the latest first, writing a new
+/// one first when the latest no longer matches the order, its client or the
+/// template of its process
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn get_all(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<ContractsParams>,
+) -> Result<Response> {
+    let order = orders::Entity::find()
+        .filter(
+            model::query::condition()
+                .eq(orders::Column::Pid, params.order_pid)
+                .build(),
+        )
+        .one(&ctx.db)
+        .await?;
+    let Some(order) = order else {
+        tracing::info!(
+            order_pid = params.order_pid.to_string(),
+            "could not find order",
+        );
+        return format::json(());
+    };
+    let letterhead = Letterhead::from_settings(ctx.config.settings.as_ref());
+    if let Some(latest) = contracts::Model::latest(&ctx.db, order.id).await? {
+        let fingerprint =
+            contracts::Model::current_fingerprint(&ctx.db, &letterhead, order.id).await?;
+        if latest.fingerprint != fingerprint {
+            OrdersController::refresh_contract(&ctx, None, order.pid).await;
+        }
+    }
+    let contracts = contracts::Model::find_by_order_id(&ctx.db, order.id).await?;
+    contracts_response(&ctx, &letterhead, order.id, contracts).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/contracts")
+        .add("/generate", post(generate))
+        .add("/all", get(get_all))
+}
@@ -0,0 +1,113 @@
+use crate::controllers::orders as OrdersController;
+use crate::models::_entities::credit_notes;
+use crate::models::credit_notes::CreateNewCreditNote;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateCreditNote {
+    pub pid: Uuid,
+    pub order_pid: Uuid,
+    pub order_fee_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub value: f32,
+    pub reason: String,
+    pub credit_date: chrono::NaiveDate,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteCreditNote {
+    pub pid: Uuid,
+}
+
+/// Creates a new credit note
+///
+/// # Errors
+///
+/// When could not create credit note or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewCreditNote>,
+) -> Result<Response> {
+    let response = credit_notes::Model::create(&ctx.db, req_body).await;
+
+    let credit_note = match response {
+        Ok(credit_note) => credit_note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create credit note",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, credit_note.order_id).await
+}
+
+/// Updates a credit note
+///
+/// # Errors
+///
+/// When could not find credit note by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateCreditNote>,
+) -> Result<Response> {
+    let update_credit_note_params = CreateNewCreditNote {
+        order_pid: req_body.order_pid,
+        order_fee_pid: req_body.order_fee_pid,
+        payment_pid: req_body.payment_pid,
+        value: req_body.value,
+        reason: req_body.reason.clone(),
+        credit_date: req_body.credit_date,
+    };
+    let response =
+        credit_notes::Model::update(&ctx.db, req_body.pid, update_credit_note_params).await;
+
+    let credit_note = match response {
+        Ok(credit_note) => credit_note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update credit note",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, credit_note.order_id).await
+}
+
+/// Deletes a credit note
+///
+/// # Errors
+///
+/// When could not find credit note by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteCreditNote>,
+) -> Result<Response> {
+    let response = credit_notes::Model::delete(&ctx.db, req_body.pid).await;
+
+    let credit_note = match response {
+        Ok(credit_note) => credit_note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete credit note",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, credit_note.order_id).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/credit_notes")
+        .add("/create", post(create_new))
+        .add("/edit", put(update))
+        .add("/delete", delete(del))
+}
@@ -0,0 +1,144 @@
+use crate::documents::{self, pix::PixSettings, Letterhead};
+use crate::models::_entities::{documents as issued_documents, orders, payments};
+use crate::models::documents::DocumentKind;
+use axum::body::Body;
+use axum::debug_handler;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::Response;
+use loco_rs::prelude::*;
+
+/// Responds with a PDF file to download
+///
+/// # Errors
+///
+/// When the response could not be built
+fn pdf_response(file_name: &str, content: Vec<u8>) -> Result<Response> {
+    let response = format::render()
+        .response()
+        .header(header::CONTENT_TYPE, "application/pdf")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}.pdf\""),
+        )
+        .body(Body::from(content))?;
+    Ok(response)
+}
+
+/// Downloads the receipt of a settled payment, issuing its number the first
+/// time it is requested
+///
+/// # Errors
+///
+/// When could not find the payment or DB query error
+#[debug_handler]
+pub async fn receipt(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    let payment = payments::Entity::find()
+        .filter(
+            model::query::condition()
+                .eq(payments::Column::Pid, pid)
+                .build(),
+        )
+        .one(&ctx.db)
+        .await?;
+    let payment = match payment {
+        Some(payment) if !payment.open => payment,
+        _ => {
+            tracing::info!(
+                payment_pid = pid.to_string(),
+                "could not find a settled payment for the receipt",
+            );
+            return format::json(());
+        }
+    };
+    let order = orders::Entity::find_by_id(payment.order_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    let order = orders::Model::find_by_pid(&ctx.db, order.pid).await?;
+    let Some(order_payment) = order
+        .payments
+        .iter()
+        .find(|order_payment| order_payment.pid == Some(payment.pid))
+    else {
+        return format::json(());
+    };
+
+    let document = match issued_documents::Model::find_receipt(&ctx.db, payment.id).await? {
+        Some(document) => document,
+        None => {
+            issued_documents::Model::issue(
+                &ctx.db,
+                DocumentKind::Receipt,
+                payment.order_id,
+                Some(payment.id),
+            )
+            .await?
+        }
+    };
+    let letterhead = Letterhead::from_settings(ctx.config.settings.as_ref());
+    let pix = PixSettings::from_settings(ctx.config.settings.as_ref());
+    let content = documents::payment_receipt(
+        &letterhead,
+        pix.as_ref(),
+        &document.code(),
+        &order,
+        order_payment,
+        document.created_at.date_naive(),
+    );
+    pdf_response(&document.code(), content)
+}
+
+/// Downloads a statement of an order, numbered anew every time it is issued
+///
+/// # Errors
+///
+/// When could not find the order or DB query error
+#[debug_handler]
+pub async fn statement(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    let order = match orders::Model::find_by_pid(&ctx.db, pid).await {
+        Ok(order) => order,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find order",);
+            return format::json(());
+        }
+    };
+    let order_id = orders::Entity::find()
+        .filter(
+            model::query::condition()
+                .eq(orders::Column::Pid, pid)
+                .build(),
+        )
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?
+        .id;
+
+    let document =
+        issued_documents::Model::issue(&ctx.db, DocumentKind::Statement, order_id, None).await?;
+    let letterhead = Letterhead::from_settings(ctx.config.settings.as_ref());
+    let pix = PixSettings::from_settings(ctx.config.settings.as_ref());
+    let content = documents::order_statement(
+        &letterhead,
+        pix.as_ref(),
+        &document.code(),
+        &order,
+        document.created_at.date_naive(),
+    );
+    pdf_response(&document.code(), content)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/documents")
+        .add("/receipt/:pid", get(receipt))
+        .add("/statement/:pid", get(statement))
+}
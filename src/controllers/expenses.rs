@@ -0,0 +1,164 @@
+use crate::business_days::BusinessCalendar;
+use crate::controllers::orders as OrdersController;
+use crate::models::_entities::{expenses, payments};
+use crate::models::expenses::{BillExpenses, CreateNewExpense};
+use crate::views::expenses as ExpensesView;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateExpense {
+    pub pid: Uuid,
+    pub order_pid: Uuid,
+    pub category: String,
+    pub value: f32,
+    pub expense_date: chrono::NaiveDate,
+    pub description: Option<String>,
+    pub receipt: Option<String>,
+    pub reimbursable: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteExpense {
+    pub pid: Uuid,
+}
+
+/// Creates a new expense
+///
+/// # Errors
+///
+/// When could not create expense or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewExpense>,
+) -> Result<Response> {
+    let response = expenses::Model::create(&ctx.db, req_body).await;
+
+    let expense = match response {
+        Ok(expense) => expense,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create expense",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, expense.order_id).await
+}
+
+/// Gets all expenses
+///
+/// # Errors
+///
+/// When could not find expenses or DB query error
+#[debug_handler]
+pub async fn get_all(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let expenses = expenses::Model::find_all(&ctx.db).await;
+
+    let expenses = match expenses {
+        Ok(expenses) => expenses,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find expenses",);
+            return format::json(());
+        }
+    };
+
+    let payments = payments::Model::find_all(&ctx.db).await?;
+    format::json(ExpensesView::ExpenseView::from_model(expenses, &payments))
+}
+
+/// Updates an expense
+///
+/// # Errors
+///
+/// When could not find expense by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateExpense>,
+) -> Result<Response> {
+    let update_expense_params = CreateNewExpense {
+        order_pid: req_body.order_pid,
+        category: req_body.category.clone(),
+        value: req_body.value,
+        expense_date: req_body.expense_date,
+        description: req_body.description.clone(),
+        receipt: req_body.receipt.clone(),
+        reimbursable: req_body.reimbursable,
+    };
+    let response = expenses::Model::update(&ctx.db, req_body.pid, update_expense_params).await;
+
+    let expense = match response {
+        Ok(expense) => expense,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update expense",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, expense.order_id).await
+}
+
+/// Deletes an expense
+///
+/// # Errors
+///
+/// When could not find expense by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteExpense>,
+) -> Result<Response> {
+    let response = expenses::Model::delete(&ctx.db, req_body.pid).await;
+
+    let expense = match response {
+        Ok(expense) => expense,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete expense",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, expense.order_id).await
+}
+
+/// Bills reimbursable expenses into an instalment of the order
+///
+/// # Errors
+///
+/// When could not bill the expenses or DB query error
+#[debug_handler]
+pub async fn bill(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<BillExpenses>,
+) -> Result<Response> {
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let response = expenses::Model::bill(&ctx.db, req_body, &calendar).await;
+
+    let payment = match response {
+        Ok(payment) => payment,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not bill expenses",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, payment.order_id).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/expenses")
+        .add("/create", post(create_new))
+        .add("/all", get(get_all))
+        .add("/edit", put(update))
+        .add("/delete", delete(del))
+        .add("/bill", post(bill))
+}
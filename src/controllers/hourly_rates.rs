@@ -0,0 +1,117 @@
+use crate::models::_entities::{hourly_rates, orders, users};
+use crate::models::hourly_rates::CreateNewHourlyRate;
+use crate::views::hourly_rates as HourlyRatesView;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateHourlyRate {
+    pub pid: Uuid,
+    pub value: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteHourlyRate {
+    pub pid: Uuid,
+}
+
+/// Responds with every hourly rate
+///
+/// # Errors
+///
+/// When could not find hourly rates or DB query error
+async fn hourly_rates_response(ctx: &AppContext) -> Result<Response> {
+    let hourly_rates = hourly_rates::Model::find_all(&ctx.db).await?;
+    let users = users::Entity::find().all(&ctx.db).await?;
+    let orders = orders::Entity::find().all(&ctx.db).await?;
+    format::json(HourlyRatesView::HourlyRateView::from_model(
+        hourly_rates,
+        &users,
+        &orders,
+    ))
+}
+
+/// Creates a new hourly rate
+///
+/// # Errors
+///
+/// When could not create hourly rate or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewHourlyRate>,
+) -> Result<Response> {
+    let response = hourly_rates::Model::create(&ctx.db, req_body).await;
+
+    if let Err(err) = response {
+        tracing::info!(message = err.to_string(), "could not create hourly rate",);
+        return format::json(());
+    }
+
+    hourly_rates_response(&ctx).await
+}
+
+/// Gets all hourly rates
+///
+/// # Errors
+///
+/// When could not find hourly rates or DB query error
+#[debug_handler]
+pub async fn get_all(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    hourly_rates_response(&ctx).await
+}
+
+/// Updates the value of an hourly rate
+///
+/// # Errors
+///
+/// When could not find hourly rate by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateHourlyRate>,
+) -> Result<Response> {
+    let response = hourly_rates::Model::update(&ctx.db, req_body.pid, req_body.value).await;
+
+    if let Err(err) = response {
+        tracing::info!(message = err.to_string(), "could not update hourly rate",);
+        return format::json(());
+    }
+
+    hourly_rates_response(&ctx).await
+}
+
+/// Deletes an hourly rate
+///
+/// # Errors
+///
+/// When could not find hourly rate by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteHourlyRate>,
+) -> Result<Response> {
+    let response = hourly_rates::Model::delete(&ctx.db, req_body.pid).await;
+
+    if let Err(err) = response {
+        tracing::info!(message = err.to_string(), "could not delete hourly rate",);
+        return format::json(());
+    }
+
+    hourly_rates_response(&ctx).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/hourly_rates")
+        .add("/create", post(create_new))
+        .add("/all", get(get_all))
+        .add("/edit", put(update))
+        .add("/delete", delete(del))
+}
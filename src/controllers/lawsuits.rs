@@ -0,0 +1,166 @@
+use crate::controllers::orders as OrdersController;
+use crate::models::_entities::lawsuits;
+use crate::models::lawsuits::{CnjNumber, CreateNewLawsuit, LawsuitStatus};
+use crate::views::lawsuits::{CnjView, LawsuitView};
+use axum::debug_handler;
+use axum::extract::{Json, Path, Query, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateLawsuit {
+    pub pid: Uuid,
+    pub order_pid: Uuid,
+    pub cnj_number: String,
+    pub court: Option<String>,
+    pub district: Option<String>,
+    pub opposing_party: Option<String>,
+    pub filing_date: Option<chrono::NaiveDate>,
+    #[serde(default)]
+    pub status: LawsuitStatus,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteLawsuit {
+    pub pid: Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CnjParams {
+    #[serde(default)]
+    pub number: String,
+}
+
+/// Creates the lawsuit of an order
+///
+/// # Errors
+///
+/// When could not create lawsuit or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewLawsuit>,
+) -> Result<Response> {
+    let response = lawsuits::Model::create(&ctx.db, req_body).await;
+
+    let lawsuit = match response {
+        Ok(lawsuit) => lawsuit,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create lawsuit",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, lawsuit.order_id).await
+}
+
+/// Gets a lawsuit by its pid
+///
+/// # Errors
+///
+/// When could not find lawsuit by the given pid or DB query error
+#[debug_handler]
+pub async fn get_one(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    let lawsuit = match lawsuits::Model::find_by_pid(&ctx.db, pid).await {
+        Ok(lawsuit) => lawsuit,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find lawsuit",);
+            return format::json(());
+        }
+    };
+
+    format::json(LawsuitView::from(lawsuit))
+}
+
+/// Decodes a CNJ number, telling the court it belongs to
+///
+/// # Errors
+///
+/// When the number is not a valid CNJ number
+#[debug_handler]
+pub async fn cnj(
+    _auth: auth::JWT,
+    State(_ctx): State<AppContext>,
+    Query(params): Query<CnjParams>,
+) -> Result<Response> {
+    match CnjNumber::parse(&params.number) {
+        Some(cnj) => format::json(CnjView::from(cnj)),
+        None => {
+            tracing::info!(number = params.number, "not a valid CNJ number",);
+            format::json(())
+        }
+    }
+}
+
+/// Updates a lawsuit
+///
+/// # Errors
+///
+/// When could not find lawsuit by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateLawsuit>,
+) -> Result<Response> {
+    let update_lawsuit_params = CreateNewLawsuit {
+        order_pid: req_body.order_pid,
+        cnj_number: req_body.cnj_number.clone(),
+        court: req_body.court.clone(),
+        district: req_body.district.clone(),
+        opposing_party: req_body.opposing_party.clone(),
+        filing_date: req_body.filing_date,
+        status: req_body.status,
+    };
+    let response = lawsuits::Model::update(&ctx.db, req_body.pid, update_lawsuit_params).await;
+
+    let lawsuit = match response {
+        Ok(lawsuit) => lawsuit,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update lawsuit",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, lawsuit.order_id).await
+}
+
+/// Deletes a lawsuit
+///
+/// # Errors
+///
+/// When could not find lawsuit by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteLawsuit>,
+) -> Result<Response> {
+    let response = lawsuits::Model::delete(&ctx.db, req_body.pid).await;
+
+    let lawsuit = match response {
+        Ok(lawsuit) => lawsuit,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete lawsuit",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, lawsuit.order_id).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/lawsuits")
+        .add("/create", post(create_new))
+        .add("/cnj", get(cnj))
+        .add("/edit", put(update))
+        .add("/delete", delete(del))
+        .add("/:pid", get(get_one))
+}
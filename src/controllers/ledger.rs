@@ -0,0 +1,24 @@
+use crate::models::_entities::journal_entries;
+use crate::views::ledger::TrialBalanceView;
+use axum::debug_handler;
+use axum::extract::State;
+use axum::response::Response;
+use loco_rs::prelude::*;
+
+/// Gets the debits, credits and balance of every ledger account, with the
+/// totals that must match for the books to balance
+///
+/// # Errors
+///
+/// When could not add up the ledger or DB query error
+#[debug_handler]
+pub async fn trial_balance(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let balances = journal_entries::Model::trial_balance(&ctx.db).await?;
+    format::json(TrialBalanceView::from(balances))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/ledger")
+        .add("/trial_balance", get(trial_balance))
+}
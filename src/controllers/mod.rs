@@ -1,9 +1,32 @@
+pub mod accounting;
+pub mod attachments;
 pub mod auth;
+pub mod bank_transactions;
+pub mod billing_plans;
+pub mod boletos;
+pub mod business_days;
+pub mod calendar;
 pub mod clients;
+pub mod contract_templates;
+pub mod contracts;
+pub mod credit_notes;
+pub mod documents;
+pub mod expenses;
 pub mod fees;
+pub mod hourly_rates;
+pub mod lawsuits;
+pub mod ledger;
+pub mod notes;
 pub mod orders;
 pub mod partners;
+pub mod payment_charges;
+pub mod pix;
 pub mod postponed_payments;
 pub mod processes;
 pub mod processes_fees;
+pub mod refunds;
+pub mod reports;
+pub mod search;
 pub mod sellers;
+pub mod time_entries;
+pub mod webhooks;
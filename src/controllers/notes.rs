@@ -0,0 +1,271 @@
+use crate::models::_entities::{clients, notes, orders, users};
+use crate::models::notes::{CreateNewNote, NoteTarget};
+use crate::views::notes::{NoteHistoryView, NoteView, Timeline};
+use axum::debug_handler;
+use axum::extract::{Json, Path, Query, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateNote {
+    pub pid: Uuid,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PinNote {
+    pub pid: Uuid,
+    #[serde(default = "pinned")]
+    pub pinned: bool,
+}
+
+const fn pinned() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteNote {
+    pub pid: Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct NotesParams {
+    pub order_pid: Option<Uuid>,
+    pub client_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct TimelineParams {
+    pub order_pid: Option<Uuid>,
+    pub client_pid: Option<Uuid>,
+}
+
+/// the note with who wrote it and what it is on
+async fn note_response(ctx: &AppContext, note: notes::Model) -> Result<Response> {
+    let notes = vec![note];
+    let links = notes::Model::find_links(&ctx.db, &notes).await?;
+    format::json(NoteView::from_model(notes, &links))
+}
+
+/// Creates a new note on an order, a client or a payment, written by the
+/// signed in user
+///
+/// # Errors
+///
+/// When could not find the user, could not create note or DB query error
+#[debug_handler]
+pub async fn create_new(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewNote>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let response = notes::Model::create(&ctx.db, Some(user.id), req_body).await;
+
+    let note = match response {
+        Ok(note) => note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create note",);
+            return format::json(());
+        }
+    };
+
+    note_response(&ctx, note).await
+}
+
+/// Changes the body of a note, keeping the body it had in its history
+///
+/// # Errors
+///
+/// When could not find note by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateNote>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let response = notes::Model::update(&ctx.db, req_body.pid, Some(user.id), req_body.body).await;
+
+    let note = match response {
+        Ok(note) => note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update note",);
+            return format::json(());
+        }
+    };
+
+    note_response(&ctx, note).await
+}
+
+/// Pins a note, or unpins it with `pinned` false
+///
+/// # Errors
+///
+/// When could not find note by the given pid or DB query error
+#[debug_handler]
+pub async fn pin(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<PinNote>,
+) -> Result<Response> {
+    let response = notes::Model::pin(&ctx.db, req_body.pid, req_body.pinned).await;
+
+    let note = match response {
+        Ok(note) => note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not pin note",);
+            return format::json(());
+        }
+    };
+
+    note_response(&ctx, note).await
+}
+
+/// Deletes a note, which is kept with its history but no longer shown
+///
+/// # Errors
+///
+/// When could not find note by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteNote>,
+) -> Result<Response> {
+    let response = notes::Model::delete(&ctx.db, req_body.pid).await;
+
+    let note = match response {
+        Ok(note) => note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete note",);
+            return format::json(());
+        }
+    };
+
+    note_response(&ctx, note).await
+}
+
+/// Gets the notes on an order, a client or a payment, the pinned ones first
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn get_all(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<NotesParams>,
+) -> Result<Response> {
+    let target = match NoteTarget::find(
+        &ctx.db,
+        params.order_pid,
+        params.client_pid,
+        params.payment_pid,
+    )
+    .await
+    {
+        Ok(target) => target,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find notes",);
+            return format::json(());
+        }
+    };
+    let notes = notes::Model::find_by_target(&ctx.db, target).await?;
+    let links = notes::Model::find_links(&ctx.db, &notes).await?;
+    format::json(NoteView::from_model(notes, &links))
+}
+
+/// Gets a note with the bodies it had before it was edited
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn history(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    let note = match notes::Model::find_by_pid(&ctx.db, pid).await {
+        Ok(note) => note,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find note",);
+            return format::json(());
+        }
+    };
+    let links = notes::Model::find_links(&ctx.db, std::slice::from_ref(&note)).await?;
+    format::json(NoteHistoryView::new(note, &links))
+}
+
+/// Gets the activity of an order, or of a client with all their orders: the
+/// notes on them merged with when they were created, their payments settled,
+/// postponed and their status changed, in the order it happened
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn timeline(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<TimelineParams>,
+) -> Result<Response> {
+    let response = match (params.order_pid, params.client_pid) {
+        (Some(pid), None) => {
+            let order = orders::Entity::find()
+                .filter(
+                    model::query::condition()
+                        .eq(orders::Column::Pid, pid)
+                        .build(),
+                )
+                .one(&ctx.db)
+                .await?;
+            match order {
+                Some(order) => Timeline::find(&ctx.db, None, &[order]).await,
+                None => Err(ModelError::EntityNotFound),
+            }
+        }
+        (None, Some(pid)) => match clients::Model::find_by_pid(&ctx.db, pid).await {
+            Ok(client) => {
+                let orders = orders::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(orders::Column::ClientId, client.id)
+                            .build(),
+                    )
+                    .all(&ctx.db)
+                    .await?;
+                Timeline::find(&ctx.db, Some(&client), &orders).await
+            }
+            Err(err) => Err(err),
+        },
+        _ => Err(ModelError::Any(
+            "a timeline is of either an order or a client".into(),
+        )),
+    };
+
+    let timeline = match response {
+        Ok(timeline) => timeline,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find timeline",);
+            return format::json(());
+        }
+    };
+
+    format::json(timeline)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/notes")
+        .add("/create", post(create_new))
+        .add("/edit", put(update))
+        .add("/pin", put(pin))
+        .add("/delete", delete(del))
+        .add("/all", get(get_all))
+        .add("/timeline", get(timeline))
+        .add("/:pid/history", get(history))
+}
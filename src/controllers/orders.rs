@@ -1,4 +1,6 @@
-use crate::models::_entities::orders;
+use crate::business_days::BusinessCalendar;
+use crate::documents::Letterhead;
+use crate::models::_entities::{contracts, orders, users};
 use crate::views::orders as OrdersView;
 use axum::debug_handler;
 use loco_rs::prelude::*;
@@ -37,6 +39,46 @@ pub struct JsonOrderToCreate {
     pub payments: Vec<OrderPaymentsRequest>,
 }
 
+/// Writes a new version of the contract of an order when what it is written
+/// from changed since the latest one; the order is kept changed when it
+/// could not be written, and the nightly refresh tries again
+pub(crate) async fn refresh_contract(ctx: &AppContext, user_id: Option<i32>, order_pid: Uuid) {
+    let letterhead = Letterhead::from_settings(ctx.config.settings.as_ref());
+    if let Err(err) = contracts::Model::generate(
+        &ctx.db,
+        &ctx.storage,
+        &letterhead,
+        user_id,
+        order_pid,
+        chrono::Utc::now().date_naive(),
+    )
+    .await
+    {
+        tracing::warn!(
+            message = err.to_string(),
+            order_pid = order_pid.to_string(),
+            "could not write the order contract",
+        );
+    }
+}
+
+/// Responds with an order that was just changed, looked up by id, as
+/// returned by the orders API, versioning its contract when the change is in
+/// it
+///
+/// # Errors
+///
+/// When could not find the order or DB query error
+pub(crate) async fn order_response(ctx: &AppContext, order_id: i32) -> Result<Response> {
+    let order = orders::Entity::find_by_id(order_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    refresh_contract(ctx, None, order.pid).await;
+    let order = orders::Model::find_by_pid(&ctx.db, order.pid).await?;
+    format::json(OrdersView::GetOrderReturn::from(order))
+}
+
 /// Creates a new order
 ///
 /// # Errors
@@ -44,11 +86,12 @@ pub struct JsonOrderToCreate {
 /// When could not create order or DB query error
 #[debug_handler]
 async fn create_new(
-    _auth: auth::JWT,
+    auth: auth::JWT,
     State(ctx): State<AppContext>,
     Json(params): Json<JsonOrderToCreate>,
 ) -> Result<Response> {
-    let res = orders::Model::create(&ctx.db, &params).await;
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let res = orders::Model::create(&ctx.db, &params, &calendar).await;
 
     let order = match res {
         Ok(order) => order,
@@ -58,6 +101,12 @@ async fn create_new(
         }
     };
 
+    let user_id = users::Model::find_by_pid(&ctx.db, &auth.claims.pid)
+        .await
+        .ok()
+        .map(|user| user.id);
+    refresh_contract(&ctx, user_id, order.pid).await;
+
     format::json(OrdersView::GetOrderReturn::from(order))
 }
 
@@ -0,0 +1,57 @@
+use crate::models::_entities::{payment_charges, payments};
+use crate::models::payment_charges::CreateNewPaymentCharge;
+use crate::payment_providers;
+use crate::views::payment_charges::PaymentChargeView;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+
+/// Creates a charge for an open payment with the configured provider
+///
+/// # Errors
+///
+/// When could not create the charge or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewPaymentCharge>,
+) -> Result<Response> {
+    let Some(provider) = payment_providers::from_settings(ctx.config.settings.as_ref()) else {
+        tracing::info!("could not find a payment provider in the settings");
+        return format::json(());
+    };
+    let response =
+        payment_charges::Model::create(&ctx.db, provider.as_ref(), req_body.payment_pid).await;
+
+    let payment_charge = match response {
+        Ok(payment_charge) => payment_charge,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create payment charge",);
+            return format::json(());
+        }
+    };
+
+    let payments = payments::Entity::find().all(&ctx.db).await?;
+    format::json(PaymentChargeView::from(payment_charge, &payments))
+}
+
+/// Gets all payment charges
+///
+/// # Errors
+///
+/// When could not find payment charges or DB query error
+#[debug_handler]
+pub async fn get_all(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let payment_charges = payment_charges::Entity::find().all(&ctx.db).await?;
+    let payments = payments::Entity::find().all(&ctx.db).await?;
+    format::json(PaymentChargeView::from_model(payment_charges, &payments))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/payment_charges")
+        .add("/create", post(create_new))
+        .add("/all", get(get_all))
+}
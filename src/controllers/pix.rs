@@ -0,0 +1,86 @@
+use crate::documents::pix::PixSettings;
+use crate::models::_entities::payments;
+use crate::views::pix::PixView;
+use axum::body::Body;
+use axum::debug_handler;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::Response;
+use loco_rs::prelude::*;
+
+/// Builds the PIX charge of an open instalment
+///
+/// # Errors
+///
+/// When DB query error
+async fn find_charge(ctx: &AppContext, pid: Uuid) -> Result<Option<PixView>> {
+    let Some(settings) = PixSettings::from_settings(ctx.config.settings.as_ref()) else {
+        tracing::info!("could not find the PIX account in the settings");
+        return Ok(None);
+    };
+    let payment = payments::Entity::find()
+        .filter(
+            model::query::condition()
+                .eq(payments::Column::Pid, pid)
+                .build(),
+        )
+        .one(&ctx.db)
+        .await?;
+    match payment {
+        Some(payment) if payment.open => Ok(PixView::from(&payment, &settings)),
+        _ => {
+            tracing::info!(
+                payment_pid = pid.to_string(),
+                "could not find an open payment for the PIX charge",
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Returns the PIX "copia e cola" payload of an open instalment along with
+/// its QR code as SVG
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn charge(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    match find_charge(&ctx, pid).await? {
+        Some(charge) => format::json(charge),
+        None => format::json(()),
+    }
+}
+
+/// Returns the QR code of the PIX charge of an open instalment as an SVG
+/// image
+///
+/// # Errors
+///
+/// When DB query error or the response could not be built
+#[debug_handler]
+pub async fn qr_code(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Path(pid): Path<Uuid>,
+) -> Result<Response> {
+    let Some(charge) = find_charge(&ctx, pid).await? else {
+        return format::json(());
+    };
+    let response = format::render()
+        .response()
+        .header(header::CONTENT_TYPE, "image/svg+xml")
+        .body(Body::from(charge.qr_code))?;
+    Ok(response)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/pix")
+        .add("/:pid", get(charge))
+        .add("/:pid/qr_code", get(qr_code))
+}
@@ -0,0 +1,108 @@
+use crate::controllers::orders as OrdersController;
+use crate::models::_entities::refunds;
+use crate::models::refunds::{CreateNewRefund, EditRefund};
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateRefund {
+    pub pid: Uuid,
+    pub value: f32,
+    pub refund_date: chrono::NaiveDate,
+    pub refund_method: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteRefund {
+    pub pid: Uuid,
+}
+
+/// Creates a new refund
+///
+/// # Errors
+///
+/// When could not create refund or DB query error
+#[debug_handler]
+pub async fn create_new(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewRefund>,
+) -> Result<Response> {
+    let response = refunds::Model::create(&ctx.db, req_body).await;
+
+    let refund = match response {
+        Ok(refund) => refund,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create refund",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, refund.order_id).await
+}
+
+/// Updates a refund
+///
+/// # Errors
+///
+/// When could not find refund by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateRefund>,
+) -> Result<Response> {
+    let update_refund_params = EditRefund {
+        value: req_body.value,
+        refund_date: req_body.refund_date,
+        refund_method: req_body.refund_method.clone(),
+        reason: req_body.reason.clone(),
+    };
+    let response = refunds::Model::update(&ctx.db, req_body.pid, update_refund_params).await;
+
+    let refund = match response {
+        Ok(refund) => refund,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update refund",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, refund.order_id).await
+}
+
+/// Deletes a refund
+///
+/// # Errors
+///
+/// When could not find refund by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteRefund>,
+) -> Result<Response> {
+    let response = refunds::Model::delete(&ctx.db, req_body.pid).await;
+
+    let refund = match response {
+        Ok(refund) => refund,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete refund",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, refund.order_id).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/refunds")
+        .add("/create", post(create_new))
+        .add("/edit", put(update))
+        .add("/delete", delete(del))
+}
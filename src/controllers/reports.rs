@@ -0,0 +1,99 @@
+use crate::business_days::BusinessCalendar;
+use crate::reports::aging::{self, AgingGroupBy, AgingReport};
+use crate::reports::forecast::{self, ForecastPeriod, ForecastReport, RateBy};
+use axum::body::Body;
+use axum::debug_handler;
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AgingParams {
+    pub as_of: Option<chrono::NaiveDate>,
+    #[serde(default)]
+    pub group_by: AgingGroupBy,
+    pub format: Option<String>,
+}
+
+/// Gets the aging of the open instalments grouped by client, seller, partner
+/// or process, as JSON or, with `format=csv`, as the CSV of the drill-down
+/// list
+///
+/// # Errors
+///
+/// When could not age the instalments or DB query error
+#[debug_handler]
+pub async fn aging(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<AgingParams>,
+) -> Result<Response> {
+    let as_of = params
+        .as_of
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let items = aging::aging_items(&ctx.db, as_of, &calendar).await?;
+    let report = AgingReport::new(as_of, params.group_by, items);
+
+    if params.format.as_deref() == Some("csv") {
+        let response = format::render()
+            .response()
+            .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+            .header(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"aging-{as_of}.csv\""),
+            )
+            .body(Body::from(report.to_csv()))?;
+        return Ok(response);
+    }
+    format::json(report)
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ForecastParams {
+    pub from: Option<chrono::NaiveDate>,
+    pub until: Option<chrono::NaiveDate>,
+    #[serde(default)]
+    pub period: ForecastPeriod,
+    #[serde(default)]
+    pub rate_by: RateBy,
+}
+
+/// Gets the cash-flow forecast by week or month, from today to six months
+/// ahead unless `from` and `until` are given
+///
+/// # Errors
+///
+/// When could not project the instalments or DB query error
+#[debug_handler]
+pub async fn forecast(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<ForecastParams>,
+) -> Result<Response> {
+    let from = params
+        .from
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let until = params
+        .until
+        .or_else(|| from.checked_add_months(chrono::Months::new(6)))
+        .unwrap_or(from);
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let lines = forecast::forecast_lines(&ctx.db, until, params.rate_by, &calendar).await?;
+    format::json(ForecastReport::new(
+        from,
+        until,
+        params.period,
+        params.rate_by,
+        &lines,
+    ))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/reports")
+        .add("/aging", get(aging))
+        .add("/forecast", get(forecast))
+}
@@ -0,0 +1,34 @@
+use crate::search::{self, DEFAULT_LIMIT};
+use axum::debug_handler;
+use axum::extract::{Query, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SearchParams {
+    #[serde(default)]
+    pub q: String,
+    pub limit: Option<u64>,
+}
+
+/// Searches clients by name, email and phone, partners and sellers by name,
+/// processes by case type and orders by the info of their fees and the CNJ
+/// number, opposing party and court of their lawsuit, best match first
+///
+/// # Errors
+///
+/// When DB query error
+#[debug_handler]
+pub async fn search(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Query(params): Query<SearchParams>,
+) -> Result<Response> {
+    let results = search::search(&ctx.db, &params.q, params.limit.unwrap_or(DEFAULT_LIMIT)).await?;
+    format::json(results)
+}
+
+pub fn routes() -> Routes {
+    Routes::new().prefix("/api").add("/search", get(search))
+}
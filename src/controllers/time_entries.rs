@@ -0,0 +1,165 @@
+use crate::business_days::BusinessCalendar;
+use crate::controllers::orders as OrdersController;
+use crate::models::_entities::{payments, time_entries, users};
+use crate::models::time_entries::{CreateNewTimeEntry, EditTimeEntry, InvoiceTimeEntries};
+use crate::views::time_entries as TimeEntriesView;
+use axum::debug_handler;
+use axum::extract::{Json, State};
+use axum::response::Response;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateTimeEntry {
+    pub pid: Uuid,
+    pub entry_date: chrono::NaiveDate,
+    pub minutes: i32,
+    pub description: String,
+    pub hourly_rate: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteTimeEntry {
+    pub pid: Uuid,
+}
+
+/// Logs time on an order
+///
+/// # Errors
+///
+/// When could not create time entry or DB query error
+#[debug_handler]
+pub async fn create_new(
+    auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<CreateNewTimeEntry>,
+) -> Result<Response> {
+    let user = users::Model::find_by_pid(&ctx.db, &auth.claims.pid).await?;
+    let response = time_entries::Model::create(&ctx.db, &user, req_body).await;
+
+    let time_entry = match response {
+        Ok(time_entry) => time_entry,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not create time entry",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, time_entry.order_id).await
+}
+
+/// Gets all time entries
+///
+/// # Errors
+///
+/// When could not find time entries or DB query error
+#[debug_handler]
+pub async fn get_all(_auth: auth::JWT, State(ctx): State<AppContext>) -> Result<Response> {
+    let time_entries = time_entries::Model::find_all(&ctx.db).await;
+
+    let time_entries = match time_entries {
+        Ok(time_entries) => time_entries,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not find time entries",);
+            return format::json(());
+        }
+    };
+
+    let users = users::Entity::find().all(&ctx.db).await?;
+    let payments = payments::Model::find_all(&ctx.db).await?;
+    format::json(TimeEntriesView::TimeEntryView::from_model(
+        time_entries,
+        &users,
+        &payments,
+    ))
+}
+
+/// Updates a time entry
+///
+/// # Errors
+///
+/// When could not find time entry by the given pid or DB query error
+#[debug_handler]
+pub async fn update(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<UpdateTimeEntry>,
+) -> Result<Response> {
+    let update_time_entry_params = EditTimeEntry {
+        entry_date: req_body.entry_date,
+        minutes: req_body.minutes,
+        description: req_body.description.clone(),
+        hourly_rate: req_body.hourly_rate,
+    };
+    let response =
+        time_entries::Model::update(&ctx.db, req_body.pid, update_time_entry_params).await;
+
+    let time_entry = match response {
+        Ok(time_entry) => time_entry,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not update time entry",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, time_entry.order_id).await
+}
+
+/// Deletes a time entry
+///
+/// # Errors
+///
+/// When could not find time entry by the given pid or DB query error
+#[debug_handler]
+pub async fn del(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<DeleteTimeEntry>,
+) -> Result<Response> {
+    let response = time_entries::Model::delete(&ctx.db, req_body.pid).await;
+
+    let time_entry = match response {
+        Ok(time_entry) => time_entry,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not delete time entry",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, time_entry.order_id).await
+}
+
+/// Rolls the unbilled time of an order into a new instalment
+///
+/// # Errors
+///
+/// When could not invoice the time entries or DB query error
+#[debug_handler]
+pub async fn invoice(
+    _auth: auth::JWT,
+    State(ctx): State<AppContext>,
+    Json(req_body): Json<InvoiceTimeEntries>,
+) -> Result<Response> {
+    let calendar = BusinessCalendar::from_settings(ctx.config.settings.as_ref());
+    let response = time_entries::Model::invoice(&ctx.db, req_body, &calendar).await;
+
+    let payment = match response {
+        Ok(payment) => payment,
+        Err(err) => {
+            tracing::info!(message = err.to_string(), "could not invoice time entries",);
+            return format::json(());
+        }
+    };
+
+    OrdersController::order_response(&ctx, payment.order_id).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/time_entries")
+        .add("/create", post(create_new))
+        .add("/all", get(get_all))
+        .add("/edit", put(update))
+        .add("/delete", delete(del))
+        .add("/invoice", post(invoice))
+}
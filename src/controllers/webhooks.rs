@@ -0,0 +1,61 @@
+use crate::models::_entities::provider_events;
+use crate::payment_providers::{self, ProviderError};
+use axum::body::Bytes;
+use axum::debug_handler;
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::response::Response;
+use loco_rs::model::ModelError;
+use loco_rs::prelude::*;
+use serde_json::json;
+
+/// Receives the webhook of a payment provider, settling the payment when its
+/// charge is paid. Events delivered again are acknowledged without changes.
+///
+/// # Errors
+///
+/// When the provider is not configured, the signature does not match, the
+/// charge is not known or DB query error
+#[debug_handler]
+pub async fn receive(
+    State(ctx): State<AppContext>,
+    Path(provider_name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response> {
+    let provider = payment_providers::from_settings(ctx.config.settings.as_ref())
+        .filter(|provider| provider.name() == provider_name)
+        .ok_or_else(|| Error::NotFound)?;
+    let signature = headers
+        .get(provider.signature_header())
+        .and_then(|signature| signature.to_str().ok())
+        .unwrap_or_default();
+    let event = match provider.verify_webhook(signature, &body) {
+        Ok(event) => event,
+        Err(ProviderError::InvalidSignature) => {
+            tracing::info!(provider = provider_name, "webhook signature does not match",);
+            return unauthorized("invalid signature");
+        }
+        Err(err) => return Err(Error::BadRequest(err.to_string())),
+    };
+
+    let event_id = event.event_id.clone();
+    match provider_events::Model::record(&ctx.db, provider.name(), event).await {
+        Ok(Some(_)) => format::json(json!({ "status": "processed" })),
+        Ok(None) => {
+            tracing::info!(event_id, "webhook event was already processed",);
+            format::json(json!({ "status": "duplicate" }))
+        }
+        Err(ModelError::EntityNotFound) => {
+            tracing::info!(event_id, "could not find the charge of the webhook event",);
+            Err(Error::NotFound)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/webhooks")
+        .add("/:provider", post(receive))
+}
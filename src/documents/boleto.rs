@@ -0,0 +1,167 @@
+//! Boletos for the instalments of clients who do not pay by PIX, following
+//! the FEBRABAN layout of the barcode and its digitable line.
+//!
+//! The free field of the barcode follows the layout of Banco do Brasil for
+//! agreements with seven digits: six zeros, the "nosso número" made of the
+//! agreement and a sequence, and the wallet.
+
+use serde::{Deserialize, Serialize};
+
+/// the code boletos use for the real
+const CURRENCY_CODE: &str = "9";
+const NOSSO_NUMERO_SEQUENCE_LEN: usize = 10;
+
+/// The firm's agreement with its bank, read from the `settings.boleto`
+/// section of the configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BoletoSettings {
+    pub bank_code: String,
+    pub agreement: String,
+    pub wallet: String,
+    pub beneficiary: String,
+}
+
+impl BoletoSettings {
+    /// reads the bank agreement from the app settings, if it is configured
+    /// with the number of digits the layout expects
+    #[must_use]
+    pub fn from_settings(settings: Option<&serde_json::Value>) -> Option<Self> {
+        settings
+            .and_then(|settings| settings.get("boleto"))
+            .and_then(|boleto| serde_json::from_value::<Self>(boleto.clone()).ok())
+            .filter(|boleto| {
+                is_digits(&boleto.bank_code, 3)
+                    && is_digits(&boleto.agreement, 7)
+                    && is_digits(&boleto.wallet, 2)
+            })
+    }
+
+    /// the "nosso número" of the boleto with the given sequence, which
+    /// identifies it with the bank
+    #[must_use]
+    pub fn nosso_numero(&self, sequence: i32) -> String {
+        format!(
+            "{}{:0width$}",
+            self.agreement,
+            sequence,
+            width = NOSSO_NUMERO_SEQUENCE_LEN
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Boleto {
+    pub nosso_numero: String,
+    pub due_date_factor: u32,
+    pub barcode: String,
+    pub digitable_line: String,
+}
+
+impl Boleto {
+    /// computes the barcode and the digitable line of a boleto, or `None`
+    /// when the due date or the value cannot be written in them
+    #[must_use]
+    pub fn new(
+        settings: &BoletoSettings,
+        nosso_numero: &str,
+        value: f32,
+        due_date: chrono::NaiveDate,
+    ) -> Option<Self> {
+        let due_date_factor = due_date_factor(due_date)?;
+        #[allow(clippy::cast_possible_truncation)]
+        let cents = (f64::from(value) * 100.0).round() as i64;
+        if !(0..10_000_000_000).contains(&cents) {
+            return None;
+        }
+        let free_field = format!("000000{nosso_numero}{}", settings.wallet);
+        if !is_digits(&free_field, 25) {
+            return None;
+        }
+        let without_check_digit = format!(
+            "{}{CURRENCY_CODE}{due_date_factor:04}{cents:010}{free_field}",
+            settings.bank_code
+        );
+        let check_digit = barcode_check_digit(&without_check_digit);
+        let barcode = format!(
+            "{}{check_digit}{}",
+            &without_check_digit[..4],
+            &without_check_digit[4..]
+        );
+        Some(Self {
+            nosso_numero: nosso_numero.to_string(),
+            due_date_factor,
+            digitable_line: digitable_line(&barcode),
+            barcode,
+        })
+    }
+}
+
+/// The days from 1997-10-07 to the due date, written in four digits that
+/// restart from 1000 after reaching 9999 on 2025-02-21
+#[must_use]
+pub fn due_date_factor(due_date: chrono::NaiveDate) -> Option<u32> {
+    let base = chrono::NaiveDate::from_ymd_opt(1997, 10, 7)?;
+    let days = u32::try_from((due_date - base).num_days()).ok()?;
+    if days < 1000 {
+        return None;
+    }
+    Some((days - 1000) % 9000 + 1000)
+}
+
+/// Writes the digitable line of a barcode: the free field split in three
+/// fields with their own check digits, the check digit of the barcode, and
+/// the due date factor followed by the value
+#[must_use]
+pub fn digitable_line(barcode: &str) -> String {
+    let field = |digits: String| {
+        let check_digit = modulo10(&digits);
+        let digits = format!("{digits}{check_digit}");
+        format!("{}.{}", &digits[..5], &digits[5..])
+    };
+    format!(
+        "{} {} {} {} {}",
+        field(format!("{}{}", &barcode[..4], &barcode[19..24])),
+        field(barcode[24..34].to_string()),
+        field(barcode[34..44].to_string()),
+        &barcode[4..5],
+        &barcode[5..19]
+    )
+}
+
+/// The modulo 10 check digit of the fields of the digitable line, weighting
+/// the digits by 2 and 1 from the right and adding the digits of each product
+#[must_use]
+pub fn modulo10(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .zip([2, 1].into_iter().cycle())
+        .map(|(digit, weight)| {
+            let product = digit * weight;
+            product / 10 + product % 10
+        })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// The modulo 11 check digit of the barcode, weighting the digits from 2 to 9
+/// from the right; results of 0, 10 and 11 become 1
+#[must_use]
+pub fn barcode_check_digit(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .zip((2..=9).cycle())
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    match 11 - sum % 11 {
+        0 | 10 | 11 => 1,
+        check_digit => check_digit,
+    }
+}
+
+fn is_digits(text: &str, len: usize) -> bool {
+    text.len() == len && text.chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,401 @@
+//! Fee agreements generated from the contract template of the order's
+//! process.
+//!
+//! A template is plain text. A line starting with `# ` is a heading, lines
+//! next to each other make a paragraph and blank lines separate paragraphs.
+//! Placeholders such as `{{ client.name }}` are replaced with the details of
+//! the order, see [`PLACEHOLDERS`], and a line holding only `{{ fees }}` or
+//! `{{ instalments }}` becomes the table of the fees or of the instalments.
+
+use super::docx::Docx;
+use super::pdf::{Font, Pdf};
+use super::{format_date, format_money, Letterhead};
+use crate::models::clients::{format_document, TaxIdKind};
+use crate::views::orders::GetOrderReturn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The placeholders a template can hold, with what they are replaced with
+pub const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("firm.name", "name of the firm, from the letterhead"),
+    ("firm.document", "CNPJ of the firm, from the letterhead"),
+    ("firm.address", "address of the firm, from the letterhead"),
+    ("firm.city", "city of the firm, from the letterhead"),
+    ("client.name", "name of the client"),
+    (
+        "client.document",
+        "CPF or CNPJ of the client, with its kind",
+    ),
+    ("client.email", "primary email of the client"),
+    ("client.phone", "primary phone of the client"),
+    ("seller.name", "name of the seller who closed the order"),
+    (
+        "partner.name",
+        "name of the partner who referred the client",
+    ),
+    ("partner.fee", "fee paid to the partner"),
+    ("process.case_type", "case type of the process"),
+    ("lawsuit.number", "CNJ number of the lawsuit of the order"),
+    ("order.fee", "total fee of the order"),
+    ("order.instalments", "number of instalments"),
+    ("date", "date the contract is issued on"),
+];
+
+/// The placeholders that stand alone on a line and become a table
+pub const TABLES: &[(&str, &str)] = &[
+    ("fees", "table of the fees of the order"),
+    ("instalments", "table of the instalments, by due date"),
+];
+
+/// The template of the processes that do not have one of their own
+pub const DEFAULT_TEMPLATE: &str = "\
+# CONTRATO DE PRESTAÇÃO DE SERVIÇOS ADVOCATÍCIOS E HONORÁRIOS
+
+CONTRATANTE: {{ client.name }}, {{ client.document }}, e-mail {{ client.email }}, \
+telefone {{ client.phone }}.
+
+CONTRATADA: {{ firm.name }}, {{ firm.document }}, com sede em {{ firm.address }}.
+
+# CLÁUSULA PRIMEIRA - DO OBJETO
+
+A CONTRATADA prestará ao CONTRATANTE serviços advocatícios no processo de \
+{{ process.case_type }}.
+
+# CLÁUSULA SEGUNDA - DOS HONORÁRIOS
+
+Pelos serviços, o CONTRATANTE pagará à CONTRATADA honorários no total de \
+{{ order.fee }}, compostos por:
+{{ fees }}
+
+# CLÁUSULA TERCEIRA - DO PAGAMENTO
+
+Os honorários serão pagos em {{ order.instalments }} parcela(s), conforme a tabela \
+abaixo. A parcela que vencer em dia sem expediente bancário poderá ser paga no \
+primeiro dia útil seguinte.
+{{ instalments }}
+
+# CLÁUSULA QUARTA - DO FORO
+
+Fica eleito o foro da comarca de {{ firm.city }} para dirimir quaisquer questões \
+oriundas deste contrato.
+
+{{ firm.city }}, {{ date }}.
+
+________________________________________
+{{ client.name }}
+
+________________________________________
+{{ firm.name }}
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableKind {
+    Fees,
+    Instalments,
+}
+
+impl TableKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "fees" => Some(Self::Fees),
+            "instalments" => Some(Self::Instalments),
+            _ => None,
+        }
+    }
+
+    /// the titles of the columns with where they start on a PDF page
+    const fn columns(self) -> &'static [(f32, &'static str)] {
+        match self {
+            Self::Fees => &[
+                (0.0, "Descrição"),
+                (170.0, "Tipo"),
+                (250.0, "Valor"),
+                (340.0, "Observações"),
+            ],
+            Self::Instalments => &[(0.0, "Parcela"), (90.0, "Vencimento"), (200.0, "Valor")],
+        }
+    }
+}
+
+/// A part of a template, with its placeholders still in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplateBlock {
+    Heading(String),
+    Paragraph(String),
+    Table(TableKind),
+}
+
+/// the name in a line holding only a placeholder, as `{{ fees }}`
+fn lone_placeholder(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix("{{")?
+        .strip_suffix("}}")
+        .map(str::trim)
+}
+
+/// replaces the placeholders of a text with their values
+///
+/// # Errors
+///
+/// When a placeholder is not closed or is not known
+fn substitute(text: &str, value: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut output = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            return Err(format!(
+                "the placeholder in \"{}\" is not closed",
+                &rest[start..]
+            ));
+        };
+        let name = rest[start + 2..start + end].trim();
+        match value(name) {
+            Some(value) => output.push_str(&value),
+            None => return Err(format!("unknown placeholder {{{{ {name} }}}}")),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// splits a template into headings, paragraphs and tables, checking every
+/// placeholder is known
+///
+/// # Errors
+///
+/// When a placeholder is not closed or is not known, with what is wrong
+fn parse(template: &str) -> Result<Vec<TemplateBlock>, String> {
+    let known = |name: &str| {
+        PLACEHOLDERS
+            .iter()
+            .any(|(placeholder, _)| *placeholder == name)
+            .then(String::new)
+    };
+    let mut blocks = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<TemplateBlock>| {
+        if !paragraph.is_empty() {
+            blocks.push(TemplateBlock::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+    };
+    for line in template.lines() {
+        let line = line.trim();
+        if let Some(table) = lone_placeholder(line).and_then(TableKind::parse) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(TemplateBlock::Table(table));
+            continue;
+        }
+        substitute(line, known)?;
+        if line.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some(heading) = line.strip_prefix("# ") {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(TemplateBlock::Heading(heading.trim().to_string()));
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    Ok(blocks)
+}
+
+/// checks a template can be rendered
+///
+/// # Errors
+///
+/// When a placeholder is not closed or is not known, with what is wrong
+pub fn check(template: &str) -> Result<(), String> {
+    parse(template).map(|_| ())
+}
+
+/// The details of an order a contract is written with, apart from the date
+/// it is issued on, so the same order gives the same data every day
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContractData {
+    pub values: BTreeMap<String, String>,
+    pub fees: Vec<Vec<String>>,
+    pub instalments: Vec<Vec<String>>,
+}
+
+impl ContractData {
+    #[must_use]
+    pub fn new(letterhead: &Letterhead, order: &GetOrderReturn) -> Self {
+        let mut payments = order.payments.iter().collect::<Vec<_>>();
+        payments.sort_by_key(|payment| payment.due_date);
+        let client_document = order.client.document.as_deref().map(|document| {
+            let kind = TaxIdKind::of(document).map_or("Documento", TaxIdKind::as_str);
+            format!("{kind} {}", format_document(document))
+        });
+        let partner = order.client.partner.as_ref();
+        let values = [
+            ("firm.name", Some(letterhead.name.clone())),
+            ("firm.document", letterhead.document.clone()),
+            ("firm.address", letterhead.address.clone()),
+            ("firm.city", letterhead.city.clone()),
+            ("client.name", Some(order.client.name.clone())),
+            ("client.document", client_document),
+            ("client.email", order.client.email.clone()),
+            ("client.phone", order.client.phone.clone()),
+            ("seller.name", Some(order.seller.name.clone())),
+            ("partner.name", partner.map(|partner| partner.name.clone())),
+            (
+                "partner.fee",
+                partner.and(order.partner_fee).map(format_money),
+            ),
+            ("process.case_type", Some(order.process.case_type.clone())),
+            (
+                "lawsuit.number",
+                order
+                    .lawsuit
+                    .as_ref()
+                    .map(|lawsuit| lawsuit.cnj_number.clone()),
+            ),
+            ("order.fee", Some(format_money(order.fee))),
+            ("order.instalments", Some(payments.len().to_string())),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.unwrap_or_default()))
+        .collect();
+        let fees = order
+            .fees
+            .iter()
+            .map(|fee| {
+                vec![
+                    fee.fee.clone(),
+                    fee.r#type.clone().unwrap_or_default(),
+                    format_money(fee.value),
+                    fee.info.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        let instalments = payments
+            .iter()
+            .enumerate()
+            .map(|(index, payment)| {
+                vec![
+                    format!("{}/{}", index + 1, payments.len()),
+                    format_date(payment.due_date),
+                    format_money(payment.value),
+                ]
+            })
+            .collect();
+        Self {
+            values,
+            fees,
+            instalments,
+        }
+    }
+}
+
+/// A part of a contract, ready to be written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Block {
+    Heading(String),
+    Paragraph(String),
+    Table {
+        kind: TableKind,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+/// A contract rendered from a template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Contract {
+    pub blocks: Vec<Block>,
+}
+
+/// fills a template with the details of an order
+///
+/// # Errors
+///
+/// When a placeholder is not closed or is not known, with what is wrong
+pub fn render(
+    template: &str,
+    data: &ContractData,
+    issued_on: chrono::NaiveDate,
+) -> Result<Contract, String> {
+    let value = |name: &str| {
+        if name == "date" {
+            return Some(format_date(issued_on));
+        }
+        data.values.get(name).cloned()
+    };
+    let blocks = parse(template)?
+        .into_iter()
+        .map(|block| {
+            Ok(match block {
+                TemplateBlock::Heading(text) => Block::Heading(substitute(&text, value)?),
+                TemplateBlock::Paragraph(text) => Block::Paragraph(substitute(&text, value)?),
+                TemplateBlock::Table(kind) => Block::Table {
+                    kind,
+                    rows: match kind {
+                        TableKind::Fees => data.fees.clone(),
+                        TableKind::Instalments => data.instalments.clone(),
+                    },
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(Contract { blocks })
+}
+
+impl Contract {
+    /// writes the contract as a PDF with the firm letterhead
+    #[must_use]
+    pub fn to_pdf(&self, letterhead: &Letterhead) -> Vec<u8> {
+        let mut pdf = Pdf::new();
+        letterhead.draw(&mut pdf);
+        for block in &self.blocks {
+            match block {
+                Block::Heading(text) => {
+                    pdf.space(6.0);
+                    pdf.line(12.0, Font::Bold, text);
+                }
+                Block::Paragraph(text) => {
+                    pdf.line(10.0, Font::Regular, text);
+                    pdf.space(6.0);
+                }
+                Block::Table { kind, rows } => {
+                    let columns = kind.columns();
+                    pdf.row(9.0, Font::Bold, columns);
+                    pdf.rule();
+                    for row in rows {
+                        let cells = columns
+                            .iter()
+                            .zip(row)
+                            .map(|((x, _), cell)| (*x, cell.as_str()))
+                            .collect::<Vec<_>>();
+                        pdf.row(9.0, Font::Regular, &cells);
+                    }
+                    pdf.space(8.0);
+                }
+            }
+        }
+        pdf.finish()
+    }
+
+    /// writes the contract as a DOCX, for the firm to edit before signing
+    #[must_use]
+    pub fn to_docx(&self) -> Vec<u8> {
+        let mut docx = Docx::new();
+        for block in &self.blocks {
+            match block {
+                Block::Heading(text) => docx.line(12.0, Font::Bold, text),
+                Block::Paragraph(text) => docx.line(11.0, Font::Regular, text),
+                Block::Table { kind, rows } => {
+                    let header = kind
+                        .columns()
+                        .iter()
+                        .map(|(_, title)| *title)
+                        .collect::<Vec<_>>();
+                    docx.table(10.0, &header, rows);
+                }
+            }
+        }
+        docx.finish()
+    }
+}
@@ -0,0 +1,166 @@
+//! A small DOCX writer for the documents the firm edits after they are
+//! generated, such as contracts.
+//!
+//! It only knows paragraphs in regular or bold text and tables with a
+//! header row. The package is a ZIP file with its parts stored without
+//! compression, which every word processor reads.
+
+use super::pdf::Font;
+use std::fmt::Write as _;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/></Types>"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+
+/// A4 paper with the margins of the PDF documents, in twentieths of a point
+const SECTION: &str = r#"<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1000" w:right="1000" w:bottom="1000" w:left="1000" w:header="0" w:footer="0" w:gutter="0"/></w:sectPr>"#;
+
+#[derive(Debug, Default)]
+pub struct Docx {
+    body: String,
+}
+
+/// escapes the characters XML gives a meaning to
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// a run of text in the given size, in points, and font
+fn run(size: f32, font: Font, text: &str) -> String {
+    #[allow(clippy::cast_possible_truncation)]
+    let half_points = (size * 2.0).round() as i32;
+    let bold = if font == Font::Bold { "<w:b/>" } else { "" };
+    format!(
+        "<w:r><w:rPr>{bold}<w:sz w:val=\"{half_points}\"/></w:rPr>\
+         <w:t xml:space=\"preserve\">{}</w:t></w:r>",
+        escape(text)
+    )
+}
+
+impl Docx {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// writes a paragraph of text, which the word processor wraps
+    pub fn line(&mut self, size: f32, font: Font, text: &str) {
+        let _ = write!(
+            self.body,
+            "<w:p><w:pPr><w:spacing w:after=\"120\"/><w:jc w:val=\"both\"/></w:pPr>{}</w:p>",
+            run(size, font, text)
+        );
+    }
+
+    /// writes a table with a bold header row and a thin border around every
+    /// cell
+    pub fn table(&mut self, size: f32, header: &[&str], rows: &[Vec<String>]) {
+        let border = "w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"808080\"";
+        let _ = write!(
+            self.body,
+            "<w:tbl><w:tblPr><w:tblW w:w=\"5000\" w:type=\"pct\"/><w:tblBorders>\
+             <w:top {border}/><w:left {border}/><w:bottom {border}/><w:right {border}/>\
+             <w:insideH {border}/><w:insideV {border}/></w:tblBorders></w:tblPr>"
+        );
+        let header = header.iter().map(|cell| (*cell).to_string()).collect();
+        for (index, cells) in std::iter::once(&header).chain(rows).enumerate() {
+            let font = if index == 0 {
+                Font::Bold
+            } else {
+                Font::Regular
+            };
+            self.body.push_str("<w:tr>");
+            for cell in cells {
+                let _ = write!(
+                    self.body,
+                    "<w:tc><w:p>{}</w:p></w:tc>",
+                    run(size, font, cell)
+                );
+            }
+            self.body.push_str("</w:tr>");
+        }
+        // a table cannot be the last thing before the section
+        self.body.push_str("</w:tbl><w:p/>");
+    }
+
+    /// packs the document as a DOCX file
+    #[must_use]
+    pub fn finish(self) -> Vec<u8> {
+        let document = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+             <w:body>{}{SECTION}</w:body></w:document>",
+            self.body
+        );
+        stored_zip(&[
+            ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+            ("_rels/.rels", RELATIONSHIPS.as_bytes()),
+            ("word/document.xml", document.as_bytes()),
+        ])
+    }
+}
+
+/// CRC-32 as ZIP files check their entries with
+#[must_use]
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFF_u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// a ZIP file with the given entries stored as they are, dated 1980-01-01
+fn stored_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    // version 2.0, no flags, stored, DOS time 00:00 and date 1980-01-01
+    const ENTRY_HEADER: [u8; 10] = [20, 0, 0, 0, 0, 0, 0, 0, 0x21, 0];
+    let mut zip = vec![];
+    let mut directory = vec![];
+    for (name, content) in entries {
+        let offset = u32::try_from(zip.len()).unwrap_or(u32::MAX);
+        let size = u32::try_from(content.len()).unwrap_or(u32::MAX);
+        let name_length = u16::try_from(name.len()).unwrap_or(u16::MAX);
+        let mut sizes = vec![];
+        sizes.extend_from_slice(&crc32(content).to_le_bytes());
+        sizes.extend_from_slice(&size.to_le_bytes());
+        sizes.extend_from_slice(&size.to_le_bytes());
+        sizes.extend_from_slice(&name_length.to_le_bytes());
+
+        zip.extend_from_slice(b"PK\x03\x04");
+        zip.extend_from_slice(&ENTRY_HEADER);
+        zip.extend_from_slice(&sizes);
+        zip.extend_from_slice(&0_u16.to_le_bytes());
+        zip.extend_from_slice(name.as_bytes());
+        zip.extend_from_slice(content);
+
+        directory.extend_from_slice(b"PK\x01\x02");
+        directory.extend_from_slice(&20_u16.to_le_bytes());
+        directory.extend_from_slice(&ENTRY_HEADER);
+        directory.extend_from_slice(&sizes);
+        // no extra field, comment, disk number or attributes
+        directory.extend_from_slice(&[0; 12]);
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+    let directory_offset = u32::try_from(zip.len()).unwrap_or(u32::MAX);
+    let directory_size = u32::try_from(directory.len()).unwrap_or(u32::MAX);
+    let count = u16::try_from(entries.len()).unwrap_or(u16::MAX);
+    zip.extend_from_slice(&directory);
+    zip.extend_from_slice(b"PK\x05\x06");
+    zip.extend_from_slice(&[0; 4]);
+    zip.extend_from_slice(&count.to_le_bytes());
+    zip.extend_from_slice(&count.to_le_bytes());
+    zip.extend_from_slice(&directory_size.to_le_bytes());
+    zip.extend_from_slice(&directory_offset.to_le_bytes());
+    zip.extend_from_slice(&0_u16.to_le_bytes());
+    zip
+}
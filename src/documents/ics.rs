@@ -0,0 +1,118 @@
+//! iCalendar (RFC 5545) feeds that calendar apps subscribe to.
+//!
+//! Times are written in UTC so that no time zone has to be described, and
+//! whole days, such as the day an instalment is due, as dates.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// When an event happens: at a time or on a whole day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcsTime {
+    At(DateTime<Utc>),
+    Day(NaiveDate),
+}
+
+impl IcsTime {
+    fn property(&self, name: &str) -> String {
+        match self {
+            Self::At(at) => format!("{name}:{}", at.format("%Y%m%dT%H%M%SZ")),
+            Self::Day(day) => format!("{name};VALUE=DATE:{}", day.format("%Y%m%d")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsEvent {
+    /// unique across calendars, such as the pid of the event and the domain
+    pub uid: String,
+    /// when the event was last changed
+    pub stamp: DateTime<Utc>,
+    pub start: IcsTime,
+    pub end: Option<IcsTime>,
+    pub summary: String,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    /// done events do not block time in the calendar
+    pub completed: bool,
+}
+
+/// Escapes the text of a property value
+#[must_use]
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Ends a content line, folding it so that no line is longer than 75 bytes
+/// and without breaking a character
+#[must_use]
+pub fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut length = 0;
+    for c in line.chars() {
+        // continuation lines start with a space, which counts
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Writes a calendar with the given name and events
+#[must_use]
+pub fn calendar(name: &str, events: &[IcsEvent]) -> String {
+    let mut ics = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//Johandler//Calendar//PT-BR",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+    ] {
+        ics.push_str(&fold(line));
+    }
+    ics.push_str(&fold(&format!("X-WR-CALNAME:{}", escape(name))));
+    for event in events {
+        ics.push_str(&fold("BEGIN:VEVENT"));
+        ics.push_str(&fold(&format!("UID:{}", escape(&event.uid))));
+        ics.push_str(&fold(&format!(
+            "DTSTAMP:{}",
+            event.stamp.format("%Y%m%dT%H%M%SZ")
+        )));
+        ics.push_str(&fold(&event.start.property("DTSTART")));
+        if let Some(end) = &event.end {
+            ics.push_str(&fold(&end.property("DTEND")));
+        }
+        ics.push_str(&fold(&format!("SUMMARY:{}", escape(&event.summary))));
+        if let Some(location) = &event.location {
+            ics.push_str(&fold(&format!("LOCATION:{}", escape(location))));
+        }
+        if let Some(description) = &event.description {
+            ics.push_str(&fold(&format!("DESCRIPTION:{}", escape(description))));
+        }
+        if let Some(category) = &event.category {
+            ics.push_str(&fold(&format!("CATEGORIES:{}", escape(category))));
+        }
+        if event.completed {
+            ics.push_str(&fold("TRANSP:TRANSPARENT"));
+        }
+        ics.push_str(&fold("END:VEVENT"));
+    }
+    ics.push_str(&fold("END:VCALENDAR"));
+    ics
+}
@@ -0,0 +1,90 @@
+//! The monthly journal handed to the firm's accountant: every line posted to
+//! the ledger in a period, as CSV, with the ledger accounts translated to the
+//! accountant's chart of accounts.
+
+use super::csv_row;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// The accountant's chart of accounts, read from the `settings.accounting`
+/// section of the configuration.
+///
+/// Ledger accounts are looked up by their full code, like `revenue:retainer`,
+/// and then by the code without the client, seller or partner they belong
+/// to, like `receivables`. The revenue of a fee type is looked up in
+/// `fee_types` first. Accounts that are not mapped keep their ledger code.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ChartOfAccounts {
+    #[serde(default)]
+    pub accounts: BTreeMap<String, String>,
+    #[serde(default)]
+    pub fee_types: BTreeMap<String, String>,
+}
+
+impl ChartOfAccounts {
+    /// reads the chart of accounts from the app settings, mapping nothing
+    /// when it is not configured
+    #[must_use]
+    pub fn from_settings(settings: Option<&serde_json::Value>) -> Self {
+        settings
+            .and_then(|settings| settings.get("accounting"))
+            .and_then(|accounting| serde_json::from_value(accounting.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// the code of the accountant's account a ledger account is exported to
+    #[must_use]
+    pub fn account_code(&self, ledger_code: &str) -> String {
+        let fee_type = ledger_code
+            .strip_prefix("revenue:")
+            .and_then(|fee_type| self.fee_types.get(fee_type));
+        let prefix = ledger_code.split(':').next().unwrap_or(ledger_code);
+        fee_type
+            .or_else(|| self.accounts.get(ledger_code))
+            .or_else(|| self.accounts.get(prefix))
+            .cloned()
+            .unwrap_or_else(|| ledger_code.to_string())
+    }
+}
+
+/// A line of the journal, with the ledger account it was posted to
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct JournalRow {
+    pub entry_date: chrono::NaiveDate,
+    pub entry_pid: Uuid,
+    pub ledger_code: String,
+    pub ledger_name: String,
+    pub debit: f32,
+    pub credit: f32,
+    pub description: String,
+}
+
+/// The first and the last day of a month written as `YYYY-MM`
+#[must_use]
+pub fn month_bounds(month: &str) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let starts_on = chrono::NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").ok()?;
+    let ends_on = starts_on
+        .checked_add_months(chrono::Months::new(1))?
+        .pred_opt()?;
+    Some((starts_on, ends_on))
+}
+
+/// Writes the journal as CSV, one row per line posted
+#[must_use]
+pub fn to_csv(rows: &[JournalRow], chart: &ChartOfAccounts) -> String {
+    let mut csv = String::from("date,entry,account,ledger_account,debit,credit,description\n");
+    for row in rows {
+        let fields = [
+            row.entry_date.to_string(),
+            row.entry_pid.to_string(),
+            chart.account_code(&row.ledger_code),
+            row.ledger_name.clone(),
+            format!("{:.2}", row.debit),
+            format!("{:.2}", row.credit),
+            row.description.clone(),
+        ];
+        csv.push_str(&csv_row(&fields));
+    }
+    csv
+}
@@ -0,0 +1,497 @@
+//! Printable documents handed to clients: payment receipts, order and client
+//! statements and contracts, rendered as PDF on the server with the firm
+//! letterhead.
+
+pub mod boleto;
+pub mod contract;
+pub mod docx;
+pub mod ics;
+pub mod itf;
+pub mod journal;
+pub mod pdf;
+pub mod pix;
+pub mod qrcode;
+
+use crate::models::clients::{format_document, TaxIdKind};
+use crate::views::clients::{ClientStatementView, StatementMovementKind};
+use crate::views::orders::{GetOrderReturn, OrderPayments};
+use pdf::{Font, Pdf};
+use pix::PixSettings;
+use qrcode::QrCode;
+use serde::{Deserialize, Serialize};
+
+/// The firm details printed at the top of every document, read from the
+/// `settings.letterhead` section of the configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Letterhead {
+    #[serde(default)]
+    pub name: String,
+    pub document: Option<String>,
+    pub address: Option<String>,
+    pub city: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Letterhead {
+    /// reads the letterhead from the app settings, leaving it blank when it
+    /// is not configured
+    #[must_use]
+    pub fn from_settings(settings: Option<&serde_json::Value>) -> Self {
+        settings
+            .and_then(|settings| settings.get("letterhead"))
+            .and_then(|letterhead| serde_json::from_value(letterhead.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    fn draw(&self, pdf: &mut Pdf) {
+        pdf.line(16.0, Font::Bold, &self.name);
+        if let Some(document) = &self.document {
+            pdf.line(9.0, Font::Regular, document);
+        }
+        if let Some(address) = &self.address {
+            pdf.line(9.0, Font::Regular, address);
+        }
+        let contact = [self.phone.as_deref(), self.email.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" • ");
+        if !contact.is_empty() {
+            pdf.line(9.0, Font::Regular, &contact);
+        }
+        pdf.rule();
+        pdf.space(12.0);
+    }
+}
+
+/// Writes a line of CSV, quoting the fields that hold a comma, a quote or a
+/// line break
+#[must_use]
+pub fn csv_row(fields: &[String]) -> String {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("{}\n", fields.join(","))
+}
+
+/// Formats an amount of money the way it is written in Brazil, as in
+/// `R$ 1.234,56`
+#[must_use]
+pub fn format_money(value: f32) -> String {
+    #[allow(clippy::cast_possible_truncation)]
+    let cents = (f64::from(value) * 100.0).round() as i64;
+    let units = (cents.abs() / 100).to_string();
+    let mut grouped = String::new();
+    for (index, digit) in units.chars().enumerate() {
+        if index > 0 && (units.len() - index).is_multiple_of(3) {
+            grouped.push('.');
+        }
+        grouped.push(digit);
+    }
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{sign}R$ {grouped},{:02}", cents.abs() % 100)
+}
+
+#[must_use]
+pub fn format_date(date: chrono::NaiveDate) -> String {
+    date.format("%d/%m/%Y").to_string()
+}
+
+/// The CPF or CNPJ written after the client's name, if the client has one
+fn client_document(document: Option<&str>) -> String {
+    document.map_or_else(String::new, |document| {
+        let kind = TaxIdKind::of(document).map_or("Documento", TaxIdKind::as_str);
+        format!(", {kind} {}", format_document(document))
+    })
+}
+
+fn payment_status(payment: &OrderPayments) -> &'static str {
+    match (payment.open, payment.postponed_payment) {
+        (false, _) => "Pago",
+        (true, Some(true)) => "Adiado",
+        (true, _) => "Em aberto",
+    }
+}
+
+/// Draws the PIX QR code and "copia e cola" payload of the next open
+/// instalment of the order, if there is one
+fn draw_next_pix(pdf: &mut Pdf, settings: Option<&PixSettings>, order: &GetOrderReturn) {
+    let Some(settings) = settings else {
+        return;
+    };
+    let Some((payment, payment_pid)) = order
+        .payments
+        .iter()
+        .filter(|payment| payment.open)
+        .filter_map(|payment| payment.pid.map(|pid| (payment, pid)))
+        .min_by_key(|(payment, _)| payment.due_date)
+    else {
+        return;
+    };
+    let payload = pix::payload(settings, payment.value, &pix::txid(payment_pid));
+    let Some(qr) = QrCode::encode(payload.as_bytes()) else {
+        return;
+    };
+    pdf.space(12.0);
+    pdf.line(12.0, Font::Bold, "Pague a próxima parcela com PIX");
+    pdf.line(
+        9.0,
+        Font::Regular,
+        &format!(
+            "Parcela com vencimento em {}, no valor de {}.",
+            format_date(payment.due_date),
+            format_money(payment.value)
+        ),
+    );
+    pdf.space(6.0);
+    pdf.qr_code(&qr, 120.0);
+    pdf.space(6.0);
+    pdf.line(9.0, Font::Bold, "PIX copia e cola:");
+    let chars = payload.chars().collect::<Vec<_>>();
+    for chunk in chars.chunks(80) {
+        pdf.line(8.0, Font::Regular, &chunk.iter().collect::<String>());
+    }
+}
+
+/// Renders the receipt of a settled instalment
+#[must_use]
+pub fn payment_receipt(
+    letterhead: &Letterhead,
+    pix: Option<&PixSettings>,
+    number: &str,
+    order: &GetOrderReturn,
+    payment: &OrderPayments,
+    issued_on: chrono::NaiveDate,
+) -> Vec<u8> {
+    let mut pdf = Pdf::new();
+    letterhead.draw(&mut pdf);
+
+    pdf.row(
+        14.0,
+        Font::Bold,
+        &[(0.0, "RECIBO"), (330.0, &format!("Nº {number}"))],
+    );
+    pdf.row(12.0, Font::Bold, &[(330.0, &format_money(payment.value))]);
+    pdf.space(16.0);
+
+    let paid_on = payment.payment_date.unwrap_or(issued_on);
+    pdf.line(
+        11.0,
+        Font::Regular,
+        &format!(
+            "Recebemos de {}{} a importância de {} referente à parcela com vencimento em {} \
+             dos honorários do processo {}, paga em {}.",
+            order.client.name,
+            client_document(order.client.document.as_deref()),
+            format_money(payment.value),
+            format_date(payment.due_date),
+            order.process.case_type,
+            format_date(paid_on),
+        ),
+    );
+    pdf.space(10.0);
+    if let Some(payment_method) = &payment.payment_method {
+        pdf.line(
+            10.0,
+            Font::Regular,
+            &format!("Forma de pagamento: {payment_method}"),
+        );
+    }
+    if let Some(currency) = &payment.currency {
+        pdf.line(10.0, Font::Regular, &format!("Moeda: {currency}"));
+    }
+    pdf.line(
+        10.0,
+        Font::Regular,
+        "Para maior clareza, firmamos o presente recibo, dando plena quitação do valor recebido.",
+    );
+    pdf.space(30.0);
+
+    let place = letterhead
+        .city
+        .as_ref()
+        .map_or_else(String::new, |city| format!("{city}, "));
+    pdf.line(
+        10.0,
+        Font::Regular,
+        &format!("{place}{}", format_date(issued_on)),
+    );
+    pdf.space(40.0);
+    pdf.line(
+        10.0,
+        Font::Regular,
+        "________________________________________",
+    );
+    pdf.line(10.0, Font::Bold, &letterhead.name);
+    draw_next_pix(&mut pdf, pix, order);
+    pdf.finish()
+}
+
+/// Renders the statement of an order with its fees, instalments and
+/// postponements
+#[must_use]
+pub fn order_statement(
+    letterhead: &Letterhead,
+    pix: Option<&PixSettings>,
+    number: &str,
+    order: &GetOrderReturn,
+    issued_on: chrono::NaiveDate,
+) -> Vec<u8> {
+    let mut pdf = Pdf::new();
+    letterhead.draw(&mut pdf);
+
+    pdf.row(
+        14.0,
+        Font::Bold,
+        &[(0.0, "EXTRATO DO PEDIDO"), (330.0, &format!("Nº {number}"))],
+    );
+    pdf.line(
+        9.0,
+        Font::Regular,
+        &format!("Emitido em {}", format_date(issued_on)),
+    );
+    pdf.space(8.0);
+    pdf.line(
+        10.0,
+        Font::Regular,
+        &format!(
+            "Cliente: {}{}",
+            order.client.name,
+            client_document(order.client.document.as_deref())
+        ),
+    );
+    pdf.line(
+        10.0,
+        Font::Regular,
+        &format!("Processo: {}", order.process.case_type),
+    );
+    pdf.space(12.0);
+
+    pdf.line(12.0, Font::Bold, "Honorários");
+    pdf.row(
+        9.0,
+        Font::Bold,
+        &[
+            (0.0, "Descrição"),
+            (250.0, "Tipo"),
+            (360.0, "Valor"),
+            (440.0, "Situação"),
+        ],
+    );
+    pdf.rule();
+    for fee in &order.fees {
+        pdf.row(
+            9.0,
+            Font::Regular,
+            &[
+                (0.0, &fee.fee),
+                (250.0, fee.r#type.as_deref().unwrap_or("-")),
+                (360.0, &format_money(fee.value)),
+                (440.0, if fee.open { "Em aberto" } else { "Quitado" }),
+            ],
+        );
+    }
+    pdf.space(12.0);
+
+    pdf.line(12.0, Font::Bold, "Parcelas");
+    pdf.row(
+        9.0,
+        Font::Bold,
+        &[
+            (0.0, "Vencimento"),
+            (90.0, "Valor"),
+            (180.0, "Pagamento"),
+            (270.0, "Situação"),
+            (350.0, "Adiamentos"),
+        ],
+    );
+    pdf.rule();
+    for payment in &order.payments {
+        let postponed_dates = payment
+            .postponed_dates
+            .as_ref()
+            .map(|dates| {
+                dates
+                    .iter()
+                    .map(|date| format_date(*date))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        pdf.row(
+            9.0,
+            Font::Regular,
+            &[
+                (0.0, &format_date(payment.due_date)),
+                (90.0, &format_money(payment.value)),
+                (
+                    180.0,
+                    &payment.payment_date.map(format_date).unwrap_or_default(),
+                ),
+                (270.0, payment_status(payment)),
+                (350.0, &postponed_dates),
+            ],
+        );
+    }
+    pdf.space(12.0);
+
+    pdf.rule();
+    let totals = [
+        ("Total contratado", order.fee),
+        ("Total recebido", order.net_received),
+        ("Saldo em aberto", order.outstanding_balance),
+    ];
+    for (label, value) in totals {
+        pdf.row(
+            10.0,
+            Font::Bold,
+            &[(270.0, label), (380.0, &format_money(value))],
+        );
+    }
+    draw_next_pix(&mut pdf, pix, order);
+    pdf.finish()
+}
+
+/// Renders the account statement of a client across all their orders, with
+/// the running balance as of the date of the statement
+#[must_use]
+pub fn client_statement(
+    letterhead: &Letterhead,
+    statement: &ClientStatementView,
+    issued_on: chrono::NaiveDate,
+) -> Vec<u8> {
+    let mut pdf = Pdf::new();
+    letterhead.draw(&mut pdf);
+
+    pdf.line(14.0, Font::Bold, "EXTRATO DO CLIENTE");
+    pdf.line(
+        9.0,
+        Font::Regular,
+        &format!(
+            "Posição em {}, emitido em {}",
+            format_date(statement.as_of),
+            format_date(issued_on)
+        ),
+    );
+    pdf.space(8.0);
+    pdf.line(
+        10.0,
+        Font::Regular,
+        &format!(
+            "Cliente: {}{}",
+            statement.client_name,
+            client_document(statement.client_document.as_deref())
+        ),
+    );
+    pdf.space(12.0);
+
+    for order in &statement.orders {
+        pdf.line(12.0, Font::Bold, &format!("Processo: {}", order.case_type));
+        pdf.row(
+            9.0,
+            Font::Bold,
+            &[
+                (0.0, "Vencimento"),
+                (90.0, "Valor"),
+                (180.0, "Créditos"),
+                (260.0, "Pagamento"),
+                (350.0, "Adiamentos"),
+            ],
+        );
+        pdf.rule();
+        for instalment in &order.instalments {
+            let postponed_dates = instalment
+                .postponed_dates
+                .iter()
+                .map(|date| format_date(*date))
+                .collect::<Vec<_>>()
+                .join(", ");
+            pdf.row(
+                9.0,
+                Font::Regular,
+                &[
+                    (0.0, &format_date(instalment.effective_due_date)),
+                    (90.0, &format_money(instalment.value)),
+                    (180.0, &format_money(instalment.credited)),
+                    (
+                        260.0,
+                        &instalment
+                            .payment_date
+                            .map_or_else(|| "Em aberto".to_string(), format_date),
+                    ),
+                    (350.0, &postponed_dates),
+                ],
+            );
+        }
+        pdf.space(12.0);
+    }
+
+    pdf.line(12.0, Font::Bold, "Movimentação");
+    pdf.row(
+        9.0,
+        Font::Bold,
+        &[
+            (0.0, "Data"),
+            (70.0, "Lançamento"),
+            (250.0, "Débito"),
+            (330.0, "Crédito"),
+            (410.0, "Saldo"),
+        ],
+    );
+    pdf.rule();
+    for movement in &statement.movements {
+        let case_type = statement
+            .orders
+            .iter()
+            .find(|order| order.pid == movement.order_pid)
+            .map(|order| order.case_type.as_str())
+            .unwrap_or_default();
+        let kind = match movement.kind {
+            StatementMovementKind::Instalment => "Parcela",
+            StatementMovementKind::Payment => "Pagamento",
+            StatementMovementKind::CreditNote => "Crédito",
+            StatementMovementKind::Refund => "Reembolso",
+        };
+        let amount = |value: f32| {
+            if value > 0.0 {
+                format_money(value)
+            } else {
+                String::new()
+            }
+        };
+        pdf.row(
+            9.0,
+            Font::Regular,
+            &[
+                (0.0, &format_date(movement.date)),
+                (70.0, &format!("{kind} - {case_type}")),
+                (250.0, &amount(movement.debit)),
+                (330.0, &amount(movement.credit)),
+                (410.0, &format_money(movement.balance)),
+            ],
+        );
+    }
+    pdf.space(12.0);
+
+    pdf.rule();
+    let totals = [
+        ("Saldo devedor", statement.totals.balance),
+        ("A vencer", statement.totals.not_yet_due),
+    ];
+    for (label, value) in totals {
+        pdf.row(
+            10.0,
+            Font::Bold,
+            &[(270.0, label), (380.0, &format_money(value))],
+        );
+    }
+    pdf.finish()
+}
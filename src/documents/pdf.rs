@@ -0,0 +1,255 @@
+//! A small PDF writer for the documents the firm hands to clients.
+//!
+//! It only knows what receipts and statements need: lines of text in the
+//! standard Helvetica fonts, horizontal rules, QR codes and page breaks on A4
+//! paper.
+//! Text is written with the `WinAnsiEncoding`, which covers the accented
+//! characters used in Portuguese.
+
+use super::qrcode::QrCode;
+use std::fmt::Write as _;
+
+pub const PAGE_WIDTH: f32 = 595.0;
+pub const PAGE_HEIGHT: f32 = 842.0;
+pub const MARGIN: f32 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    const fn resource(self) -> &'static str {
+        match self {
+            Self::Regular => "F1",
+            Self::Bold => "F2",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Pdf {
+    pages: Vec<Vec<u8>>,
+    current: Vec<u8>,
+    y: f32,
+}
+
+impl Default for Pdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pdf {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            pages: vec![],
+            current: vec![],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// starts a new page when less than `height` is left on the current one
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.pages.push(std::mem::take(&mut self.current));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    /// writes `text` at `x` on the current line without moving down
+    fn put(&mut self, x: f32, size: f32, font: Font, text: &str) {
+        let mut operators = String::new();
+        let _ = write!(
+            operators,
+            "BT /{} {size:.1} Tf {x:.2} {:.2} Td (",
+            font.resource(),
+            self.y
+        );
+        self.current.extend_from_slice(operators.as_bytes());
+        self.current.extend(encode(text));
+        self.current.extend_from_slice(b") Tj ET\n");
+    }
+
+    /// writes a line of text at the left margin, wrapping it to the page width
+    pub fn line(&mut self, size: f32, font: Font, text: &str) {
+        for wrapped in wrap(text, size, PAGE_WIDTH - 2.0 * MARGIN) {
+            self.reserve(size * 1.4);
+            self.y -= size * 1.4;
+            self.put(MARGIN, size, font, &wrapped);
+        }
+    }
+
+    /// writes a line of cells, each starting at its own distance from the
+    /// left margin
+    pub fn row(&mut self, size: f32, font: Font, cells: &[(f32, &str)]) {
+        self.reserve(size * 1.4);
+        self.y -= size * 1.4;
+        for (x, text) in cells {
+            self.put(MARGIN + x, size, font, text);
+        }
+    }
+
+    /// draws a horizontal rule across the page
+    pub fn rule(&mut self) {
+        self.reserve(8.0);
+        self.y -= 4.0;
+        let mut operators = String::new();
+        let _ = writeln!(
+            operators,
+            "0.5 w {MARGIN:.2} {y:.2} m {:.2} {y:.2} l S",
+            PAGE_WIDTH - MARGIN,
+            y = self.y
+        );
+        self.current.extend_from_slice(operators.as_bytes());
+        self.y -= 4.0;
+    }
+
+    /// draws a QR code `width` points wide at the left margin, with its dark
+    /// modules as filled squares
+    pub fn qr_code(&mut self, qr: &QrCode, width: f32) {
+        self.reserve(width);
+        #[allow(clippy::cast_precision_loss)]
+        let module = width / qr.size() as f32;
+        let mut operators = String::new();
+        for y in 0..qr.size() {
+            for x in 0..qr.size() {
+                if qr.module(x, y) {
+                    #[allow(clippy::cast_precision_loss)]
+                    let _ = write!(
+                        operators,
+                        "{:.2} {:.2} {module:.2} {module:.2} re ",
+                        MARGIN + x as f32 * module,
+                        self.y - (y + 1) as f32 * module,
+                    );
+                }
+            }
+        }
+        operators.push_str("f\n");
+        self.current.extend_from_slice(operators.as_bytes());
+        self.y -= width;
+    }
+
+    /// leaves `height` points of blank space
+    pub fn space(&mut self, height: f32) {
+        self.reserve(height);
+        self.y -= height;
+    }
+
+    /// lays out the pages and returns the bytes of the PDF file
+    #[must_use]
+    pub fn finish(mut self) -> Vec<u8> {
+        if !self.current.is_empty() || self.pages.is_empty() {
+            self.pages.push(std::mem::take(&mut self.current));
+        }
+        // objects: 1 catalog, 2 page tree, 3 and 4 fonts, then a page and its
+        // content stream for every page
+        let page_ids = (0..self.pages.len())
+            .map(|index| 5 + index * 2)
+            .collect::<Vec<_>>();
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids
+                    .iter()
+                    .map(|id| format!("{id} 0 R"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                page_ids.len()
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        ];
+        for (page_id, content) in page_ids.iter().zip(self.pages) {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    page_id + 1
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend(content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut file = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = vec![];
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(file.len());
+            file.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+            file.extend_from_slice(object);
+            file.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = file.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        file.extend(trailer.into_bytes());
+        file
+    }
+}
+
+/// Encodes text for a PDF string literal in `WinAnsiEncoding`, escaping the
+/// characters the syntax reserves and replacing what the encoding lacks
+fn encode(text: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    for c in text.chars() {
+        let byte = match c {
+            '€' => 0x80,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                c as u8
+            }
+            c if (' '..='~').contains(&c) || ('\u{a0}'..='\u{ff}').contains(&c) => {
+                u8::try_from(u32::from(c)).unwrap_or(b'?')
+            }
+            _ => b'?',
+        };
+        bytes.push(byte);
+    }
+    bytes
+}
+
+/// Splits text into lines that fit `width`, estimating Helvetica glyphs at
+/// half the font size
+fn wrap(text: &str, size: f32, width: f32) -> Vec<String> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let max_chars = (width / (size * 0.5)).max(1.0) as usize;
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
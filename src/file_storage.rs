@@ -0,0 +1,126 @@
+//! Where uploaded files are kept.
+//!
+//! Files go through the app's [`Storage`], so the attachments model only
+//! deals with keys and bytes and the backend is chosen by the `storage`
+//! section of the settings: a directory on the local disk by default, or a
+//! bucket of any S3-compatible service, such as AWS or a `MinIO` server.
+
+use std::sync::Arc;
+
+use loco_rs::storage::{
+    drivers::{self, object_store_adapter::ObjectStoreAdapter, StoreDriver},
+    Storage,
+};
+use loco_rs::{Error, Result};
+use object_store::aws::{AmazonS3Builder, AwsCredential};
+use object_store::StaticCredentialProvider;
+use serde::{Deserialize, Serialize};
+
+/// the name [`Storage::single`] gives its only store
+pub const STORE: &str = "store";
+
+/// where files are kept when the settings do not say
+const DEFAULT_ROOT: &str = "storage";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "driver", rename_all = "snake_case")]
+pub enum StorageSettings {
+    /// a directory on the disk of the server, created when missing
+    Local { root: String },
+    /// a bucket of an S3-compatible service; `endpoint` points to services
+    /// other than AWS, such as a `MinIO` server
+    S3 {
+        bucket: String,
+        #[serde(default = "default_region")]
+        region: String,
+        endpoint: Option<String>,
+        access_key_id: Option<String>,
+        secret_access_key: Option<String>,
+        /// lets the endpoint be plain HTTP, for a local stand-in
+        #[serde(default)]
+        allow_http: bool,
+    },
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+impl Default for StorageSettings {
+    fn default() -> Self {
+        Self::Local {
+            root: DEFAULT_ROOT.to_string(),
+        }
+    }
+}
+
+impl StorageSettings {
+    /// reads the storage from the app settings, keeping files on the local
+    /// disk when it is not configured
+    #[must_use]
+    pub fn from_settings(settings: Option<&serde_json::Value>) -> Self {
+        settings
+            .and_then(|settings| settings.get("storage"))
+            .and_then(|storage| serde_json::from_value(storage.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// builds the driver of the configured backend
+    ///
+    /// # Errors
+    ///
+    /// When the local directory could not be created or the bucket settings
+    /// are invalid
+    pub fn driver(&self) -> Result<Box<dyn StoreDriver>> {
+        match self {
+            Self::Local { root } => {
+                std::fs::create_dir_all(root)?;
+                drivers::local::new_with_prefix(root)
+            }
+            Self::S3 {
+                bucket,
+                region,
+                endpoint,
+                access_key_id,
+                secret_access_key,
+                allow_http,
+            } => {
+                let mut builder = AmazonS3Builder::new()
+                    .with_bucket_name(bucket)
+                    .with_region(region)
+                    .with_allow_http(*allow_http);
+                if let Some(endpoint) = endpoint {
+                    builder = builder.with_endpoint(endpoint);
+                }
+                match (access_key_id, secret_access_key) {
+                    (Some(key_id), Some(secret_key)) => {
+                        builder = builder.with_credentials(Arc::new(
+                            StaticCredentialProvider::new(AwsCredential {
+                                key_id: key_id.clone(),
+                                secret_key: secret_key.clone(),
+                                token: None,
+                            }),
+                        ));
+                    }
+                    (None, None) => {}
+                    _ => {
+                        return Err(Error::string(
+                            "storage needs both the access key id and the secret access key",
+                        ))
+                    }
+                }
+                let s3 = builder.build().map_err(Box::from)?;
+                Ok(Box::new(ObjectStoreAdapter::new(Box::new(s3))))
+            }
+        }
+    }
+
+    /// builds the app's storage with the configured backend as its only store
+    ///
+    /// # Errors
+    ///
+    /// When the driver could not be built
+    pub fn storage(&self) -> Result<Storage> {
+        Ok(Storage::single(self.driver()?))
+    }
+}
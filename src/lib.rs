@@ -1,8 +1,15 @@
 pub mod app;
+pub mod bank_statements;
+pub mod business_days;
 pub mod controllers;
+pub mod documents;
+pub mod file_storage;
 pub mod initializers;
 pub mod mailers;
 pub mod models;
+pub mod payment_providers;
+pub mod reports;
+pub mod search;
 pub mod tasks;
 pub mod views;
 pub mod workers;
@@ -14,14 +14,23 @@ pub struct Model {
     pub pid: Uuid,
     pub name: String,
     pub contact: String,
-    pub phone: String,
-    pub phone2: Option<String>,
-    pub email: String,
     pub partner_id: Option<i32>,
+    #[sea_orm(unique)]
+    pub document: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::attachments::Entity")]
+    Attachments,
+    #[sea_orm(has_many = "super::client_addresses::Entity")]
+    ClientAddresses,
+    #[sea_orm(has_many = "super::client_contacts::Entity")]
+    ClientContacts,
+    #[sea_orm(has_many = "super::client_merges::Entity")]
+    ClientMerges,
+    #[sea_orm(has_many = "super::notes::Entity")]
+    Notes,
     #[sea_orm(has_many = "super::orders::Entity")]
     Orders,
     #[sea_orm(
@@ -34,6 +43,36 @@ pub enum Relation {
     Partners,
 }
 
+impl Related<super::attachments::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attachments.def()
+    }
+}
+
+impl Related<super::client_addresses::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClientAddresses.def()
+    }
+}
+
+impl Related<super::client_contacts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClientContacts.def()
+    }
+}
+
+impl Related<super::client_merges::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClientMerges.def()
+    }
+}
+
+impl Related<super::notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notes.def()
+    }
+}
+
 impl Related<super::orders::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Orders.def()
@@ -2,15 +2,43 @@
 
 pub mod prelude;
 
+pub mod accounting_periods;
+pub mod attachments;
+pub mod bank_transactions;
+pub mod billing_plan_prices;
+pub mod billing_plans;
+pub mod calendar_events;
+pub mod calendar_feeds;
+pub mod client_addresses;
+pub mod client_contacts;
+pub mod client_merges;
 pub mod clients;
+pub mod contract_templates;
+pub mod contracts;
+pub mod credit_notes;
+pub mod document_counters;
+pub mod documents;
+pub mod expenses;
 pub mod fees;
+pub mod hourly_rates;
+pub mod journal_entries;
+pub mod journal_lines;
+pub mod lawsuits;
+pub mod ledger_accounts;
+pub mod note_revisions;
+pub mod notes;
 pub mod order_fees;
 pub mod orders;
 pub mod parties;
 pub mod partners;
+pub mod payment_charges;
 pub mod payments;
 pub mod postponed_payments;
 pub mod processes;
 pub mod processes_fees;
+pub mod provider_events;
+pub mod refunds;
 pub mod sellers;
+pub mod status_changes;
+pub mod time_entries;
 pub mod users;
@@ -22,6 +22,8 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::credit_notes::Entity")]
+    CreditNotes,
     #[sea_orm(
         belongs_to = "super::fees::Entity",
         from = "Column::FeeId",
@@ -40,6 +42,12 @@ pub enum Relation {
     Orders,
 }
 
+impl Related<super::credit_notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CreditNotes.def()
+    }
+}
+
 impl Related<super::fees::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Fees.def()
@@ -26,6 +26,12 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::attachments::Entity")]
+    Attachments,
+    #[sea_orm(has_many = "super::billing_plans::Entity")]
+    BillingPlans,
+    #[sea_orm(has_many = "super::calendar_events::Entity")]
+    CalendarEvents,
     #[sea_orm(
         belongs_to = "super::clients::Entity",
         from = "Column::ClientId",
@@ -34,6 +40,20 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Clients,
+    #[sea_orm(has_many = "super::contracts::Entity")]
+    Contracts,
+    #[sea_orm(has_many = "super::credit_notes::Entity")]
+    CreditNotes,
+    #[sea_orm(has_many = "super::documents::Entity")]
+    Documents,
+    #[sea_orm(has_many = "super::expenses::Entity")]
+    Expenses,
+    #[sea_orm(has_many = "super::hourly_rates::Entity")]
+    HourlyRates,
+    #[sea_orm(has_one = "super::lawsuits::Entity")]
+    Lawsuits,
+    #[sea_orm(has_many = "super::notes::Entity")]
+    Notes,
     #[sea_orm(has_many = "super::order_fees::Entity")]
     OrderFees,
     #[sea_orm(has_many = "super::payments::Entity")]
@@ -46,6 +66,8 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Processes,
+    #[sea_orm(has_many = "super::refunds::Entity")]
+    Refunds,
     #[sea_orm(
         belongs_to = "super::sellers::Entity",
         from = "Column::SellerId",
@@ -54,6 +76,28 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Sellers,
+    #[sea_orm(has_many = "super::status_changes::Entity")]
+    StatusChanges,
+    #[sea_orm(has_many = "super::time_entries::Entity")]
+    TimeEntries,
+}
+
+impl Related<super::attachments::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attachments.def()
+    }
+}
+
+impl Related<super::billing_plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BillingPlans.def()
+    }
+}
+
+impl Related<super::calendar_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CalendarEvents.def()
+    }
 }
 
 impl Related<super::clients::Entity> for Entity {
@@ -62,6 +106,48 @@ impl Related<super::clients::Entity> for Entity {
     }
 }
 
+impl Related<super::contracts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Contracts.def()
+    }
+}
+
+impl Related<super::credit_notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CreditNotes.def()
+    }
+}
+
+impl Related<super::documents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Documents.def()
+    }
+}
+
+impl Related<super::expenses::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Expenses.def()
+    }
+}
+
+impl Related<super::hourly_rates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HourlyRates.def()
+    }
+}
+
+impl Related<super::lawsuits::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Lawsuits.def()
+    }
+}
+
+impl Related<super::notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notes.def()
+    }
+}
+
 impl Related<super::order_fees::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::OrderFees.def()
@@ -80,8 +166,26 @@ impl Related<super::processes::Entity> for Entity {
     }
 }
 
+impl Related<super::refunds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Refunds.def()
+    }
+}
+
 impl Related<super::sellers::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Sellers.def()
     }
 }
+
+impl Related<super::status_changes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StatusChanges.def()
+    }
+}
+
+impl Related<super::time_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TimeEntries.def()
+    }
+}
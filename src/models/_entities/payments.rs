@@ -21,10 +21,33 @@ pub struct Model {
     pub postponed_payment: Option<bool>,
     pub order_id: i32,
     pub open: bool,
+    pub billing_plan_id: Option<i32>,
+    pub period_date: Option<Date>,
+    pub nosso_numero: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::attachments::Entity")]
+    Attachments,
+    #[sea_orm(has_many = "super::bank_transactions::Entity")]
+    BankTransactions,
+    #[sea_orm(
+        belongs_to = "super::billing_plans::Entity",
+        from = "Column::BillingPlanId",
+        to = "super::billing_plans::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    BillingPlans,
+    #[sea_orm(has_many = "super::credit_notes::Entity")]
+    CreditNotes,
+    #[sea_orm(has_many = "super::documents::Entity")]
+    Documents,
+    #[sea_orm(has_many = "super::expenses::Entity")]
+    Expenses,
+    #[sea_orm(has_many = "super::notes::Entity")]
+    Notes,
     #[sea_orm(
         belongs_to = "super::orders::Entity",
         from = "Column::OrderId",
@@ -33,8 +56,56 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Orders,
+    #[sea_orm(has_many = "super::payment_charges::Entity")]
+    PaymentCharges,
     #[sea_orm(has_many = "super::postponed_payments::Entity")]
     PostponedPayments,
+    #[sea_orm(has_many = "super::refunds::Entity")]
+    Refunds,
+    #[sea_orm(has_many = "super::time_entries::Entity")]
+    TimeEntries,
+}
+
+impl Related<super::attachments::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attachments.def()
+    }
+}
+
+impl Related<super::bank_transactions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BankTransactions.def()
+    }
+}
+
+impl Related<super::billing_plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BillingPlans.def()
+    }
+}
+
+impl Related<super::credit_notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CreditNotes.def()
+    }
+}
+
+impl Related<super::documents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Documents.def()
+    }
+}
+
+impl Related<super::expenses::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Expenses.def()
+    }
+}
+
+impl Related<super::notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notes.def()
+    }
 }
 
 impl Related<super::orders::Entity> for Entity {
@@ -43,8 +114,26 @@ impl Related<super::orders::Entity> for Entity {
     }
 }
 
+impl Related<super::payment_charges::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PaymentCharges.def()
+    }
+}
+
 impl Related<super::postponed_payments::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::PostponedPayments.def()
     }
 }
+
+impl Related<super::refunds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Refunds.def()
+    }
+}
+
+impl Related<super::time_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TimeEntries.def()
+    }
+}
@@ -1,14 +1,42 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.3
 
+pub use super::accounting_periods::Entity as AccountingPeriods;
+pub use super::attachments::Entity as Attachments;
+pub use super::bank_transactions::Entity as BankTransactions;
+pub use super::billing_plan_prices::Entity as BillingPlanPrices;
+pub use super::billing_plans::Entity as BillingPlans;
+pub use super::calendar_events::Entity as CalendarEvents;
+pub use super::calendar_feeds::Entity as CalendarFeeds;
+pub use super::client_addresses::Entity as ClientAddresses;
+pub use super::client_contacts::Entity as ClientContacts;
+pub use super::client_merges::Entity as ClientMerges;
 pub use super::clients::Entity as Clients;
+pub use super::contract_templates::Entity as ContractTemplates;
+pub use super::contracts::Entity as Contracts;
+pub use super::credit_notes::Entity as CreditNotes;
+pub use super::document_counters::Entity as DocumentCounters;
+pub use super::documents::Entity as Documents;
+pub use super::expenses::Entity as Expenses;
 pub use super::fees::Entity as Fees;
+pub use super::hourly_rates::Entity as HourlyRates;
+pub use super::journal_entries::Entity as JournalEntries;
+pub use super::journal_lines::Entity as JournalLines;
+pub use super::lawsuits::Entity as Lawsuits;
+pub use super::ledger_accounts::Entity as LedgerAccounts;
+pub use super::note_revisions::Entity as NoteRevisions;
+pub use super::notes::Entity as Notes;
 pub use super::order_fees::Entity as OrderFees;
 pub use super::orders::Entity as Orders;
 pub use super::parties::Entity as Parties;
 pub use super::partners::Entity as Partners;
+pub use super::payment_charges::Entity as PaymentCharges;
 pub use super::payments::Entity as Payments;
 pub use super::postponed_payments::Entity as PostponedPayments;
 pub use super::processes::Entity as Processes;
 pub use super::processes_fees::Entity as ProcessesFees;
+pub use super::provider_events::Entity as ProviderEvents;
+pub use super::refunds::Entity as Refunds;
 pub use super::sellers::Entity as Sellers;
+pub use super::status_changes::Entity as StatusChanges;
+pub use super::time_entries::Entity as TimeEntries;
 pub use super::users::Entity as Users;
@@ -17,12 +17,20 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::contract_templates::Entity")]
+    ContractTemplates,
     #[sea_orm(has_many = "super::orders::Entity")]
     Orders,
     #[sea_orm(has_many = "super::processes_fees::Entity")]
     ProcessesFees,
 }
 
+impl Related<super::contract_templates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContractTemplates.def()
+    }
+}
+
 impl Related<super::orders::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Orders.def()
@@ -26,8 +26,74 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::attachments::Entity")]
+    Attachments,
+    #[sea_orm(has_many = "super::calendar_events::Entity")]
+    CalendarEvents,
+    #[sea_orm(has_many = "super::calendar_feeds::Entity")]
+    CalendarFeeds,
+    #[sea_orm(has_many = "super::client_merges::Entity")]
+    ClientMerges,
+    #[sea_orm(has_many = "super::contracts::Entity")]
+    Contracts,
+    #[sea_orm(has_many = "super::hourly_rates::Entity")]
+    HourlyRates,
+    #[sea_orm(has_many = "super::note_revisions::Entity")]
+    NoteRevisions,
+    #[sea_orm(has_many = "super::notes::Entity")]
+    Notes,
     #[sea_orm(has_many = "super::parties::Entity")]
     Parties,
+    #[sea_orm(has_many = "super::time_entries::Entity")]
+    TimeEntries,
+}
+
+impl Related<super::attachments::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attachments.def()
+    }
+}
+
+impl Related<super::calendar_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CalendarEvents.def()
+    }
+}
+
+impl Related<super::calendar_feeds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CalendarFeeds.def()
+    }
+}
+
+impl Related<super::client_merges::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClientMerges.def()
+    }
+}
+
+impl Related<super::contracts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Contracts.def()
+    }
+}
+
+impl Related<super::hourly_rates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::HourlyRates.def()
+    }
+}
+
+impl Related<super::note_revisions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::NoteRevisions.def()
+    }
+}
+
+impl Related<super::notes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notes.def()
+    }
 }
 
 impl Related<super::parties::Entity> for Entity {
@@ -35,3 +101,9 @@ impl Related<super::parties::Entity> for Entity {
         Relation::Parties.def()
     }
 }
+
+impl Related<super::time_entries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TimeEntries.def()
+    }
+}
@@ -0,0 +1,109 @@
+use super::_entities::accounting_periods::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type AccountingPeriods = Entity;
+use crate::documents::journal::{self, ChartOfAccounts};
+use crate::models::_entities::{accounting_periods, journal_entries};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExportJournal {
+    pub month: String,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::accounting_periods::Model {
+    /// finds all locked periods, the latest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let accounting_periods = Entity::find()
+            .order_by_desc(accounting_periods::Column::StartsOn)
+            .all(db)
+            .await?;
+        Ok(accounting_periods)
+    }
+
+    /// finds the locked period a date falls in, if any
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_locked<C>(db: &C, date: chrono::NaiveDate) -> ModelResult<Option<Self>>
+    where
+        C: ConnectionTrait,
+    {
+        let accounting_period = Entity::find()
+            .filter(
+                model::query::condition()
+                    .lte(accounting_periods::Column::StartsOn, date)
+                    .gte(accounting_periods::Column::EndsOn, date)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        Ok(accounting_period)
+    }
+
+    /// exports the journal of a month, written as `YYYY-MM`, as CSV and
+    /// locks the month, so what the accountant received cannot change
+    /// afterwards. A locked month can be exported again.
+    ///
+    /// # Errors
+    ///
+    /// When the month is not valid or DB query error
+    pub async fn export(
+        db: &DatabaseConnection,
+        month: &str,
+        chart: &ChartOfAccounts,
+    ) -> ModelResult<String> {
+        let (starts_on, ends_on) = journal::month_bounds(month)
+            .ok_or_else(|| ModelError::Any("month must be written as YYYY-MM".into()))?;
+        let txn = db.begin().await?;
+        let locked = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(accounting_periods::Column::StartsOn, starts_on)
+                    .build(),
+            )
+            .one(&txn)
+            .await?;
+        if locked.is_none() {
+            accounting_periods::ActiveModel {
+                starts_on: ActiveValue::Set(starts_on),
+                ends_on: ActiveValue::Set(ends_on),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+        let rows = journal_entries::Model::find_rows(&txn, starts_on, ends_on).await?;
+        txn.commit().await?;
+        Ok(journal::to_csv(&rows, chart))
+    }
+}
@@ -0,0 +1,623 @@
+use super::_entities::attachments::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type Attachments = Entity;
+use crate::file_storage::STORE;
+use crate::models::_entities::{attachments, clients, expenses, orders, payments, users};
+use bytes::Bytes;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult, ModelValidation};
+use loco_rs::storage::{drivers::GetResponse, Storage};
+use sea_orm::sea_query::{Query, SelectStatement};
+use sea_orm::{ActiveValue, Condition, PaginatorTrait, QueryOrder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// the largest file accepted when the settings do not say, 20 MiB
+pub const DEFAULT_MAX_SIZE: u64 = 20 * 1024 * 1024;
+
+/// What a file kept with a record is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentCategory {
+    Contract,
+    PowerOfAttorney,
+    PaymentProof,
+    CourtFiling,
+    Other,
+}
+
+impl AttachmentCategory {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Contract => "contract",
+            Self::PowerOfAttorney => "power_of_attorney",
+            Self::PaymentProof => "payment_proof",
+            Self::CourtFiling => "court_filing",
+            Self::Other => "other",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(category: &str) -> Option<Self> {
+        match category {
+            "contract" => Some(Self::Contract),
+            "power_of_attorney" => Some(Self::PowerOfAttorney),
+            "payment_proof" => Some(Self::PaymentProof),
+            "court_filing" => Some(Self::CourtFiling),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+/// What an attachment is kept with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentTarget {
+    Client(i32),
+    Order(i32),
+    Payment(i32),
+    Expense(i32),
+}
+
+impl AttachmentTarget {
+    /// finds what the pid given is of, when exactly one is given
+    ///
+    /// # Errors
+    ///
+    /// When none or more than one pid is given, could not find what it is of
+    /// or DB query error
+    pub async fn find(
+        db: &DatabaseConnection,
+        client_pid: Option<Uuid>,
+        order_pid: Option<Uuid>,
+        payment_pid: Option<Uuid>,
+        expense_pid: Option<Uuid>,
+    ) -> ModelResult<Self> {
+        match (client_pid, order_pid, payment_pid, expense_pid) {
+            (Some(pid), None, None, None) => {
+                let client = clients::Model::find_by_pid(db, pid).await?;
+                Ok(Self::Client(client.id))
+            }
+            (None, Some(pid), None, None) => {
+                let order = orders::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(orders::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                Ok(Self::Order(order.id))
+            }
+            (None, None, Some(pid), None) => {
+                let payment = payments::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(payments::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                Ok(Self::Payment(payment.id))
+            }
+            (None, None, None, Some(pid)) => {
+                let expense = expenses::Model::find_by_pid(db, pid).await?;
+                Ok(Self::Expense(expense.id))
+            }
+            _ => Err(ModelError::Any(
+                "an attachment is kept with exactly one of a client, an order, a payment or an \
+                 expense"
+                    .into(),
+            )),
+        }
+    }
+
+    fn condition(self) -> Condition {
+        match self {
+            Self::Client(id) => Condition::all().add(attachments::Column::ClientId.eq(id)),
+            Self::Order(id) => Condition::all().add(attachments::Column::OrderId.eq(id)),
+            Self::Payment(id) => Condition::all().add(attachments::Column::PaymentId.eq(id)),
+            Self::Expense(id) => Condition::all().add(attachments::Column::ExpenseId.eq(id)),
+        }
+    }
+}
+
+/// A file sent to be kept with a record
+#[derive(Debug, Clone)]
+pub struct Upload {
+    pub category: AttachmentCategory,
+    pub file_name: String,
+    pub content_type: String,
+    pub content: Bytes,
+    /// the SHA-256 the sender worked out, in hex, checked against the
+    /// content when given
+    pub checksum: Option<String>,
+}
+
+/// The files accepted as attachments, from the `attachments` section of the
+/// settings
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AttachmentRules {
+    /// the largest file accepted, in bytes
+    #[serde(default = "default_max_size")]
+    pub max_size: u64,
+    #[serde(default = "default_content_types")]
+    pub content_types: Vec<String>,
+}
+
+const fn default_max_size() -> u64 {
+    DEFAULT_MAX_SIZE
+}
+
+fn default_content_types() -> Vec<String> {
+    [
+        "application/pdf",
+        "image/jpeg",
+        "image/png",
+        "application/msword",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "text/plain",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
+impl Default for AttachmentRules {
+    fn default() -> Self {
+        Self {
+            max_size: default_max_size(),
+            content_types: default_content_types(),
+        }
+    }
+}
+
+/// the bytes files of a content type start with, for the types that have
+/// them
+fn signature(content_type: &str) -> Option<&'static [u8]> {
+    match content_type {
+        "application/pdf" => Some(b"%PDF-"),
+        "image/jpeg" => Some(&[0xFF, 0xD8, 0xFF]),
+        "image/png" => Some(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]),
+        "application/msword" => Some(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]),
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => {
+            Some(b"PK\x03\x04")
+        }
+        _ => None,
+    }
+}
+
+/// the error of an upload that is not accepted, with the reason
+fn rejected(reason: String) -> ModelError {
+    ModelError::ModelValidation {
+        errors: ModelValidation {
+            code: "invalid_attachment".to_string(),
+            message: Some(reason),
+        },
+    }
+}
+
+/// the content type without its parameters, in lowercase
+#[must_use]
+pub fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// the name of the file without the folders some browsers send with it
+#[must_use]
+pub fn clean_file_name(file_name: &str) -> String {
+    file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// the SHA-256 of the content, in lowercase hex
+#[must_use]
+pub fn checksum(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+impl AttachmentRules {
+    /// reads the rules from the app settings, accepting documents and images
+    /// of up to [`DEFAULT_MAX_SIZE`] when they are not configured
+    #[must_use]
+    pub fn from_settings(settings: Option<&serde_json::Value>) -> Self {
+        settings
+            .and_then(|settings| settings.get("attachments"))
+            .and_then(|attachments| serde_json::from_value(attachments.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// checks the upload is a non-empty file of an accepted type, no larger
+    /// than allowed, whose content is of the type it claims to be
+    ///
+    /// # Errors
+    ///
+    /// When the upload is not accepted, as a validation error with the
+    /// reason
+    pub fn check(&self, upload: &Upload) -> ModelResult<()> {
+        if clean_file_name(&upload.file_name).is_empty() {
+            return Err(rejected("the file has no name".to_string()));
+        }
+        if upload.content.is_empty() {
+            return Err(rejected("the file is empty".to_string()));
+        }
+        if upload.content.len() as u64 > self.max_size {
+            return Err(rejected(format!(
+                "the file is larger than {} bytes",
+                self.max_size
+            )));
+        }
+        let content_type = media_type(&upload.content_type);
+        if !self
+            .content_types
+            .iter()
+            .any(|accepted| media_type(accepted) == content_type)
+        {
+            return Err(rejected(format!(
+                "files of type {content_type} are not accepted"
+            )));
+        }
+        if signature(&content_type).is_some_and(|signature| !upload.content.starts_with(signature))
+        {
+            return Err(rejected(format!(
+                "the content of the file is not {content_type}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Who uploaded the attachments and what they are kept with
+#[derive(Debug, Clone, Default)]
+pub struct AttachmentLinks {
+    pub users: Vec<users::Model>,
+    pub clients: Vec<clients::Model>,
+    pub orders: Vec<orders::Model>,
+    pub payments: Vec<payments::Model>,
+    pub expenses: Vec<expenses::Model>,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::attachments::Model {
+    /// finds an attachment by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find attachment by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let attachment = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(attachments::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        attachment.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the attachments kept with a client, an order, a payment or an
+    /// expense, the latest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find attachments or DB query error
+    pub async fn find_by_target(
+        db: &DatabaseConnection,
+        target: AttachmentTarget,
+    ) -> ModelResult<Vec<Self>> {
+        let attachments = Entity::find()
+            .filter(target.condition())
+            .order_by_desc(attachments::Column::CreatedAt)
+            .all(db)
+            .await?;
+        Ok(attachments)
+    }
+
+    /// whether files are kept with a record or with what deleting it would
+    /// delete along: the orders of a client and the payments and expenses of
+    /// its orders, or the payments and expenses of an order. Their
+    /// attachments keep them from being deleted, so signed contracts and
+    /// payment proofs are not lost.
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn any_kept_with(
+        db: &DatabaseConnection,
+        target: AttachmentTarget,
+    ) -> ModelResult<bool> {
+        let orders_of_client = |client_id: i32| {
+            Query::select()
+                .column(orders::Column::Id)
+                .from(orders::Entity)
+                .and_where(orders::Column::ClientId.eq(client_id))
+                .to_owned()
+        };
+        let held_by_orders = |orders: SelectStatement| {
+            Condition::any()
+                .add(attachments::Column::OrderId.in_subquery(orders.clone()))
+                .add(
+                    attachments::Column::PaymentId.in_subquery(
+                        Query::select()
+                            .column(payments::Column::Id)
+                            .from(payments::Entity)
+                            .and_where(payments::Column::OrderId.in_subquery(orders.clone()))
+                            .to_owned(),
+                    ),
+                )
+                .add(
+                    attachments::Column::ExpenseId.in_subquery(
+                        Query::select()
+                            .column(expenses::Column::Id)
+                            .from(expenses::Entity)
+                            .and_where(expenses::Column::OrderId.in_subquery(orders))
+                            .to_owned(),
+                    ),
+                )
+        };
+        let condition = match target {
+            AttachmentTarget::Client(id) => Condition::any()
+                .add(target.condition())
+                .add(held_by_orders(orders_of_client(id))),
+            AttachmentTarget::Order(id) => held_by_orders(
+                Query::select()
+                    .column(orders::Column::Id)
+                    .from(orders::Entity)
+                    .and_where(orders::Column::Id.eq(id))
+                    .to_owned(),
+            ),
+            AttachmentTarget::Payment(_) | AttachmentTarget::Expense(_) => target.condition(),
+        };
+        let count = Entity::find().filter(condition).count(db).await?;
+        Ok(count > 0)
+    }
+
+    /// finds who uploaded the given attachments and what they are kept with
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_links(
+        db: &DatabaseConnection,
+        attachments: &[Self],
+    ) -> ModelResult<AttachmentLinks> {
+        let users = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        users::Column::Id,
+                        attachments
+                            .iter()
+                            .filter_map(|attachment| attachment.user_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let clients = clients::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        clients::Column::Id,
+                        attachments
+                            .iter()
+                            .filter_map(|attachment| attachment.client_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let orders = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        orders::Column::Id,
+                        attachments
+                            .iter()
+                            .filter_map(|attachment| attachment.order_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let payments = payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        payments::Column::Id,
+                        attachments
+                            .iter()
+                            .filter_map(|attachment| attachment.payment_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let expenses = expenses::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        expenses::Column::Id,
+                        attachments
+                            .iter()
+                            .filter_map(|attachment| attachment.expense_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(AttachmentLinks {
+            users,
+            clients,
+            orders,
+            payments,
+            expenses,
+        })
+    }
+
+    /// checks an upload against the rules and keeps it in the storage with
+    /// what it is attached to, uploaded by the given user
+    ///
+    /// # Errors
+    ///
+    /// When the upload is not accepted, its checksum does not match, could
+    /// not write to the storage or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        storage: &Storage,
+        rules: &AttachmentRules,
+        user_id: Option<i32>,
+        target: AttachmentTarget,
+        upload: Upload,
+    ) -> ModelResult<Self> {
+        rules.check(&upload)?;
+        if let Some(expected) = &upload.checksum {
+            if !expected
+                .trim()
+                .eq_ignore_ascii_case(&checksum(&upload.content))
+            {
+                return Err(rejected(
+                    "the checksum does not match the content of the file".to_string(),
+                ));
+            }
+        }
+        Self::store(db, storage, user_id, target, upload).await
+    }
+
+    /// keeps a file in the storage with what it is attached to, without
+    /// checking it against the rules, for the files the app generates
+    ///
+    /// # Errors
+    ///
+    /// When could not write to the storage or DB query error
+    pub async fn store(
+        db: &DatabaseConnection,
+        storage: &Storage,
+        user_id: Option<i32>,
+        target: AttachmentTarget,
+        upload: Upload,
+    ) -> ModelResult<Self> {
+        let checksum = checksum(&upload.content);
+        let (client_id, order_id, payment_id, expense_id) = match target {
+            AttachmentTarget::Client(id) => (Some(id), None, None, None),
+            AttachmentTarget::Order(id) => (None, Some(id), None, None),
+            AttachmentTarget::Payment(id) => (None, None, Some(id), None),
+            AttachmentTarget::Expense(id) => (None, None, None, Some(id)),
+        };
+        let storage_key = format!("attachments/{}", Uuid::new_v4().simple());
+        storage
+            .upload(Path::new(&storage_key), &upload.content)
+            .await
+            .map_err(|err| ModelError::Any(err.into()))?;
+        let attachment = attachments::ActiveModel {
+            category: ActiveValue::Set(upload.category.as_str().to_string()),
+            file_name: ActiveValue::Set(clean_file_name(&upload.file_name)),
+            content_type: ActiveValue::Set(media_type(&upload.content_type)),
+            size: ActiveValue::Set(i64::try_from(upload.content.len()).unwrap_or(i64::MAX)),
+            checksum: ActiveValue::Set(checksum),
+            storage_key: ActiveValue::Set(storage_key.clone()),
+            user_id: ActiveValue::Set(user_id),
+            client_id: ActiveValue::Set(client_id),
+            order_id: ActiveValue::Set(order_id),
+            payment_id: ActiveValue::Set(payment_id),
+            expense_id: ActiveValue::Set(expense_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await;
+        match attachment {
+            Ok(attachment) => Ok(attachment),
+            Err(err) => {
+                // the file is of no use without its record
+                if let Err(storage_err) = storage.delete(Path::new(&storage_key)).await {
+                    tracing::warn!(
+                        storage_key,
+                        message = storage_err.to_string(),
+                        "could not remove the file of an attachment that was not saved",
+                    );
+                }
+                Err(err.into())
+            }
+        }
+    }
+
+    /// opens the file of the attachment in the storage, to be read as a
+    /// stream
+    ///
+    /// # Errors
+    ///
+    /// When the file is missing or could not read from the storage
+    pub async fn open(&self, storage: &Storage) -> ModelResult<GetResponse> {
+        let store = storage
+            .as_store_err(STORE)
+            .map_err(|err| ModelError::Any(err.into()))?;
+        store
+            .get(Path::new(&self.storage_key))
+            .await
+            .map_err(|err| ModelError::Any(err.into()))
+    }
+
+    /// deletes an attachment and then its file, which is left behind when
+    /// the storage could not remove it
+    ///
+    /// # Errors
+    ///
+    /// When could not find attachment by the given pid or DB query error
+    pub async fn delete(
+        db: &DatabaseConnection,
+        storage: &Storage,
+        pid: Uuid,
+    ) -> ModelResult<Self> {
+        let existing_attachment = Self::find_by_pid(db, pid).await?;
+        existing_attachment.clone().delete(db).await?;
+        if let Err(err) = storage
+            .delete(Path::new(&existing_attachment.storage_key))
+            .await
+        {
+            tracing::warn!(
+                storage_key = existing_attachment.storage_key,
+                message = err.to_string(),
+                "could not remove the file of a deleted attachment",
+            );
+        }
+        Ok(existing_attachment)
+    }
+}
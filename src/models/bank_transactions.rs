@@ -0,0 +1,407 @@
+use super::_entities::bank_transactions::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type BankTransactions = Entity;
+use crate::bank_statements::{self, StatementLine};
+use crate::documents::pix;
+use crate::models::_entities::{bank_transactions, journal_entries, payments};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, IntoActiveModel, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// how many days apart a credit and the due date of an instalment with the
+/// same value can be for them to be matched
+pub const DATE_TOLERANCE_DAYS: i64 = 5;
+/// how far apart a credit and the value of an instalment can be for them to
+/// be considered the same amount
+pub const AMOUNT_TOLERANCE: f32 = 0.005;
+
+/// Where a bank transaction stands in reconciliation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// waiting in the review queue for someone to match it by hand
+    Unmatched,
+    /// matched to an instalment, waiting for confirmation
+    Proposed,
+    /// settled the instalment it was matched to
+    Confirmed,
+    /// not an instalment, such as a transfer between the firm's accounts
+    Ignored,
+}
+
+impl TransactionStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unmatched => "unmatched",
+            Self::Proposed => "proposed",
+            Self::Confirmed => "confirmed",
+            Self::Ignored => "ignored",
+        }
+    }
+}
+
+/// How a bank transaction was matched to an instalment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchRule {
+    NossoNumero,
+    Txid,
+    Amount,
+    Manual,
+}
+
+impl MatchRule {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NossoNumero => "nosso_numero",
+            Self::Txid => "txid",
+            Self::Amount => "amount",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportBankStatement {
+    pub content: String,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+/// Finds the open instalment a credit pays, trying its "nosso número", then
+/// the PIX txid in its description, then an instalment of the same value due
+/// around the date of the credit. Instalments in `taken` are already matched
+/// to other credits and are left out.
+#[must_use]
+pub fn propose_match(
+    line: &StatementLine,
+    open_payments: &[payments::Model],
+    taken: &HashSet<i32>,
+) -> Option<(i32, MatchRule)> {
+    let candidates = open_payments
+        .iter()
+        .filter(|payment| payment.open && !taken.contains(&payment.id))
+        .collect::<Vec<_>>();
+    let description = line.description.to_uppercase();
+
+    let by_nosso_numero = candidates.iter().find(|payment| {
+        payment.nosso_numero.as_ref().is_some_and(|nosso_numero| {
+            line.nosso_numero.as_ref() == Some(nosso_numero) || description.contains(nosso_numero)
+        })
+    });
+    if let Some(payment) = by_nosso_numero {
+        return Some((payment.id, MatchRule::NossoNumero));
+    }
+
+    let by_txid = candidates
+        .iter()
+        .find(|payment| description.contains(&pix::txid(payment.pid)));
+    if let Some(payment) = by_txid {
+        return Some((payment.id, MatchRule::Txid));
+    }
+
+    let mut by_amount = candidates
+        .iter()
+        .filter(|payment| (payment.value - line.value).abs() < AMOUNT_TOLERANCE)
+        .map(|payment| ((payment.due_date - line.date).num_days().abs(), payment))
+        .filter(|(days, _)| *days <= DATE_TOLERANCE_DAYS)
+        .collect::<Vec<_>>();
+    by_amount.sort_by_key(|(days, _)| *days);
+    match by_amount.as_slice() {
+        [(days, payment), (next_days, _), ..] if days < next_days => {
+            Some((payment.id, MatchRule::Amount))
+        }
+        [(_, payment)] => Some((payment.id, MatchRule::Amount)),
+        _ => None,
+    }
+}
+
+impl super::_entities::bank_transactions::Model {
+    /// finds a bank transaction by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find bank transaction by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let bank_transaction = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(bank_transactions::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        bank_transaction.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds all bank transactions, the latest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find bank transactions or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let bank_transactions = Entity::find()
+            .order_by_desc(bank_transactions::Column::TransactionDate)
+            .all(db)
+            .await?;
+        Ok(bank_transactions)
+    }
+
+    /// finds the bank transactions waiting for review: the unmatched ones and
+    /// the matches not confirmed yet
+    ///
+    /// # Errors
+    ///
+    /// When could not find bank transactions or DB query error
+    pub async fn find_review_queue(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let bank_transactions = Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        bank_transactions::Column::Status,
+                        [
+                            TransactionStatus::Unmatched.as_str(),
+                            TransactionStatus::Proposed.as_str(),
+                        ],
+                    )
+                    .build(),
+            )
+            .order_by_asc(bank_transactions::Column::TransactionDate)
+            .all(db)
+            .await?;
+        Ok(bank_transactions)
+    }
+
+    /// imports the credits of a bank statement or return file, proposing the
+    /// instalment each one pays and leaving the rest for review; lines
+    /// already imported are skipped
+    ///
+    /// # Errors
+    ///
+    /// When the file could not be read or DB query error
+    pub async fn import(db: &DatabaseConnection, content: &str) -> ModelResult<Vec<Self>> {
+        let Some((format, lines)) = bank_statements::parse(content) else {
+            return Err(ModelError::Any(
+                "could not tell the format of the bank statement".into(),
+            ));
+        };
+        let open_payments = payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(payments::Column::Open, true)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let mut taken = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(
+                        bank_transactions::Column::Status,
+                        TransactionStatus::Proposed.as_str(),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|bank_transaction| bank_transaction.payment_id)
+            .collect::<HashSet<_>>();
+
+        let txn = db.begin().await?;
+        let mut imported = vec![];
+        for line in lines {
+            let existing = Entity::find()
+                .filter(
+                    model::query::condition()
+                        .eq(bank_transactions::Column::Source, format.as_str())
+                        .eq(bank_transactions::Column::ExternalId, &line.external_id)
+                        .build(),
+                )
+                .one(&txn)
+                .await?;
+            if existing.is_some() {
+                continue;
+            }
+            let proposal = propose_match(&line, &open_payments, &taken);
+            if let Some((payment_id, _)) = proposal {
+                taken.insert(payment_id);
+            }
+            let status = if proposal.is_some() {
+                TransactionStatus::Proposed
+            } else {
+                TransactionStatus::Unmatched
+            };
+            let bank_transaction = bank_transactions::ActiveModel {
+                source: ActiveValue::Set(format.as_str().to_string()),
+                external_id: ActiveValue::Set(line.external_id),
+                transaction_date: ActiveValue::Set(line.date),
+                value: ActiveValue::Set(line.value),
+                description: ActiveValue::Set(line.description),
+                nosso_numero: ActiveValue::Set(line.nosso_numero),
+                status: ActiveValue::Set(status.as_str().to_string()),
+                match_rule: ActiveValue::Set(proposal.map(|(_, rule)| rule.as_str().to_string())),
+                payment_id: ActiveValue::Set(proposal.map(|(payment_id, _)| payment_id)),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+            imported.push(bank_transaction);
+        }
+        txn.commit().await?;
+        Ok(imported)
+    }
+
+    /// confirms a bank transaction, settling its instalment on the date the
+    /// money came in; a payment pid matches it by hand to another instalment.
+    /// Only a credit of the value of the instalment settles it, unless the
+    /// difference is accepted.
+    ///
+    /// # Errors
+    ///
+    /// When there is no open instalment to settle, the transaction is not a
+    /// credit, its value is not the one of the instalment and the difference
+    /// was not accepted, or DB query error
+    pub async fn confirm(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        payment_pid: Option<Uuid>,
+        accept_difference: bool,
+    ) -> ModelResult<Self> {
+        let bank_transaction = Self::find_by_pid(db, pid).await?;
+        if bank_transaction.status == TransactionStatus::Confirmed.as_str() {
+            return Err(ModelError::Any(
+                "bank transaction is already confirmed".into(),
+            ));
+        }
+        if bank_transaction.value <= 0.0 {
+            return Err(ModelError::Any(
+                "only a credit can settle an instalment".into(),
+            ));
+        }
+        let (payment, match_rule) = match payment_pid {
+            Some(payment_pid) => {
+                let payment = payments::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(payments::Column::Pid, payment_pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?;
+                let match_rule =
+                    if payment.as_ref().map(|payment| payment.id) == bank_transaction.payment_id {
+                        bank_transaction.match_rule.clone()
+                    } else {
+                        Some(MatchRule::Manual.as_str().to_string())
+                    };
+                (payment, match_rule)
+            }
+            None => {
+                let payment = match bank_transaction.payment_id {
+                    Some(payment_id) => payments::Entity::find_by_id(payment_id).one(db).await?,
+                    None => None,
+                };
+                (payment, bank_transaction.match_rule.clone())
+            }
+        };
+        let payment = payment.ok_or_else(|| ModelError::EntityNotFound)?;
+        if !payment.open {
+            return Err(ModelError::Any("payment is already settled".into()));
+        }
+        if (bank_transaction.value - payment.value).abs() >= AMOUNT_TOLERANCE && !accept_difference
+        {
+            return Err(ModelError::Any(
+                "the bank transaction is not for the value of the instalment".into(),
+            ));
+        }
+
+        let txn = db.begin().await?;
+        let payment_id = payment.id;
+        let order_id = payment.order_id;
+        let mut settled_payment = payment.into_active_model();
+        settled_payment.open = ActiveValue::Set(false);
+        settled_payment.payment_date = ActiveValue::Set(Some(bank_transaction.transaction_date));
+        settled_payment.update(&txn).await?;
+        let mut confirmed = bank_transaction.into_active_model();
+        confirmed.status = ActiveValue::Set(TransactionStatus::Confirmed.as_str().to_string());
+        confirmed.match_rule = ActiveValue::Set(match_rule);
+        confirmed.payment_id = ActiveValue::Set(Some(payment_id));
+        let confirmed = confirmed.update(&txn).await?;
+        journal_entries::Model::sync_order(&txn, order_id).await?;
+        txn.commit().await?;
+        Ok(confirmed)
+    }
+
+    /// rejects the instalment proposed for a bank transaction, sending it
+    /// back to the review queue
+    ///
+    /// # Errors
+    ///
+    /// When the bank transaction has no proposal or DB query error
+    pub async fn reject(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let bank_transaction = Self::find_by_pid(db, pid).await?;
+        if bank_transaction.status != TransactionStatus::Proposed.as_str() {
+            return Err(ModelError::Any(
+                "only proposed matches can be rejected".into(),
+            ));
+        }
+        Self::set_unmatched(db, bank_transaction, TransactionStatus::Unmatched).await
+    }
+
+    /// takes a bank transaction out of the review queue, as it does not pay
+    /// any instalment
+    ///
+    /// # Errors
+    ///
+    /// When the bank transaction is confirmed or DB query error
+    pub async fn ignore(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let bank_transaction = Self::find_by_pid(db, pid).await?;
+        if bank_transaction.status == TransactionStatus::Confirmed.as_str() {
+            return Err(ModelError::Any(
+                "confirmed bank transactions cannot be ignored".into(),
+            ));
+        }
+        Self::set_unmatched(db, bank_transaction, TransactionStatus::Ignored).await
+    }
+
+    async fn set_unmatched(
+        db: &DatabaseConnection,
+        bank_transaction: Self,
+        status: TransactionStatus,
+    ) -> ModelResult<Self> {
+        let mut edited = bank_transaction.into_active_model();
+        edited.status = ActiveValue::Set(status.as_str().to_string());
+        edited.match_rule = ActiveValue::Set(None);
+        edited.payment_id = ActiveValue::Set(None);
+        let txn = db.begin().await?;
+        let bank_transaction = edited.update(&txn).await?;
+        txn.commit().await?;
+        Ok(bank_transaction)
+    }
+}
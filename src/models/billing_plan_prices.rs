@@ -0,0 +1,101 @@
+use super::_entities::billing_plan_prices::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type BillingPlanPrices = Entity;
+use crate::models::_entities::billing_plans;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::ActiveValue;
+use serde::Deserialize;
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(range(exclusive_min = 0.0, message = "Value must be greater than zero."))]
+    pub value: f32,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            value: *self.value.as_ref(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::billing_plan_prices::Model {
+    /// finds the price changes of a billing plan
+    ///
+    /// # Errors
+    ///
+    /// When could not find price changes or DB query error
+    pub async fn find_by_billing_plan_id<C>(db: &C, billing_plan_id: i32) -> ModelResult<Vec<Self>>
+    where
+        C: ConnectionTrait,
+    {
+        let prices = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(
+                        super::_entities::billing_plan_prices::Column::BillingPlanId,
+                        billing_plan_id,
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(prices)
+    }
+
+    /// finds the price changes of every billing plan of an order
+    ///
+    /// # Errors
+    ///
+    /// When could not find price changes or DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let prices = Entity::find()
+            .inner_join(billing_plans::Entity)
+            .filter(
+                model::query::condition()
+                    .eq(billing_plans::Column::OrderId, order_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(prices)
+    }
+
+    /// finds all price changes
+    ///
+    /// # Errors
+    ///
+    /// When could not find price changes or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let prices = Entity::find().all(db).await?;
+        Ok(prices)
+    }
+}
@@ -0,0 +1,480 @@
+use super::_entities::billing_plans::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type BillingPlans = Entity;
+use crate::business_days::BusinessCalendar;
+use crate::models::_entities::{
+    billing_plan_prices, billing_plans, journal_entries, orders, payments,
+};
+use chrono::Datelike;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewBillingPlan {
+    pub order_pid: Uuid,
+    pub value: f32,
+    pub day_of_month: i32,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditBillingPlan {
+    pub day_of_month: i32,
+    pub end_date: Option<chrono::NaiveDate>,
+}
+
+/// A new amount for the plan, billed on every instalment for a plan date from
+/// `effective_date` onwards
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChangeBillingPlanPrice {
+    pub value: f32,
+    pub effective_date: chrono::NaiveDate,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(range(exclusive_min = 0.0, message = "Value must be greater than zero."))]
+    pub value: f32,
+    #[validate(range(min = 1, max = 31, message = "Day of month must be between 1 and 31."))]
+    pub day_of_month: i32,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            value: *self.value.as_ref(),
+            day_of_month: *self.day_of_month.as_ref(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+/// Returns the due date of a plan in the given month, moved back to the last
+/// day of the month when the month is shorter than `day_of_month`
+#[must_use]
+pub fn due_date_in(year: i32, month: u32, day_of_month: i32) -> Option<chrono::NaiveDate> {
+    let day = u32::try_from(day_of_month).ok()?;
+    (1..=day)
+        .rev()
+        .find_map(|day| chrono::NaiveDate::from_ymd_opt(year, month, day))
+}
+
+/// deletes the open instalments of a plan due after `date`, keeping the ones
+/// due up to `date` that were moved to the business day after it
+async fn drop_open_payments_after<C>(
+    db: &C,
+    billing_plan_id: i32,
+    date: chrono::NaiveDate,
+    calendar: &BusinessCalendar,
+) -> ModelResult<()>
+where
+    C: ConnectionTrait,
+{
+    let date = calendar.next_business_day(date);
+    payments::Entity::delete_many()
+        .filter(
+            model::query::condition()
+                .eq(payments::Column::BillingPlanId, billing_plan_id)
+                .eq(payments::Column::Open, true)
+                .gt(payments::Column::DueDate, date)
+                .build(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+impl super::_entities::billing_plans::Model {
+    /// the amount billed on `date`, taken from the latest price change in
+    /// effect on that day or the plan amount when there is none
+    #[must_use]
+    pub fn value_on(&self, prices: &[billing_plan_prices::Model], date: chrono::NaiveDate) -> f32 {
+        prices
+            .iter()
+            .filter(|price| price.billing_plan_id == self.id && price.effective_date <= date)
+            .max_by_key(|price| (price.effective_date, price.id))
+            .map_or(self.value, |price| price.value)
+    }
+
+    /// the due dates the plan still has to bill up to `until`, starting after
+    /// the last date it was billed for and never past its end or cancellation
+    #[must_use]
+    pub fn due_dates(&self, until: chrono::NaiveDate) -> Vec<chrono::NaiveDate> {
+        if self.paused {
+            return vec![];
+        }
+        let from = self
+            .billed_until
+            .and_then(|billed_until| billed_until.succ_opt())
+            .map_or(self.start_date, |next| next.max(self.start_date));
+        let last = [Some(until), self.end_date, self.cancelled_at]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(until);
+
+        let mut dates = vec![];
+        let (mut year, mut month) = (from.year(), from.month());
+        while let Some(date) = due_date_in(year, month, self.day_of_month) {
+            if date > last {
+                break;
+            }
+            if date >= from {
+                dates.push(date);
+            }
+            (year, month) = if month == 12 {
+                (year + 1, 1)
+            } else {
+                (year, month + 1)
+            };
+        }
+        dates
+    }
+
+    /// finds a billing plan by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find billing plan by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let billing_plan = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::billing_plans::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        billing_plan.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the billing plans of an order
+    ///
+    /// # Errors
+    ///
+    /// When could not find billing plans or DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let billing_plans = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::billing_plans::Column::OrderId, order_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(billing_plans)
+    }
+
+    /// finds all billing plans
+    ///
+    /// # Errors
+    ///
+    /// When could not find billing plans or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let billing_plans = Entity::find().all(db).await?;
+        Ok(billing_plans)
+    }
+
+    /// finds a billing plan that can still be changed
+    ///
+    /// # Errors
+    ///
+    /// When could not find billing plan, when it was cancelled or DB query
+    /// error
+    async fn find_active_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let billing_plan = Self::find_by_pid(db, pid).await?;
+        if billing_plan.cancelled_at.is_some() {
+            return Err(ModelError::Any(
+                "cancelled billing plans cannot be changed".into(),
+            ));
+        }
+        Ok(billing_plan)
+    }
+
+    /// creates a new billing plan
+    ///
+    /// # Errors
+    ///
+    /// When could not create billing plan or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        billing_plan: CreateNewBillingPlan,
+    ) -> ModelResult<Self> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, billing_plan.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        if billing_plan
+            .end_date
+            .is_some_and(|end_date| end_date < billing_plan.start_date)
+        {
+            return Err(ModelError::Any(
+                "end date cannot be before the start date".into(),
+            ));
+        }
+        let txn = db.begin().await?;
+        let billing_plan = billing_plans::ActiveModel {
+            order_id: ActiveValue::Set(order.id),
+            value: ActiveValue::Set(billing_plan.value),
+            day_of_month: ActiveValue::Set(billing_plan.day_of_month),
+            start_date: ActiveValue::Set(billing_plan.start_date),
+            end_date: ActiveValue::Set(billing_plan.end_date),
+            paused: ActiveValue::Set(false),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(billing_plan)
+    }
+
+    /// updates the day of month and end date of a billing plan, dropping the
+    /// open instalments already generated past the new end date
+    ///
+    /// # Errors
+    ///
+    /// When the plan was cancelled, could not update billing plan or DB query
+    /// error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        billing_plan: EditBillingPlan,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<Self> {
+        let existing_billing_plan = Self::find_active_by_pid(db, pid).await?;
+        if billing_plan
+            .end_date
+            .is_some_and(|end_date| end_date < existing_billing_plan.start_date)
+        {
+            return Err(ModelError::Any(
+                "end date cannot be before the start date".into(),
+            ));
+        }
+        let mut edited_billing_plan = existing_billing_plan.into_active_model();
+        edited_billing_plan.day_of_month = ActiveValue::Set(billing_plan.day_of_month);
+        edited_billing_plan.end_date = ActiveValue::Set(billing_plan.end_date);
+        let txn = db.begin().await?;
+        let edited_billing_plan = edited_billing_plan.update(&txn).await?;
+        if let Some(end_date) = edited_billing_plan.end_date {
+            drop_open_payments_after(&txn, edited_billing_plan.id, end_date, calendar).await?;
+        }
+        txn.commit().await?;
+        Ok(edited_billing_plan)
+    }
+
+    /// pauses a billing plan on `date`, dropping the open instalments already
+    /// generated after it. Months spent paused are never billed
+    ///
+    /// # Errors
+    ///
+    /// When the plan was cancelled, could not update billing plan or DB query
+    /// error
+    pub async fn pause(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        date: chrono::NaiveDate,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<Self> {
+        let existing_billing_plan = Self::find_active_by_pid(db, pid).await?;
+        let billing_plan_id = existing_billing_plan.id;
+        let billed_until = existing_billing_plan
+            .billed_until
+            .map(|billed_until| billed_until.min(date));
+        let mut paused_billing_plan = existing_billing_plan.into_active_model();
+        paused_billing_plan.paused = ActiveValue::Set(true);
+        paused_billing_plan.billed_until = ActiveValue::Set(billed_until);
+        let txn = db.begin().await?;
+        drop_open_payments_after(&txn, billing_plan_id, date, calendar).await?;
+        let billing_plan = paused_billing_plan.update(&txn).await?;
+        txn.commit().await?;
+        Ok(billing_plan)
+    }
+
+    /// resumes a paused billing plan, billing again from the first due date
+    /// after `date`
+    ///
+    /// # Errors
+    ///
+    /// When the plan was cancelled, could not update billing plan or DB query
+    /// error
+    pub async fn resume(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        date: chrono::NaiveDate,
+    ) -> ModelResult<Self> {
+        let existing_billing_plan = Self::find_active_by_pid(db, pid).await?;
+        let billed_until = existing_billing_plan
+            .billed_until
+            .map_or(date, |billed_until| billed_until.max(date));
+        let mut resumed_billing_plan = existing_billing_plan.into_active_model();
+        resumed_billing_plan.paused = ActiveValue::Set(false);
+        resumed_billing_plan.billed_until = ActiveValue::Set(Some(billed_until));
+        let txn = db.begin().await?;
+        let billing_plan = resumed_billing_plan.update(&txn).await?;
+        txn.commit().await?;
+        Ok(billing_plan)
+    }
+
+    /// cancels a billing plan from `cancelled_at`, dropping the open
+    /// instalments already generated after it
+    ///
+    /// # Errors
+    ///
+    /// When the plan was already cancelled, could not update billing plan or
+    /// DB query error
+    pub async fn cancel(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        cancelled_at: chrono::NaiveDate,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<Self> {
+        let existing_billing_plan = Self::find_active_by_pid(db, pid).await?;
+        let billing_plan_id = existing_billing_plan.id;
+        let mut cancelled_billing_plan = existing_billing_plan.into_active_model();
+        cancelled_billing_plan.cancelled_at = ActiveValue::Set(Some(cancelled_at));
+        let txn = db.begin().await?;
+        drop_open_payments_after(&txn, billing_plan_id, cancelled_at, calendar).await?;
+        let billing_plan = cancelled_billing_plan.update(&txn).await?;
+        txn.commit().await?;
+        Ok(billing_plan)
+    }
+
+    /// records a price change of a billing plan and moves the open
+    /// instalments already generated for its dates from the effective date on
+    /// to the new amount, keeping anything else billed into them. An
+    /// instalment moved to a business day is priced by the plan date it bills
+    /// for, not the day it is due.
+    ///
+    /// # Errors
+    ///
+    /// When the plan was cancelled, could not create price change or DB query
+    /// error
+    pub async fn change_price(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        price: ChangeBillingPlanPrice,
+    ) -> ModelResult<Self> {
+        let billing_plan = Self::find_active_by_pid(db, pid).await?;
+        let prices =
+            billing_plan_prices::Model::find_by_billing_plan_id(db, billing_plan.id).await?;
+        let open_payments = payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(payments::Column::BillingPlanId, billing_plan.id)
+                    .eq(payments::Column::Open, true)
+                    .gte(payments::Column::PeriodDate, price.effective_date)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let txn = db.begin().await?;
+        billing_plan_prices::ActiveModel {
+            billing_plan_id: ActiveValue::Set(billing_plan.id),
+            value: ActiveValue::Set(price.value),
+            effective_date: ActiveValue::Set(price.effective_date),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        for payment in open_payments {
+            let period_date = payment.period_date.unwrap_or(payment.due_date);
+            let value = payment.value - billing_plan.value_on(&prices, period_date) + price.value;
+            let mut edited_payment = payment.into_active_model();
+            edited_payment.value = ActiveValue::Set(value);
+            edited_payment.update(&txn).await?;
+        }
+        journal_entries::Model::sync_order(&txn, billing_plan.order_id).await?;
+        txn.commit().await?;
+        Ok(billing_plan)
+    }
+
+    /// generates the open instalments every running billing plan has due up
+    /// to `until`, each due on the business day its due date falls on or
+    /// after, returning the instalments created
+    ///
+    /// # Errors
+    ///
+    /// When could not create the instalments or DB query error
+    pub async fn generate_payments(
+        db: &DatabaseConnection,
+        until: chrono::NaiveDate,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<Vec<payments::Model>> {
+        let billing_plans = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(billing_plans::Column::Paused, false)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let mut generated = vec![];
+        for billing_plan in billing_plans {
+            if billing_plan
+                .billed_until
+                .is_some_and(|billed_until| billed_until >= until)
+            {
+                continue;
+            }
+            let prices =
+                billing_plan_prices::Model::find_by_billing_plan_id(db, billing_plan.id).await?;
+            let txn = db.begin().await?;
+            for due_date in billing_plan.due_dates(until) {
+                let payment = payments::ActiveModel {
+                    value: ActiveValue::Set(billing_plan.value_on(&prices, due_date)),
+                    due_date: ActiveValue::Set(calendar.next_business_day(due_date)),
+                    order_id: ActiveValue::Set(billing_plan.order_id),
+                    open: ActiveValue::Set(true),
+                    billing_plan_id: ActiveValue::Set(Some(billing_plan.id)),
+                    period_date: ActiveValue::Set(Some(due_date)),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?;
+                generated.push(payment);
+            }
+            let order_id = billing_plan.order_id;
+            let mut billed_billing_plan = billing_plan.into_active_model();
+            billed_billing_plan.billed_until = ActiveValue::Set(Some(until));
+            billed_billing_plan.update(&txn).await?;
+            journal_entries::Model::sync_order(&txn, order_id).await?;
+            txn.commit().await?;
+        }
+        Ok(generated)
+    }
+}
@@ -0,0 +1,376 @@
+use super::_entities::calendar_events::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type CalendarEvents = Entity;
+use crate::models::_entities::{calendar_events, lawsuits, orders, users};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::{ActiveValue, IntoActiveModel, QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Hearing,
+    Deadline,
+    Meeting,
+}
+
+impl EventKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Hearing => "hearing",
+            Self::Deadline => "deadline",
+            Self::Meeting => "meeting",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "hearing" => Some(Self::Hearing),
+            "deadline" => Some(Self::Deadline),
+            "meeting" => Some(Self::Meeting),
+            _ => None,
+        }
+    }
+}
+
+/// The UTC offset of the firm, from `calendar.utc_offset` in the settings in
+/// hours, which decides the day an event falls on. Brasília time by default.
+#[must_use]
+pub fn firm_offset(settings: Option<&serde_json::Value>) -> chrono::FixedOffset {
+    settings
+        .and_then(|settings| settings.get("calendar"))
+        .and_then(|calendar| calendar.get("utc_offset"))
+        .and_then(serde_json::Value::as_f64)
+        .and_then(|hours| {
+            #[allow(clippy::cast_possible_truncation)]
+            chrono::FixedOffset::east_opt((hours * 3600.0).round() as i32)
+        })
+        .unwrap_or(DEFAULT_OFFSET)
+}
+
+/// Brasília time, UTC-3
+const DEFAULT_OFFSET: chrono::FixedOffset = match chrono::FixedOffset::west_opt(3 * 3600) {
+    Some(offset) => offset,
+    None => unreachable!(),
+};
+
+/// The start of a day at the given offset
+fn start_of_day(day: chrono::NaiveDate, offset: chrono::FixedOffset) -> DateTimeWithTimeZone {
+    day.and_time(chrono::NaiveTime::MIN)
+        .and_local_timezone(offset)
+        .single()
+        .unwrap_or_else(|| {
+            day.and_time(chrono::NaiveTime::MIN)
+                .and_utc()
+                .fixed_offset()
+        })
+}
+
+/// An event of the calendar. An event of a lawsuit is also of its order, so
+/// `order_pid` may be left out when `lawsuit_pid` is given.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewEvent {
+    pub kind: EventKind,
+    pub title: String,
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: Option<DateTimeWithTimeZone>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub completed: bool,
+    pub order_pid: Option<Uuid>,
+    pub lawsuit_pid: Option<Uuid>,
+    /// the user responsible for the event
+    pub user_pid: Option<Uuid>,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(length(min = 2, message = "Title must be at least 2 characters long."))]
+    pub title: String,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            title: self.title.as_ref().to_owned(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+/// What an event is tied to and who is responsible for it
+struct EventLinks {
+    order_id: Option<i32>,
+    lawsuit_id: Option<i32>,
+    user_id: Option<i32>,
+}
+
+impl super::_entities::calendar_events::Model {
+    /// finds an event by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find event by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let event = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(calendar_events::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        event.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the events starting from `from` up to the end of `until`, days
+    /// being taken at the given offset, of the given user only when one is
+    /// given, earliest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find events or DB query error
+    pub async fn find_between(
+        db: &DatabaseConnection,
+        from: chrono::NaiveDate,
+        until: Option<chrono::NaiveDate>,
+        user_id: Option<i32>,
+        offset: chrono::FixedOffset,
+    ) -> ModelResult<Vec<Self>> {
+        let mut condition = model::query::condition().gte(
+            calendar_events::Column::StartsAt,
+            start_of_day(from, offset),
+        );
+        if let Some(until) = until.and_then(|until| until.succ_opt()) {
+            condition = condition.lt(
+                calendar_events::Column::StartsAt,
+                start_of_day(until, offset),
+            );
+        }
+        if let Some(user_id) = user_id {
+            condition = condition.eq(calendar_events::Column::UserId, user_id);
+        }
+        let events = Entity::find()
+            .filter(condition.build())
+            .order_by_asc(calendar_events::Column::StartsAt)
+            .all(db)
+            .await?;
+        Ok(events)
+    }
+
+    /// finds the orders, lawsuits and users of the given events
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_links(
+        db: &DatabaseConnection,
+        events: &[Self],
+    ) -> ModelResult<(Vec<orders::Model>, Vec<lawsuits::Model>, Vec<users::Model>)> {
+        let orders = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        orders::Column::Id,
+                        events.iter().filter_map(|event| event.order_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let lawsuits = lawsuits::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        lawsuits::Column::Id,
+                        events.iter().filter_map(|event| event.lawsuit_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let users = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        users::Column::Id,
+                        events.iter().filter_map(|event| event.user_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok((orders, lawsuits, users))
+    }
+
+    /// checks an event and finds what it is tied to
+    async fn links(db: &DatabaseConnection, event: &CreateNewEvent) -> ModelResult<EventLinks> {
+        if event
+            .ends_at
+            .is_some_and(|ends_at| ends_at < event.starts_at)
+        {
+            return Err(ModelError::Any(
+                "an event cannot end before it starts".into(),
+            ));
+        }
+        let lawsuit = match event.lawsuit_pid {
+            Some(pid) => Some(lawsuits::Model::find_by_pid(db, pid).await?),
+            None => None,
+        };
+        let order_id = match event.order_pid {
+            Some(pid) => Some(
+                orders::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(orders::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?
+                    .id,
+            ),
+            None => None,
+        };
+        if let (Some(order_id), Some(lawsuit)) = (order_id, &lawsuit) {
+            if lawsuit.order_id != order_id {
+                return Err(ModelError::Any(
+                    "the lawsuit is not the lawsuit of the order".into(),
+                ));
+            }
+        }
+        let user_id = match event.user_pid {
+            Some(pid) => Some(
+                users::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(users::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?
+                    .id,
+            ),
+            None => None,
+        };
+        Ok(EventLinks {
+            order_id: order_id.or(lawsuit.as_ref().map(|lawsuit| lawsuit.order_id)),
+            lawsuit_id: lawsuit.map(|lawsuit| lawsuit.id),
+            user_id,
+        })
+    }
+
+    /// creates a new event
+    ///
+    /// # Errors
+    ///
+    /// When the event ends before it starts, the lawsuit is not the order's,
+    /// could not create event or DB query error
+    pub async fn create(db: &DatabaseConnection, event: CreateNewEvent) -> ModelResult<Self> {
+        let links = Self::links(db, &event).await?;
+        let txn = db.begin().await?;
+        let event = calendar_events::ActiveModel {
+            kind: ActiveValue::Set(event.kind.as_str().to_string()),
+            title: ActiveValue::Set(event.title),
+            starts_at: ActiveValue::Set(event.starts_at),
+            ends_at: ActiveValue::Set(event.ends_at),
+            location: ActiveValue::Set(event.location),
+            description: ActiveValue::Set(event.description),
+            completed: ActiveValue::Set(event.completed),
+            order_id: ActiveValue::Set(links.order_id),
+            lawsuit_id: ActiveValue::Set(links.lawsuit_id),
+            user_id: ActiveValue::Set(links.user_id),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(event)
+    }
+
+    /// updates an event
+    ///
+    /// # Errors
+    ///
+    /// When could not find event by the given pid, the event ends before it
+    /// starts, the lawsuit is not the order's or DB query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        event: CreateNewEvent,
+    ) -> ModelResult<Self> {
+        let existing_event = Self::find_by_pid(db, pid).await?;
+        let links = Self::links(db, &event).await?;
+        let mut edited_event = existing_event.into_active_model();
+        edited_event.kind = ActiveValue::Set(event.kind.as_str().to_string());
+        edited_event.title = ActiveValue::Set(event.title);
+        edited_event.starts_at = ActiveValue::Set(event.starts_at);
+        edited_event.ends_at = ActiveValue::Set(event.ends_at);
+        edited_event.location = ActiveValue::Set(event.location);
+        edited_event.description = ActiveValue::Set(event.description);
+        edited_event.completed = ActiveValue::Set(event.completed);
+        edited_event.order_id = ActiveValue::Set(links.order_id);
+        edited_event.lawsuit_id = ActiveValue::Set(links.lawsuit_id);
+        edited_event.user_id = ActiveValue::Set(links.user_id);
+        let txn = db.begin().await?;
+        let event = edited_event.update(&txn).await?;
+        txn.commit().await?;
+        Ok(event)
+    }
+
+    /// marks an event done or not done
+    ///
+    /// # Errors
+    ///
+    /// When could not find event by the given pid or DB query error
+    pub async fn complete(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        completed: bool,
+    ) -> ModelResult<Self> {
+        let mut event = Self::find_by_pid(db, pid).await?.into_active_model();
+        event.completed = ActiveValue::Set(completed);
+        Ok(event.update(db).await?)
+    }
+
+    /// deletes an event
+    ///
+    /// # Errors
+    ///
+    /// When could not find event by the given pid or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_event = Self::find_by_pid(db, pid).await?;
+        let txn = db.begin().await?;
+        existing_event.clone().delete(&txn).await?;
+        txn.commit().await?;
+        Ok(existing_event)
+    }
+}
@@ -0,0 +1,125 @@
+use super::_entities::calendar_feeds::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type CalendarFeeds = Entity;
+use crate::models::_entities::calendar_feeds;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+/// A feed of the events of the user who creates it or, with `firm`, of
+/// every event
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CreateNewFeed {
+    #[serde(default)]
+    pub firm: bool,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            this.token = ActiveValue::Set(Uuid::new_v4().simple().to_string());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::calendar_feeds::Model {
+    /// the path calendar apps subscribe to, which the token alone secures
+    #[must_use]
+    pub fn path(&self) -> String {
+        format!("/api/calendar/feed/{}.ics", self.token)
+    }
+
+    /// finds a feed by its token, with or without the `.ics` of its path
+    ///
+    /// # Errors
+    ///
+    /// When could not find feed by the given token or DB query error
+    pub async fn find_by_token(db: &DatabaseConnection, token: &str) -> ModelResult<Self> {
+        let feed = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(
+                        calendar_feeds::Column::Token,
+                        token.trim_end_matches(".ics"),
+                    )
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        feed.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the feeds a user created
+    ///
+    /// # Errors
+    ///
+    /// When could not find feeds or DB query error
+    pub async fn find_by_user_id(db: &DatabaseConnection, user_id: i32) -> ModelResult<Vec<Self>> {
+        let feeds = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(calendar_feeds::Column::UserId, user_id)
+                    .build(),
+            )
+            .order_by_asc(calendar_feeds::Column::CreatedAt)
+            .all(db)
+            .await?;
+        Ok(feeds)
+    }
+
+    /// creates a feed for a user with a new token
+    ///
+    /// # Errors
+    ///
+    /// When could not create feed or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        user_id: i32,
+        feed: CreateNewFeed,
+    ) -> ModelResult<Self> {
+        let feed = calendar_feeds::ActiveModel {
+            user_id: ActiveValue::Set(user_id),
+            firm: ActiveValue::Set(feed.firm),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(feed)
+    }
+
+    /// revokes a feed of a user, so that its token no longer works
+    ///
+    /// # Errors
+    ///
+    /// When the user has no feed with the given pid or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid, user_id: i32) -> ModelResult<Self> {
+        let feed = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(calendar_feeds::Column::Pid, pid)
+                    .eq(calendar_feeds::Column::UserId, user_id)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        feed.clone().delete(db).await?;
+        Ok(feed)
+    }
+}
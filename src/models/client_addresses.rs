@@ -0,0 +1,177 @@
+use super::_entities::client_addresses::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type ClientAddresses = Entity;
+use crate::models::_entities::client_addresses;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+/// The abbreviations of the Brazilian states and the Federal District
+pub const STATES: [&str; 27] = [
+    "AC", "AL", "AM", "AP", "BA", "CE", "DF", "ES", "GO", "MA", "MG", "MS", "MT", "PA", "PB", "PE",
+    "PI", "PR", "RJ", "RN", "RO", "RR", "RS", "SC", "SE", "SP", "TO",
+];
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NewAddress {
+    pub cep: String,
+    pub street: String,
+    pub number: String,
+    pub complement: Option<String>,
+    pub district: Option<String>,
+    pub city: String,
+    pub state: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+/// Strips the mask from a CEP, returning its eight digits when it has them
+#[must_use]
+pub fn normalize_cep(cep: &str) -> Option<String> {
+    let cep = cep.trim();
+    let digits = match cep.split_once('-') {
+        Some((prefix, suffix)) if prefix.len() == 5 && suffix.len() == 3 => {
+            format!("{prefix}{suffix}")
+        }
+        Some(_) => return None,
+        None => cep.to_string(),
+    };
+    let valid =
+        digits.len() == 8 && digits.chars().all(|c| c.is_ascii_digit()) && digits != "00000000";
+    valid.then_some(digits)
+}
+
+/// Writes a CEP with its mask, as in `01310-100`
+#[must_use]
+pub fn format_cep(cep: &str) -> String {
+    if cep.len() == 8 && cep.is_ascii() {
+        format!("{}-{}", &cep[..5], &cep[5..])
+    } else {
+        cep.to_string()
+    }
+}
+
+/// Checks the addresses given for a client, leaving one primary address: the
+/// one marked so or else the first one
+///
+/// # Errors
+///
+/// When an address is not valid or more than one is primary
+pub fn check_addresses(addresses: Vec<NewAddress>) -> ModelResult<Vec<NewAddress>> {
+    let mut checked: Vec<NewAddress> = vec![];
+    for address in addresses {
+        let cep = normalize_cep(&address.cep)
+            .ok_or_else(|| ModelError::Any(format!("{} is not a valid CEP", address.cep).into()))?;
+        let state = address.state.trim().to_uppercase();
+        if !STATES.contains(&state.as_str()) {
+            return Err(ModelError::Any(
+                format!("{} is not a Brazilian state", address.state).into(),
+            ));
+        }
+        if address.street.trim().is_empty() || address.city.trim().is_empty() {
+            return Err(ModelError::Any("street and city are required".into()));
+        }
+        checked.push(NewAddress {
+            cep,
+            state,
+            ..address
+        });
+    }
+    match checked.iter().filter(|address| address.primary).count() {
+        0 => {
+            if let Some(address) = checked.first_mut() {
+                address.primary = true;
+            }
+        }
+        1 => {}
+        _ => return Err(ModelError::Any("only one address can be primary".into())),
+    }
+    Ok(checked)
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::client_addresses::Model {
+    /// finds the addresses of a client, the primary one first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_client_id<C: ConnectionTrait>(
+        db: &C,
+        client_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let addresses = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(client_addresses::Column::ClientId, client_id)
+                    .build(),
+            )
+            .order_by_desc(client_addresses::Column::Primary)
+            .order_by_asc(client_addresses::Column::Id)
+            .all(db)
+            .await?;
+        Ok(addresses)
+    }
+
+    /// replaces the addresses of a client with the given ones, already
+    /// checked
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn replace_for_client<C: ConnectionTrait>(
+        db: &C,
+        client_id: i32,
+        addresses: Vec<NewAddress>,
+    ) -> ModelResult<Vec<Self>> {
+        Entity::delete_many()
+            .filter(
+                model::query::condition()
+                    .eq(client_addresses::Column::ClientId, client_id)
+                    .build(),
+            )
+            .exec(db)
+            .await?;
+        let mut created = vec![];
+        for address in addresses {
+            let address = client_addresses::ActiveModel {
+                client_id: ActiveValue::Set(client_id),
+                cep: ActiveValue::Set(address.cep),
+                street: ActiveValue::Set(address.street),
+                number: ActiveValue::Set(address.number),
+                complement: ActiveValue::Set(address.complement),
+                district: ActiveValue::Set(address.district),
+                city: ActiveValue::Set(address.city),
+                state: ActiveValue::Set(address.state),
+                primary: ActiveValue::Set(address.primary),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            created.push(address);
+        }
+        Ok(created)
+    }
+}
@@ -0,0 +1,208 @@
+use super::_entities::client_contacts::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type ClientContacts = Entity;
+use crate::models::_entities::client_contacts;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+/// How a client is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactKind {
+    Phone,
+    Email,
+}
+
+impl ContactKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Phone => "phone",
+            Self::Email => "email",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "phone" => Some(Self::Phone),
+            "email" => Some(Self::Email),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NewContact {
+    pub kind: ContactKind,
+    pub value: String,
+    #[serde(default)]
+    pub primary: bool,
+    #[serde(default)]
+    pub whatsapp: bool,
+    pub notes: Option<String>,
+}
+
+/// Checks a phone or an email, returning it the way it is stored: the
+/// digits of a phone, with the country and area codes, and an email in
+/// lowercase
+#[must_use]
+pub fn normalize_contact(kind: ContactKind, value: &str) -> Option<String> {
+    let value = value.trim();
+    match kind {
+        ContactKind::Phone => {
+            if !value
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '+' | '(' | ')' | '-' | '.' | ' '))
+            {
+                return None;
+            }
+            let digits = value
+                .chars()
+                .filter(char::is_ascii_digit)
+                .collect::<String>();
+            (8..=13).contains(&digits.len()).then_some(digits)
+        }
+        ContactKind::Email => {
+            let (user, domain) = value.split_once('@')?;
+            let valid = !user.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !value.contains(char::is_whitespace);
+            valid.then(|| value.to_lowercase())
+        }
+    }
+}
+
+/// Checks the contacts given for a client, leaving one primary contact of
+/// each kind: the one marked so or else the first one
+///
+/// # Errors
+///
+/// When a contact is not valid or more than one of a kind is primary
+pub fn check_contacts(contacts: Vec<NewContact>) -> ModelResult<Vec<NewContact>> {
+    let mut checked: Vec<NewContact> = vec![];
+    for contact in contacts {
+        let value = normalize_contact(contact.kind, &contact.value).ok_or_else(|| {
+            ModelError::Any(
+                format!("{} is not a valid {}", contact.value, contact.kind.as_str()).into(),
+            )
+        })?;
+        checked.push(NewContact { value, ..contact });
+    }
+    for kind in [ContactKind::Phone, ContactKind::Email] {
+        let primaries = checked
+            .iter()
+            .filter(|contact| contact.kind == kind && contact.primary)
+            .count();
+        if primaries > 1 {
+            return Err(ModelError::Any(
+                format!("only one {} can be primary", kind.as_str()).into(),
+            ));
+        }
+        if primaries == 0 {
+            if let Some(contact) = checked.iter_mut().find(|contact| contact.kind == kind) {
+                contact.primary = true;
+            }
+        }
+    }
+    Ok(checked)
+}
+
+/// The value of the primary contact of a kind, or of the first secondary one
+#[must_use]
+pub fn contact_value(
+    contacts: &[client_contacts::Model],
+    kind: ContactKind,
+    primary: bool,
+) -> Option<String> {
+    contacts
+        .iter()
+        .find(|contact| contact.kind == kind.as_str() && contact.primary == primary)
+        .map(|contact| contact.value.clone())
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::client_contacts::Model {
+    /// finds the contacts of a client, the primary ones first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_client_id<C: ConnectionTrait>(
+        db: &C,
+        client_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let contacts = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(client_contacts::Column::ClientId, client_id)
+                    .build(),
+            )
+            .order_by_desc(client_contacts::Column::Primary)
+            .order_by_asc(client_contacts::Column::Id)
+            .all(db)
+            .await?;
+        Ok(contacts)
+    }
+
+    /// replaces the contacts of a client with the given ones, already checked
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn replace_for_client<C: ConnectionTrait>(
+        db: &C,
+        client_id: i32,
+        contacts: Vec<NewContact>,
+    ) -> ModelResult<Vec<Self>> {
+        Entity::delete_many()
+            .filter(
+                model::query::condition()
+                    .eq(client_contacts::Column::ClientId, client_id)
+                    .build(),
+            )
+            .exec(db)
+            .await?;
+        let mut created = vec![];
+        for contact in contacts {
+            let contact = client_contacts::ActiveModel {
+                client_id: ActiveValue::Set(client_id),
+                kind: ActiveValue::Set(contact.kind.as_str().to_string()),
+                value: ActiveValue::Set(contact.value),
+                primary: ActiveValue::Set(contact.primary),
+                whatsapp: ActiveValue::Set(contact.whatsapp),
+                notes: ActiveValue::Set(contact.notes),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            created.push(contact);
+        }
+        Ok(created)
+    }
+}
@@ -0,0 +1,361 @@
+use super::_entities::client_merges::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type ClientMerges = Entity;
+use crate::models::_entities::{
+    attachments, client_addresses, client_contacts, client_merges, clients, journal_entries, notes,
+    orders,
+};
+use crate::models::client_contacts::ContactKind;
+use crate::search::fold_accent;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, IntoActiveModel, QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// the score from which two clients are listed as likely duplicates
+pub const DEFAULT_MIN_SCORE: u32 = 50;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MergeClients {
+    pub primary_pid: Uuid,
+    pub secondary_pid: Uuid,
+}
+
+/// Lowercases a name, strips its accents and punctuation and collapses its
+/// spaces, so that `José  da Silva.` and `jose da silva` compare equal
+#[must_use]
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match fold_accent(c) {
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The last eight digits of a phone, the part that is the same whether it is
+/// written with the country and area codes or not
+#[must_use]
+pub fn phone_key(phone: &str) -> Option<String> {
+    let digits = phone
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<Vec<_>>();
+    (digits.len() >= 8).then(|| digits[digits.len() - 8..].iter().collect())
+}
+
+/// What two clients are compared by
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClientKeys {
+    pub pid: Uuid,
+    pub name: String,
+    pub document: Option<String>,
+    pub emails: BTreeSet<String>,
+    pub phones: BTreeSet<String>,
+}
+
+impl ClientKeys {
+    #[must_use]
+    pub fn new(client: &clients::Model, contacts: &[client_contacts::Model]) -> Self {
+        let values = |kind: ContactKind| {
+            contacts
+                .iter()
+                .filter(move |contact| {
+                    contact.client_id == client.id && contact.kind == kind.as_str()
+                })
+                .map(|contact| contact.value.clone())
+        };
+        Self {
+            pid: client.pid,
+            name: normalize_name(&client.name),
+            document: client.document.clone(),
+            emails: values(ContactKind::Email)
+                .map(|email| email.to_lowercase())
+                .collect(),
+            phones: values(ContactKind::Phone)
+                .filter_map(|phone| phone_key(&phone))
+                .collect(),
+        }
+    }
+}
+
+/// Scores how likely two clients are the same one, from 0 to 100, with what
+/// they have in common. The same document settles it; emails, phones and
+/// names add up.
+#[must_use]
+pub fn duplicate_score(a: &ClientKeys, b: &ClientKeys) -> (u32, Vec<String>) {
+    let mut score = 0;
+    let mut reasons = vec![];
+    if a.document.is_some() && a.document == b.document {
+        score += 100;
+        reasons.push("document".to_string());
+    }
+    if !a.emails.is_disjoint(&b.emails) {
+        score += 40;
+        reasons.push("email".to_string());
+    }
+    if !a.phones.is_disjoint(&b.phones) {
+        score += 30;
+        reasons.push("phone".to_string());
+    }
+    if !a.name.is_empty() && a.name == b.name {
+        score += 40;
+        reasons.push("name".to_string());
+    } else {
+        let words = |name: &str| {
+            name.split(' ')
+                .filter(|word| word.len() > 2)
+                .map(ToString::to_string)
+                .collect::<BTreeSet<_>>()
+        };
+        let (a_words, b_words) = (words(&a.name), words(&b.name));
+        let common = a_words.intersection(&b_words).count();
+        let all = a_words.union(&b_words).count();
+        // most of the words in common, as with a middle name left out
+        if all > 0 && common * 3 >= all * 2 {
+            score += 20;
+            reasons.push("similar name".to_string());
+        }
+    }
+    (score.min(100), reasons)
+}
+
+/// Two clients that are likely the same one, the one registered first as the
+/// one to keep
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DuplicateCandidate {
+    pub primary_pid: Uuid,
+    pub secondary_pid: Uuid,
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+/// Pairs up the clients scoring at least `min_score`, the likeliest first.
+/// The clients are expected in the order they were registered.
+#[must_use]
+pub fn find_candidates(clients: &[ClientKeys], min_score: u32) -> Vec<DuplicateCandidate> {
+    let mut candidates = vec![];
+    for (index, primary) in clients.iter().enumerate() {
+        for secondary in &clients[index + 1..] {
+            let (score, reasons) = duplicate_score(primary, secondary);
+            if score >= min_score && score > 0 {
+                candidates.push(DuplicateCandidate {
+                    primary_pid: primary.pid,
+                    secondary_pid: secondary.pid,
+                    score,
+                    reasons,
+                });
+            }
+        }
+    }
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.score));
+    candidates
+}
+
+/// What the duplicate was before it was merged, kept in the audit trail
+#[derive(Debug, Serialize)]
+struct MergedClient<'a> {
+    client: &'a clients::Model,
+    contacts: &'a [client_contacts::Model],
+    addresses: &'a [client_addresses::Model],
+    orders: Vec<Uuid>,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::client_merges::Model {
+    /// finds all merges, the latest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find merges or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let client_merges = Entity::find()
+            .order_by_desc(client_merges::Column::Id)
+            .all(db)
+            .await?;
+        Ok(client_merges)
+    }
+
+    /// finds the clients that are likely duplicates of each other
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_duplicates(
+        db: &DatabaseConnection,
+        min_score: u32,
+    ) -> ModelResult<Vec<DuplicateCandidate>> {
+        let clients = clients::Entity::find()
+            .order_by_asc(clients::Column::Id)
+            .all(db)
+            .await?;
+        let contacts = client_contacts::Entity::find().all(db).await?;
+        let keys = clients
+            .iter()
+            .map(|client| ClientKeys::new(client, &contacts))
+            .collect::<Vec<_>>();
+        Ok(find_candidates(&keys, min_score))
+    }
+
+    /// merges a duplicate client into the one to keep: its orders, and with
+    /// them their payments and fees, its contacts and addresses the kept
+    /// client does not have yet, its notes and attachments and its document
+    /// if the kept client has none. The duplicate is then removed, recording what it was.
+    ///
+    /// # Errors
+    ///
+    /// When could not find the clients, they are the same one or DB query
+    /// error
+    pub async fn merge(
+        db: &DatabaseConnection,
+        params: &MergeClients,
+        user_id: Option<i32>,
+    ) -> ModelResult<Self> {
+        if params.primary_pid == params.secondary_pid {
+            return Err(ModelError::Any("cannot merge a client into itself".into()));
+        }
+        let primary = clients::Model::find_by_pid(db, params.primary_pid).await?;
+        let secondary = clients::Model::find_by_pid(db, params.secondary_pid).await?;
+        let txn = db.begin().await?;
+
+        let orders = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::ClientId, secondary.id)
+                    .build(),
+            )
+            .all(&txn)
+            .await?;
+        let contacts = client_contacts::Model::find_by_client_id(&txn, secondary.id).await?;
+        let addresses = client_addresses::Model::find_by_client_id(&txn, secondary.id).await?;
+        let snapshot = serde_json::to_string(&MergedClient {
+            client: &secondary,
+            contacts: &contacts,
+            addresses: &addresses,
+            orders: orders.iter().map(|order| order.pid).collect(),
+        })
+        .map_err(|err| ModelError::Any(err.into()))?;
+
+        let mut moved_orders = vec![];
+        for order in orders {
+            moved_orders.push(order.id);
+            let mut moved_order = order.into_active_model();
+            moved_order.client_id = ActiveValue::Set(primary.id);
+            moved_order.update(&txn).await?;
+        }
+
+        let kept_contacts = client_contacts::Model::find_by_client_id(&txn, primary.id).await?;
+        for contact in contacts {
+            if kept_contacts
+                .iter()
+                .any(|kept| kept.kind == contact.kind && kept.value == contact.value)
+            {
+                continue;
+            }
+            let has_primary = kept_contacts
+                .iter()
+                .any(|kept| kept.kind == contact.kind && kept.primary);
+            let primary_flag = contact.primary && !has_primary;
+            let mut moved_contact = contact.into_active_model();
+            moved_contact.client_id = ActiveValue::Set(primary.id);
+            moved_contact.primary = ActiveValue::Set(primary_flag);
+            moved_contact.update(&txn).await?;
+        }
+        let kept_addresses = client_addresses::Model::find_by_client_id(&txn, primary.id).await?;
+        for address in addresses {
+            if kept_addresses
+                .iter()
+                .any(|kept| kept.cep == address.cep && kept.number == address.number)
+            {
+                continue;
+            }
+            let primary_flag = address.primary && kept_addresses.is_empty();
+            let mut moved_address = address.into_active_model();
+            moved_address.client_id = ActiveValue::Set(primary.id);
+            moved_address.primary = ActiveValue::Set(primary_flag);
+            moved_address.update(&txn).await?;
+        }
+
+        let moved_notes = notes::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(notes::Column::ClientId, secondary.id)
+                    .build(),
+            )
+            .all(&txn)
+            .await?;
+        for note in moved_notes {
+            let mut moved_note = note.into_active_model();
+            moved_note.client_id = ActiveValue::Set(Some(primary.id));
+            moved_note.update(&txn).await?;
+        }
+
+        let moved_attachments = attachments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(attachments::Column::ClientId, secondary.id)
+                    .build(),
+            )
+            .all(&txn)
+            .await?;
+        for attachment in moved_attachments {
+            let mut moved_attachment = attachment.into_active_model();
+            moved_attachment.client_id = ActiveValue::Set(Some(primary.id));
+            moved_attachment.update(&txn).await?;
+        }
+
+        let secondary_pid = secondary.pid;
+        let secondary_name = secondary.name.clone();
+        let document = secondary.document.clone();
+        // the document is unique, so the duplicate lets go of it first
+        secondary.into_active_model().delete(&txn).await?;
+        if primary.document.is_none() && document.is_some() {
+            let mut kept_client = primary.clone().into_active_model();
+            kept_client.document = ActiveValue::Set(document);
+            kept_client.update(&txn).await?;
+        }
+
+        let client_merge = client_merges::ActiveModel {
+            primary_client_id: ActiveValue::Set(primary.id),
+            secondary_client_pid: ActiveValue::Set(secondary_pid),
+            secondary_name: ActiveValue::Set(secondary_name),
+            snapshot: ActiveValue::Set(snapshot),
+            moved_orders: ActiveValue::Set(i32::try_from(moved_orders.len()).unwrap_or(i32::MAX)),
+            user_id: ActiveValue::Set(user_id),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        // the receivables of the moved orders now belong to the kept client
+        for order_id in moved_orders {
+            journal_entries::Model::sync_order(&txn, order_id).await?;
+        }
+        txn.commit().await?;
+        Ok(client_merge)
+    }
+}
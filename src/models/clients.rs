@@ -1,7 +1,10 @@
 use super::_entities::clients::{ActiveModel, Entity};
 use sea_orm::entity::prelude::*;
 pub type Clients = Entity;
-use crate::models::_entities::{clients, partners};
+use crate::models::_entities::{attachments, client_addresses, client_contacts, clients, partners};
+use crate::models::attachments::AttachmentTarget;
+use crate::models::client_addresses::{check_addresses, NewAddress};
+use crate::models::client_contacts::{check_contacts, NewContact};
 use loco_rs::model::ModelError;
 use loco_rs::model::{self, ModelResult};
 use sea_orm::TransactionTrait;
@@ -12,10 +15,115 @@ use serde::{Deserialize, Serialize};
 pub struct CreateNewClient {
     pub name: String,
     pub contact: String,
-    pub phone: String,
-    pub phone2: Option<String>,
-    pub email: String,
     pub partner_pid: Option<Uuid>,
+    /// the CPF or the CNPJ of the client, with or without its mask; when
+    /// updating, an empty one removes it and a missing one keeps it
+    pub document: Option<String>,
+    /// the phones and emails of the client; when updating, missing ones keep
+    /// those it has and given ones replace them
+    pub contacts: Option<Vec<NewContact>>,
+    /// the addresses of the client; when updating, missing ones keep those it
+    /// has and given ones replace them
+    pub addresses: Option<Vec<NewAddress>>,
+}
+
+/// The Brazilian tax ID of a person (CPF) or of a company (CNPJ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxIdKind {
+    Cpf,
+    Cnpj,
+}
+
+impl TaxIdKind {
+    /// tells a CPF from a CNPJ by the number of digits
+    #[must_use]
+    pub const fn of(digits: &str) -> Option<Self> {
+        match digits.len() {
+            11 => Some(Self::Cpf),
+            14 => Some(Self::Cnpj),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Cpf => "CPF",
+            Self::Cnpj => "CNPJ",
+        }
+    }
+}
+
+/// The check digit of a CPF or a CNPJ over the given digits, the weights
+/// applied from the last digit backwards
+fn check_digit(digits: &[u32], weights: impl Iterator<Item = u32>) -> u32 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .zip(weights)
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    match sum % 11 {
+        0 | 1 => 0,
+        rest => 11 - rest,
+    }
+}
+
+/// Strips the mask from a CPF or a CNPJ and checks its digits, returning
+/// only the digits of a valid one
+#[must_use]
+pub fn normalize_document(document: &str) -> Option<String> {
+    let digits = document
+        .chars()
+        .filter(|c| !matches!(c, '.' | '-' | '/' | ' '))
+        .collect::<String>();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let kind = TaxIdKind::of(&digits)?;
+    let numbers = digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect::<Vec<_>>();
+    // sequences of a single digit pass the check but are never issued
+    if numbers.iter().all(|digit| *digit == numbers[0]) {
+        return None;
+    }
+    let base = numbers.len() - 2;
+    let first = match kind {
+        TaxIdKind::Cpf => check_digit(&numbers[..base], 2..),
+        TaxIdKind::Cnpj => check_digit(&numbers[..base], (2..=9).cycle()),
+    };
+    let second = match kind {
+        TaxIdKind::Cpf => check_digit(&numbers[..=base], 2..),
+        TaxIdKind::Cnpj => check_digit(&numbers[..=base], (2..=9).cycle()),
+    };
+    (numbers[base] == first && numbers[base + 1] == second).then_some(digits)
+}
+
+/// Writes a CPF or a CNPJ with its mask, as in `123.456.789-09` or
+/// `12.345.678/0001-95`, leaving anything else as it is
+#[must_use]
+pub fn format_document(document: &str) -> String {
+    match TaxIdKind::of(document) {
+        Some(TaxIdKind::Cpf) if document.is_ascii() => format!(
+            "{}.{}.{}-{}",
+            &document[..3],
+            &document[3..6],
+            &document[6..9],
+            &document[9..]
+        ),
+        Some(TaxIdKind::Cnpj) if document.is_ascii() => format!(
+            "{}.{}.{}/{}-{}",
+            &document[..2],
+            &document[2..5],
+            &document[5..8],
+            &document[8..12],
+            &document[12..]
+        ),
+        _ => document.to_string(),
+    }
 }
 
 #[async_trait::async_trait]
@@ -41,6 +149,69 @@ impl ActiveModelBehavior for ActiveModel {
 }
 
 impl super::_entities::clients::Model {
+    /// the client's CPF or CNPJ with its mask
+    #[must_use]
+    pub fn formatted_document(&self) -> Option<String> {
+        self.document.as_deref().map(format_document)
+    }
+
+    /// checks the document given for a client and that no other client has
+    /// it, returning it without its mask. A blank document is no document.
+    ///
+    /// # Errors
+    ///
+    /// When the document is not a valid CPF or CNPJ, another client has it
+    /// or DB query error
+    async fn check_document(
+        db: &DatabaseConnection,
+        document: Option<&str>,
+        client_id: Option<i32>,
+    ) -> ModelResult<Option<String>> {
+        let Some(document) = document.filter(|document| !document.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let document = normalize_document(document)
+            .ok_or_else(|| ModelError::Any("document is not a valid CPF or CNPJ".into()))?;
+        let existing_client = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(clients::Column::Document, document.as_str())
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        match existing_client {
+            Some(existing_client) if Some(existing_client.id) != client_id => Err(ModelError::Any(
+                "another client already has this document".into(),
+            )),
+            _ => Ok(Some(document)),
+        }
+    }
+
+    /// finds the clients whose CPF or CNPJ holds the given digits, masked or
+    /// not
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_document(
+        db: &DatabaseConnection,
+        document: &str,
+    ) -> ModelResult<Vec<Self>> {
+        let digits = document
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+        if digits.is_empty() {
+            return Ok(vec![]);
+        }
+        let clients = Entity::find()
+            .filter(clients::Column::Document.contains(&digits))
+            .all(db)
+            .await?;
+        Ok(clients)
+    }
+
     /// finds a client by the provided pid
     ///
     /// # Errors
@@ -95,23 +266,27 @@ impl super::_entities::clients::Model {
             Some(pid) => Some(partners::Model::find_by_pid(db, pid).await?),
             None => None,
         };
+        let document = Self::check_document(db, client.document.as_deref(), None).await?;
+        let contacts = check_contacts(client.contacts.unwrap_or_default())?;
+        let addresses = check_addresses(client.addresses.unwrap_or_default())?;
         let txn = db.begin().await?;
         let client = clients::ActiveModel {
             name: ActiveValue::Set(client.name),
             contact: ActiveValue::Set(client.contact),
-            phone: ActiveValue::Set(client.phone),
-            phone2: ActiveValue::Set(client.phone2),
-            email: ActiveValue::Set(client.email),
             partner_id: ActiveValue::Set(partner.map(|p| p.id)),
+            document: ActiveValue::Set(document),
             ..Default::default()
         }
         .insert(&txn)
         .await?;
+        client_contacts::Model::replace_for_client(&txn, client.id, contacts).await?;
+        client_addresses::Model::replace_for_client(&txn, client.id, addresses).await?;
         txn.commit().await?;
         Ok(client)
     }
 
-    /// updates a client
+    /// updates a client, keeping its document, contacts and addresses when
+    /// none are given
     ///
     /// # Errors
     ///
@@ -130,6 +305,15 @@ impl super::_entities::clients::Model {
             .one(db)
             .await?
             .ok_or_else(|| ModelError::EntityNotFound)?;
+        // a client edited without its document keeps the one it has
+        let document = match client.document.as_deref() {
+            Some(document) => {
+                Self::check_document(db, Some(document), Some(existing_client.id)).await?
+            }
+            None => existing_client.document.clone(),
+        };
+        let contacts = client.contacts.map(check_contacts).transpose()?;
+        let addresses = client.addresses.map(check_addresses).transpose()?;
         let mut edited_client = existing_client.into_active_model();
         let partner = match client.partner_pid {
             Some(pid) => Some(partners::Model::find_by_pid(db, pid).await?),
@@ -137,21 +321,26 @@ impl super::_entities::clients::Model {
         };
         edited_client.name = ActiveValue::Set(client.name);
         edited_client.contact = ActiveValue::Set(client.contact);
-        edited_client.phone = ActiveValue::Set(client.phone);
-        edited_client.phone2 = ActiveValue::Set(client.phone2);
-        edited_client.email = ActiveValue::Set(client.email);
         edited_client.partner_id = ActiveValue::Set(partner.map(|p| p.id));
+        edited_client.document = ActiveValue::Set(document);
         let txn = db.begin().await?;
         let client = edited_client.update(&txn).await?;
+        if let Some(contacts) = contacts {
+            client_contacts::Model::replace_for_client(&txn, client.id, contacts).await?;
+        }
+        if let Some(addresses) = addresses {
+            client_addresses::Model::replace_for_client(&txn, client.id, addresses).await?;
+        }
         txn.commit().await?;
         Ok(client)
     }
 
-    /// deletes a client
+    /// deletes a client, unless files are kept with it or with its orders
     ///
     /// # Errors
     ///
-    /// When could not delete client or DB query error
+    /// When the client has attachments, could not delete client or DB query
+    /// error
     pub async fn delete(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
         let existing_client = Entity::find()
             .filter(
@@ -162,6 +351,13 @@ impl super::_entities::clients::Model {
             .one(db)
             .await?
             .ok_or_else(|| ModelError::EntityNotFound)?;
+        if attachments::Model::any_kept_with(db, AttachmentTarget::Client(existing_client.id))
+            .await?
+        {
+            return Err(ModelError::Any(
+                "a client with attachments cannot be deleted".into(),
+            ));
+        }
         let txn = db.begin().await?;
         existing_client.delete(&txn).await?;
         txn.commit().await?;
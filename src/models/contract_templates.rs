@@ -0,0 +1,168 @@
+use super::_entities::contract_templates::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type ContractTemplates = Entity;
+use crate::documents::contract;
+use crate::models::_entities::{contract_templates, processes};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult, ModelValidation};
+use sea_orm::{ActiveValue, IntoActiveModel, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+/// The contract template of a process, given by its pid
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewContractTemplate {
+    pub process_pid: Uuid,
+    pub name: String,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateContractTemplate {
+    pub name: String,
+    pub body: String,
+}
+
+/// checks the body of a template can be rendered, telling what is wrong
+/// when it cannot
+fn check_body(body: &str) -> ModelResult<()> {
+    contract::check(body).map_err(|message| ModelError::ModelValidation {
+        errors: ModelValidation {
+            code: "invalid_template".to_string(),
+            message: Some(message),
+        },
+    })
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::contract_templates::Model {
+    /// finds a contract template by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find contract template by the given pid or DB query
+    /// error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let template = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(contract_templates::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        template.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the contract template of a process, if it has one
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_process_id(
+        db: &DatabaseConnection,
+        process_id: i32,
+    ) -> ModelResult<Option<Self>> {
+        let template = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(contract_templates::Column::ProcessId, process_id)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        Ok(template)
+    }
+
+    /// finds all contract templates
+    ///
+    /// # Errors
+    ///
+    /// When could not find contract templates or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let templates = Entity::find()
+            .order_by_asc(contract_templates::Column::Name)
+            .all(db)
+            .await?;
+        Ok(templates)
+    }
+
+    /// creates the contract template of a process
+    ///
+    /// # Errors
+    ///
+    /// When could not find the process, it already has a template, the body
+    /// has a placeholder that is not known or not closed or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        template: CreateNewContractTemplate,
+    ) -> ModelResult<Self> {
+        check_body(&template.body)?;
+        let process = processes::Model::find_by_pid(db, template.process_pid).await?;
+        if Self::find_by_process_id(db, process.id).await?.is_some() {
+            return Err(ModelError::EntityAlreadyExists);
+        }
+        let template = contract_templates::ActiveModel {
+            process_id: ActiveValue::Set(process.id),
+            name: ActiveValue::Set(template.name),
+            body: ActiveValue::Set(template.body),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(template)
+    }
+
+    /// changes the name and body of a contract template; the contracts of
+    /// the orders of its process get a new version the next time they are
+    /// generated
+    ///
+    /// # Errors
+    ///
+    /// When could not find contract template by the given pid, the body has
+    /// a placeholder that is not known or not closed or DB query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        template: UpdateContractTemplate,
+    ) -> ModelResult<Self> {
+        check_body(&template.body)?;
+        let mut edited_template = Self::find_by_pid(db, pid).await?.into_active_model();
+        edited_template.name = ActiveValue::Set(template.name);
+        edited_template.body = ActiveValue::Set(template.body);
+        Ok(edited_template.update(db).await?)
+    }
+
+    /// deletes a contract template, so its process goes back to the default
+    /// one
+    ///
+    /// # Errors
+    ///
+    /// When could not find contract template by the given pid or DB query
+    /// error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_template = Self::find_by_pid(db, pid).await?;
+        existing_template.clone().delete(db).await?;
+        Ok(existing_template)
+    }
+}
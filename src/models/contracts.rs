@@ -0,0 +1,329 @@
+use super::_entities::contracts::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type Contracts = Entity;
+use crate::documents::contract::{self, ContractData, DEFAULT_TEMPLATE};
+use crate::documents::Letterhead;
+use crate::models::_entities::{attachments, contract_templates, contracts, orders, users};
+use crate::models::attachments::{AttachmentCategory, AttachmentTarget, Upload};
+use bytes::Bytes;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::storage::Storage;
+use sea_orm::{ActiveValue, QueryOrder};
+
+/// The files and the template of contract versions, with who generated them
+#[derive(Debug, Clone, Default)]
+pub struct ContractLinks {
+    pub templates: Vec<contract_templates::Model>,
+    pub attachments: Vec<attachments::Model>,
+    pub users: Vec<users::Model>,
+}
+
+/// What the contract of an order is written from
+struct Draft {
+    order: orders::Model,
+    order_name: String,
+    template: Option<contract_templates::Model>,
+    data: ContractData,
+    fingerprint: String,
+}
+
+impl Draft {
+    fn body(&self) -> &str {
+        self.template
+            .as_ref()
+            .map_or(DEFAULT_TEMPLATE, |template| template.body.as_str())
+    }
+}
+
+/// the SHA-256 of a template and the data of an order, which changes only
+/// when the contract would read differently
+fn fingerprint(template: &str, data: &ContractData) -> String {
+    let data = serde_json::to_string(data).unwrap_or_default();
+    crate::models::attachments::checksum(format!("{template}\n{data}").as_bytes())
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::contracts::Model {
+    /// finds the contract versions of an order, the latest first
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let contracts = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(contracts::Column::OrderId, order_id)
+                    .build(),
+            )
+            .order_by_desc(contracts::Column::Version)
+            .all(db)
+            .await?;
+        Ok(contracts)
+    }
+
+    /// finds the latest contract version of an order, if it has one
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn latest(db: &DatabaseConnection, order_id: i32) -> ModelResult<Option<Self>> {
+        let contract = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(contracts::Column::OrderId, order_id)
+                    .build(),
+            )
+            .order_by_desc(contracts::Column::Version)
+            .one(db)
+            .await?;
+        Ok(contract)
+    }
+
+    /// finds the files, templates and users of contract versions
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_links(
+        db: &DatabaseConnection,
+        contracts: &[Self],
+    ) -> ModelResult<ContractLinks> {
+        let templates = contract_templates::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        contract_templates::Column::Id,
+                        contracts.iter().filter_map(|contract| contract.template_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let attachments = attachments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        attachments::Column::Id,
+                        contracts.iter().flat_map(|contract| {
+                            [contract.pdf_attachment_id, contract.docx_attachment_id]
+                                .into_iter()
+                                .flatten()
+                        }),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let users = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        users::Column::Id,
+                        contracts.iter().filter_map(|contract| contract.user_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(ContractLinks {
+            templates,
+            attachments,
+            users,
+        })
+    }
+
+    /// works out what the contract of an order is written from: the template
+    /// of its process, or the default one, and its data
+    async fn draft(
+        db: &DatabaseConnection,
+        letterhead: &Letterhead,
+        order: orders::Model,
+    ) -> ModelResult<Draft> {
+        let details = orders::Model::find_by_pid(db, order.pid).await?;
+        let template = contract_templates::Model::find_by_process_id(db, order.process_id).await?;
+        let data = ContractData::new(letterhead, &details);
+        let body = template
+            .as_ref()
+            .map_or(DEFAULT_TEMPLATE, |template| template.body.as_str());
+        let fingerprint = fingerprint(body, &data);
+        Ok(Draft {
+            order,
+            order_name: format!("{} - {}", details.process.case_type, details.client.name),
+            template,
+            data,
+            fingerprint,
+        })
+    }
+
+    /// the fingerprint the contract of an order would have if written now,
+    /// which the versions written from the same details share
+    ///
+    /// # Errors
+    ///
+    /// When could not find the order or DB query error
+    pub async fn current_fingerprint(
+        db: &DatabaseConnection,
+        letterhead: &Letterhead,
+        order_id: i32,
+    ) -> ModelResult<String> {
+        let order = orders::Entity::find_by_id(order_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let draft = Self::draft(db, letterhead, order).await?;
+        Ok(draft.fingerprint)
+    }
+
+    /// writes a new version of the contract of an order as PDF and DOCX,
+    /// kept as attachments of the order, unless the latest version was
+    /// written from the same template and order details, which is returned
+    /// as it is
+    ///
+    /// # Errors
+    ///
+    /// When could not find the order, the template could not be rendered,
+    /// could not write to the storage or DB query error
+    pub async fn generate(
+        db: &DatabaseConnection,
+        storage: &Storage,
+        letterhead: &Letterhead,
+        user_id: Option<i32>,
+        order_pid: Uuid,
+        issued_on: chrono::NaiveDate,
+    ) -> ModelResult<Self> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let draft = Self::draft(db, letterhead, order).await?;
+        let latest = Self::latest(db, draft.order.id).await?;
+        if let Some(latest) = latest.as_ref() {
+            if latest.fingerprint == draft.fingerprint {
+                return Ok(latest.clone());
+            }
+        }
+        let version = latest.map_or(1, |latest| latest.version + 1);
+
+        let rendered = contract::render(draft.body(), &draft.data, issued_on)
+            .map_err(|message| ModelError::Any(message.into()))?;
+        let file_name = format!("Contrato - {} - v{version}", draft.order_name);
+        let pdf = attachments::Model::store(
+            db,
+            storage,
+            user_id,
+            AttachmentTarget::Order(draft.order.id),
+            Upload {
+                category: AttachmentCategory::Contract,
+                file_name: format!("{file_name}.pdf"),
+                content_type: "application/pdf".to_string(),
+                content: Bytes::from(rendered.to_pdf(letterhead)),
+                checksum: None,
+            },
+        )
+        .await?;
+        let docx = attachments::Model::store(
+            db,
+            storage,
+            user_id,
+            AttachmentTarget::Order(draft.order.id),
+            Upload {
+                category: AttachmentCategory::Contract,
+                file_name: format!("{file_name}.docx"),
+                content_type:
+                    "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+                        .to_string(),
+                content: Bytes::from(rendered.to_docx()),
+                checksum: None,
+            },
+        )
+        .await?;
+
+        let contract = contracts::ActiveModel {
+            order_id: ActiveValue::Set(draft.order.id),
+            version: ActiveValue::Set(version),
+            fingerprint: ActiveValue::Set(draft.fingerprint),
+            template_id: ActiveValue::Set(draft.template.map(|template| template.id)),
+            pdf_attachment_id: ActiveValue::Set(Some(pdf.id)),
+            docx_attachment_id: ActiveValue::Set(Some(docx.id)),
+            user_id: ActiveValue::Set(user_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(contract)
+    }
+
+    /// writes a new contract version for every open order whose contract
+    /// no longer matches the order or the template of its process, giving
+    /// the versions written
+    ///
+    /// # Errors
+    ///
+    /// When DB query error; an order whose contract could not be written is
+    /// logged and skipped
+    pub async fn refresh_all(
+        db: &DatabaseConnection,
+        storage: &Storage,
+        letterhead: &Letterhead,
+        issued_on: chrono::NaiveDate,
+    ) -> ModelResult<Vec<Self>> {
+        let orders = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Open, true)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let mut refreshed = vec![];
+        for order in orders {
+            let Some(latest) = Self::latest(db, order.id).await? else {
+                continue;
+            };
+            let order_pid = order.pid;
+            match Self::generate(db, storage, letterhead, None, order_pid, issued_on).await {
+                Ok(contract) if contract.version != latest.version => refreshed.push(contract),
+                Ok(_) => {}
+                Err(err) => {
+                    tracing::warn!(
+                        order_pid = order_pid.to_string(),
+                        message = err.to_string(),
+                        "could not write a new contract version",
+                    );
+                }
+            }
+        }
+        Ok(refreshed)
+    }
+}
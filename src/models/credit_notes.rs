@@ -0,0 +1,282 @@
+use super::_entities::credit_notes::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type CreditNotes = Entity;
+use crate::models::_entities::{credit_notes, journal_entries, order_fees, orders, payments};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewCreditNote {
+    pub order_pid: Uuid,
+    pub order_fee_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub value: f32,
+    pub reason: String,
+    pub credit_date: chrono::NaiveDate,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(range(exclusive_min = 0.0, message = "Value must be greater than zero."))]
+    pub value: f32,
+    #[validate(length(min = 2, message = "Reason must be at least 2 characters long."))]
+    pub reason: String,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            value: *self.value.as_ref(),
+            reason: self.reason.as_ref().to_owned(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+/// Returns what the client still owes on an order once its credit notes are
+/// taken into account.
+///
+/// A credit note allocated to an instalment only reduces that instalment, and
+/// only while it is open. Credit notes without an allocation reduce the order
+/// as a whole. The result never goes below zero.
+#[must_use]
+pub fn outstanding_balance(
+    payments: &[payments::Model],
+    credit_notes: &[credit_notes::Model],
+) -> f32 {
+    let instalments: f32 = payments
+        .iter()
+        .filter(|payment| payment.open)
+        .map(|payment| {
+            let credited: f32 = credit_notes
+                .iter()
+                .filter(|credit_note| credit_note.payment_id == Some(payment.id))
+                .map(|credit_note| credit_note.value)
+                .sum();
+            (payment.value - credited).max(0.0)
+        })
+        .sum();
+    let unallocated: f32 = credit_notes
+        .iter()
+        .filter(|credit_note| credit_note.payment_id.is_none())
+        .map(|credit_note| credit_note.value)
+        .sum();
+    (instalments - unallocated).max(0.0)
+}
+
+impl super::_entities::credit_notes::Model {
+    /// finds a credit note by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find credit note by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let credit_note = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::credit_notes::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        credit_note.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the credit notes of an order
+    ///
+    /// # Errors
+    ///
+    /// When could not find credit notes or DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let credit_notes = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::credit_notes::Column::OrderId, order_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(credit_notes)
+    }
+
+    /// finds all credit notes
+    ///
+    /// # Errors
+    ///
+    /// When could not find credit notes or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let credit_notes = Entity::find().all(db).await?;
+        Ok(credit_notes)
+    }
+
+    /// resolves the order, order fee and payment a credit note points to
+    ///
+    /// # Errors
+    ///
+    /// When any of them could not be found, when the order fee or payment
+    /// belongs to another order, or when the payment is already closed and
+    /// was not the one the credit note was allocated to
+    async fn resolve_targets(
+        db: &DatabaseConnection,
+        credit_note: &CreateNewCreditNote,
+        allocated_payment_id: Option<i32>,
+    ) -> ModelResult<(orders::Model, Option<i32>, Option<i32>)> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, credit_note.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let order_fee_id = match credit_note.order_fee_pid {
+            Some(pid) => {
+                let order_fee = order_fees::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(order_fees::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                if order_fee.order_id != order.id {
+                    return Err(ModelError::Any(
+                        "order fee does not belong to the order".into(),
+                    ));
+                }
+                Some(order_fee.id)
+            }
+            None => None,
+        };
+        let payment_id = match credit_note.payment_pid {
+            Some(pid) => {
+                let payment = payments::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(payments::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                if payment.order_id != order.id {
+                    return Err(ModelError::Any(
+                        "payment does not belong to the order".into(),
+                    ));
+                }
+                if !payment.open && allocated_payment_id != Some(payment.id) {
+                    return Err(ModelError::Any(
+                        "credit notes can only be allocated to open payments".into(),
+                    ));
+                }
+                Some(payment.id)
+            }
+            None => None,
+        };
+        Ok((order, order_fee_id, payment_id))
+    }
+
+    /// creates a new credit note
+    ///
+    /// # Errors
+    ///
+    /// When could not create credit note or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        credit_note: CreateNewCreditNote,
+    ) -> ModelResult<Self> {
+        let (order, order_fee_id, payment_id) =
+            Self::resolve_targets(db, &credit_note, None).await?;
+        let txn = db.begin().await?;
+        let credit_note = credit_notes::ActiveModel {
+            order_id: ActiveValue::Set(order.id),
+            order_fee_id: ActiveValue::Set(order_fee_id),
+            payment_id: ActiveValue::Set(payment_id),
+            value: ActiveValue::Set(credit_note.value),
+            reason: ActiveValue::Set(credit_note.reason),
+            credit_date: ActiveValue::Set(credit_note.credit_date),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        journal_entries::Model::sync_order(&txn, credit_note.order_id).await?;
+        txn.commit().await?;
+        Ok(credit_note)
+    }
+
+    /// updates a credit note
+    ///
+    /// # Errors
+    ///
+    /// When could not update credit note or DB query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        credit_note: CreateNewCreditNote,
+    ) -> ModelResult<Self> {
+        let existing_credit_note = Self::find_by_pid(db, pid).await?;
+        let previous_order_id = existing_credit_note.order_id;
+        let (order, order_fee_id, payment_id) =
+            Self::resolve_targets(db, &credit_note, existing_credit_note.payment_id).await?;
+        let mut edited_credit_note = existing_credit_note.into_active_model();
+        edited_credit_note.order_id = ActiveValue::Set(order.id);
+        edited_credit_note.order_fee_id = ActiveValue::Set(order_fee_id);
+        edited_credit_note.payment_id = ActiveValue::Set(payment_id);
+        edited_credit_note.value = ActiveValue::Set(credit_note.value);
+        edited_credit_note.reason = ActiveValue::Set(credit_note.reason);
+        edited_credit_note.credit_date = ActiveValue::Set(credit_note.credit_date);
+        let txn = db.begin().await?;
+        let credit_note = edited_credit_note.update(&txn).await?;
+        journal_entries::Model::sync_order(&txn, credit_note.order_id).await?;
+        if previous_order_id != credit_note.order_id {
+            journal_entries::Model::sync_order(&txn, previous_order_id).await?;
+        }
+        txn.commit().await?;
+        Ok(credit_note)
+    }
+
+    /// deletes a credit note
+    ///
+    /// # Errors
+    ///
+    /// When could not delete credit note or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_credit_note = Self::find_by_pid(db, pid).await?;
+        let txn = db.begin().await?;
+        existing_credit_note.clone().delete(&txn).await?;
+        journal_entries::Model::sync_order(&txn, existing_credit_note.order_id).await?;
+        txn.commit().await?;
+        Ok(existing_credit_note)
+    }
+}
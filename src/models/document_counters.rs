@@ -0,0 +1,59 @@
+use super::_entities::document_counters::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type DocumentCounters = Entity;
+use crate::models::_entities::document_counters;
+use crate::models::documents::DocumentKind;
+use loco_rs::model::{self, ModelError, ModelResult};
+use sea_orm::{ActiveValue, IntoActiveModel, QuerySelect};
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::document_counters::Model {
+    /// takes the next number of a kind of document, locking its counter
+    /// until `db` commits so no other document gets it, and no number is
+    /// given again even when its document is gone
+    ///
+    /// # Errors
+    ///
+    /// When the kind has no counter or DB query error
+    pub async fn next_number<C>(db: &C, kind: DocumentKind) -> ModelResult<i32>
+    where
+        C: ConnectionTrait,
+    {
+        let counter = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(document_counters::Column::Kind, kind.as_str())
+                    .build(),
+            )
+            .lock_exclusive()
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let number = counter.last_number + 1;
+        let mut edited_counter = counter.into_active_model();
+        edited_counter.last_number = ActiveValue::Set(number);
+        edited_counter.update(db).await?;
+        Ok(number)
+    }
+}
@@ -0,0 +1,155 @@
+use super::_entities::documents::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type Documents = Entity;
+use crate::models::_entities::document_counters;
+use crate::models::_entities::documents;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::ActiveValue;
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+
+/// The documents handed to clients, each numbered in its own sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentKind {
+    Receipt,
+    Statement,
+}
+
+impl DocumentKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Receipt => "receipt",
+            Self::Statement => "statement",
+        }
+    }
+
+    #[must_use]
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::Receipt => "REC",
+            Self::Statement => "EXT",
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::documents::Model {
+    /// the number printed on the document, such as `REC-000042`
+    #[must_use]
+    pub fn code(&self) -> String {
+        let prefix = if self.kind == DocumentKind::Receipt.as_str() {
+            DocumentKind::Receipt.prefix()
+        } else {
+            DocumentKind::Statement.prefix()
+        };
+        format!("{prefix}-{:06}", self.number)
+    }
+
+    /// finds the receipt already issued for a payment
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_receipt(
+        db: &DatabaseConnection,
+        payment_id: i32,
+    ) -> ModelResult<Option<Self>> {
+        let document = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(documents::Column::Kind, DocumentKind::Receipt.as_str())
+                    .eq(documents::Column::PaymentId, payment_id)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        Ok(document)
+    }
+
+    /// issues the next document of a kind, numbered from its counter so a
+    /// number is never given twice; a payment that already has a receipt,
+    /// issued by a download at the same time, gets that receipt back
+    ///
+    /// # Errors
+    ///
+    /// When could not create document or DB query error
+    pub async fn issue(
+        db: &DatabaseConnection,
+        kind: DocumentKind,
+        order_id: i32,
+        payment_id: Option<i32>,
+    ) -> ModelResult<Self> {
+        let txn = db.begin().await?;
+        let number = document_counters::Model::next_number(&txn, kind).await?;
+        if let (DocumentKind::Receipt, Some(payment_id)) = (kind, payment_id) {
+            let issued = Entity::find()
+                .filter(
+                    model::query::condition()
+                        .eq(documents::Column::Kind, kind.as_str())
+                        .eq(documents::Column::PaymentId, payment_id)
+                        .build(),
+                )
+                .one(&txn)
+                .await?;
+            if let Some(issued) = issued {
+                // the number taken is handed back by rolling back
+                txn.rollback().await?;
+                return Ok(issued);
+            }
+        }
+        let document = documents::ActiveModel {
+            kind: ActiveValue::Set(kind.as_str().to_string()),
+            number: ActiveValue::Set(number),
+            order_id: ActiveValue::Set(order_id),
+            payment_id: ActiveValue::Set(payment_id),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(document)
+    }
+
+    /// finds the documents issued on an order
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let documents = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(documents::Column::OrderId, order_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(documents)
+    }
+}
@@ -0,0 +1,342 @@
+use super::_entities::expenses::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type Expenses = Entity;
+use crate::business_days::BusinessCalendar;
+use crate::models::_entities::{attachments, expenses, journal_entries, orders, payments};
+use crate::models::attachments::AttachmentTarget;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewExpense {
+    pub order_pid: Uuid,
+    pub category: String,
+    pub value: f32,
+    pub expense_date: chrono::NaiveDate,
+    pub description: Option<String>,
+    pub receipt: Option<String>,
+    pub reimbursable: bool,
+}
+
+/// Expenses to bill back to the client, either added to an open instalment
+/// of the order or as a new instalment due on `due_date`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BillExpenses {
+    pub order_pid: Uuid,
+    pub expense_pids: Vec<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub due_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseStatus {
+    /// absorbed by the firm, never billed to the client
+    NotReimbursable,
+    /// reimbursable but not billed into an instalment yet
+    Pending,
+    /// billed into an instalment the client has not paid yet
+    Billed,
+    /// billed into an instalment the client has paid
+    Reimbursed,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(length(min = 2, message = "Category must be at least 2 characters long."))]
+    pub category: String,
+    #[validate(range(exclusive_min = 0.0, message = "Value must be greater than zero."))]
+    pub value: f32,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            category: self.category.as_ref().to_owned(),
+            value: *self.value.as_ref(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::expenses::Model {
+    /// tells whether the expense was billed to the client and paid back
+    #[must_use]
+    pub fn status(&self, payments: &[payments::Model]) -> ExpenseStatus {
+        if !self.reimbursable {
+            return ExpenseStatus::NotReimbursable;
+        }
+        match self
+            .payment_id
+            .and_then(|id| payments.iter().find(|payment| payment.id == id))
+        {
+            Some(payment) if payment.open => ExpenseStatus::Billed,
+            Some(_) => ExpenseStatus::Reimbursed,
+            None => ExpenseStatus::Pending,
+        }
+    }
+
+    /// finds an expense by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find expense by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let expense = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::expenses::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        expense.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the expenses of an order
+    ///
+    /// # Errors
+    ///
+    /// When could not find expenses or DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let expenses = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::expenses::Column::OrderId, order_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(expenses)
+    }
+
+    /// finds all expenses
+    ///
+    /// # Errors
+    ///
+    /// When could not find expenses or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let expenses = Entity::find().all(db).await?;
+        Ok(expenses)
+    }
+
+    /// creates a new expense
+    ///
+    /// # Errors
+    ///
+    /// When could not create expense or DB query error
+    pub async fn create(db: &DatabaseConnection, expense: CreateNewExpense) -> ModelResult<Self> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, expense.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let txn = db.begin().await?;
+        let expense = expenses::ActiveModel {
+            order_id: ActiveValue::Set(order.id),
+            category: ActiveValue::Set(expense.category),
+            value: ActiveValue::Set(expense.value),
+            expense_date: ActiveValue::Set(expense.expense_date),
+            description: ActiveValue::Set(expense.description),
+            receipt: ActiveValue::Set(expense.receipt),
+            reimbursable: ActiveValue::Set(expense.reimbursable),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(expense)
+    }
+
+    /// updates an expense that was not billed yet
+    ///
+    /// # Errors
+    ///
+    /// When the expense was already billed, could not update expense or DB
+    /// query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        expense: CreateNewExpense,
+    ) -> ModelResult<Self> {
+        let existing_expense = Self::find_by_pid(db, pid).await?;
+        if existing_expense.payment_id.is_some() {
+            return Err(ModelError::Any("billed expenses cannot be changed".into()));
+        }
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, expense.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let mut edited_expense = existing_expense.into_active_model();
+        edited_expense.order_id = ActiveValue::Set(order.id);
+        edited_expense.category = ActiveValue::Set(expense.category);
+        edited_expense.value = ActiveValue::Set(expense.value);
+        edited_expense.expense_date = ActiveValue::Set(expense.expense_date);
+        edited_expense.description = ActiveValue::Set(expense.description);
+        edited_expense.receipt = ActiveValue::Set(expense.receipt);
+        edited_expense.reimbursable = ActiveValue::Set(expense.reimbursable);
+        let txn = db.begin().await?;
+        let expense = edited_expense.update(&txn).await?;
+        txn.commit().await?;
+        Ok(expense)
+    }
+
+    /// deletes an expense that was not billed yet and has no files kept
+    /// with it
+    ///
+    /// # Errors
+    ///
+    /// When the expense was already billed or has attachments, could not
+    /// delete expense or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_expense = Self::find_by_pid(db, pid).await?;
+        if existing_expense.payment_id.is_some() {
+            return Err(ModelError::Any("billed expenses cannot be deleted".into()));
+        }
+        if attachments::Model::any_kept_with(db, AttachmentTarget::Expense(existing_expense.id))
+            .await?
+        {
+            return Err(ModelError::Any(
+                "an expense with attachments cannot be deleted".into(),
+            ));
+        }
+        let txn = db.begin().await?;
+        existing_expense.clone().delete(&txn).await?;
+        txn.commit().await?;
+        Ok(existing_expense)
+    }
+
+    /// bills reimbursable expenses back to the client, returning the
+    /// instalment they were billed into; a new instalment is due on the
+    /// business day its due date falls on or after
+    ///
+    /// # Errors
+    ///
+    /// When an expense is not reimbursable, already billed or from another
+    /// order, when the instalment is not open, or DB query error
+    pub async fn bill(
+        db: &DatabaseConnection,
+        bill: BillExpenses,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<payments::Model> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, bill.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let mut to_bill = vec![];
+        // an expense sent twice is billed once
+        let mut seen = HashSet::new();
+        for pid in &bill.expense_pids {
+            if !seen.insert(*pid) {
+                continue;
+            }
+            let expense = Self::find_by_pid(db, *pid).await?;
+            if expense.order_id != order.id {
+                return Err(ModelError::Any(
+                    "expense does not belong to the order".into(),
+                ));
+            }
+            if !expense.reimbursable {
+                return Err(ModelError::Any("expense is not reimbursable".into()));
+            }
+            if expense.payment_id.is_some() {
+                return Err(ModelError::Any("expense was already billed".into()));
+            }
+            to_bill.push(expense);
+        }
+        if to_bill.is_empty() {
+            return Err(ModelError::Any("no expenses to bill".into()));
+        }
+        let total: f32 = to_bill.iter().map(|expense| expense.value).sum();
+
+        let txn = db.begin().await?;
+        let payment = match bill.payment_pid {
+            Some(pid) => {
+                let payment = payments::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(payments::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(&txn)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                if payment.order_id != order.id || !payment.open {
+                    return Err(ModelError::Any(
+                        "expenses can only be billed into an open payment of the order".into(),
+                    ));
+                }
+                let value = payment.value + total;
+                let mut edited_payment = payment.into_active_model();
+                edited_payment.value = ActiveValue::Set(value);
+                edited_payment.update(&txn).await?
+            }
+            None => {
+                let due_date = bill.due_date.ok_or_else(|| {
+                    ModelError::Any("a due date is needed to bill into a new payment".into())
+                })?;
+                payments::ActiveModel {
+                    value: ActiveValue::Set(total),
+                    due_date: ActiveValue::Set(calendar.next_business_day(due_date)),
+                    order_id: ActiveValue::Set(order.id),
+                    open: ActiveValue::Set(true),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?
+            }
+        };
+        for expense in to_bill {
+            let mut billed_expense = expense.into_active_model();
+            billed_expense.payment_id = ActiveValue::Set(Some(payment.id));
+            billed_expense.update(&txn).await?;
+        }
+        journal_entries::Model::sync_order(&txn, order.id).await?;
+        txn.commit().await?;
+        Ok(payment)
+    }
+}
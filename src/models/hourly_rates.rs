@@ -0,0 +1,215 @@
+use super::_entities::hourly_rates::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type HourlyRates = Entity;
+use crate::models::_entities::{hourly_rates, orders, users};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+
+/// An hourly rate for a user, for an order, or for a user on one order
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewHourlyRate {
+    pub user_pid: Option<Uuid>,
+    pub order_pid: Option<Uuid>,
+    pub value: f32,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(range(exclusive_min = 0.0, message = "Value must be greater than zero."))]
+    pub value: f32,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            value: *self.value.as_ref(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+/// Returns the hourly rate billed for a user's time on an order.
+///
+/// A rate set for the user on that order wins, then the rate of the order,
+/// then the user's own rate.
+#[must_use]
+pub fn resolve_hourly_rate(
+    rates: &[hourly_rates::Model],
+    user_id: i32,
+    order_id: i32,
+) -> Option<f32> {
+    let find = |user: Option<i32>, order: Option<i32>| {
+        rates
+            .iter()
+            .find(|rate| rate.user_id == user && rate.order_id == order)
+            .map(|rate| rate.value)
+    };
+    find(Some(user_id), Some(order_id))
+        .or_else(|| find(None, Some(order_id)))
+        .or_else(|| find(Some(user_id), None))
+}
+
+impl super::_entities::hourly_rates::Model {
+    /// finds an hourly rate by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find hourly rate by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let hourly_rate = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::hourly_rates::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        hourly_rate.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds all hourly rates
+    ///
+    /// # Errors
+    ///
+    /// When could not find hourly rates or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let hourly_rates = Entity::find().all(db).await?;
+        Ok(hourly_rates)
+    }
+
+    /// resolves the user and order an hourly rate applies to
+    ///
+    /// # Errors
+    ///
+    /// When neither is given, when any of them could not be found or DB query
+    /// error
+    async fn resolve_targets(
+        db: &DatabaseConnection,
+        hourly_rate: &CreateNewHourlyRate,
+    ) -> ModelResult<(Option<i32>, Option<i32>)> {
+        if hourly_rate.user_pid.is_none() && hourly_rate.order_pid.is_none() {
+            return Err(ModelError::Any(
+                "an hourly rate needs a user or an order".into(),
+            ));
+        }
+        let user_id = match hourly_rate.user_pid {
+            Some(pid) => Some(
+                users::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(users::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?
+                    .id,
+            ),
+            None => None,
+        };
+        let order_id = match hourly_rate.order_pid {
+            Some(pid) => Some(
+                orders::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(orders::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?
+                    .id,
+            ),
+            None => None,
+        };
+        let duplicate = Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .any(|rate| rate.user_id == user_id && rate.order_id == order_id);
+        if duplicate {
+            return Err(ModelError::Any(
+                "an hourly rate already exists for this user and order".into(),
+            ));
+        }
+        Ok((user_id, order_id))
+    }
+
+    /// creates a new hourly rate
+    ///
+    /// # Errors
+    ///
+    /// When could not create hourly rate or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        hourly_rate: CreateNewHourlyRate,
+    ) -> ModelResult<Self> {
+        let (user_id, order_id) = Self::resolve_targets(db, &hourly_rate).await?;
+        let txn = db.begin().await?;
+        let hourly_rate = hourly_rates::ActiveModel {
+            user_id: ActiveValue::Set(user_id),
+            order_id: ActiveValue::Set(order_id),
+            value: ActiveValue::Set(hourly_rate.value),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(hourly_rate)
+    }
+
+    /// updates the value of an hourly rate. Time already logged keeps the
+    /// rate it was recorded with
+    ///
+    /// # Errors
+    ///
+    /// When could not update hourly rate or DB query error
+    pub async fn update(db: &DatabaseConnection, pid: Uuid, value: f32) -> ModelResult<Self> {
+        let existing_hourly_rate = Self::find_by_pid(db, pid).await?;
+        let mut edited_hourly_rate = existing_hourly_rate.into_active_model();
+        edited_hourly_rate.value = ActiveValue::Set(value);
+        let txn = db.begin().await?;
+        let hourly_rate = edited_hourly_rate.update(&txn).await?;
+        txn.commit().await?;
+        Ok(hourly_rate)
+    }
+
+    /// deletes an hourly rate
+    ///
+    /// # Errors
+    ///
+    /// When could not delete hourly rate or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_hourly_rate = Self::find_by_pid(db, pid).await?;
+        let txn = db.begin().await?;
+        existing_hourly_rate.clone().delete(&txn).await?;
+        txn.commit().await?;
+        Ok(existing_hourly_rate)
+    }
+}
@@ -0,0 +1,751 @@
+use super::_entities::journal_entries::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type JournalEntries = Entity;
+use crate::documents::journal::JournalRow;
+use crate::models::_entities::{
+    accounting_periods, clients, credit_notes, expenses, fees, journal_entries, journal_lines,
+    ledger_accounts, order_fees, orders, partners, payments, refunds, sellers, time_entries,
+};
+use crate::models::ledger_accounts::{Account, AccountKind};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// A value in cents, so postings are compared and added up without the
+/// rounding errors of floats
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn cents(value: f32) -> i64 {
+    (f64::from(value) * 100.0).round() as i64
+}
+
+/// Whether the debits of the given `(debit, credit)` lines add up to their
+/// credits
+pub fn is_balanced(lines: impl IntoIterator<Item = (f32, f32)>) -> bool {
+    lines
+        .into_iter()
+        .map(|(debit, credit)| cents(debit) - cents(credit))
+        .sum::<i64>()
+        == 0
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PostingLine {
+    pub account: Account,
+    pub debit: f32,
+    pub credit: f32,
+}
+
+/// A journal entry the books should hold. Each source of an order, like an
+/// order fee or the settlement of an instalment, has at most one posting.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Posting {
+    pub source: String,
+    pub entry_date: chrono::NaiveDate,
+    pub description: String,
+    pub lines: Vec<PostingLine>,
+}
+
+impl Posting {
+    /// a posting moving the value from the credited account to the debited
+    /// one
+    #[must_use]
+    pub fn transfer(
+        source: String,
+        entry_date: chrono::NaiveDate,
+        description: String,
+        debit: Account,
+        credit: Account,
+        value: f32,
+    ) -> Self {
+        Self {
+            source,
+            entry_date,
+            description,
+            lines: vec![
+                PostingLine {
+                    account: debit,
+                    debit: value,
+                    credit: 0.0,
+                },
+                PostingLine {
+                    account: credit,
+                    debit: 0.0,
+                    credit: value,
+                },
+            ],
+        }
+    }
+
+    #[must_use]
+    pub fn is_balanced(&self) -> bool {
+        is_balanced(self.lines.iter().map(|line| (line.debit, line.credit)))
+    }
+
+    /// the lines as `(account code, debit cents, credit cents)`, sorted so
+    /// postings can be compared regardless of the order of their lines
+    fn key(&self) -> Vec<(String, i64, i64)> {
+        let mut key = self
+            .lines
+            .iter()
+            .map(|line| {
+                (
+                    line.account.code.clone(),
+                    cents(line.debit),
+                    cents(line.credit),
+                )
+            })
+            .collect::<Vec<_>>();
+        key.sort();
+        key
+    }
+}
+
+/// Everything about an order that moves money, as found in the database
+pub struct OrderActivity<'a> {
+    pub order: &'a orders::Model,
+    pub client: &'a clients::Model,
+    pub seller: &'a sellers::Model,
+    pub partner: Option<&'a partners::Model>,
+    pub order_fees: &'a [(order_fees::Model, fees::Model)],
+    pub payments: &'a [payments::Model],
+    pub refunds: &'a [refunds::Model],
+    pub credit_notes: &'a [credit_notes::Model],
+    pub time_entries: &'a [time_entries::Model],
+    pub expenses: &'a [expenses::Model],
+}
+
+/// Works out the postings the books should hold for an order as it is now.
+///
+/// Order fees, retainer instalments, invoiced time and billed expenses are
+/// receivable from the client as revenue. Settled instalments move what was
+/// paid of the receivable to cash, which leaves out their credit notes,
+/// refunds take cash back out and credit notes write the receivable off. The
+/// seller commission and the partner fee are expenses owed to them, and a
+/// refund takes back the share of them it was paid on. A chargeback reopens
+/// its instalment, which reverses the settlement, so it has no posting of its
+/// own.
+#[must_use]
+pub fn postings_for_order(activity: &OrderActivity<'_>) -> Vec<Posting> {
+    let order = activity.order;
+    let order_date = order.created_at.date_naive();
+    let receivables = || Account::receivables(activity.client.pid, &activity.client.name);
+    let mut postings = vec![];
+
+    for (order_fee, fee) in activity.order_fees {
+        let fee_type = fee
+            .r#type
+            .as_deref()
+            .filter(|fee_type| !fee_type.trim().is_empty())
+            .unwrap_or(&fee.fee);
+        postings.push(Posting::transfer(
+            format!("order_fee:{}", order_fee.id),
+            order_fee.created_at.date_naive(),
+            format!("{} for {}", fee.fee, activity.client.name),
+            receivables(),
+            Account::fee_revenue(fee_type),
+            order_fee.value,
+        ));
+    }
+
+    for payment in activity.payments {
+        let invoiced_time: f32 = activity
+            .time_entries
+            .iter()
+            .filter(|time_entry| time_entry.payment_id == Some(payment.id))
+            .map(time_entries::Model::amount)
+            .sum();
+        let billed_expenses: f32 = activity
+            .expenses
+            .iter()
+            .filter(|expense| expense.payment_id == Some(payment.id))
+            .map(|expense| expense.value)
+            .sum();
+        if payment.billing_plan_id.is_some() {
+            // time and expenses billed into a retainer instalment raise its
+            // value but are revenue of their own
+            postings.push(Posting::transfer(
+                format!("retainer:{}", payment.id),
+                payment.created_at.date_naive(),
+                format!("Retainer instalment due {}", payment.due_date),
+                receivables(),
+                Account::retainer_revenue(),
+                (payment.value - invoiced_time - billed_expenses).max(0.0),
+            ));
+        }
+        postings.push(Posting::transfer(
+            format!("time_entries:{}", payment.id),
+            payment.created_at.date_naive(),
+            format!("Billable hours invoiced for {}", payment.due_date),
+            receivables(),
+            Account::billable_hours_revenue(),
+            invoiced_time,
+        ));
+        postings.push(Posting::transfer(
+            format!("expenses:{}", payment.id),
+            payment.created_at.date_naive(),
+            format!("Expenses billed for {}", payment.due_date),
+            receivables(),
+            Account::reimbursed_expenses(),
+            billed_expenses,
+        ));
+        if !payment.open {
+            // the client pays the instalment less the credit notes allocated
+            // to it, which write that part of the receivable off instead
+            let credited: f32 = activity
+                .credit_notes
+                .iter()
+                .filter(|credit_note| credit_note.payment_id == Some(payment.id))
+                .map(|credit_note| credit_note.value)
+                .sum();
+            postings.push(Posting::transfer(
+                format!("settlement:{}", payment.id),
+                payment.payment_date.unwrap_or(payment.due_date),
+                format!("Instalment due {} received", payment.due_date),
+                Account::cash(),
+                receivables(),
+                (payment.value - credited).max(0.0),
+            ));
+        }
+    }
+
+    for refund in activity.refunds.iter().filter(|refund| !refund.chargeback) {
+        postings.push(Posting::transfer(
+            format!("refund:{}", refund.id),
+            refund.refund_date,
+            format!("Refund: {}", refund.reason),
+            Account::refunds(),
+            Account::cash(),
+            refund.value,
+        ));
+    }
+
+    for credit_note in activity.credit_notes {
+        postings.push(Posting::transfer(
+            format!("credit_note:{}", credit_note.id),
+            credit_note.credit_date,
+            format!("Credit note: {}", credit_note.reason),
+            Account::credit_notes(),
+            receivables(),
+            credit_note.value,
+        ));
+    }
+
+    // the commission and the partner fee are paid on what the order bills, so
+    // a refund takes back the share of them it was paid on
+    let billed: f32 = activity.payments.iter().map(|payment| payment.value).sum();
+    let refunded_share = |value: f32, refund: &refunds::Model| {
+        if billed > 0.0 {
+            #[allow(clippy::cast_precision_loss)]
+            let share = cents(value * (refund.value / billed).min(1.0)) as f32 / 100.0;
+            share
+        } else {
+            0.0
+        }
+    };
+    let refunds = activity
+        .refunds
+        .iter()
+        .filter(|refund| !refund.chargeback)
+        .collect::<Vec<_>>();
+
+    postings.push(Posting::transfer(
+        format!("commission:{}", order.id),
+        order_date,
+        format!("Commission of {}", activity.seller.name),
+        Account::commission_expense(),
+        Account::commission_payable(activity.seller.pid, &activity.seller.name),
+        order.payout,
+    ));
+    for refund in &refunds {
+        postings.push(Posting::transfer(
+            format!("commission_refund:{}", refund.id),
+            refund.refund_date,
+            format!("Commission of {} refunded", activity.seller.name),
+            Account::commission_payable(activity.seller.pid, &activity.seller.name),
+            Account::commission_expense(),
+            refunded_share(order.payout, refund),
+        ));
+    }
+    if let (Some(partner), Some(partner_fee)) = (activity.partner, order.partner_fee) {
+        postings.push(Posting::transfer(
+            format!("partner_fee:{}", order.id),
+            order_date,
+            format!("Fee of partner {}", partner.name),
+            Account::partner_fee_expense(),
+            Account::partner_payable(partner.pid, &partner.name),
+            partner_fee,
+        ));
+        for refund in &refunds {
+            postings.push(Posting::transfer(
+                format!("partner_fee_refund:{}", refund.id),
+                refund.refund_date,
+                format!("Fee of partner {} refunded", partner.name),
+                Account::partner_payable(partner.pid, &partner.name),
+                Account::partner_fee_expense(),
+                refunded_share(partner_fee, refund),
+            ));
+        }
+    }
+
+    postings.retain(|posting| posting.lines.iter().any(|line| cents(line.debit) != 0));
+    postings
+}
+
+/// The debits and credits posted to an account
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountBalance {
+    pub account: ledger_accounts::Model,
+    pub debit: f32,
+    pub credit: f32,
+}
+
+impl AccountBalance {
+    /// the balance on the side the account grows on
+    #[must_use]
+    pub fn balance(&self) -> f32 {
+        let debit_normal =
+            AccountKind::parse(&self.account.kind).is_some_and(|kind| kind.debit_normal());
+        #[allow(clippy::cast_precision_loss)]
+        let balance = if debit_normal {
+            (cents(self.debit) - cents(self.credit)) as f32 / 100.0
+        } else {
+            (cents(self.credit) - cents(self.debit)) as f32 / 100.0
+        };
+        balance
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::journal_entries::Model {
+    /// finds all journal entries, in the order they were posted
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let journal_entries = Entity::find()
+            .order_by_asc(journal_entries::Column::Id)
+            .all(db)
+            .await?;
+        Ok(journal_entries)
+    }
+
+    /// finds the lines of the given journal entries
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_lines<C>(
+        db: &C,
+        journal_entry_ids: Vec<i32>,
+    ) -> ModelResult<Vec<journal_lines::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        let journal_lines = journal_lines::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(journal_lines::Column::JournalEntryId, journal_entry_ids)
+                    .build(),
+            )
+            .order_by_asc(journal_lines::Column::Id)
+            .all(db)
+            .await?;
+        Ok(journal_lines)
+    }
+
+    /// posts a balanced journal entry for an order, opening the accounts it
+    /// moves
+    async fn post<C>(
+        db: &C,
+        order_id: i32,
+        posting: &Posting,
+        reverses_id: Option<i32>,
+    ) -> ModelResult<Self>
+    where
+        C: ConnectionTrait,
+    {
+        if !posting.is_balanced() {
+            return Err(ModelError::Any(
+                format!("journal entry {} does not balance", posting.source).into(),
+            ));
+        }
+        if let Some(locked) = accounting_periods::Model::find_locked(db, posting.entry_date).await?
+        {
+            return Err(ModelError::Any(
+                format!(
+                    "the books from {} to {} were exported and are locked",
+                    locked.starts_on, locked.ends_on
+                )
+                .into(),
+            ));
+        }
+        let journal_entry = journal_entries::ActiveModel {
+            order_id: ActiveValue::Set(Some(order_id)),
+            source: ActiveValue::Set(posting.source.clone()),
+            entry_date: ActiveValue::Set(posting.entry_date),
+            description: ActiveValue::Set(posting.description.clone()),
+            reverses_id: ActiveValue::Set(reverses_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        for line in &posting.lines {
+            let ledger_account = ledger_accounts::Model::find_or_open(db, &line.account).await?;
+            journal_lines::ActiveModel {
+                journal_entry_id: ActiveValue::Set(journal_entry.id),
+                ledger_account_id: ActiveValue::Set(ledger_account.id),
+                debit: ActiveValue::Set(line.debit),
+                credit: ActiveValue::Set(line.credit),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+        }
+        Ok(journal_entry)
+    }
+
+    /// works out the postings the books should hold for an order, or none
+    /// when the order was deleted
+    async fn order_postings<C>(db: &C, order_id: i32) -> ModelResult<Vec<Posting>>
+    where
+        C: ConnectionTrait,
+    {
+        let Some(order) = orders::Entity::find_by_id(order_id).one(db).await? else {
+            return Ok(vec![]);
+        };
+        let client = clients::Entity::find_by_id(order.client_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let seller = sellers::Entity::find_by_id(order.seller_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let partner = match client.partner_id {
+            Some(id) => partners::Entity::find_by_id(id).one(db).await?,
+            None => None,
+        };
+        let order_fees = order_fees::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(order_fees::Column::OrderId, order.id)
+                    .build(),
+            )
+            .find_also_related(fees::Entity)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|(order_fee, fee)| fee.map(|fee| (order_fee, fee)))
+            .collect::<Vec<_>>();
+        let payments = payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(payments::Column::OrderId, order.id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let refunds = refunds::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(refunds::Column::OrderId, order.id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let credit_notes = credit_notes::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(credit_notes::Column::OrderId, order.id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let time_entries = time_entries::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(time_entries::Column::OrderId, order.id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let expenses = expenses::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(expenses::Column::OrderId, order.id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(postings_for_order(&OrderActivity {
+            order: &order,
+            client: &client,
+            seller: &seller,
+            partner: partner.as_ref(),
+            order_fees: &order_fees,
+            payments: &payments,
+            refunds: &refunds,
+            credit_notes: &credit_notes,
+            time_entries: &time_entries,
+            expenses: &expenses,
+        }))
+    }
+
+    /// brings the books of an order up to date with the order. Postings are
+    /// never edited or deleted: a posting that no longer matches the order is
+    /// reversed, and the posting it should be is made again. Once an order is
+    /// deleted, everything posted for it is reversed. It runs in the
+    /// transaction of the change, so a change the books refuse is rolled back.
+    ///
+    /// # Errors
+    ///
+    /// When a posting does not balance or DB query error
+    pub async fn sync_order<C>(db: &C, order_id: i32) -> ModelResult<Vec<Self>>
+    where
+        C: ConnectionTrait,
+    {
+        let postings = Self::order_postings(db, order_id).await?;
+
+        let journal_entries = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(journal_entries::Column::OrderId, order_id)
+                    .build(),
+            )
+            .order_by_asc(journal_entries::Column::Id)
+            .all(db)
+            .await?;
+        let reversed = journal_entries
+            .iter()
+            .filter_map(|journal_entry| journal_entry.reverses_id)
+            .collect::<HashSet<_>>();
+        let in_force = journal_entries
+            .into_iter()
+            .filter(|journal_entry| {
+                journal_entry.reverses_id.is_none() && !reversed.contains(&journal_entry.id)
+            })
+            .collect::<Vec<_>>();
+        let journal_lines =
+            Self::find_lines(db, in_force.iter().map(|entry| entry.id).collect()).await?;
+        let ledger_accounts = ledger_accounts::Entity::find().all(db).await?;
+
+        let mut posted = vec![];
+        let mut up_to_date = HashSet::new();
+        let today = chrono::Utc::now().date_naive();
+        for journal_entry in in_force {
+            let booked = Posting {
+                source: journal_entry.source.clone(),
+                entry_date: journal_entry.entry_date,
+                description: journal_entry.description.clone(),
+                lines: journal_lines
+                    .iter()
+                    .filter(|line| line.journal_entry_id == journal_entry.id)
+                    .filter_map(|line| {
+                        let ledger_account = ledger_accounts
+                            .iter()
+                            .find(|account| account.id == line.ledger_account_id)?;
+                        Some(PostingLine {
+                            account: Account {
+                                code: ledger_account.code.clone(),
+                                name: ledger_account.name.clone(),
+                                kind: AccountKind::parse(&ledger_account.kind)?,
+                            },
+                            debit: line.debit,
+                            credit: line.credit,
+                        })
+                    })
+                    .collect(),
+            };
+            let matches = postings.iter().any(|posting| {
+                posting.source == booked.source
+                    && posting.entry_date == booked.entry_date
+                    && posting.key() == booked.key()
+            });
+            if matches {
+                up_to_date.insert(booked.source);
+                continue;
+            }
+            let reversal = Posting {
+                source: booked.source,
+                entry_date: today,
+                description: format!("Reversal: {}", booked.description),
+                lines: booked
+                    .lines
+                    .into_iter()
+                    .map(|line| PostingLine {
+                        account: line.account,
+                        debit: line.credit,
+                        credit: line.debit,
+                    })
+                    .collect(),
+            };
+            posted.push(Self::post(db, order_id, &reversal, Some(journal_entry.id)).await?);
+        }
+        for posting in postings
+            .iter()
+            .filter(|posting| !up_to_date.contains(&posting.source))
+        {
+            posted.push(Self::post(db, order_id, posting, None).await?);
+        }
+        Ok(posted)
+    }
+
+    /// brings the books of every order up to date, including orders that
+    /// were deleted after something was posted for them
+    ///
+    /// # Errors
+    ///
+    /// When a posting does not balance or DB query error
+    pub async fn sync_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let mut order_ids = orders::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|order| order.id)
+            .collect::<HashSet<_>>();
+        order_ids.extend(
+            Entity::find()
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|journal_entry| journal_entry.order_id),
+        );
+        let mut posted = vec![];
+        for order_id in order_ids {
+            let txn = db.begin().await?;
+            posted.extend(Self::sync_order(&txn, order_id).await?);
+            txn.commit().await?;
+        }
+        Ok(posted)
+    }
+
+    /// finds the lines posted from `starts_on` to `ends_on`, in the order
+    /// they were posted
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_rows<C>(
+        db: &C,
+        starts_on: chrono::NaiveDate,
+        ends_on: chrono::NaiveDate,
+    ) -> ModelResult<Vec<JournalRow>>
+    where
+        C: ConnectionTrait,
+    {
+        let journal_entries = Entity::find()
+            .filter(
+                model::query::condition()
+                    .gte(journal_entries::Column::EntryDate, starts_on)
+                    .lte(journal_entries::Column::EntryDate, ends_on)
+                    .build(),
+            )
+            .order_by_asc(journal_entries::Column::EntryDate)
+            .order_by_asc(journal_entries::Column::Id)
+            .all(db)
+            .await?;
+        let journal_lines =
+            Self::find_lines(db, journal_entries.iter().map(|entry| entry.id).collect()).await?;
+        let ledger_accounts = ledger_accounts::Entity::find().all(db).await?;
+        let rows = journal_entries
+            .iter()
+            .flat_map(|journal_entry| {
+                journal_lines
+                    .iter()
+                    .filter(|line| line.journal_entry_id == journal_entry.id)
+                    .filter_map(|line| {
+                        let ledger_account = ledger_accounts
+                            .iter()
+                            .find(|account| account.id == line.ledger_account_id)?;
+                        Some(JournalRow {
+                            entry_date: journal_entry.entry_date,
+                            entry_pid: journal_entry.pid,
+                            ledger_code: ledger_account.code.clone(),
+                            ledger_name: ledger_account.name.clone(),
+                            debit: line.debit,
+                            credit: line.credit,
+                            description: journal_entry.description.clone(),
+                        })
+                    })
+            })
+            .collect();
+        Ok(rows)
+    }
+
+    /// finds the journal entries whose debits do not add up to their credits
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_unbalanced(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let journal_entries = Self::find_all(db).await?;
+        let journal_lines = journal_lines::Entity::find().all(db).await?;
+        let unbalanced = journal_entries
+            .into_iter()
+            .filter(|journal_entry| {
+                let lines = journal_lines
+                    .iter()
+                    .filter(|line| line.journal_entry_id == journal_entry.id)
+                    .collect::<Vec<_>>();
+                lines.is_empty() || !is_balanced(lines.iter().map(|line| (line.debit, line.credit)))
+            })
+            .collect();
+        Ok(unbalanced)
+    }
+
+    /// adds up the debits and credits posted to every account, in the order
+    /// of their codes
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn trial_balance(db: &DatabaseConnection) -> ModelResult<Vec<AccountBalance>> {
+        let ledger_accounts = ledger_accounts::Model::find_all(db).await?;
+        let journal_lines = journal_lines::Entity::find().all(db).await?;
+        let mut totals: BTreeMap<i32, (i64, i64)> = BTreeMap::new();
+        for line in &journal_lines {
+            let total = totals.entry(line.ledger_account_id).or_default();
+            total.0 += cents(line.debit);
+            total.1 += cents(line.credit);
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let balances = ledger_accounts
+            .into_iter()
+            .map(|account| {
+                let (debit, credit) = totals.get(&account.id).copied().unwrap_or_default();
+                AccountBalance {
+                    account,
+                    debit: debit as f32 / 100.0,
+                    credit: credit as f32 / 100.0,
+                }
+            })
+            .collect();
+        Ok(balances)
+    }
+}
@@ -0,0 +1,468 @@
+use super::_entities::lawsuits::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type Lawsuits = Entity;
+use crate::models::_entities::{lawsuits, orders, status_changes};
+use crate::models::status_changes::StatusSubject;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, IntoActiveModel, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LawsuitStatus {
+    #[default]
+    Active,
+    Suspended,
+    Settled,
+    Closed,
+    Archived,
+}
+
+impl LawsuitStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Suspended => "suspended",
+            Self::Settled => "settled",
+            Self::Closed => "closed",
+            Self::Archived => "archived",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "active" => Some(Self::Active),
+            "suspended" => Some(Self::Suspended),
+            "settled" => Some(Self::Settled),
+            "closed" => Some(Self::Closed),
+            "archived" => Some(Self::Archived),
+            _ => None,
+        }
+    }
+}
+
+/// The branch of the judiciary a case is in, the `J` of its CNJ number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JusticeBranch {
+    SupremeCourt,
+    NationalCouncil,
+    SuperiorCourt,
+    Federal,
+    Labour,
+    Electoral,
+    FederalMilitary,
+    State,
+    StateMilitary,
+}
+
+impl JusticeBranch {
+    #[must_use]
+    pub const fn from_segment(segment: u8) -> Option<Self> {
+        match segment {
+            1 => Some(Self::SupremeCourt),
+            2 => Some(Self::NationalCouncil),
+            3 => Some(Self::SuperiorCourt),
+            4 => Some(Self::Federal),
+            5 => Some(Self::Labour),
+            6 => Some(Self::Electoral),
+            7 => Some(Self::FederalMilitary),
+            8 => Some(Self::State),
+            9 => Some(Self::StateMilitary),
+            _ => None,
+        }
+    }
+}
+
+/// The states in the order of their codes in CNJ numbers, `01` for Acre up
+/// to `27` for Tocantins
+pub const COURT_STATES: [&str; 27] = [
+    "AC", "AL", "AP", "AM", "BA", "CE", "DF", "ES", "GO", "MA", "MT", "MS", "MG", "PA", "PB", "PR",
+    "PE", "PI", "RJ", "RN", "RS", "RO", "RR", "SC", "SE", "SP", "TO",
+];
+
+/// A CNJ unified case number, `NNNNNNN-DD.AAAA.J.TR.OOOO`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CnjNumber {
+    pub sequence: u32,
+    pub check_digits: u8,
+    pub year: u16,
+    pub segment: u8,
+    pub court: u8,
+    pub origin: u16,
+}
+
+impl CnjNumber {
+    /// Reads a CNJ number, masked or as its twenty digits, when its check
+    /// digits are right
+    #[must_use]
+    pub fn parse(number: &str) -> Option<Self> {
+        let number = number.trim();
+        let digits = if number.len() == 25 {
+            let masked = number.char_indices().all(|(i, c)| match i {
+                7 => c == '-',
+                10 | 15 | 17 | 20 => c == '.',
+                _ => c.is_ascii_digit(),
+            });
+            if !masked {
+                return None;
+            }
+            number.replace(['-', '.'], "")
+        } else {
+            number.to_string()
+        };
+        if digits.len() != 20 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let cnj = Self {
+            sequence: digits[0..7].parse().ok()?,
+            check_digits: digits[7..9].parse().ok()?,
+            year: digits[9..13].parse().ok()?,
+            segment: digits[13..14].parse().ok()?,
+            court: digits[14..16].parse().ok()?,
+            origin: digits[16..20].parse().ok()?,
+        };
+        (cnj.expected_check_digits() == cnj.check_digits).then_some(cnj)
+    }
+
+    /// The check digits of the rest of the number, by ISO 7064 mod 97-10
+    #[must_use]
+    pub fn expected_check_digits(&self) -> u8 {
+        let number = format!(
+            "{:07}{:04}{}{:02}{:04}00",
+            self.sequence, self.year, self.segment, self.court, self.origin
+        );
+        let remainder = number.bytes().fold(0_u32, |remainder, digit| {
+            (remainder * 10 + u32::from(digit - b'0')) % 97
+        });
+        u8::try_from(98 - remainder).unwrap_or_default()
+    }
+
+    /// The twenty digits the number is stored as
+    #[must_use]
+    pub fn digits(&self) -> String {
+        format!(
+            "{:07}{:02}{:04}{}{:02}{:04}",
+            self.sequence, self.check_digits, self.year, self.segment, self.court, self.origin
+        )
+    }
+
+    #[must_use]
+    pub fn branch(&self) -> Option<JusticeBranch> {
+        JusticeBranch::from_segment(self.segment)
+    }
+
+    /// The state of the court, for the branches whose courts are by state
+    #[must_use]
+    pub fn state(&self) -> Option<&'static str> {
+        match self.branch()? {
+            JusticeBranch::Electoral | JusticeBranch::State | JusticeBranch::StateMilitary => {
+                COURT_STATES
+                    .get(usize::from(self.court).checked_sub(1)?)
+                    .copied()
+            }
+            _ => None,
+        }
+    }
+
+    /// The court the case is in, as `TJSP`, `TRF3` or `TRT2`, when the `TR`
+    /// of the number is one of its branch
+    #[must_use]
+    pub fn court_name(&self) -> Option<String> {
+        let court = self.court;
+        let name = match (self.branch()?, court) {
+            (JusticeBranch::SupremeCourt, 0) => "STF".to_string(),
+            (JusticeBranch::NationalCouncil, 0) => "CNJ".to_string(),
+            (JusticeBranch::SuperiorCourt, 0) => "STJ".to_string(),
+            (JusticeBranch::Federal, 90) => "CJF".to_string(),
+            (JusticeBranch::Federal, 1..=6) => format!("TRF{court}"),
+            (JusticeBranch::Labour, 0) => "TST".to_string(),
+            (JusticeBranch::Labour, 90) => "CSJT".to_string(),
+            (JusticeBranch::Labour, 1..=24) => format!("TRT{court}"),
+            (JusticeBranch::Electoral, 0) => "TSE".to_string(),
+            (JusticeBranch::Electoral, _) => format!("TRE-{}", self.state()?),
+            (JusticeBranch::FederalMilitary, 0) => "STM".to_string(),
+            (JusticeBranch::FederalMilitary, 1..=12) => format!("{court}ª CJM"),
+            (JusticeBranch::State, 7) => "TJDFT".to_string(),
+            (JusticeBranch::State, _) => format!("TJ{}", self.state()?),
+            (JusticeBranch::StateMilitary, 13 | 21 | 26) => format!("TJM{}", self.state()?),
+            _ => return None,
+        };
+        Some(name)
+    }
+}
+
+/// Writes a CNJ number stored as its twenty digits with its mask
+#[must_use]
+pub fn format_cnj(cnj_number: &str) -> String {
+    if cnj_number.len() == 20 && cnj_number.is_ascii() {
+        format!(
+            "{}-{}.{}.{}.{}.{}",
+            &cnj_number[0..7],
+            &cnj_number[7..9],
+            &cnj_number[9..13],
+            &cnj_number[13..14],
+            &cnj_number[14..16],
+            &cnj_number[16..20]
+        )
+    } else {
+        cnj_number.to_string()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewLawsuit {
+    pub order_pid: Uuid,
+    pub cnj_number: String,
+    /// the court of the number when not given
+    pub court: Option<String>,
+    pub district: Option<String>,
+    pub opposing_party: Option<String>,
+    pub filing_date: Option<chrono::NaiveDate>,
+    #[serde(default)]
+    pub status: LawsuitStatus,
+}
+
+/// Checks a CNJ number, returning it decoded
+///
+/// # Errors
+///
+/// When the number is not a valid CNJ number of a known court or the case
+/// was not filed in the year of the number
+pub fn check_cnj_number(
+    cnj_number: &str,
+    filing_date: Option<chrono::NaiveDate>,
+) -> ModelResult<CnjNumber> {
+    let cnj = CnjNumber::parse(cnj_number)
+        .ok_or_else(|| ModelError::Any(format!("{cnj_number} is not a valid CNJ number").into()))?;
+    if cnj.court_name().is_none() {
+        return Err(ModelError::Any(
+            format!("{cnj_number} is not the number of a known court").into(),
+        ));
+    }
+    if let Some(filing_date) = filing_date {
+        if chrono::Datelike::year(&filing_date) != i32::from(cnj.year) {
+            return Err(ModelError::Any(
+                format!("{cnj_number} was not filed in {}", filing_date.format("%Y")).into(),
+            ));
+        }
+    }
+    Ok(cnj)
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::lawsuits::Model {
+    /// the CNJ number of the lawsuit, decoded
+    #[must_use]
+    pub fn cnj(&self) -> Option<CnjNumber> {
+        CnjNumber::parse(&self.cnj_number)
+    }
+
+    /// finds a lawsuit by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find lawsuit by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let lawsuit = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(lawsuits::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        lawsuit.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the lawsuit of an order, if it has one
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Option<Self>> {
+        let lawsuit = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(lawsuits::Column::OrderId, order_id)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        Ok(lawsuit)
+    }
+
+    /// finds a lawsuit by its CNJ number, masked or not
+    ///
+    /// # Errors
+    ///
+    /// When the number is not a valid CNJ number, could not find lawsuit by
+    /// it or DB query error
+    pub async fn find_by_cnj_number(
+        db: &DatabaseConnection,
+        cnj_number: &str,
+    ) -> ModelResult<Self> {
+        let cnj = CnjNumber::parse(cnj_number).ok_or_else(|| ModelError::EntityNotFound)?;
+        let lawsuit = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(lawsuits::Column::CnjNumber, cnj.digits())
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        lawsuit.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// checks that no other lawsuit has the CNJ number
+    async fn check_unique(
+        db: &DatabaseConnection,
+        cnj: &CnjNumber,
+        lawsuit_id: Option<i32>,
+    ) -> ModelResult<()> {
+        let existing = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(lawsuits::Column::CnjNumber, cnj.digits())
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        match existing {
+            Some(existing) if Some(existing.id) != lawsuit_id => Err(ModelError::Any(
+                format!("{} is already registered", format_cnj(&cnj.digits())).into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// creates the lawsuit of an order
+    ///
+    /// # Errors
+    ///
+    /// When the CNJ number is not valid or already registered, the order
+    /// already has a lawsuit, or DB query error
+    pub async fn create(db: &DatabaseConnection, lawsuit: CreateNewLawsuit) -> ModelResult<Self> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, lawsuit.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        if Self::find_by_order_id(db, order.id).await?.is_some() {
+            return Err(ModelError::Any("the order already has a lawsuit".into()));
+        }
+        let cnj = check_cnj_number(&lawsuit.cnj_number, lawsuit.filing_date)?;
+        Self::check_unique(db, &cnj, None).await?;
+        let txn = db.begin().await?;
+        let lawsuit = lawsuits::ActiveModel {
+            order_id: ActiveValue::Set(order.id),
+            cnj_number: ActiveValue::Set(cnj.digits()),
+            court: ActiveValue::Set(lawsuit.court.or_else(|| cnj.court_name())),
+            district: ActiveValue::Set(lawsuit.district),
+            opposing_party: ActiveValue::Set(lawsuit.opposing_party),
+            filing_date: ActiveValue::Set(lawsuit.filing_date),
+            status: ActiveValue::Set(lawsuit.status.as_str().to_string()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(lawsuit)
+    }
+
+    /// updates a lawsuit
+    ///
+    /// # Errors
+    ///
+    /// When could not find lawsuit by the given pid, the CNJ number is not
+    /// valid or already registered, the order it is moved to already has a
+    /// lawsuit, or DB query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        lawsuit: CreateNewLawsuit,
+    ) -> ModelResult<Self> {
+        let existing_lawsuit = Self::find_by_pid(db, pid).await?;
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, lawsuit.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        if order.id != existing_lawsuit.order_id
+            && Self::find_by_order_id(db, order.id).await?.is_some()
+        {
+            return Err(ModelError::Any("the order already has a lawsuit".into()));
+        }
+        let cnj = check_cnj_number(&lawsuit.cnj_number, lawsuit.filing_date)?;
+        Self::check_unique(db, &cnj, Some(existing_lawsuit.id)).await?;
+        let previous_status = existing_lawsuit.status.clone();
+        let mut edited_lawsuit = existing_lawsuit.into_active_model();
+        edited_lawsuit.order_id = ActiveValue::Set(order.id);
+        edited_lawsuit.cnj_number = ActiveValue::Set(cnj.digits());
+        edited_lawsuit.court = ActiveValue::Set(lawsuit.court.or_else(|| cnj.court_name()));
+        edited_lawsuit.district = ActiveValue::Set(lawsuit.district);
+        edited_lawsuit.opposing_party = ActiveValue::Set(lawsuit.opposing_party);
+        edited_lawsuit.filing_date = ActiveValue::Set(lawsuit.filing_date);
+        edited_lawsuit.status = ActiveValue::Set(lawsuit.status.as_str().to_string());
+        let txn = db.begin().await?;
+        let lawsuit = edited_lawsuit.update(&txn).await?;
+        status_changes::Model::record(
+            &txn,
+            lawsuit.order_id,
+            StatusSubject::Lawsuit,
+            &previous_status,
+            &lawsuit.status,
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(lawsuit)
+    }
+
+    /// deletes a lawsuit
+    ///
+    /// # Errors
+    ///
+    /// When could not find lawsuit by the given pid or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_lawsuit = Self::find_by_pid(db, pid).await?;
+        let txn = db.begin().await?;
+        existing_lawsuit.clone().delete(&txn).await?;
+        txn.commit().await?;
+        Ok(existing_lawsuit)
+    }
+}
@@ -0,0 +1,238 @@
+use super::_entities::ledger_accounts::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type LedgerAccounts = Entity;
+use crate::models::_entities::ledger_accounts;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+/// Whether an account holds what the firm has, what it owes, what it earns or
+/// what it spends. Assets and expenses grow with debits, liabilities and
+/// revenue with credits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountKind {
+    Asset,
+    Liability,
+    Revenue,
+    Expense,
+}
+
+impl AccountKind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Asset => "asset",
+            Self::Liability => "liability",
+            Self::Revenue => "revenue",
+            Self::Expense => "expense",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "asset" => Some(Self::Asset),
+            "liability" => Some(Self::Liability),
+            "revenue" => Some(Self::Revenue),
+            "expense" => Some(Self::Expense),
+            _ => None,
+        }
+    }
+
+    /// whether the balance of the account is its debits minus its credits
+    #[must_use]
+    pub fn debit_normal(&self) -> bool {
+        matches!(self, Self::Asset | Self::Expense)
+    }
+}
+
+/// An account of the chart the postings are made to, opened in the ledger
+/// the first time something is posted to it
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Account {
+    pub code: String,
+    pub name: String,
+    pub kind: AccountKind,
+}
+
+impl Account {
+    fn new(code: String, name: String, kind: AccountKind) -> Self {
+        Self { code, name, kind }
+    }
+
+    #[must_use]
+    pub fn cash() -> Self {
+        Self::new("cash".into(), "Cash".into(), AccountKind::Asset)
+    }
+
+    #[must_use]
+    pub fn receivables(client_pid: Uuid, client_name: &str) -> Self {
+        Self::new(
+            format!("receivables:{client_pid}"),
+            format!("Receivables - {client_name}"),
+            AccountKind::Asset,
+        )
+    }
+
+    /// revenue of a type of fee, falling back to the fee itself when it has
+    /// no type
+    #[must_use]
+    pub fn fee_revenue(fee_type: &str) -> Self {
+        Self::new(
+            format!("revenue:{}", fee_type.trim().to_lowercase()),
+            format!("Revenue - {}", fee_type.trim()),
+            AccountKind::Revenue,
+        )
+    }
+
+    #[must_use]
+    pub fn retainer_revenue() -> Self {
+        Self::new(
+            "revenue:retainer".into(),
+            "Revenue - Retainers".into(),
+            AccountKind::Revenue,
+        )
+    }
+
+    #[must_use]
+    pub fn billable_hours_revenue() -> Self {
+        Self::new(
+            "revenue:billable_hours".into(),
+            "Revenue - Billable hours".into(),
+            AccountKind::Revenue,
+        )
+    }
+
+    #[must_use]
+    pub fn reimbursed_expenses() -> Self {
+        Self::new(
+            "revenue:reimbursed_expenses".into(),
+            "Revenue - Reimbursed expenses".into(),
+            AccountKind::Revenue,
+        )
+    }
+
+    #[must_use]
+    pub fn credit_notes() -> Self {
+        Self::new(
+            "revenue:credit_notes".into(),
+            "Credit notes".into(),
+            AccountKind::Revenue,
+        )
+    }
+
+    #[must_use]
+    pub fn refunds() -> Self {
+        Self::new(
+            "revenue:refunds".into(),
+            "Refunds".into(),
+            AccountKind::Revenue,
+        )
+    }
+
+    #[must_use]
+    pub fn commission_expense() -> Self {
+        Self::new(
+            "expense:commissions".into(),
+            "Seller commissions".into(),
+            AccountKind::Expense,
+        )
+    }
+
+    #[must_use]
+    pub fn partner_fee_expense() -> Self {
+        Self::new(
+            "expense:partner_fees".into(),
+            "Partner fees".into(),
+            AccountKind::Expense,
+        )
+    }
+
+    #[must_use]
+    pub fn commission_payable(seller_pid: Uuid, seller_name: &str) -> Self {
+        Self::new(
+            format!("commission_payable:{seller_pid}"),
+            format!("Commission payable - {seller_name}"),
+            AccountKind::Liability,
+        )
+    }
+
+    #[must_use]
+    pub fn partner_payable(partner_pid: Uuid, partner_name: &str) -> Self {
+        Self::new(
+            format!("partner_payable:{partner_pid}"),
+            format!("Partner payable - {partner_name}"),
+            AccountKind::Liability,
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::ledger_accounts::Model {
+    /// finds the ledger account of the given account of the chart, opening it
+    /// when nothing was posted to it yet
+    ///
+    /// # Errors
+    ///
+    /// When could not open the account or DB query error
+    pub async fn find_or_open<C>(db: &C, account: &Account) -> ModelResult<Self>
+    where
+        C: ConnectionTrait,
+    {
+        let ledger_account = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(ledger_accounts::Column::Code, account.code.as_str())
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        if let Some(ledger_account) = ledger_account {
+            return Ok(ledger_account);
+        }
+        let ledger_account = ledger_accounts::ActiveModel {
+            code: ActiveValue::Set(account.code.clone()),
+            name: ActiveValue::Set(account.name.clone()),
+            kind: ActiveValue::Set(account.kind.as_str().to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(ledger_account)
+    }
+
+    /// finds all ledger accounts
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let ledger_accounts = Entity::find()
+            .order_by_asc(ledger_accounts::Column::Code)
+            .all(db)
+            .await?;
+        Ok(ledger_accounts)
+    }
+}
@@ -0,0 +1,52 @@
+use super::_entities::note_revisions::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type NoteRevisions = Entity;
+use crate::models::_entities::note_revisions;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, QueryOrder};
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::note_revisions::Model {
+    /// finds the earlier bodies of the given notes, oldest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find revisions or DB query error
+    pub async fn find_by_note_ids(
+        db: &DatabaseConnection,
+        note_ids: impl IntoIterator<Item = i32>,
+    ) -> ModelResult<Vec<Self>> {
+        let revisions = Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(note_revisions::Column::NoteId, note_ids)
+                    .build(),
+            )
+            .order_by_asc(note_revisions::Column::CreatedAt)
+            .order_by_asc(note_revisions::Column::Id)
+            .all(db)
+            .await?;
+        Ok(revisions)
+    }
+}
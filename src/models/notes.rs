@@ -0,0 +1,354 @@
+use super::_entities::notes::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type Notes = Entity;
+use crate::models::_entities::{clients, note_revisions, notes, orders, payments, users};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::{ActiveValue, Condition, IntoActiveModel, QueryOrder, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+/// A note on an order, a client or a payment, given by the pid of exactly
+/// one of them
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewNote {
+    pub body: String,
+    #[serde(default)]
+    pub pinned: bool,
+    pub order_pid: Option<Uuid>,
+    pub client_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+}
+
+/// What a note is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteTarget {
+    Order(i32),
+    Client(i32),
+    Payment(i32),
+}
+
+impl NoteTarget {
+    /// finds what the pid given is of, when exactly one is given
+    ///
+    /// # Errors
+    ///
+    /// When none or more than one pid is given, could not find what it is of
+    /// or DB query error
+    pub async fn find(
+        db: &DatabaseConnection,
+        order_pid: Option<Uuid>,
+        client_pid: Option<Uuid>,
+        payment_pid: Option<Uuid>,
+    ) -> ModelResult<Self> {
+        match (order_pid, client_pid, payment_pid) {
+            (Some(pid), None, None) => {
+                let order = orders::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(orders::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                Ok(Self::Order(order.id))
+            }
+            (None, Some(pid), None) => {
+                let client = clients::Model::find_by_pid(db, pid).await?;
+                Ok(Self::Client(client.id))
+            }
+            (None, None, Some(pid)) => {
+                let payment = payments::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(payments::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                Ok(Self::Payment(payment.id))
+            }
+            _ => Err(ModelError::Any(
+                "a note is on exactly one of an order, a client or a payment".into(),
+            )),
+        }
+    }
+
+    fn condition(self) -> Condition {
+        match self {
+            Self::Order(id) => Condition::all().add(notes::Column::OrderId.eq(id)),
+            Self::Client(id) => Condition::all().add(notes::Column::ClientId.eq(id)),
+            Self::Payment(id) => Condition::all().add(notes::Column::PaymentId.eq(id)),
+        }
+    }
+}
+
+/// Who wrote the notes, what they are on and how they were edited
+#[derive(Debug, Clone, Default)]
+pub struct NoteLinks {
+    pub users: Vec<users::Model>,
+    pub orders: Vec<orders::Model>,
+    pub clients: Vec<clients::Model>,
+    pub payments: Vec<payments::Model>,
+    pub revisions: Vec<note_revisions::Model>,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(length(min = 1, message = "Body must not be empty."))]
+    pub body: String,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            body: self.body.as_ref().trim().to_owned(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::notes::Model {
+    /// finds a note by the provided pid, unless it was deleted
+    ///
+    /// # Errors
+    ///
+    /// When could not find note by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let note = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(notes::Column::Pid, pid)
+                    .is_null(notes::Column::DeletedAt)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        note.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the notes on an order, a client or a payment, the pinned ones
+    /// first and then the latest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find notes or DB query error
+    pub async fn find_by_target(
+        db: &DatabaseConnection,
+        target: NoteTarget,
+    ) -> ModelResult<Vec<Self>> {
+        let notes = Entity::find()
+            .filter(target.condition())
+            .filter(notes::Column::DeletedAt.is_null())
+            .order_by_desc(notes::Column::Pinned)
+            .order_by_desc(notes::Column::CreatedAt)
+            .all(db)
+            .await?;
+        Ok(notes)
+    }
+
+    /// finds the notes on a client, on the given orders and on their
+    /// payments, oldest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find notes or DB query error
+    pub async fn find_by_orders(
+        db: &DatabaseConnection,
+        client_id: Option<i32>,
+        order_ids: &[i32],
+        payment_ids: &[i32],
+    ) -> ModelResult<Vec<Self>> {
+        let mut condition = Condition::any()
+            .add(notes::Column::OrderId.is_in(order_ids.iter().copied()))
+            .add(notes::Column::PaymentId.is_in(payment_ids.iter().copied()));
+        if let Some(client_id) = client_id {
+            condition = condition.add(notes::Column::ClientId.eq(client_id));
+        }
+        let notes = Entity::find()
+            .filter(condition)
+            .filter(notes::Column::DeletedAt.is_null())
+            .order_by_asc(notes::Column::CreatedAt)
+            .all(db)
+            .await?;
+        Ok(notes)
+    }
+
+    /// finds who wrote and edited the given notes, what they are on and
+    /// their earlier bodies
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_links(db: &DatabaseConnection, notes: &[Self]) -> ModelResult<NoteLinks> {
+        let revisions =
+            note_revisions::Model::find_by_note_ids(db, notes.iter().map(|note| note.id)).await?;
+        let users = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        users::Column::Id,
+                        notes
+                            .iter()
+                            .filter_map(|note| note.user_id)
+                            .chain(revisions.iter().filter_map(|revision| revision.user_id)),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let orders = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        orders::Column::Id,
+                        notes.iter().filter_map(|note| note.order_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let clients = clients::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        clients::Column::Id,
+                        notes.iter().filter_map(|note| note.client_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let payments = payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        payments::Column::Id,
+                        notes.iter().filter_map(|note| note.payment_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(NoteLinks {
+            users,
+            orders,
+            clients,
+            payments,
+            revisions,
+        })
+    }
+
+    /// creates a new note written by the given user
+    ///
+    /// # Errors
+    ///
+    /// When the note is not on exactly one of an order, a client or a
+    /// payment, its body is empty, could not create note or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        user_id: Option<i32>,
+        note: CreateNewNote,
+    ) -> ModelResult<Self> {
+        let target =
+            NoteTarget::find(db, note.order_pid, note.client_pid, note.payment_pid).await?;
+        let (order_id, client_id, payment_id) = match target {
+            NoteTarget::Order(id) => (Some(id), None, None),
+            NoteTarget::Client(id) => (None, Some(id), None),
+            NoteTarget::Payment(id) => (None, None, Some(id)),
+        };
+        let txn = db.begin().await?;
+        let note = notes::ActiveModel {
+            body: ActiveValue::Set(note.body),
+            pinned: ActiveValue::Set(note.pinned),
+            user_id: ActiveValue::Set(user_id),
+            order_id: ActiveValue::Set(order_id),
+            client_id: ActiveValue::Set(client_id),
+            payment_id: ActiveValue::Set(payment_id),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(note)
+    }
+
+    /// changes the body of a note, keeping the body it had as a revision
+    /// made by the given user
+    ///
+    /// # Errors
+    ///
+    /// When could not find note by the given pid, the body is empty or DB
+    /// query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        user_id: Option<i32>,
+        body: String,
+    ) -> ModelResult<Self> {
+        let existing_note = Self::find_by_pid(db, pid).await?;
+        if existing_note.body == body {
+            return Ok(existing_note);
+        }
+        let txn = db.begin().await?;
+        note_revisions::ActiveModel {
+            note_id: ActiveValue::Set(existing_note.id),
+            body: ActiveValue::Set(existing_note.body.clone()),
+            user_id: ActiveValue::Set(user_id),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        let mut edited_note = existing_note.into_active_model();
+        edited_note.body = ActiveValue::Set(body);
+        let note = edited_note.update(&txn).await?;
+        txn.commit().await?;
+        Ok(note)
+    }
+
+    /// pins a note on top of the others, or unpins it
+    ///
+    /// # Errors
+    ///
+    /// When could not find note by the given pid or DB query error
+    pub async fn pin(db: &DatabaseConnection, pid: Uuid, pinned: bool) -> ModelResult<Self> {
+        let mut note = Self::find_by_pid(db, pid).await?.into_active_model();
+        note.pinned = ActiveValue::Set(pinned);
+        Ok(note.update(db).await?)
+    }
+
+    /// deletes a note, which is kept with its revisions but no longer found
+    ///
+    /// # Errors
+    ///
+    /// When could not find note by the given pid or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let mut note = Self::find_by_pid(db, pid).await?.into_active_model();
+        note.deleted_at = ActiveValue::Set(Some(chrono::Utc::now().into()));
+        Ok(note.update(db).await?)
+    }
+}
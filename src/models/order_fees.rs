@@ -1,7 +1,7 @@
 use super::_entities::order_fees::{ActiveModel, Entity};
 use sea_orm::entity::prelude::*;
 pub type OrderFees = Entity;
-use crate::models::_entities::order_fees;
+use crate::models::_entities::{journal_entries, order_fees};
 use loco_rs::model::ModelError;
 use loco_rs::model::{self, ModelResult};
 use sea_orm::TransactionTrait;
@@ -92,7 +92,7 @@ impl super::_entities::order_fees::Model {
         order_fee: CreateNewOrderFee,
     ) -> ModelResult<Vec<Self>> {
         let txn = db.begin().await?;
-        let _order_fee = order_fees::ActiveModel {
+        let created_order_fee = order_fees::ActiveModel {
             fee_id: ActiveValue::Set(order_fee.fee_id),
             order_id: ActiveValue::Set(order_fee.order_id),
             open: ActiveValue::Set(order_fee.open),
@@ -102,6 +102,7 @@ impl super::_entities::order_fees::Model {
         }
         .insert(&txn)
         .await?;
+        journal_entries::Model::sync_order(&txn, created_order_fee.order_id).await?;
         txn.commit().await?;
         let response = Self::find_all(db).await?;
         Ok(response)
@@ -126,6 +127,7 @@ impl super::_entities::order_fees::Model {
             .one(db)
             .await?
             .ok_or_else(|| ModelError::EntityNotFound)?;
+        let previous_order_id = existing_order_fee.order_id;
         let mut edited_order_fee = existing_order_fee.into_active_model();
         edited_order_fee.fee_id = ActiveValue::Set(order_fee.fee_id);
         edited_order_fee.order_id = ActiveValue::Set(order_fee.order_id);
@@ -133,13 +135,17 @@ impl super::_entities::order_fees::Model {
         edited_order_fee.value = ActiveValue::Set(order_fee.value);
         edited_order_fee.info = ActiveValue::Set(order_fee.info);
         let txn = db.begin().await?;
-        let _order_fee = edited_order_fee.update(&txn).await?;
+        let edited_order_fee = edited_order_fee.update(&txn).await?;
+        journal_entries::Model::sync_order(&txn, edited_order_fee.order_id).await?;
+        if previous_order_id != edited_order_fee.order_id {
+            journal_entries::Model::sync_order(&txn, previous_order_id).await?;
+        }
         txn.commit().await?;
         let response = Self::find_all(db).await?;
         Ok(response)
     }
 
-    /// deletes an order fee
+    /// deletes an order fee; its credit notes stay on the order
     ///
     /// # Errors
     ///
@@ -154,8 +160,10 @@ impl super::_entities::order_fees::Model {
             .one(db)
             .await?
             .ok_or_else(|| ModelError::EntityNotFound)?;
+        let order_id = existing_order_fee.order_id;
         let txn = db.begin().await?;
         existing_order_fee.delete(&txn).await?;
+        journal_entries::Model::sync_order(&txn, order_id).await?;
         txn.commit().await?;
         let response = Self::find_all(db).await?;
         Ok(response)
@@ -1,7 +1,18 @@
 use super::_entities::orders::{ActiveModel, Entity};
 use super::_entities::{
-    clients, fees, order_fees, orders, partners, payments, postponed_payments, processes, sellers,
+    attachments, billing_plan_prices, billing_plans, client_contacts, clients, credit_notes,
+    documents, expenses, fees, journal_entries, lawsuits, order_fees, orders, partners, payments,
+    postponed_payments, processes, refunds, sellers, status_changes, time_entries,
 };
+use crate::business_days::BusinessCalendar;
+use crate::models::attachments::AttachmentTarget;
+use crate::models::credit_notes::outstanding_balance;
+use crate::models::refunds::net_received;
+use crate::models::status_changes::{order_status, StatusSubject};
+use crate::views::billing_plans::BillingPlanView;
+use crate::views::credit_notes::CreditNoteView;
+use crate::views::expenses::ExpenseView;
+use crate::views::lawsuits::LawsuitView;
 use crate::views::orders::FeeInOrdersReturn;
 use sea_orm::entity::prelude::*;
 use sea_orm::ActiveValue;
@@ -11,7 +22,9 @@ use crate::views::orders::{
     ClientOrderReturn, ClientProcessReturn, CreateNewOrder, GetOrderReturn, OrderPayments,
 };
 use crate::views::partners::PartnerView;
+use crate::views::refunds::RefundView;
 use crate::views::sellers::SellerView;
+use crate::views::time_entries::TimeEntryView;
 use loco_rs::model::ModelError;
 use loco_rs::model::{self, ModelResult};
 use sea_orm::IntoActiveModel;
@@ -45,7 +58,7 @@ impl super::_entities::orders::Model {
     /// # Errors
     ///
     /// When could not find order by the given token or DB query error
-    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<GetOrderReturn> {
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<GetOrderReturn> {
         let order = Entity::find()
             .filter(
                 model::query::condition()
@@ -65,6 +78,7 @@ impl super::_entities::orders::Model {
             .all(db)
             .await?;
         let client = clients::Model::find_by_id(db, order.client_id).await?;
+        let contacts = client_contacts::Model::find_by_client_id(db, client.id).await?;
         let seller = sellers::Model::find_by_id(db, order.seller_id).await?;
         let partner = match client.partner_id {
             Some(id) => {
@@ -82,6 +96,27 @@ impl super::_entities::orders::Model {
             )
             .all(db)
             .await?;
+        let order_credit_notes = credit_notes::Model::find_by_order_id(db, order.id).await?;
+        let balance = outstanding_balance(&payments, &order_credit_notes);
+        let credit_notes = CreditNoteView::from_model(order_credit_notes, &order_fees, &payments);
+        let order_refunds = refunds::Model::find_by_order_id(db, order.id).await?;
+        let received = net_received(&payments, &order_refunds);
+        let refunds = RefundView::from_model(order_refunds, &payments);
+        let expenses = ExpenseView::from_model(
+            expenses::Model::find_by_order_id(db, order.id).await?,
+            &payments,
+        );
+        let billing_plans = BillingPlanView::from_model(
+            billing_plans::Model::find_by_order_id(db, order.id).await?,
+            &billing_plan_prices::Model::find_by_order_id(db, order.id).await?,
+        );
+        let lawsuit = lawsuits::Model::find_by_order_id(db, order.id)
+            .await?
+            .map(LawsuitView::from);
+        let order_time_entries = time_entries::Model::find_by_order_id(db, order.id).await?;
+        let time_entry_users = time_entries::Model::find_users(db, &order_time_entries).await?;
+        let time_entries =
+            TimeEntryView::from_model(order_time_entries, &time_entry_users, &payments);
         let mut fees = vec![];
         for order_fee in order_fees {
             let fee = fees::Model::find_by_id(db, order_fee.fee_id).await?;
@@ -99,21 +134,14 @@ impl super::_entities::orders::Model {
         }
         Ok(GetOrderReturn {
             pid: order.pid,
-            client: ClientOrderReturn {
-                pid: client.pid,
-                name: client.name,
-                contact: client.contact,
-                phone: Some(client.phone),
-                phone2: client.phone2,
-                email: Some(client.email),
-                partner,
-            },
+            client: ClientOrderReturn::from_model(client, &contacts, partner),
             process: {
                 ClientProcessReturn {
                     pid: process.pid,
                     case_type: process.case_type,
                 }
             },
+            lawsuit,
             open: order.open,
             fee: order.fee,
             fees,
@@ -139,6 +167,13 @@ impl super::_entities::orders::Model {
                         .into(),
                 })
                 .collect(),
+            credit_notes,
+            outstanding_balance: balance,
+            refunds,
+            net_received: received,
+            expenses,
+            billing_plans,
+            time_entries,
         })
     }
 
@@ -181,6 +216,7 @@ impl super::_entities::orders::Model {
                 .all(db)
                 .await?;
             let client_to_find = clients::Model::find_by_id(db, order.client_id).await?;
+            let contacts = client_contacts::Model::find_by_client_id(db, client_to_find.id).await?;
             let seller = sellers::Model::find_by_id(db, order.seller_id).await?;
             let partner = match client_to_find.partner_id {
                 Some(id) => {
@@ -198,6 +234,28 @@ impl super::_entities::orders::Model {
                 )
                 .all(db)
                 .await?;
+            let order_credit_notes = credit_notes::Model::find_by_order_id(db, order.id).await?;
+            let balance = outstanding_balance(&payments, &order_credit_notes);
+            let credit_notes =
+                CreditNoteView::from_model(order_credit_notes, &order_fees, &payments);
+            let order_refunds = refunds::Model::find_by_order_id(db, order.id).await?;
+            let received = net_received(&payments, &order_refunds);
+            let refunds = RefundView::from_model(order_refunds, &payments);
+            let expenses = ExpenseView::from_model(
+                expenses::Model::find_by_order_id(db, order.id).await?,
+                &payments,
+            );
+            let billing_plans = BillingPlanView::from_model(
+                billing_plans::Model::find_by_order_id(db, order.id).await?,
+                &billing_plan_prices::Model::find_by_order_id(db, order.id).await?,
+            );
+            let lawsuit = lawsuits::Model::find_by_order_id(db, order.id)
+                .await?
+                .map(LawsuitView::from);
+            let order_time_entries = time_entries::Model::find_by_order_id(db, order.id).await?;
+            let time_entry_users = time_entries::Model::find_users(db, &order_time_entries).await?;
+            let time_entries =
+                TimeEntryView::from_model(order_time_entries, &time_entry_users, &payments);
             let mut fees = vec![];
             for order_fee in order_fees {
                 let fee = fees::Model::find_by_id(db, order_fee.fee_id).await?;
@@ -215,15 +273,7 @@ impl super::_entities::orders::Model {
             }
             orders_return.push(GetOrderReturn {
                 pid: order.pid,
-                client: ClientOrderReturn {
-                    pid: client_to_find.pid,
-                    name: client_to_find.name,
-                    contact: client_to_find.contact,
-                    phone: Some(client_to_find.phone),
-                    phone2: client_to_find.phone2,
-                    email: Some(client_to_find.email),
-                    partner,
-                },
+                client: ClientOrderReturn::from_model(client_to_find, &contacts, partner),
                 seller: SellerView::from(seller),
                 process: {
                     ClientProcessReturn {
@@ -231,6 +281,7 @@ impl super::_entities::orders::Model {
                         case_type: process.case_type,
                     }
                 },
+                lawsuit,
                 open: order.open,
                 fee: order.fee,
                 fees,
@@ -255,12 +306,20 @@ impl super::_entities::orders::Model {
                             .into(),
                     })
                     .collect(),
+                credit_notes,
+                outstanding_balance: balance,
+                refunds,
+                net_received: received,
+                expenses,
+                billing_plans,
+                time_entries,
             });
         }
         Ok(orders_return)
     }
 
-    /// creates a new order
+    /// creates a new order, each instalment due on the business day its due
+    /// date falls on or after
     ///
     /// # Errors
     ///
@@ -268,6 +327,7 @@ impl super::_entities::orders::Model {
     pub async fn create(
         db: &DatabaseConnection,
         order: &JsonOrderToCreate,
+        calendar: &BusinessCalendar,
     ) -> ModelResult<GetOrderReturn> {
         // Verifique se todas as entidades referenciadas existem
         let client = clients::Model::find_by_pid(db, order.client_pid).await?;
@@ -276,11 +336,17 @@ impl super::_entities::orders::Model {
 
         let seller = sellers::Model::find_by_pid(db, order.seller_pid).await?;
 
+        let mut fees_to_create = vec![];
+        for order_fee in &order.fees {
+            let fee = fees::Model::find_by_pid(db, order_fee.fee_pid).await?;
+            fees_to_create.push((fee, order_fee));
+        }
+
         // Inicie uma transação
         let txn = db.begin().await?;
 
         // Crie a nova ordem
-        let to_create_order = orders::ActiveModel {
+        let created_order = orders::ActiveModel {
             client_id: ActiveValue::Set(client.id),
             process_id: ActiveValue::Set(process.id),
             seller_id: ActiveValue::Set(seller.id),
@@ -292,46 +358,28 @@ impl super::_entities::orders::Model {
         }
         .insert(&txn)
         .await?;
-        txn.commit().await?;
-
-        // Encontre a ordem criada
-        let created_order = Entity::find()
-            .filter(
-                model::query::condition()
-                    .eq(super::_entities::orders::Column::Pid, to_create_order.pid)
-                    .build(),
-            )
-            .one(db)
-            .await?
-            .ok_or_else(|| ModelError::EntityNotFound)?;
 
         // Crie as taxas associadas à ordem
-        if !order.fees.is_empty() {
-            for order_fee in &order.fees {
-                let fee = fees::Model::find_by_pid(db, order_fee.fee_pid).await?;
-                let txn = db.begin().await?;
-                let _to_create_order_fee = order_fees::ActiveModel {
-                    fee_id: ActiveValue::Set(fee.id),
-                    order_id: ActiveValue::Set(created_order.id),
-                    open: ActiveValue::Set(order_fee.open),
-                    value: ActiveValue::Set(order_fee.value),
-                    info: ActiveValue::Set(order_fee.info.clone()),
-                    ..Default::default()
-                }
-                .insert(&txn)
-                .await?;
-                txn.commit().await?;
+        for (fee, order_fee) in fees_to_create {
+            let _to_create_order_fee = order_fees::ActiveModel {
+                fee_id: ActiveValue::Set(fee.id),
+                order_id: ActiveValue::Set(created_order.id),
+                open: ActiveValue::Set(order_fee.open),
+                value: ActiveValue::Set(order_fee.value),
+                info: ActiveValue::Set(order_fee.info.clone()),
+                ..Default::default()
             }
+            .insert(&txn)
+            .await?;
         }
 
         // Crie os pagamentos associados à ordem
         let mut order_payments = vec![];
         for payment in &order.payments {
-            let txn = db.begin().await?;
             let to_create_payment = payments::ActiveModel {
                 value: ActiveValue::Set(payment.value),
                 payment_date: ActiveValue::Set(payment.payment_date),
-                due_date: ActiveValue::Set(payment.due_date),
+                due_date: ActiveValue::Set(calendar.next_business_day(payment.due_date)),
                 payment_method: ActiveValue::Set(payment.payment_method.clone()),
                 currency: ActiveValue::Set(payment.currency.clone()),
                 postponed_payment: ActiveValue::Set(payment.postponed_payment),
@@ -341,11 +389,8 @@ impl super::_entities::orders::Model {
             }
             .insert(&txn)
             .await?;
-            txn.commit().await?;
-            let mut postponed_payments = vec![];
             if let Some(dates) = &payment.postponed_dates {
                 for date in dates {
-                    let txn = db.begin().await?;
                     let _to_create_postponed_payment = postponed_payments::ActiveModel {
                         payment_id: ActiveValue::Set(to_create_payment.id),
                         postponed_date: ActiveValue::Set(*date),
@@ -353,15 +398,17 @@ impl super::_entities::orders::Model {
                     }
                     .insert(&txn)
                     .await?;
-                    txn.commit().await?;
-                    postponed_payments.push(*date);
                 }
             }
             order_payments.push(to_create_payment);
         }
 
+        journal_entries::Model::sync_order(&txn, created_order.id).await?;
+        txn.commit().await?;
+
         // Encontre o cliente, vendedor e parceiro associados à ordem
         let client_to_find = clients::Model::find_by_pid(db, order.client_pid).await?;
+        let contacts = client_contacts::Model::find_by_client_id(db, client_to_find.id).await?;
 
         let seller = sellers::Model::find_by_pid(db, order.seller_pid).await?;
 
@@ -403,18 +450,13 @@ impl super::_entities::orders::Model {
             });
         }
 
+        let balance = outstanding_balance(&order_payments, &[]);
+        let received = net_received(&order_payments, &[]);
+
         // Retorne a ordem criada
         Ok(GetOrderReturn {
             pid: created_order.pid,
-            client: ClientOrderReturn {
-                pid: client_to_find.pid,
-                name: client_to_find.name,
-                contact: client_to_find.contact,
-                phone: Some(client_to_find.phone),
-                phone2: client_to_find.phone2,
-                email: Some(client_to_find.email),
-                partner,
-            },
+            client: ClientOrderReturn::from_model(client_to_find, &contacts, partner),
             seller: SellerView::from(seller),
             process: {
                 ClientProcessReturn {
@@ -422,6 +464,7 @@ impl super::_entities::orders::Model {
                     case_type: process.case_type,
                 }
             },
+            lawsuit: None,
             open: created_order.open,
             fee: created_order.fee,
             fees,
@@ -441,10 +484,18 @@ impl super::_entities::orders::Model {
                     postponed_dates: None,
                 })
                 .collect(),
+            credit_notes: vec![],
+            outstanding_balance: balance,
+            refunds: vec![],
+            net_received: received,
+            expenses: vec![],
+            billing_plans: vec![],
+            time_entries: vec![],
         })
     }
 
-    /// updates an order
+    /// updates an order, each instalment due on the business day its due
+    /// date falls on or after
     ///
     /// # Errors
     ///
@@ -453,6 +504,7 @@ impl super::_entities::orders::Model {
         db: &DatabaseConnection,
         pid: &str,
         order: CreateNewOrder,
+        calendar: &BusinessCalendar,
     ) -> ModelResult<Vec<GetOrderReturn>> {
         let existing_order = Entity::find()
             .filter(
@@ -466,6 +518,13 @@ impl super::_entities::orders::Model {
         let mut edited_order = existing_order.clone().into_active_model();
         let client = clients::Model::find_by_pid(db, order.client_pid).await?;
         let process = processes::Model::find_by_pid(db, order.process_pid).await?;
+        let mut new_fees = vec![];
+        for order_fee in &order.fees {
+            if order_fee.order_fee_pid.is_none() {
+                new_fees.push(fees::Model::find_by_pid(db, order_fee.fee_pid).await?);
+            }
+        }
+        let mut new_fees = new_fees.into_iter();
         edited_order.client_id = ActiveValue::Set(client.id);
         edited_order.process_id = ActiveValue::Set(process.id);
         edited_order.open = ActiveValue::Set(order.open);
@@ -474,7 +533,14 @@ impl super::_entities::orders::Model {
         edited_order.partner_fee = ActiveValue::Set(order.partner_fee);
         let txn = db.begin().await?;
         edited_order.update(&txn).await?;
-        txn.commit().await?;
+        status_changes::Model::record(
+            &txn,
+            existing_order.id,
+            StatusSubject::Order,
+            order_status(existing_order.open),
+            order_status(order.open),
+        )
+        .await?;
 
         let existing_payments = payments::Entity::find()
             .filter(
@@ -485,12 +551,11 @@ impl super::_entities::orders::Model {
                     )
                     .build(),
             )
-            .all(db)
+            .all(&txn)
             .await?;
         for order_fee in order.fees {
             if order_fee.order_fee_pid.is_none() {
-                let fee = fees::Model::find_by_pid(db, order_fee.fee_pid).await?;
-                let txn = db.begin().await?;
+                let fee = new_fees.next().ok_or_else(|| ModelError::EntityNotFound)?;
                 let _to_create_order_fee = order_fees::ActiveModel {
                     fee_id: ActiveValue::Set(fee.id),
                     order_id: ActiveValue::Set(existing_order.id),
@@ -501,7 +566,6 @@ impl super::_entities::orders::Model {
                 }
                 .insert(&txn)
                 .await?;
-                txn.commit().await?;
             } else {
                 let order_fee = order_fees::Entity::find()
                     .filter(
@@ -512,16 +576,14 @@ impl super::_entities::orders::Model {
                             )
                             .build(),
                     )
-                    .one(db)
+                    .one(&txn)
                     .await?
                     .ok_or_else(|| ModelError::EntityNotFound)?;
                 let mut edited_order_fee = order_fee.clone().into_active_model();
                 edited_order_fee.open = ActiveValue::Set(order_fee.open);
                 edited_order_fee.value = ActiveValue::Set(order_fee.value);
                 edited_order_fee.info = ActiveValue::Set(order_fee.info.clone());
-                let txn = db.begin().await?;
                 edited_order_fee.update(&txn).await?;
-                txn.commit().await?;
             }
         }
 
@@ -539,12 +601,11 @@ impl super::_entities::orders::Model {
                             )
                             .build(),
                     )
-                    .one(db)
+                    .one(&txn)
                     .await?
                     .ok_or_else(|| ModelError::EntityNotFound)?;
-                if new_payment.postponed_dates.is_some() {
-                    for postponed_date in new_payment.postponed_dates.as_ref().unwrap() {
-                        let txn = db.begin().await?;
+                if let Some(postponed_dates) = &new_payment.postponed_dates {
+                    for postponed_date in postponed_dates {
                         let _to_create_postponed_payment = postponed_payments::ActiveModel {
                             payment_id: ActiveValue::Set(payment.id),
                             postponed_date: ActiveValue::Set(*postponed_date),
@@ -552,31 +613,30 @@ impl super::_entities::orders::Model {
                         }
                         .insert(&txn)
                         .await?;
-                        txn.commit().await?;
                     }
                 }
                 let mut edited_payment = payment.clone().into_active_model();
                 edited_payment.value = ActiveValue::Set(new_payment.value);
                 edited_payment.payment_date = ActiveValue::Set(new_payment.payment_date);
-                edited_payment.due_date = ActiveValue::Set(new_payment.due_date);
+                edited_payment.due_date =
+                    ActiveValue::Set(calendar.next_business_day(new_payment.due_date));
                 edited_payment.payment_method =
                     ActiveValue::Set(new_payment.payment_method.clone());
                 edited_payment.currency = ActiveValue::Set(new_payment.currency.clone());
                 edited_payment.postponed_payment =
                     ActiveValue::Set(Some(new_payment.postponed_dates.is_some()));
                 edited_payment.open = ActiveValue::Set(new_payment.open);
-                let txn = db.begin().await?;
                 edited_payment.update(&txn).await?;
-                txn.commit().await?;
             }
         } else {
             for new_payment in &order.payments {
                 if new_payment.pid.is_none() {
-                    let txn = db.begin().await?;
                     let to_create_payment = payments::ActiveModel {
                         value: ActiveValue::Set(new_payment.value),
                         payment_date: ActiveValue::Set(new_payment.payment_date),
-                        due_date: ActiveValue::Set(new_payment.due_date),
+                        due_date: ActiveValue::Set(
+                            calendar.next_business_day(new_payment.due_date),
+                        ),
                         payment_method: ActiveValue::Set(new_payment.payment_method.clone()),
                         currency: ActiveValue::Set(new_payment.currency.clone()),
                         postponed_payment: ActiveValue::Set(new_payment.postponed_payment),
@@ -586,10 +646,8 @@ impl super::_entities::orders::Model {
                     }
                     .insert(&txn)
                     .await?;
-                    txn.commit().await?;
-                    if new_payment.postponed_dates.is_some() {
-                        for date in new_payment.postponed_dates.as_ref().unwrap() {
-                            let txn = db.begin().await?;
+                    if let Some(dates) = &new_payment.postponed_dates {
+                        for date in dates {
                             let _to_create_postponed_payment = postponed_payments::ActiveModel {
                                 payment_id: ActiveValue::Set(to_create_payment.id),
                                 postponed_date: ActiveValue::Set(*date),
@@ -597,22 +655,26 @@ impl super::_entities::orders::Model {
                             }
                             .insert(&txn)
                             .await?;
-                            txn.commit().await?;
                         }
                     }
                 }
             }
         }
+        journal_entries::Model::sync_order(&txn, existing_order.id).await?;
+        txn.commit().await?;
 
         let response = Self::find_all(db).await?;
         Ok(response)
     }
 
-    /// deletes an order
+    /// deletes an order, unless credit notes, receipts or statements were
+    /// issued on it, refunds were paid from it or files are kept with it, its
+    /// payments or its expenses
     ///
     /// # Errors
     ///
-    /// When could not delete order or DB query error
+    /// When the order has credit notes, documents, refunds or attachments,
+    /// could not delete order or DB query error
     pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Vec<GetOrderReturn>> {
         let existing_order = Entity::find()
             .filter(
@@ -623,8 +685,39 @@ impl super::_entities::orders::Model {
             .one(db)
             .await?
             .ok_or_else(|| ModelError::EntityNotFound)?;
+        let order_id = existing_order.id;
+        if !credit_notes::Model::find_by_order_id(db, order_id)
+            .await?
+            .is_empty()
+        {
+            return Err(ModelError::Any(
+                "an order with credit notes cannot be deleted".into(),
+            ));
+        }
+        if !refunds::Model::find_by_order_id(db, order_id)
+            .await?
+            .is_empty()
+        {
+            return Err(ModelError::Any(
+                "an order with refunds cannot be deleted".into(),
+            ));
+        }
+        if !documents::Model::find_by_order_id(db, order_id)
+            .await?
+            .is_empty()
+        {
+            return Err(ModelError::Any(
+                "an order with issued documents cannot be deleted".into(),
+            ));
+        }
+        if attachments::Model::any_kept_with(db, AttachmentTarget::Order(order_id)).await? {
+            return Err(ModelError::Any(
+                "an order with attachments cannot be deleted".into(),
+            ));
+        }
         let txn = db.begin().await?;
         existing_order.delete(&txn).await?;
+        journal_entries::Model::sync_order(&txn, order_id).await?;
         txn.commit().await?;
         let response = Self::find_all(db).await?;
         Ok(response)
@@ -0,0 +1,142 @@
+use super::_entities::payment_charges::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type PaymentCharges = Entity;
+use crate::models::_entities::{payment_charges, payments};
+use crate::payment_providers::{ChargeStatus, PaymentProvider};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::ActiveValue;
+use sea_orm::TransactionTrait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewPaymentCharge {
+    pub payment_pid: Uuid,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::payment_charges::Model {
+    /// finds the charges created for a payment
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_payment_id(
+        db: &DatabaseConnection,
+        payment_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let payment_charges = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(payment_charges::Column::PaymentId, payment_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(payment_charges)
+    }
+
+    /// finds the charge a provider knows by the given id
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_external_id<C>(
+        db: &C,
+        provider: &str,
+        external_id: &str,
+    ) -> ModelResult<Option<Self>>
+    where
+        C: ConnectionTrait,
+    {
+        let payment_charge = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(payment_charges::Column::Provider, provider)
+                    .eq(payment_charges::Column::ExternalId, external_id)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        Ok(payment_charge)
+    }
+
+    /// asks the provider to charge an open payment, reusing the charge still
+    /// pending from an earlier request
+    ///
+    /// # Errors
+    ///
+    /// When the payment is not open, the provider refuses the charge or DB
+    /// query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        provider: &dyn PaymentProvider,
+        payment_pid: Uuid,
+    ) -> ModelResult<Self> {
+        let payment = payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(payments::Column::Pid, payment_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        if !payment.open {
+            return Err(ModelError::Any(
+                "charges can only be created for open payments".into(),
+            ));
+        }
+        let pending = Self::find_by_payment_id(db, payment.id)
+            .await?
+            .into_iter()
+            .find(|charge| {
+                charge.provider == provider.name()
+                    && charge.status == ChargeStatus::Pending.as_str()
+                    && (charge.value - payment.value).abs() < f32::EPSILON
+            });
+        if let Some(pending) = pending {
+            return Ok(pending);
+        }
+
+        let charge = provider
+            .create_charge(&payment)
+            .await
+            .map_err(|err| ModelError::Any(err.to_string().into()))?;
+        let txn = db.begin().await?;
+        let payment_charge = payment_charges::ActiveModel {
+            payment_id: ActiveValue::Set(payment.id),
+            provider: ActiveValue::Set(provider.name().to_string()),
+            external_id: ActiveValue::Set(charge.external_id),
+            checkout_url: ActiveValue::Set(charge.checkout_url),
+            value: ActiveValue::Set(payment.value),
+            status: ActiveValue::Set(ChargeStatus::Pending.as_str().to_string()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(payment_charge)
+    }
+}
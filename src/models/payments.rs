@@ -1,4 +1,8 @@
 use super::_entities::payments::{ActiveModel, Entity};
+use crate::business_days::BusinessCalendar;
+use crate::documents::boleto::BoletoSettings;
+use crate::models::_entities::{attachments, documents, journal_entries, time_entries};
+use crate::models::attachments::AttachmentTarget;
 use sea_orm::entity::prelude::*;
 pub type Payments = Entity;
 use loco_rs::model::ModelError;
@@ -58,6 +62,29 @@ impl super::_entities::payments::Model {
         payment.ok_or_else(|| ModelError::EntityNotFound)
     }
 
+    /// finds the payment a boleto was issued for by its "nosso número"
+    ///
+    /// # Errors
+    ///
+    /// When could not find payment by the given number or DB query error
+    pub async fn find_by_nosso_numero(
+        db: &DatabaseConnection,
+        nosso_numero: &str,
+    ) -> ModelResult<Self> {
+        let payment = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(
+                        super::_entities::payments::Column::NossoNumero,
+                        nosso_numero,
+                    )
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        payment.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
     /// finds all payments
     ///
     /// # Errors
@@ -68,17 +95,22 @@ impl super::_entities::payments::Model {
         Ok(payments)
     }
 
-    /// creates a new payment
+    /// creates a new payment, due on the business day its due date falls on
+    /// or after
     ///
     /// # Errors
     ///
     /// When could not create payment or DB query error
-    pub async fn create(db: &DatabaseConnection, payment: CreateNewPayment) -> ModelResult<Self> {
+    pub async fn create(
+        db: &DatabaseConnection,
+        payment: CreateNewPayment,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<Self> {
         let txn = db.begin().await?;
         let payment = ActiveModel {
             value: ActiveValue::Set(payment.value),
             payment_date: ActiveValue::Set(Some(payment.payment_date)),
-            due_date: ActiveValue::Set(payment.due_date),
+            due_date: ActiveValue::Set(calendar.next_business_day(payment.due_date)),
             payment_method: ActiveValue::Set(payment.payment_method),
             currency: ActiveValue::Set(payment.currency),
             postponed_payment: ActiveValue::Set(payment.postponed_payment),
@@ -87,11 +119,13 @@ impl super::_entities::payments::Model {
         }
         .insert(&txn)
         .await?;
+        journal_entries::Model::sync_order(&txn, payment.order_id).await?;
         txn.commit().await?;
         Ok(payment)
     }
 
-    /// updates a payment
+    /// updates a payment, due on the business day its due date falls on or
+    /// after
     ///
     /// # Errors
     ///
@@ -100,6 +134,7 @@ impl super::_entities::payments::Model {
         db: &DatabaseConnection,
         pid: &str,
         payment: CreateNewPayment,
+        calendar: &BusinessCalendar,
     ) -> ModelResult<Self> {
         let existing_payment = Entity::find()
             .filter(
@@ -113,23 +148,27 @@ impl super::_entities::payments::Model {
         let mut edited_payment = existing_payment.into_active_model();
         edited_payment.value = ActiveValue::Set(payment.value);
         edited_payment.payment_date = ActiveValue::Set(Some(payment.payment_date));
-        edited_payment.due_date = ActiveValue::Set(payment.due_date);
+        edited_payment.due_date = ActiveValue::Set(calendar.next_business_day(payment.due_date));
         edited_payment.payment_method = ActiveValue::Set(payment.payment_method);
         edited_payment.currency = ActiveValue::Set(payment.currency);
         edited_payment.postponed_payment = ActiveValue::Set(payment.postponed_payment);
         edited_payment.open = ActiveValue::Set(payment.open);
         let txn = db.begin().await?;
         let payment = edited_payment.update(&txn).await?;
+        journal_entries::Model::sync_order(&txn, payment.order_id).await?;
         txn.commit().await?;
         Ok(payment)
     }
 
-    /// deletes a payment
+    /// deletes a payment, unless a receipt was issued for it or files are
+    /// kept with it; the time invoiced into it can be invoiced again and its
+    /// credit notes stay on the order
     ///
     /// # Errors
     ///
-    /// When could not delete payment or DB query error
-    pub async fn delete(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+    /// When the payment has a receipt or attachments, could not delete
+    /// payment or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<()> {
         let existing_payment = Entity::find()
             .filter(
                 model::query::condition()
@@ -139,9 +178,65 @@ impl super::_entities::payments::Model {
             .one(db)
             .await?
             .ok_or_else(|| ModelError::EntityNotFound)?;
+        if documents::Model::find_receipt(db, existing_payment.id)
+            .await?
+            .is_some()
+        {
+            return Err(ModelError::Any(
+                "a payment with a receipt cannot be deleted".into(),
+            ));
+        }
+        if attachments::Model::any_kept_with(db, AttachmentTarget::Payment(existing_payment.id))
+            .await?
+        {
+            return Err(ModelError::Any(
+                "a payment with attachments cannot be deleted".into(),
+            ));
+        }
+        let order_id = existing_payment.order_id;
         let txn = db.begin().await?;
+        time_entries::Model::release(&txn, existing_payment.id).await?;
         existing_payment.delete(&txn).await?;
+        journal_entries::Model::sync_order(&txn, order_id).await?;
         txn.commit().await?;
         Ok(())
     }
+
+    /// assigns the "nosso número" of the boleto of an open payment the first
+    /// time it is issued, keeping it afterwards so the payment can be found
+    /// when the bank reports it
+    ///
+    /// # Errors
+    ///
+    /// When could not find the payment, it is not open or DB query error
+    pub async fn issue_nosso_numero(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        settings: &BoletoSettings,
+    ) -> ModelResult<Self> {
+        let payment = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::payments::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        if !payment.open {
+            return Err(ModelError::Any(
+                "boletos can only be issued for open payments".into(),
+            ));
+        }
+        if payment.nosso_numero.is_some() {
+            return Ok(payment);
+        }
+        let nosso_numero = settings.nosso_numero(payment.id);
+        let mut edited_payment = payment.into_active_model();
+        edited_payment.nosso_numero = ActiveValue::Set(Some(nosso_numero));
+        let txn = db.begin().await?;
+        let payment = edited_payment.update(&txn).await?;
+        txn.commit().await?;
+        Ok(payment)
+    }
 }
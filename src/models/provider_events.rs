@@ -0,0 +1,149 @@
+use super::_entities::provider_events::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type ProviderEvents = Entity;
+use crate::models::_entities::{journal_entries, payment_charges, payments, provider_events};
+use crate::models::bank_transactions::AMOUNT_TOLERANCE;
+use crate::payment_providers::{ChargeStatus, WebhookEvent};
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use sea_orm::{TransactionTrait, TryInsertResult};
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::provider_events::Model {
+    /// finds an event a provider already delivered
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find_by_event_id(
+        db: &DatabaseConnection,
+        provider: &str,
+        event_id: &str,
+    ) -> ModelResult<Option<Self>> {
+        let provider_event = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(provider_events::Column::Provider, provider)
+                    .eq(provider_events::Column::EventId, event_id)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        Ok(provider_event)
+    }
+
+    /// records a webhook event and updates its charge, settling the payment
+    /// when the charge is paid for the value of the payment. Events are
+    /// recorded once, so a provider delivering the same event again, even
+    /// while the first delivery is still being processed, gets `None` and
+    /// changes nothing. A charge paid for another value, as one created
+    /// before the payment was changed, is recorded as paid but leaves the
+    /// payment open to be settled by hand.
+    ///
+    /// # Errors
+    ///
+    /// When the charge of the event is not known or DB query error
+    pub async fn record(
+        db: &DatabaseConnection,
+        provider: &str,
+        event: WebhookEvent,
+    ) -> ModelResult<Option<Self>> {
+        let txn = db.begin().await?;
+        let payment_charge =
+            payment_charges::Model::find_by_external_id(&txn, provider, &event.charge_id)
+                .await?
+                .ok_or_else(|| ModelError::EntityNotFound)?;
+        // the unique index decides which delivery records the event, so two
+        // deliveries arriving together cannot both settle the payment
+        let inserted = Entity::insert(provider_events::ActiveModel {
+            pid: ActiveValue::Set(Uuid::new_v4()),
+            provider: ActiveValue::Set(provider.to_string()),
+            event_id: ActiveValue::Set(event.event_id),
+            payment_charge_id: ActiveValue::Set(Some(payment_charge.id)),
+            status: ActiveValue::Set(event.status.as_str().to_string()),
+            paid_on: ActiveValue::Set(event.paid_on),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([
+                provider_events::Column::Provider,
+                provider_events::Column::EventId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .do_nothing()
+        .exec(&txn)
+        .await?;
+        let TryInsertResult::Inserted(inserted) = inserted else {
+            txn.rollback().await?;
+            return Ok(None);
+        };
+        let provider_event = Entity::find_by_id(inserted.last_insert_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+
+        // a late event must not undo a payment already received
+        let mut settled_order_id = None;
+        if payment_charge.status != ChargeStatus::Paid.as_str() {
+            let payment_id = payment_charge.payment_id;
+            let charged = payment_charge.value;
+            let mut edited_charge = payment_charge.into_active_model();
+            edited_charge.status = ActiveValue::Set(event.status.as_str().to_string());
+            if event.status == ChargeStatus::Paid {
+                let paid_on = event
+                    .paid_on
+                    .unwrap_or_else(|| chrono::Utc::now().date_naive());
+                edited_charge.paid_on = ActiveValue::Set(Some(paid_on));
+                let payment = payments::Entity::find_by_id(payment_id)
+                    .one(&txn)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                if (charged - payment.value).abs() >= AMOUNT_TOLERANCE {
+                    tracing::warn!(
+                        payment_id,
+                        charged,
+                        value = payment.value,
+                        "a charge was paid for another value than its payment's, which was left open",
+                    );
+                } else if payment.open {
+                    settled_order_id = Some(payment.order_id);
+                    let mut settled_payment = payment.into_active_model();
+                    settled_payment.open = ActiveValue::Set(false);
+                    settled_payment.payment_date = ActiveValue::Set(Some(paid_on));
+                    settled_payment.update(&txn).await?;
+                }
+            }
+            edited_charge.update(&txn).await?;
+        }
+        if let Some(order_id) = settled_order_id {
+            journal_entries::Model::sync_order(&txn, order_id).await?;
+        }
+        txn.commit().await?;
+        Ok(Some(provider_event))
+    }
+}
@@ -0,0 +1,354 @@
+use super::_entities::refunds::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type Refunds = Entity;
+use crate::models::_entities::{journal_entries, orders, payments, refunds};
+use crate::models::journal_entries::cents;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewRefund {
+    pub order_pid: Uuid,
+    pub payment_pid: Option<Uuid>,
+    pub value: f32,
+    pub refund_date: chrono::NaiveDate,
+    pub refund_method: Option<String>,
+    pub reason: String,
+    pub chargeback: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditRefund {
+    pub value: f32,
+    pub refund_date: chrono::NaiveDate,
+    pub refund_method: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(range(exclusive_min = 0.0, message = "Value must be greater than zero."))]
+    pub value: f32,
+    #[validate(length(min = 2, message = "Reason must be at least 2 characters long."))]
+    pub reason: String,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            value: *self.value.as_ref(),
+            reason: self.reason.as_ref().to_owned(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+/// Returns the money actually kept from an order: settled instalments minus
+/// the refunds paid back to the client.
+///
+/// Chargebacks are left out because recording one reopens its instalment,
+/// which already removes it from the settled total.
+#[must_use]
+pub fn net_received(payments: &[payments::Model], refunds: &[refunds::Model]) -> f32 {
+    let received: f32 = payments
+        .iter()
+        .filter(|payment| !payment.open)
+        .map(|payment| payment.value)
+        .sum();
+    let refunded: f32 = refunds
+        .iter()
+        .filter(|refund| !refund.chargeback)
+        .map(refunds::Model::signed_value)
+        .sum();
+    received + refunded
+}
+
+impl super::_entities::refunds::Model {
+    /// the refund as a movement of money, negative since it leaves the firm
+    #[must_use]
+    pub fn signed_value(&self) -> f32 {
+        -self.value
+    }
+
+    /// finds a refund by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find refund by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let refund = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::refunds::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        refund.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the refunds of an order
+    ///
+    /// # Errors
+    ///
+    /// When could not find refunds or DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let refunds = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::refunds::Column::OrderId, order_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(refunds)
+    }
+
+    /// finds all refunds
+    ///
+    /// # Errors
+    ///
+    /// When could not find refunds or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let refunds = Entity::find().all(db).await?;
+        Ok(refunds)
+    }
+
+    /// checks that `value` can still be refunded from the order, or from the
+    /// payment when one is given, without giving back more than was received.
+    /// Chargebacks are not counted as previous refunds since they reopen the
+    /// payment instead of reducing what was kept from it
+    ///
+    /// # Errors
+    ///
+    /// When the refund is larger than what is left to refund or DB query error
+    async fn check_refundable(
+        db: &DatabaseConnection,
+        order: &orders::Model,
+        payment: Option<&payments::Model>,
+        value: f32,
+        ignored_refund_id: Option<i32>,
+    ) -> ModelResult<()> {
+        let previous_refunds: f32 = Self::find_by_order_id(db, order.id)
+            .await?
+            .iter()
+            .filter(|refund| Some(refund.id) != ignored_refund_id && !refund.chargeback)
+            .filter(|refund| payment.is_none() || refund.payment_id == payment.map(|p| p.id))
+            .map(|refund| refund.value)
+            .sum();
+        let received = match payment {
+            Some(payment) => payment.value,
+            None => payments::Entity::find()
+                .filter(
+                    model::query::condition()
+                        .eq(payments::Column::OrderId, order.id)
+                        .eq(payments::Column::Open, false)
+                        .build(),
+                )
+                .all(db)
+                .await?
+                .iter()
+                .map(|payment| payment.value)
+                .sum(),
+        };
+        if previous_refunds + value > received {
+            return Err(ModelError::Any(
+                "refund is larger than the amount received".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// checks that a chargeback takes back the whole payment, since it reopens
+    /// the payment for its full value
+    ///
+    /// # Errors
+    ///
+    /// When the chargeback is for part of the payment
+    fn check_chargeback(payment: &payments::Model, value: f32) -> ModelResult<()> {
+        if cents(value) != cents(payment.value) {
+            return Err(ModelError::Any(
+                "a chargeback must be for the whole payment".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// creates a new refund, reopening the payment when it is a chargeback
+    ///
+    /// # Errors
+    ///
+    /// When a chargeback is for part of its payment, could not create refund
+    /// or DB query error
+    pub async fn create(db: &DatabaseConnection, refund: CreateNewRefund) -> ModelResult<Self> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, refund.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let payment = match refund.payment_pid {
+            Some(pid) => {
+                let payment = payments::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(payments::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?;
+                if payment.order_id != order.id {
+                    return Err(ModelError::Any(
+                        "payment does not belong to the order".into(),
+                    ));
+                }
+                if payment.open {
+                    return Err(ModelError::Any(
+                        "only settled payments can be refunded".into(),
+                    ));
+                }
+                Some(payment)
+            }
+            None if refund.chargeback => {
+                return Err(ModelError::Any(
+                    "a chargeback must point to a payment".into(),
+                ));
+            }
+            None => None,
+        };
+        if let (Some(payment), true) = (&payment, refund.chargeback) {
+            Self::check_chargeback(payment, refund.value)?;
+        }
+        Self::check_refundable(db, &order, payment.as_ref(), refund.value, None).await?;
+
+        let txn = db.begin().await?;
+        let created_refund = refunds::ActiveModel {
+            order_id: ActiveValue::Set(order.id),
+            payment_id: ActiveValue::Set(payment.as_ref().map(|p| p.id)),
+            value: ActiveValue::Set(refund.value),
+            refund_date: ActiveValue::Set(refund.refund_date),
+            refund_method: ActiveValue::Set(refund.refund_method),
+            reason: ActiveValue::Set(refund.reason),
+            chargeback: ActiveValue::Set(refund.chargeback),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        if let (Some(payment), true) = (payment, refund.chargeback) {
+            let mut reopened_payment = payment.into_active_model();
+            reopened_payment.open = ActiveValue::Set(true);
+            reopened_payment.update(&txn).await?;
+        }
+        journal_entries::Model::sync_order(&txn, created_refund.order_id).await?;
+        txn.commit().await?;
+        Ok(created_refund)
+    }
+
+    /// updates the amount, date, method and reason of a refund
+    ///
+    /// # Errors
+    ///
+    /// When a chargeback is for part of its payment, could not update refund
+    /// or DB query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        refund: EditRefund,
+    ) -> ModelResult<Self> {
+        let existing_refund = Self::find_by_pid(db, pid).await?;
+        let order = orders::Entity::find_by_id(existing_refund.order_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let payment = match existing_refund.payment_id {
+            Some(id) => payments::Entity::find_by_id(id).one(db).await?,
+            None => None,
+        };
+        if let (Some(payment), true) = (&payment, existing_refund.chargeback) {
+            Self::check_chargeback(payment, refund.value)?;
+        }
+        Self::check_refundable(
+            db,
+            &order,
+            payment.as_ref(),
+            refund.value,
+            Some(existing_refund.id),
+        )
+        .await?;
+        let mut edited_refund = existing_refund.into_active_model();
+        edited_refund.value = ActiveValue::Set(refund.value);
+        edited_refund.refund_date = ActiveValue::Set(refund.refund_date);
+        edited_refund.refund_method = ActiveValue::Set(refund.refund_method);
+        edited_refund.reason = ActiveValue::Set(refund.reason);
+        let txn = db.begin().await?;
+        let refund = edited_refund.update(&txn).await?;
+        journal_entries::Model::sync_order(&txn, refund.order_id).await?;
+        txn.commit().await?;
+        Ok(refund)
+    }
+
+    /// deletes a refund, settling the payment again when it was the last
+    /// chargeback against it
+    ///
+    /// # Errors
+    ///
+    /// When could not delete refund or DB query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_refund = Self::find_by_pid(db, pid).await?;
+        let txn = db.begin().await?;
+        existing_refund.clone().delete(&txn).await?;
+        if let (Some(payment_id), true) = (existing_refund.payment_id, existing_refund.chargeback) {
+            let other_chargebacks = Entity::find()
+                .filter(
+                    model::query::condition()
+                        .eq(refunds::Column::PaymentId, payment_id)
+                        .eq(refunds::Column::Chargeback, true)
+                        .build(),
+                )
+                .count(&txn)
+                .await?;
+            let payment = payments::Entity::find_by_id(payment_id).one(&txn).await?;
+            if let (0, Some(payment)) = (other_chargebacks, payment) {
+                let mut settled_payment = payment.into_active_model();
+                settled_payment.open = ActiveValue::Set(false);
+                settled_payment.update(&txn).await?;
+            }
+        }
+        journal_entries::Model::sync_order(&txn, existing_refund.order_id).await?;
+        txn.commit().await?;
+        Ok(existing_refund)
+    }
+}
@@ -0,0 +1,121 @@
+use super::_entities::status_changes::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type StatusChanges = Entity;
+use crate::models::_entities::status_changes;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{ActiveValue, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+/// What of an order had its status changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSubject {
+    /// the order being opened or closed
+    Order,
+    Lawsuit,
+}
+
+impl StatusSubject {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Order => "order",
+            Self::Lawsuit => "lawsuit",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(subject: &str) -> Option<Self> {
+        match subject {
+            "order" => Some(Self::Order),
+            "lawsuit" => Some(Self::Lawsuit),
+            _ => None,
+        }
+    }
+}
+
+/// the status of an order by whether it is open
+#[must_use]
+pub const fn order_status(open: bool) -> &'static str {
+    if open {
+        "open"
+    } else {
+        "closed"
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::status_changes::Model {
+    /// records a change of status of an order or its lawsuit, when the
+    /// status did change
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn record<C>(
+        db: &C,
+        order_id: i32,
+        subject: StatusSubject,
+        from_status: &str,
+        to_status: &str,
+    ) -> ModelResult<Option<Self>>
+    where
+        C: ConnectionTrait,
+    {
+        if from_status == to_status {
+            return Ok(None);
+        }
+        let status_change = status_changes::ActiveModel {
+            order_id: ActiveValue::Set(order_id),
+            subject: ActiveValue::Set(subject.as_str().to_string()),
+            from_status: ActiveValue::Set(from_status.to_string()),
+            to_status: ActiveValue::Set(to_status.to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(Some(status_change))
+    }
+
+    /// finds the status changes of the given orders, oldest first
+    ///
+    /// # Errors
+    ///
+    /// When could not find status changes or DB query error
+    pub async fn find_by_order_ids(
+        db: &DatabaseConnection,
+        order_ids: impl IntoIterator<Item = i32>,
+    ) -> ModelResult<Vec<Self>> {
+        let status_changes = Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(status_changes::Column::OrderId, order_ids)
+                    .build(),
+            )
+            .order_by_asc(status_changes::Column::CreatedAt)
+            .all(db)
+            .await?;
+        Ok(status_changes)
+    }
+}
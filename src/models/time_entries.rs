@@ -0,0 +1,353 @@
+use super::_entities::time_entries::{ActiveModel, Entity};
+use sea_orm::entity::prelude::*;
+pub type TimeEntries = Entity;
+use crate::business_days::BusinessCalendar;
+use crate::models::_entities::{
+    hourly_rates, journal_entries, orders, payments, time_entries, users,
+};
+use crate::models::hourly_rates::resolve_hourly_rate;
+use loco_rs::model::ModelError;
+use loco_rs::model::{self, ModelResult};
+use loco_rs::prelude::{Validatable, Validate};
+use sea_orm::sea_query::Expr;
+use sea_orm::TransactionTrait;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+
+/// Time logged on an order. Without a `user_pid` the time is logged for the
+/// signed in user, and without an `hourly_rate` the rate of the user or the
+/// order is used
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateNewTimeEntry {
+    pub order_pid: Uuid,
+    pub user_pid: Option<Uuid>,
+    pub entry_date: chrono::NaiveDate,
+    pub minutes: i32,
+    pub description: String,
+    pub hourly_rate: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditTimeEntry {
+    pub entry_date: chrono::NaiveDate,
+    pub minutes: i32,
+    pub description: String,
+    pub hourly_rate: f32,
+}
+
+/// Unbilled time of an order to roll into a new instalment due on
+/// `due_date`, optionally only the time logged up to `until`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InvoiceTimeEntries {
+    pub order_pid: Uuid,
+    pub due_date: chrono::NaiveDate,
+    pub until: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Validate, Deserialize)]
+pub struct Validator {
+    #[validate(range(min = 1, message = "Duration must be at least one minute."))]
+    pub minutes: i32,
+    #[validate(length(min = 2, message = "Description must be at least 2 characters long."))]
+    pub description: String,
+    #[validate(range(min = 0.0, message = "Hourly rate cannot be negative."))]
+    pub hourly_rate: f32,
+}
+
+impl Validatable for ActiveModel {
+    fn validator(&self) -> Box<dyn Validate> {
+        Box::new(Validator {
+            minutes: *self.minutes.as_ref(),
+            description: self.description.as_ref().to_owned(),
+            hourly_rate: *self.hourly_rate.as_ref(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // extend activemodel below (keep comment for generators)
+
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.validate()?;
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            return Ok(this);
+        }
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            return Ok(this);
+        }
+        Ok(self)
+    }
+}
+
+impl super::_entities::time_entries::Model {
+    /// the amount billed for the entry, rounded to cents
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn amount(&self) -> f32 {
+        (self.minutes as f32 * self.hourly_rate / 60.0 * 100.0).round() / 100.0
+    }
+
+    /// finds a time entry by the provided pid
+    ///
+    /// # Errors
+    ///
+    /// When could not find time entry by the given pid or DB query error
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let time_entry = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::time_entries::Column::Pid, pid)
+                    .build(),
+            )
+            .one(db)
+            .await?;
+        time_entry.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// finds the time entries of an order
+    ///
+    /// # Errors
+    ///
+    /// When could not find time entries or DB query error
+    pub async fn find_by_order_id(
+        db: &DatabaseConnection,
+        order_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        let time_entries = Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(super::_entities::time_entries::Column::OrderId, order_id)
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(time_entries)
+    }
+
+    /// finds the users who logged the given time entries
+    ///
+    /// # Errors
+    ///
+    /// When could not find users or DB query error
+    pub async fn find_users(
+        db: &DatabaseConnection,
+        time_entries: &[Self],
+    ) -> ModelResult<Vec<users::Model>> {
+        let users = users::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        users::Column::Id,
+                        time_entries.iter().map(|time_entry| time_entry.user_id),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        Ok(users)
+    }
+
+    /// finds all time entries
+    ///
+    /// # Errors
+    ///
+    /// When could not find time entries or DB query error
+    pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        let time_entries = Entity::find().all(db).await?;
+        Ok(time_entries)
+    }
+
+    /// creates a new time entry for `user`, or for the user given in the
+    /// entry
+    ///
+    /// # Errors
+    ///
+    /// When no hourly rate is given nor set for the user or order, could not
+    /// create time entry or DB query error
+    pub async fn create(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        time_entry: CreateNewTimeEntry,
+    ) -> ModelResult<Self> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, time_entry.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let user_id = match time_entry.user_pid {
+            Some(pid) => {
+                users::Entity::find()
+                    .filter(
+                        model::query::condition()
+                            .eq(users::Column::Pid, pid)
+                            .build(),
+                    )
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| ModelError::EntityNotFound)?
+                    .id
+            }
+            None => user.id,
+        };
+        let hourly_rate = match time_entry.hourly_rate {
+            Some(hourly_rate) => hourly_rate,
+            None => {
+                let rates = hourly_rates::Entity::find().all(db).await?;
+                resolve_hourly_rate(&rates, user_id, order.id).ok_or_else(|| {
+                    ModelError::Any("no hourly rate set for the user or the order".into())
+                })?
+            }
+        };
+        let txn = db.begin().await?;
+        let time_entry = time_entries::ActiveModel {
+            order_id: ActiveValue::Set(order.id),
+            user_id: ActiveValue::Set(user_id),
+            entry_date: ActiveValue::Set(time_entry.entry_date),
+            minutes: ActiveValue::Set(time_entry.minutes),
+            description: ActiveValue::Set(time_entry.description),
+            hourly_rate: ActiveValue::Set(hourly_rate),
+            billed: ActiveValue::Set(false),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok(time_entry)
+    }
+
+    /// updates a time entry that was not billed yet
+    ///
+    /// # Errors
+    ///
+    /// When the entry was already billed, could not update time entry or DB
+    /// query error
+    pub async fn update(
+        db: &DatabaseConnection,
+        pid: Uuid,
+        time_entry: EditTimeEntry,
+    ) -> ModelResult<Self> {
+        let existing_time_entry = Self::find_by_pid(db, pid).await?;
+        if existing_time_entry.billed {
+            return Err(ModelError::Any("billed time cannot be changed".into()));
+        }
+        let mut edited_time_entry = existing_time_entry.into_active_model();
+        edited_time_entry.entry_date = ActiveValue::Set(time_entry.entry_date);
+        edited_time_entry.minutes = ActiveValue::Set(time_entry.minutes);
+        edited_time_entry.description = ActiveValue::Set(time_entry.description);
+        edited_time_entry.hourly_rate = ActiveValue::Set(time_entry.hourly_rate);
+        let txn = db.begin().await?;
+        let time_entry = edited_time_entry.update(&txn).await?;
+        txn.commit().await?;
+        Ok(time_entry)
+    }
+
+    /// deletes a time entry that was not billed yet
+    ///
+    /// # Errors
+    ///
+    /// When the entry was already billed, could not delete time entry or DB
+    /// query error
+    pub async fn delete(db: &DatabaseConnection, pid: Uuid) -> ModelResult<Self> {
+        let existing_time_entry = Self::find_by_pid(db, pid).await?;
+        if existing_time_entry.billed {
+            return Err(ModelError::Any("billed time cannot be deleted".into()));
+        }
+        let txn = db.begin().await?;
+        existing_time_entry.clone().delete(&txn).await?;
+        txn.commit().await?;
+        Ok(existing_time_entry)
+    }
+
+    /// rolls the unbilled time of an order into a new instalment, due on the
+    /// business day its due date falls on or after, returning the instalment
+    /// created
+    ///
+    /// # Errors
+    ///
+    /// When there is no unbilled time to invoice, could not create the
+    /// instalment or DB query error
+    pub async fn invoice(
+        db: &DatabaseConnection,
+        invoice: InvoiceTimeEntries,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<payments::Model> {
+        let order = orders::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(orders::Column::Pid, invoice.order_pid)
+                    .build(),
+            )
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let to_bill = Self::find_by_order_id(db, order.id)
+            .await?
+            .into_iter()
+            .filter(|time_entry| !time_entry.billed)
+            .filter(|time_entry| {
+                invoice
+                    .until
+                    .is_none_or(|until| time_entry.entry_date <= until)
+            })
+            .collect::<Vec<_>>();
+        if to_bill.is_empty() {
+            return Err(ModelError::Any("no unbilled time to invoice".into()));
+        }
+        let total: f32 = to_bill.iter().map(Self::amount).sum();
+
+        let txn = db.begin().await?;
+        let payment = payments::ActiveModel {
+            value: ActiveValue::Set(total),
+            due_date: ActiveValue::Set(calendar.next_business_day(invoice.due_date)),
+            order_id: ActiveValue::Set(order.id),
+            open: ActiveValue::Set(true),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        for time_entry in to_bill {
+            let mut billed_time_entry = time_entry.into_active_model();
+            billed_time_entry.billed = ActiveValue::Set(true);
+            billed_time_entry.payment_id = ActiveValue::Set(Some(payment.id));
+            billed_time_entry.update(&txn).await?;
+        }
+        journal_entries::Model::sync_order(&txn, order.id).await?;
+        txn.commit().await?;
+        Ok(payment)
+    }
+
+    /// marks the time invoiced into a payment as unbilled again, so it can
+    /// be invoiced once more when the payment is deleted
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn release<C>(db: &C, payment_id: i32) -> ModelResult<()>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::update_many()
+            .col_expr(time_entries::Column::Billed, Expr::value(false))
+            .col_expr(time_entries::Column::PaymentId, Expr::value(None::<i32>))
+            .filter(
+                model::query::condition()
+                    .eq(time_entries::Column::PaymentId, payment_id)
+                    .build(),
+            )
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Payment gateways that charge instalments on the firm's behalf and report
+//! back through signed webhooks.
+//!
+//! Every gateway implements [`PaymentProvider`], so the payments model only
+//! deals with charges and webhook events and a new gateway does not touch it.
+
+pub mod mock;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::models::_entities::payments;
+
+/// A charge created by a provider for an instalment
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Charge {
+    /// the id the provider gave the charge, which its webhooks refer to
+    pub external_id: String,
+    /// where the client pays the charge, when the provider hosts a page
+    pub checkout_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargeStatus {
+    Pending,
+    Paid,
+    Failed,
+    Cancelled,
+}
+
+impl ChargeStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Paid => "paid",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A change in the status of a charge, as reported by a webhook
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WebhookEvent {
+    /// the id of the event, the same when the provider delivers it again
+    pub event_id: String,
+    pub charge_id: String,
+    pub status: ChargeStatus,
+    pub paid_on: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProviderError {
+    InvalidSignature,
+    InvalidPayload(String),
+    Unavailable(String),
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSignature => write!(f, "invalid webhook signature"),
+            Self::InvalidPayload(message) => write!(f, "invalid webhook payload: {message}"),
+            Self::Unavailable(message) => write!(f, "payment provider unavailable: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+#[async_trait::async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// the name the provider is configured and its webhooks are routed by
+    fn name(&self) -> &'static str;
+
+    /// the header its webhooks carry the signature in
+    fn signature_header(&self) -> &'static str;
+
+    /// creates a charge for an open instalment
+    ///
+    /// # Errors
+    ///
+    /// When the provider refuses the charge or cannot be reached
+    async fn create_charge(&self, payment: &payments::Model) -> Result<Charge, ProviderError>;
+
+    /// checks the signature of a webhook and reads the event it carries
+    ///
+    /// # Errors
+    ///
+    /// When the signature does not match the body or the body is not an event
+    fn verify_webhook(&self, signature: &str, body: &[u8]) -> Result<WebhookEvent, ProviderError>;
+}
+
+/// The provider configured in the `settings.payment_provider` section, if
+/// any
+#[must_use]
+pub fn from_settings(settings: Option<&serde_json::Value>) -> Option<Box<dyn PaymentProvider>> {
+    let provider = settings?.get("payment_provider")?;
+    let secret = provider.get("webhook_secret")?.as_str()?.to_string();
+    match provider.get("name")?.as_str()? {
+        "mock" => Some(Box::new(mock::MockProvider::new(secret))),
+        _ => None,
+    }
+}
+
+/// Signs a webhook body with HMAC-SHA256, written in lowercase hex
+#[must_use]
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Checks a hex HMAC-SHA256 signature in constant time
+#[must_use]
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature.trim()) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
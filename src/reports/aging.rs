@@ -0,0 +1,319 @@
+//! How much of what clients owe is late, and by how long.
+//!
+//! Each open instalment is aged from its effective due date, which is the
+//! last date it was postponed to, or the next business day when that falls
+//! on a weekend or a holiday, and counts for what is still owed on it
+//! once the credit notes allocated to it are taken into account.
+
+use crate::business_days::BusinessCalendar;
+use crate::documents::csv_row;
+use crate::models::_entities::{
+    clients, credit_notes, orders, partners, payments, postponed_payments, processes, sellers,
+};
+use crate::models::clients::format_document;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AgingBucket {
+    #[serde(rename = "current")]
+    Current,
+    #[serde(rename = "1-30")]
+    Days1To30,
+    #[serde(rename = "31-60")]
+    Days31To60,
+    #[serde(rename = "61-90")]
+    Days61To90,
+    #[serde(rename = "90+")]
+    Over90,
+}
+
+impl AgingBucket {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Current => "current",
+            Self::Days1To30 => "1-30",
+            Self::Days31To60 => "31-60",
+            Self::Days61To90 => "61-90",
+            Self::Over90 => "90+",
+        }
+    }
+
+    /// the bucket of an instalment the given number of days late
+    #[must_use]
+    pub fn from_days_late(days_late: i64) -> Self {
+        match days_late {
+            ..=0 => Self::Current,
+            1..=30 => Self::Days1To30,
+            31..=60 => Self::Days31To60,
+            61..=90 => Self::Days61To90,
+            _ => Self::Over90,
+        }
+    }
+}
+
+/// What the instalments of the report are grouped by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgingGroupBy {
+    #[default]
+    Client,
+    Seller,
+    Partner,
+    Process,
+}
+
+/// The date an instalment is due after its postponements
+#[must_use]
+pub fn effective_due_date(
+    payment: &payments::Model,
+    postponed_payments: &[postponed_payments::Model],
+) -> chrono::NaiveDate {
+    postponed_payments
+        .iter()
+        .filter(|postponed| postponed.payment_id == payment.id)
+        .map(|postponed| postponed.postponed_date)
+        .max()
+        .unwrap_or(payment.due_date)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct AgingTotals {
+    pub current: f32,
+    pub days_1_30: f32,
+    pub days_31_60: f32,
+    pub days_61_90: f32,
+    pub over_90: f32,
+    pub total: f32,
+}
+
+impl AgingTotals {
+    fn add(&mut self, bucket: AgingBucket, amount: f32) {
+        let total = match bucket {
+            AgingBucket::Current => &mut self.current,
+            AgingBucket::Days1To30 => &mut self.days_1_30,
+            AgingBucket::Days31To60 => &mut self.days_31_60,
+            AgingBucket::Days61To90 => &mut self.days_61_90,
+            AgingBucket::Over90 => &mut self.over_90,
+        };
+        *total += amount;
+        self.total += amount;
+    }
+}
+
+/// An open instalment with who and what it belongs to
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AgingItem {
+    pub payment_pid: Uuid,
+    pub order_pid: Uuid,
+    pub due_date: chrono::NaiveDate,
+    /// the business day the instalment is payable on after its postponements
+    pub effective_due_date: chrono::NaiveDate,
+    pub days_late: i64,
+    pub bucket: AgingBucket,
+    pub amount: f32,
+    pub client_pid: Uuid,
+    pub client: String,
+    pub client_document: Option<String>,
+    pub seller_pid: Uuid,
+    pub seller: String,
+    pub partner_pid: Option<Uuid>,
+    pub partner: Option<String>,
+    pub process_pid: Uuid,
+    pub process: String,
+}
+
+impl AgingItem {
+    /// the key and the name of the group the instalment falls in
+    #[must_use]
+    pub fn group(&self, group_by: AgingGroupBy) -> (Option<Uuid>, String) {
+        match group_by {
+            AgingGroupBy::Client => (Some(self.client_pid), self.client.clone()),
+            AgingGroupBy::Seller => (Some(self.seller_pid), self.seller.clone()),
+            AgingGroupBy::Partner => (
+                self.partner_pid,
+                self.partner
+                    .clone()
+                    .unwrap_or_else(|| "No partner".to_string()),
+            ),
+            AgingGroupBy::Process => (Some(self.process_pid), self.process.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AgingGroup {
+    pub pid: Option<Uuid>,
+    pub name: String,
+    pub totals: AgingTotals,
+    pub items: Vec<AgingItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AgingReport {
+    pub as_of: chrono::NaiveDate,
+    pub group_by: AgingGroupBy,
+    pub groups: Vec<AgingGroup>,
+    pub totals: AgingTotals,
+}
+
+impl AgingReport {
+    /// groups the instalments, the groups owing the most first and the
+    /// instalments latest first
+    #[must_use]
+    pub fn new(as_of: chrono::NaiveDate, group_by: AgingGroupBy, items: Vec<AgingItem>) -> Self {
+        let mut groups: Vec<AgingGroup> = vec![];
+        let mut totals = AgingTotals::default();
+        for item in items {
+            totals.add(item.bucket, item.amount);
+            let (pid, name) = item.group(group_by);
+            let index = match groups.iter().position(|group| group.pid == pid) {
+                Some(index) => index,
+                None => {
+                    groups.push(AgingGroup {
+                        pid,
+                        name,
+                        totals: AgingTotals::default(),
+                        items: vec![],
+                    });
+                    groups.len() - 1
+                }
+            };
+            groups[index].totals.add(item.bucket, item.amount);
+            groups[index].items.push(item);
+        }
+        for group in &mut groups {
+            group
+                .items
+                .sort_by_key(|item| std::cmp::Reverse(item.days_late));
+        }
+        groups.sort_by(|a, b| b.totals.total.total_cmp(&a.totals.total));
+        Self {
+            as_of,
+            group_by,
+            groups,
+            totals,
+        }
+    }
+
+    /// writes the drill-down list of instalments as CSV
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_row(
+            &[
+                "group",
+                "client",
+                "client_document",
+                "seller",
+                "partner",
+                "process",
+                "order",
+                "payment",
+                "due_date",
+                "effective_due_date",
+                "days_late",
+                "bucket",
+                "amount",
+            ]
+            .map(ToString::to_string),
+        );
+        for group in &self.groups {
+            for item in &group.items {
+                csv.push_str(&csv_row(&[
+                    group.name.clone(),
+                    item.client.clone(),
+                    item.client_document
+                        .as_deref()
+                        .map(format_document)
+                        .unwrap_or_default(),
+                    item.seller.clone(),
+                    item.partner.clone().unwrap_or_default(),
+                    item.process.clone(),
+                    item.order_pid.to_string(),
+                    item.payment_pid.to_string(),
+                    item.due_date.to_string(),
+                    item.effective_due_date.to_string(),
+                    item.days_late.to_string(),
+                    item.bucket.as_str().to_string(),
+                    format!("{:.2}", item.amount),
+                ]));
+            }
+        }
+        csv
+    }
+}
+
+/// Ages every open instalment on the given date, the instalments falling due
+/// on a weekend or a holiday being late only after the next business day
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn aging_items(
+    db: &DatabaseConnection,
+    as_of: chrono::NaiveDate,
+    calendar: &BusinessCalendar,
+) -> ModelResult<Vec<AgingItem>> {
+    let payments = payments::Entity::find()
+        .filter(
+            model::query::condition()
+                .eq(payments::Column::Open, true)
+                .build(),
+        )
+        .all(db)
+        .await?;
+    let postponed_payments = postponed_payments::Entity::find().all(db).await?;
+    let credit_notes = credit_notes::Entity::find().all(db).await?;
+    let orders = orders::Entity::find().all(db).await?;
+    let clients = clients::Entity::find().all(db).await?;
+    let sellers = sellers::Entity::find().all(db).await?;
+    let partners = partners::Entity::find().all(db).await?;
+    let processes = processes::Entity::find().all(db).await?;
+
+    let items = payments
+        .iter()
+        .filter_map(|payment| {
+            let order = orders.iter().find(|order| order.id == payment.order_id)?;
+            let client = clients.iter().find(|client| client.id == order.client_id)?;
+            let seller = sellers.iter().find(|seller| seller.id == order.seller_id)?;
+            let process = processes
+                .iter()
+                .find(|process| process.id == order.process_id)?;
+            let partner = client
+                .partner_id
+                .and_then(|id| partners.iter().find(|partner| partner.id == id));
+            let credited: f32 = credit_notes
+                .iter()
+                .filter(|credit_note| credit_note.payment_id == Some(payment.id))
+                .map(|credit_note| credit_note.value)
+                .sum();
+            let amount = (payment.value - credited).max(0.0);
+            let effective_due_date =
+                calendar.next_business_day(effective_due_date(payment, &postponed_payments));
+            let days_late = (as_of - effective_due_date).num_days();
+            Some(AgingItem {
+                payment_pid: payment.pid,
+                order_pid: order.pid,
+                due_date: payment.due_date,
+                effective_due_date,
+                days_late,
+                bucket: AgingBucket::from_days_late(days_late),
+                amount,
+                client_pid: client.pid,
+                client: client.name.clone(),
+                client_document: client.document.clone(),
+                seller_pid: seller.pid,
+                seller: seller.name.clone(),
+                partner_pid: partner.map(|partner| partner.pid),
+                partner: partner.map(|partner| partner.name.clone()),
+                process_pid: process.pid,
+                process: process.case_type.clone(),
+            })
+        })
+        .filter(|item| item.amount > 0.0)
+        .collect();
+    Ok(items)
+}
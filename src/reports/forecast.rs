@@ -0,0 +1,270 @@
+//! What is expected to come in over the next weeks or months.
+//!
+//! The open instalments are projected by their effective due date, moved to
+//! the next business day like the aging report does, and the
+//! running billing plans add the retainer instalments they have not
+//! generated yet. The scheduled amount of each is weighted by the share of
+//! instalments its client, or its seller, settled on time so far, and the
+//! net leaves out the commission and the partner fee of its order.
+
+use crate::business_days::BusinessCalendar;
+use crate::models::_entities::{
+    billing_plan_prices, billing_plans, orders, payments, postponed_payments,
+};
+use crate::reports::aging::effective_due_date;
+use chrono::Datelike;
+use loco_rs::model::ModelResult;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// the share of instalments expected to be paid when there is no history to
+/// go by
+const DEFAULT_ON_TIME_RATE: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastPeriod {
+    Week,
+    #[default]
+    Month,
+}
+
+impl ForecastPeriod {
+    /// the first day of the period a date falls in, weeks starting on Monday
+    #[must_use]
+    pub fn start_of(&self, date: chrono::NaiveDate) -> chrono::NaiveDate {
+        match self {
+            Self::Week => {
+                date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// the last day of the period starting on `starts_on`
+    #[must_use]
+    pub fn end_of(&self, starts_on: chrono::NaiveDate) -> chrono::NaiveDate {
+        match self {
+            Self::Week => starts_on + chrono::Duration::days(6),
+            Self::Month => starts_on
+                .checked_add_months(chrono::Months::new(1))
+                .and_then(|next| next.pred_opt())
+                .unwrap_or(starts_on),
+        }
+    }
+}
+
+/// Whose payment history the on-time rate of an instalment comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateBy {
+    #[default]
+    Client,
+    Seller,
+}
+
+/// The share of settled instalments paid by their effective due date, for
+/// each key the instalments are given with
+#[must_use]
+pub fn on_time_rates(settled: &[(i32, chrono::NaiveDate, chrono::NaiveDate)]) -> HashMap<i32, f32> {
+    let mut counts: HashMap<i32, (u32, u32)> = HashMap::new();
+    for (key, due_date, payment_date) in settled {
+        let count = counts.entry(*key).or_default();
+        count.1 += 1;
+        if payment_date <= due_date {
+            count.0 += 1;
+        }
+    }
+    #[allow(clippy::cast_precision_loss)]
+    counts
+        .into_iter()
+        .map(|(key, (on_time, total))| (key, on_time as f32 / total as f32))
+        .collect()
+}
+
+/// An amount expected on a date
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ForecastLine {
+    pub date: chrono::NaiveDate,
+    pub scheduled: f32,
+    pub on_time_rate: f32,
+    /// the share of the amount owed to the seller and the partner
+    pub deduction_rate: f32,
+    pub retainer: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ForecastTotals {
+    pub instalments: usize,
+    pub scheduled: f32,
+    pub expected: f32,
+    pub retainers: f32,
+    pub deductions: f32,
+    pub net_scheduled: f32,
+    pub net_expected: f32,
+}
+
+impl ForecastTotals {
+    fn add(&mut self, line: &ForecastLine) {
+        let expected = line.scheduled * line.on_time_rate;
+        self.instalments += 1;
+        self.scheduled += line.scheduled;
+        self.expected += expected;
+        if line.retainer {
+            self.retainers += line.scheduled;
+        }
+        self.deductions += expected * line.deduction_rate;
+        self.net_scheduled += line.scheduled * (1.0 - line.deduction_rate);
+        self.net_expected += expected * (1.0 - line.deduction_rate);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ForecastBucket {
+    pub starts_on: chrono::NaiveDate,
+    pub ends_on: chrono::NaiveDate,
+    pub totals: ForecastTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ForecastReport {
+    pub from: chrono::NaiveDate,
+    pub until: chrono::NaiveDate,
+    pub period: ForecastPeriod,
+    pub rate_by: RateBy,
+    pub periods: Vec<ForecastBucket>,
+    pub totals: ForecastTotals,
+}
+
+impl ForecastReport {
+    /// adds up the lines by period from `from` to `until`. Late instalments
+    /// are still expected, so they count in the first period.
+    #[must_use]
+    pub fn new(
+        from: chrono::NaiveDate,
+        until: chrono::NaiveDate,
+        period: ForecastPeriod,
+        rate_by: RateBy,
+        lines: &[ForecastLine],
+    ) -> Self {
+        let mut periods = vec![];
+        let mut starts_on = period.start_of(from);
+        while starts_on <= until {
+            let ends_on = period.end_of(starts_on);
+            periods.push(ForecastBucket {
+                starts_on,
+                ends_on,
+                totals: ForecastTotals::default(),
+            });
+            starts_on = ends_on + chrono::Duration::days(1);
+        }
+        let mut totals = ForecastTotals::default();
+        for line in lines.iter().filter(|line| line.date <= until) {
+            let date = line.date.max(from);
+            if let Some(bucket) = periods
+                .iter_mut()
+                .find(|bucket| bucket.starts_on <= date && date <= bucket.ends_on)
+            {
+                bucket.totals.add(line);
+                totals.add(line);
+            }
+        }
+        Self {
+            from,
+            until,
+            period,
+            rate_by,
+            periods,
+            totals,
+        }
+    }
+}
+
+/// Finds what is expected to come in up to `until`, the open instalments and
+/// the retainer instalments the billing plans have not generated yet
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn forecast_lines(
+    db: &DatabaseConnection,
+    until: chrono::NaiveDate,
+    rate_by: RateBy,
+    calendar: &BusinessCalendar,
+) -> ModelResult<Vec<ForecastLine>> {
+    let payments = payments::Entity::find().all(db).await?;
+    let postponed_payments = postponed_payments::Entity::find().all(db).await?;
+    let orders = orders::Entity::find().all(db).await?;
+    let billing_plans = billing_plans::Entity::find().all(db).await?;
+    let prices = billing_plan_prices::Entity::find().all(db).await?;
+
+    let rate_key = |order: &orders::Model| match rate_by {
+        RateBy::Client => order.client_id,
+        RateBy::Seller => order.seller_id,
+    };
+    let order_of = |order_id: i32| orders.iter().find(|order| order.id == order_id);
+    let settled = payments
+        .iter()
+        .filter(|payment| !payment.open)
+        .filter_map(|payment| {
+            let order = order_of(payment.order_id)?;
+            let payment_date = payment.payment_date?;
+            Some((
+                rate_key(order),
+                calendar.next_business_day(effective_due_date(payment, &postponed_payments)),
+                payment_date,
+            ))
+        })
+        .collect::<Vec<_>>();
+    let rates = on_time_rates(&settled);
+    let deduction_rate = |order: &orders::Model| {
+        let billed: f32 = payments
+            .iter()
+            .filter(|payment| payment.order_id == order.id)
+            .map(|payment| payment.value)
+            .sum();
+        let deductions = order.payout + order.partner_fee.unwrap_or_default();
+        if billed > 0.0 {
+            (deductions / billed).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+
+    let mut lines = payments
+        .iter()
+        .filter(|payment| payment.open)
+        .filter_map(|payment| {
+            let order = order_of(payment.order_id)?;
+            Some(ForecastLine {
+                date: calendar.next_business_day(effective_due_date(payment, &postponed_payments)),
+                scheduled: payment.value,
+                on_time_rate: rates
+                    .get(&rate_key(order))
+                    .copied()
+                    .unwrap_or(DEFAULT_ON_TIME_RATE),
+                deduction_rate: deduction_rate(order),
+                retainer: payment.billing_plan_id.is_some(),
+            })
+        })
+        .collect::<Vec<_>>();
+    for billing_plan in &billing_plans {
+        let Some(order) = order_of(billing_plan.order_id) else {
+            continue;
+        };
+        for date in billing_plan.due_dates(until) {
+            lines.push(ForecastLine {
+                date: calendar.next_business_day(date),
+                scheduled: billing_plan.value_on(&prices, date),
+                on_time_rate: rates
+                    .get(&rate_key(order))
+                    .copied()
+                    .unwrap_or(DEFAULT_ON_TIME_RATE),
+                deduction_rate: deduction_rate(order),
+                retainer: true,
+            });
+        }
+    }
+    Ok(lines)
+}
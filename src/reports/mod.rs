@@ -0,0 +1,4 @@
+//! Management reports computed from the orders and their instalments.
+
+pub mod aging;
+pub mod forecast;
@@ -0,0 +1,449 @@
+//! Searching clients, partners, sellers, processes and orders at once.
+//!
+//! The query is split into terms that must all be found in the same field,
+//! each one as the beginning of a word, without regard to case or accents.
+//! Postgres matches
+//! them against expression indexes over the tables themselves, while
+//! SQLite matches them against the FTS5 `search_index` its triggers keep up
+//! to date. Every row found becomes a hit for what it leads to, so a contact
+//! leads to its client and an order fee to its order, and the hits are
+//! ranked by the best match of each result.
+
+use std::collections::HashMap;
+
+use crate::models::_entities::{clients, orders, partners, processes, sellers};
+use crate::models::clients::format_document;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, QueryFilter, Statement,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_LIMIT: u64 = 20;
+pub const MAX_LIMIT: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    Client,
+    Partner,
+    Seller,
+    Process,
+    Order,
+}
+
+impl SearchKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Partner => "partner",
+            Self::Seller => "seller",
+            Self::Process => "process",
+            Self::Order => "order",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "client" => Some(Self::Client),
+            "partner" => Some(Self::Partner),
+            "seller" => Some(Self::Seller),
+            "process" => Some(Self::Process),
+            "order" => Some(Self::Order),
+            _ => None,
+        }
+    }
+}
+
+/// The letter without its accent, lowercased
+#[must_use]
+pub fn fold_accent(c: char) -> char {
+    match c.to_lowercase().next().unwrap_or(c) {
+        'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ç' => 'c',
+        'ñ' => 'n',
+        c => c,
+    }
+}
+
+/// The terms of a query, lowercased and without accents. Emails keep their
+/// `@`, dots and dashes so they are found whole, and a query made only of
+/// digits and the punctuation of phones and CNJ numbers becomes a single term
+/// of its digits.
+#[must_use]
+pub fn search_terms(query: &str) -> Vec<String> {
+    let query = query.trim();
+    let phone_like = query
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '+' | '(' | ')' | '-' | '.' | ' '));
+    if phone_like {
+        let digits = query
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+        return if digits.is_empty() {
+            vec![]
+        } else {
+            vec![digits]
+        };
+    }
+    query
+        .chars()
+        .map(|c| match fold_accent(c) {
+            c if c.is_alphanumeric() || matches!(c, '@' | '.' | '_' | '-') => c,
+            _ => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .map(|term| term.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|term| !term.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// A row that matched the query
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub pid: Uuid,
+    /// the field that matched, such as `name`, `phone` or `fee_info`
+    pub field: String,
+    pub rank: f32,
+}
+
+/// Merges the hits on the same result, which keeps its best rank and every
+/// field that matched, and sorts the results best first
+#[must_use]
+pub fn rank_hits(hits: Vec<SearchHit>) -> Vec<(SearchKind, Uuid, Vec<String>, f32)> {
+    let mut ranked: Vec<(SearchKind, Uuid, Vec<String>, f32)> = vec![];
+    let mut positions = HashMap::new();
+    for hit in hits {
+        match positions.get(&(hit.kind, hit.pid)) {
+            Some(&index) => {
+                let (_, _, fields, rank): &mut (_, _, Vec<String>, f32) = &mut ranked[index];
+                if !fields.contains(&hit.field) {
+                    fields.push(hit.field);
+                }
+                *rank = rank.max(hit.rank);
+            }
+            None => {
+                positions.insert((hit.kind, hit.pid), ranked.len());
+                ranked.push((hit.kind, hit.pid, vec![hit.field], hit.rank));
+            }
+        }
+    }
+    ranked.sort_by(|a, b| b.3.total_cmp(&a.3).then(a.0.as_str().cmp(b.0.as_str())));
+    ranked
+}
+
+/// A result of a search, with what to show for it
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub pid: Uuid,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// the fields that matched the query
+    pub matched: Vec<String>,
+    pub rank: f32,
+}
+
+/// What is searched on Postgres: the kind and pid of the result, the field
+/// matched, the text searched and the tables it is found in
+const POSTGRES_SOURCES: [(&str, &str, &str, &str, &str); 9] = [
+    ("client", "clients.pid", "'name'", "clients.name", "clients"),
+    (
+        "client",
+        "clients.pid",
+        "client_contacts.kind",
+        "client_contacts.value",
+        "client_contacts JOIN clients ON clients.id = client_contacts.client_id",
+    ),
+    (
+        "partner",
+        "partners.pid",
+        "'name'",
+        "partners.name",
+        "partners",
+    ),
+    ("seller", "sellers.pid", "'name'", "sellers.name", "sellers"),
+    (
+        "process",
+        "processes.pid",
+        "'case_type'",
+        "processes.case_type",
+        "processes",
+    ),
+    (
+        "order",
+        "orders.pid",
+        "'fee_info'",
+        "order_fees.info",
+        "order_fees JOIN orders ON orders.id = order_fees.order_id",
+    ),
+    (
+        "order",
+        "orders.pid",
+        "'cnj_number'",
+        "lawsuits.cnj_number",
+        "lawsuits JOIN orders ON orders.id = lawsuits.order_id",
+    ),
+    (
+        "order",
+        "orders.pid",
+        "'opposing_party'",
+        "lawsuits.opposing_party",
+        "lawsuits JOIN orders ON orders.id = lawsuits.order_id",
+    ),
+    (
+        "order",
+        "orders.pid",
+        "'court'",
+        "lawsuits.court",
+        "lawsuits JOIN orders ON orders.id = lawsuits.order_id",
+    ),
+];
+
+fn postgres_statement(terms: &[String], limit: u64) -> Statement {
+    let branches = POSTGRES_SOURCES
+        .iter()
+        .map(|(kind, pid, field, text, from)| {
+            let document =
+                format!("to_tsvector('simple', immutable_unaccent(coalesce({text}, '')))");
+            format!(
+                "SELECT '{kind}' AS kind, {pid} AS pid, {field} AS field, \
+                 ts_rank({document}, search.query) AS rank \
+                 FROM {from}, search WHERE {document} @@ search.query"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    let query = terms
+        .iter()
+        .map(|term| format!("{term}:*"))
+        .collect::<Vec<_>>()
+        .join(" & ");
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        format!(
+            "WITH search AS (SELECT to_tsquery('simple', immutable_unaccent($1)) AS query) \
+             SELECT kind, pid, field, rank FROM ({branches}) AS hits \
+             ORDER BY rank DESC LIMIT $2"
+        ),
+        [
+            query.into(),
+            i64::try_from(limit).unwrap_or(i64::MAX).into(),
+        ],
+    )
+}
+
+fn sqlite_statement(terms: &[String], limit: u64) -> Statement {
+    let query = terms
+        .iter()
+        .map(|term| format!("\"{term}\"*"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT kind, pid, field, -bm25(search_index) AS rank FROM search_index \
+         WHERE search_index MATCH ? ORDER BY rank DESC LIMIT ?",
+        [
+            query.into(),
+            i64::try_from(limit).unwrap_or(i64::MAX).into(),
+        ],
+    )
+}
+
+/// Finds the rows matching all the terms of the query
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn search_hits(
+    db: &DatabaseConnection,
+    terms: &[String],
+    limit: u64,
+) -> ModelResult<Vec<SearchHit>> {
+    if terms.is_empty() {
+        return Ok(vec![]);
+    }
+    let statement = match db.get_database_backend() {
+        DbBackend::Postgres => postgres_statement(terms, limit),
+        DbBackend::Sqlite => sqlite_statement(terms, limit),
+        DbBackend::MySql => return Ok(vec![]),
+    };
+    let rows = db.query_all(statement).await?;
+    let mut hits = vec![];
+    for row in rows {
+        let kind: String = row.try_get("", "kind")?;
+        let rank = match db.get_database_backend() {
+            DbBackend::Postgres => row.try_get::<f32>("", "rank")?,
+            #[allow(clippy::cast_possible_truncation)]
+            _ => row.try_get::<f64>("", "rank")? as f32,
+        };
+        if let Some(kind) = SearchKind::parse(&kind) {
+            hits.push(SearchHit {
+                kind,
+                pid: row.try_get("", "pid")?,
+                field: row.try_get("", "field")?,
+                rank,
+            });
+        }
+    }
+    Ok(hits)
+}
+
+/// Searches everything for the query, returning up to `limit` results best
+/// first
+///
+/// # Errors
+///
+/// When DB query error
+pub async fn search(
+    db: &DatabaseConnection,
+    query: &str,
+    limit: u64,
+) -> ModelResult<Vec<SearchResult>> {
+    let terms = search_terms(query);
+    // a result may be hit by several rows, so more rows than results are read
+    let hits = search_hits(db, &terms, limit.clamp(1, MAX_LIMIT) * 5).await?;
+    let ranked = rank_hits(hits)
+        .into_iter()
+        .take(usize::try_from(limit.clamp(1, MAX_LIMIT)).unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+    let pids = |kind: SearchKind| {
+        ranked
+            .iter()
+            .filter(|(hit_kind, ..)| *hit_kind == kind)
+            .map(|(_, pid, ..)| *pid)
+            .collect::<Vec<_>>()
+    };
+
+    let found_orders = orders::Entity::find()
+        .filter(
+            model::query::condition()
+                .is_in(orders::Column::Pid, pids(SearchKind::Order))
+                .build(),
+        )
+        .all(db)
+        .await?;
+    let found_clients = clients::Entity::find()
+        .filter(
+            model::query::condition()
+                .is_in(clients::Column::Pid, pids(SearchKind::Client))
+                .build(),
+        )
+        .all(db)
+        .await?;
+    let order_clients = clients::Entity::find()
+        .filter(
+            model::query::condition()
+                .is_in(
+                    clients::Column::Id,
+                    found_orders.iter().map(|order| order.client_id),
+                )
+                .build(),
+        )
+        .all(db)
+        .await?;
+    let found_processes = processes::Entity::find()
+        .filter(
+            model::query::condition()
+                .is_in(processes::Column::Pid, pids(SearchKind::Process))
+                .build(),
+        )
+        .all(db)
+        .await?;
+    let order_processes = processes::Entity::find()
+        .filter(
+            model::query::condition()
+                .is_in(
+                    processes::Column::Id,
+                    found_orders.iter().map(|order| order.process_id),
+                )
+                .build(),
+        )
+        .all(db)
+        .await?;
+    let found_partners = partners::Entity::find()
+        .filter(
+            model::query::condition()
+                .is_in(partners::Column::Pid, pids(SearchKind::Partner))
+                .build(),
+        )
+        .all(db)
+        .await?;
+    let found_sellers = sellers::Entity::find()
+        .filter(
+            model::query::condition()
+                .is_in(sellers::Column::Pid, pids(SearchKind::Seller))
+                .build(),
+        )
+        .all(db)
+        .await?;
+
+    let results = ranked
+        .into_iter()
+        .filter_map(|(kind, pid, matched, rank)| {
+            let (title, subtitle) = match kind {
+                SearchKind::Client => {
+                    let client = found_clients.iter().find(|client| client.pid == pid)?;
+                    (
+                        client.name.clone(),
+                        client.document.as_deref().map(format_document),
+                    )
+                }
+                SearchKind::Partner => (
+                    found_partners
+                        .iter()
+                        .find(|partner| partner.pid == pid)?
+                        .name
+                        .clone(),
+                    None,
+                ),
+                SearchKind::Seller => (
+                    found_sellers
+                        .iter()
+                        .find(|seller| seller.pid == pid)?
+                        .name
+                        .clone(),
+                    None,
+                ),
+                SearchKind::Process => (
+                    found_processes
+                        .iter()
+                        .find(|process| process.pid == pid)?
+                        .case_type
+                        .clone(),
+                    None,
+                ),
+                SearchKind::Order => {
+                    let order = found_orders.iter().find(|order| order.pid == pid)?;
+                    let process = order_processes
+                        .iter()
+                        .find(|process| process.id == order.process_id)?;
+                    let client = order_clients
+                        .iter()
+                        .find(|client| client.id == order.client_id)?;
+                    (process.case_type.clone(), Some(client.name.clone()))
+                }
+            };
+            Some(SearchResult {
+                kind,
+                pid,
+                title,
+                subtitle,
+                matched,
+                rank,
+            })
+        })
+        .collect();
+    Ok(results)
+}
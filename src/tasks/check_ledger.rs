@@ -0,0 +1,62 @@
+//! This task checks the invariant of the double-entry ledger: every journal
+//! entry, and so the books as a whole, has debits equal to its credits.
+//!
+//! # Example
+//!
+//! Check the ledger:
+//! ```sh
+//! cargo run task check_ledger
+//! ```
+//!
+//! To first post what is missing for every order, like the orders created
+//! before the ledger existed, use the `sync` argument:
+//! ```sh
+//! cargo run task check_ledger sync:true
+//! ```
+
+use loco_rs::prelude::*;
+
+use crate::models::_entities::journal_entries;
+use crate::models::journal_entries::cents;
+
+pub struct CheckLedger;
+#[async_trait]
+impl Task for CheckLedger {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "check_ledger".to_string(),
+            detail: "Task for checking that the debits of the ledger equal its credits".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        if vars.cli_arg("sync").is_ok_and(|sync| sync == "true") {
+            let posted = journal_entries::Model::sync_all(&app_context.db).await?;
+            tracing::info!(posted = posted.len(), "synced the ledger with the orders");
+        }
+
+        let unbalanced = journal_entries::Model::find_unbalanced(&app_context.db).await?;
+        for journal_entry in &unbalanced {
+            tracing::error!(
+                pid = journal_entry.pid.to_string(),
+                source = journal_entry.source,
+                "journal entry does not balance",
+            );
+        }
+        let balances = journal_entries::Model::trial_balance(&app_context.db).await?;
+        let total_debit: i64 = balances.iter().map(|balance| cents(balance.debit)).sum();
+        let total_credit: i64 = balances.iter().map(|balance| cents(balance.credit)).sum();
+        if !unbalanced.is_empty() || total_debit != total_credit {
+            return Err(Error::string(&format!(
+                "the ledger does not balance: {} unbalanced entries, debits {total_debit} and credits {total_credit} in cents",
+                unbalanced.len()
+            )));
+        }
+        tracing::info!(
+            accounts = balances.len(),
+            total_cents = total_debit,
+            "the ledger balances",
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+//! This task exports the journal of a month as CSV for the firm's
+//! accountant, mapped to the chart of accounts in `settings.accounting`, and
+//! locks the month against further changes.
+//!
+//! # Example
+//!
+//! Export March 2025 to `journal-2025-03.csv`:
+//! ```sh
+//! cargo run task export_journal month:2025-03
+//! ```
+//!
+//! To write the file somewhere else, use the `path` argument:
+//! ```sh
+//! cargo run task export_journal month:2025-03 path:/tmp/march.csv
+//! ```
+
+use loco_rs::prelude::*;
+
+use crate::documents::journal::ChartOfAccounts;
+use crate::models::_entities::accounting_periods;
+
+pub struct ExportJournal;
+#[async_trait]
+impl Task for ExportJournal {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "export_journal".to_string(),
+            detail: "Task for exporting the journal of a month for the accountant".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let month = vars
+            .cli_arg("month")
+            .map_err(|_| Error::string("month is needed, written as YYYY-MM"))?;
+        let path = vars
+            .cli_arg("path")
+            .map_or_else(|_| format!("journal-{month}.csv"), ToString::to_string);
+
+        let chart = ChartOfAccounts::from_settings(app_context.config.settings.as_ref());
+        let csv = accounting_periods::Model::export(&app_context.db, month, &chart).await?;
+        std::fs::write(&path, csv)?;
+        tracing::info!(month = month, path = path, "exported journal");
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! This task lists the clients that are likely duplicates of each other,
+//! scored by their names, emails, phones and documents, so that they can be
+//! merged.
+//!
+//! # Example
+//!
+//! List the likely duplicates:
+//! ```sh
+//! cargo run task find_duplicate_clients
+//! ```
+//!
+//! To list only the pairs scoring at least 80 out of 100:
+//! ```sh
+//! cargo run task find_duplicate_clients min_score:80
+//! ```
+
+use loco_rs::prelude::*;
+
+use crate::models::_entities::client_merges;
+use crate::models::client_merges::DEFAULT_MIN_SCORE;
+
+pub struct FindDuplicateClients;
+#[async_trait]
+impl Task for FindDuplicateClients {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "find_duplicate_clients".to_string(),
+            detail: "Task for listing the clients that are likely duplicates".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let min_score = match vars.cli_arg("min_score") {
+            Ok(min_score) => min_score
+                .parse()
+                .map_err(|_| Error::string("min_score must be a number from 0 to 100"))?,
+            Err(_) => DEFAULT_MIN_SCORE,
+        };
+        let candidates = client_merges::Model::find_duplicates(&app_context.db, min_score).await?;
+        for candidate in &candidates {
+            tracing::info!(
+                primary_pid = candidate.primary_pid.to_string(),
+                secondary_pid = candidate.secondary_pid.to_string(),
+                score = candidate.score,
+                reasons = candidate.reasons.join(", "),
+                "likely duplicate clients",
+            );
+        }
+        tracing::info!(
+            candidates = candidates.len(),
+            "looked for duplicate clients"
+        );
+        Ok(())
+    }
+}
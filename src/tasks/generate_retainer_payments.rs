@@ -0,0 +1,52 @@
+//! This task generates the upcoming instalments of every running billing
+//! plan, so retainer clients are billed ahead of their due dates.
+//!
+//! # Example
+//!
+//! Generate the instalments due in the next 30 days:
+//! ```sh
+//! cargo run task generate_retainer_payments
+//! ```
+//!
+//! To look further ahead, use the `days_ahead` argument:
+//! ```sh
+//! cargo run task generate_retainer_payments days_ahead:60
+//! ```
+
+use loco_rs::prelude::*;
+
+use crate::business_days::BusinessCalendar;
+use crate::models::_entities::billing_plans;
+
+const DEFAULT_DAYS_AHEAD: i64 = 30;
+
+pub struct GenerateRetainerPayments;
+#[async_trait]
+impl Task for GenerateRetainerPayments {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "generate_retainer_payments".to_string(),
+            detail: "Task for generating the upcoming instalments of billing plans".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+        let days_ahead = match vars.cli_arg("days_ahead") {
+            Ok(days_ahead) => days_ahead
+                .parse::<i64>()
+                .map_err(|_| Error::string("days_ahead must be a number of days"))?,
+            Err(_) => DEFAULT_DAYS_AHEAD,
+        };
+        let until = chrono::Utc::now().date_naive() + chrono::Duration::days(days_ahead);
+
+        let calendar = BusinessCalendar::from_settings(app_context.config.settings.as_ref());
+        let payments =
+            billing_plans::Model::generate_payments(&app_context.db, until, &calendar).await?;
+        tracing::info!(
+            generated = payments.len(),
+            until = until.to_string(),
+            "generated retainer payments",
+        );
+        Ok(())
+    }
+}
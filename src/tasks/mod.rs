@@ -1 +1,6 @@
+pub mod check_ledger;
+pub mod export_journal;
+pub mod find_duplicate_clients;
+pub mod generate_retainer_payments;
+pub mod refresh_contracts;
 pub mod seed;
@@ -0,0 +1,39 @@
+//! This task writes a new contract version for every open order whose
+//! contract no longer matches the order or the template of its process. The
+//! endpoints that change an order version its contract right away; this
+//! catches up the ones changed with their client, partner or template.
+//!
+//! # Example
+//!
+//! ```sh
+//! cargo run task refresh_contracts
+//! ```
+
+use loco_rs::prelude::*;
+
+use crate::documents::Letterhead;
+use crate::models::_entities::contracts;
+
+pub struct RefreshContracts;
+#[async_trait]
+impl Task for RefreshContracts {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "refresh_contracts".to_string(),
+            detail: "Task for writing new contract versions of the orders that changed".to_string(),
+        }
+    }
+
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let letterhead = Letterhead::from_settings(app_context.config.settings.as_ref());
+        let contracts = contracts::Model::refresh_all(
+            &app_context.db,
+            &app_context.storage,
+            &letterhead,
+            chrono::Utc::now().date_naive(),
+        )
+        .await?;
+        tracing::info!(refreshed = contracts.len(), "refreshed order contracts");
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::models::_entities::accounting_periods;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AccountingPeriodView {
+    pub pid: Uuid,
+    pub starts_on: chrono::NaiveDate,
+    pub ends_on: chrono::NaiveDate,
+    pub locked_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl AccountingPeriodView {
+    #[must_use]
+    pub fn from_model(model: Vec<accounting_periods::Model>) -> Vec<Self> {
+        model.into_iter().map(Self::from).collect()
+    }
+
+    #[must_use]
+    pub fn from(model: accounting_periods::Model) -> Self {
+        Self {
+            pid: model.pid,
+            starts_on: model.starts_on,
+            ends_on: model.ends_on,
+            locked_at: model.created_at,
+        }
+    }
+}
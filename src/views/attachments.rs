@@ -0,0 +1,65 @@
+use crate::models::_entities::attachments;
+use crate::models::attachments::AttachmentLinks;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AttachmentView {
+    pub pid: Uuid,
+    pub category: String,
+    pub file_name: String,
+    pub content_type: String,
+    /// in bytes
+    pub size: i64,
+    /// SHA-256 of the content, in hex
+    pub checksum: String,
+    pub uploader_pid: Option<Uuid>,
+    pub uploader_name: Option<String>,
+    pub client_pid: Option<Uuid>,
+    pub order_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub expense_pid: Option<Uuid>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl AttachmentView {
+    #[must_use]
+    pub fn from_model(model: Vec<attachments::Model>, links: &AttachmentLinks) -> Vec<Self> {
+        model.into_iter().map(|m| Self::from(m, links)).collect()
+    }
+
+    #[must_use]
+    pub fn from(model: attachments::Model, links: &AttachmentLinks) -> Self {
+        let uploader = model
+            .user_id
+            .and_then(|id| links.users.iter().find(|user| user.id == id));
+        Self {
+            pid: model.pid,
+            category: model.category,
+            file_name: model.file_name,
+            content_type: model.content_type,
+            size: model.size,
+            checksum: model.checksum,
+            uploader_pid: uploader.map(|user| user.pid),
+            uploader_name: uploader.map(|user| user.name.clone()),
+            client_pid: model
+                .client_id
+                .and_then(|id| links.clients.iter().find(|client| client.id == id))
+                .map(|client| client.pid),
+            order_pid: model
+                .order_id
+                .and_then(|id| links.orders.iter().find(|order| order.id == id))
+                .map(|order| order.pid),
+            payment_pid: model
+                .payment_id
+                .and_then(|id| links.payments.iter().find(|payment| payment.id == id))
+                .map(|payment| payment.pid),
+            expense_pid: model
+                .expense_id
+                .and_then(|id| links.expenses.iter().find(|expense| expense.id == id))
+                .map(|expense| expense.pid),
+            created_at: model.created_at,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::models::_entities::{bank_transactions, orders, payments};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BankTransactionView {
+    pub pid: Uuid,
+    pub source: String,
+    pub transaction_date: chrono::NaiveDate,
+    pub value: f32,
+    pub description: String,
+    pub nosso_numero: Option<String>,
+    pub status: String,
+    pub match_rule: Option<String>,
+    pub payment_pid: Option<Uuid>,
+    pub payment_value: Option<f32>,
+    pub payment_due_date: Option<chrono::NaiveDate>,
+    pub order_pid: Option<Uuid>,
+}
+
+impl BankTransactionView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<bank_transactions::Model>,
+        payments: &[payments::Model],
+        orders: &[orders::Model],
+    ) -> Vec<Self> {
+        model
+            .into_iter()
+            .map(|m| Self::from(m, payments, orders))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from(
+        model: bank_transactions::Model,
+        payments: &[payments::Model],
+        orders: &[orders::Model],
+    ) -> Self {
+        let payment = model
+            .payment_id
+            .and_then(|id| payments.iter().find(|payment| payment.id == id));
+        Self {
+            pid: model.pid,
+            source: model.source,
+            transaction_date: model.transaction_date,
+            value: model.value,
+            description: model.description,
+            nosso_numero: model.nosso_numero,
+            status: model.status,
+            match_rule: model.match_rule,
+            payment_pid: payment.map(|payment| payment.pid),
+            payment_value: payment.map(|payment| payment.value),
+            payment_due_date: payment.map(|payment| payment.due_date),
+            order_pid: payment.and_then(|payment| {
+                orders
+                    .iter()
+                    .find(|order| order.id == payment.order_id)
+                    .map(|order| order.pid)
+            }),
+        }
+    }
+}
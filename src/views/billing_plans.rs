@@ -0,0 +1,61 @@
+use crate::models::_entities::{billing_plan_prices, billing_plans};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BillingPlanPriceView {
+    pub value: f32,
+    pub effective_date: chrono::NaiveDate,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BillingPlanView {
+    pub pid: Uuid,
+    pub value: f32,
+    pub current_value: f32,
+    pub day_of_month: i32,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub paused: bool,
+    pub cancelled_at: Option<chrono::NaiveDate>,
+    pub billed_until: Option<chrono::NaiveDate>,
+    pub prices: Vec<BillingPlanPriceView>,
+}
+
+impl BillingPlanView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<billing_plans::Model>,
+        prices: &[billing_plan_prices::Model],
+    ) -> Vec<Self> {
+        model.into_iter().map(|m| Self::from(m, prices)).collect()
+    }
+
+    #[must_use]
+    pub fn from(model: billing_plans::Model, prices: &[billing_plan_prices::Model]) -> Self {
+        let current_value = model.value_on(prices, chrono::Utc::now().date_naive());
+        let mut plan_prices = prices
+            .iter()
+            .filter(|price| price.billing_plan_id == model.id)
+            .collect::<Vec<_>>();
+        plan_prices.sort_by_key(|price| (price.effective_date, price.id));
+        Self {
+            pid: model.pid,
+            value: model.value,
+            current_value,
+            day_of_month: model.day_of_month,
+            start_date: model.start_date,
+            end_date: model.end_date,
+            paused: model.paused,
+            cancelled_at: model.cancelled_at,
+            billed_until: model.billed_until,
+            prices: plan_prices
+                .into_iter()
+                .map(|price| BillingPlanPriceView {
+                    value: price.value,
+                    effective_date: price.effective_date,
+                })
+                .collect(),
+        }
+    }
+}
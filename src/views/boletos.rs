@@ -0,0 +1,40 @@
+use crate::documents::boleto::{Boleto, BoletoSettings};
+use crate::documents::itf::Itf;
+use crate::models::_entities::payments;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BoletoView {
+    pub payment_pid: Uuid,
+    pub beneficiary: String,
+    pub value: f32,
+    pub due_date: chrono::NaiveDate,
+    pub nosso_numero: String,
+    pub due_date_factor: u32,
+    pub barcode: String,
+    pub digitable_line: String,
+    pub barcode_image: String,
+}
+
+impl BoletoView {
+    /// builds the boleto of an instalment with its "nosso número" assigned,
+    /// or `None` when its value or due date do not fit in the barcode
+    #[must_use]
+    pub fn from(payment: &payments::Model, settings: &BoletoSettings) -> Option<Self> {
+        let nosso_numero = payment.nosso_numero.as_deref()?;
+        let boleto = Boleto::new(settings, nosso_numero, payment.value, payment.due_date)?;
+        let barcode_image = Itf::encode(&boleto.barcode)?.to_svg(50);
+        Some(Self {
+            payment_pid: payment.pid,
+            beneficiary: settings.beneficiary.clone(),
+            value: payment.value,
+            due_date: payment.due_date,
+            nosso_numero: boleto.nosso_numero,
+            due_date_factor: boleto.due_date_factor,
+            barcode: boleto.barcode,
+            digitable_line: boleto.digitable_line,
+            barcode_image,
+        })
+    }
+}
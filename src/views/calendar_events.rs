@@ -0,0 +1,256 @@
+use crate::business_days::BusinessCalendar;
+use crate::documents::ics::{IcsEvent, IcsTime};
+use crate::models::_entities::{calendar_events, calendar_feeds, lawsuits, orders, users};
+use crate::models::lawsuits::format_cnj;
+use crate::reports::aging::{self, AgingItem};
+use loco_rs::model::ModelResult;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CalendarEventView {
+    pub pid: Uuid,
+    pub kind: String,
+    pub title: String,
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: Option<DateTimeWithTimeZone>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub completed: bool,
+    pub order_pid: Option<Uuid>,
+    pub lawsuit_pid: Option<Uuid>,
+    pub cnj_number: Option<String>,
+    pub user_pid: Option<Uuid>,
+    pub user_name: Option<String>,
+}
+
+impl CalendarEventView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<calendar_events::Model>,
+        orders: &[orders::Model],
+        lawsuits: &[lawsuits::Model],
+        users: &[users::Model],
+    ) -> Vec<Self> {
+        model
+            .into_iter()
+            .map(|m| Self::from(m, orders, lawsuits, users))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from(
+        model: calendar_events::Model,
+        orders: &[orders::Model],
+        lawsuits: &[lawsuits::Model],
+        users: &[users::Model],
+    ) -> Self {
+        let lawsuit = model
+            .lawsuit_id
+            .and_then(|id| lawsuits.iter().find(|lawsuit| lawsuit.id == id));
+        let user = model
+            .user_id
+            .and_then(|id| users.iter().find(|user| user.id == id));
+        Self {
+            pid: model.pid,
+            kind: model.kind,
+            title: model.title,
+            starts_at: model.starts_at,
+            ends_at: model.ends_at,
+            location: model.location,
+            description: model.description,
+            completed: model.completed,
+            order_pid: model
+                .order_id
+                .and_then(|id| orders.iter().find(|order| order.id == id))
+                .map(|order| order.pid),
+            lawsuit_pid: lawsuit.map(|lawsuit| lawsuit.pid),
+            cnj_number: lawsuit.map(|lawsuit| format_cnj(&lawsuit.cnj_number)),
+            user_pid: user.map(|user| user.pid),
+            user_name: user.map(|user| user.name.clone()),
+        }
+    }
+}
+
+/// An event or an instalment falling due, as listed in the agenda
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct UpcomingItem {
+    /// the kind of the event, or `instalment`
+    pub kind: String,
+    pub pid: Uuid,
+    pub title: String,
+    pub date: chrono::NaiveDate,
+    /// when the event starts, instalments being due for the whole day
+    pub starts_at: Option<DateTimeWithTimeZone>,
+    pub ends_at: Option<DateTimeWithTimeZone>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub completed: bool,
+    pub order_pid: Option<Uuid>,
+    pub cnj_number: Option<String>,
+    pub user_pid: Option<Uuid>,
+    pub user_name: Option<String>,
+    /// what is still owed on an instalment
+    pub value: Option<f32>,
+}
+
+impl UpcomingItem {
+    /// the event, with its times and day at the given offset
+    #[must_use]
+    pub fn from_event(event: CalendarEventView, offset: chrono::FixedOffset) -> Self {
+        let starts_at = event.starts_at.with_timezone(&offset);
+        Self {
+            kind: event.kind,
+            pid: event.pid,
+            title: event.title,
+            date: starts_at.date_naive(),
+            starts_at: Some(starts_at),
+            ends_at: event.ends_at.map(|ends_at| ends_at.with_timezone(&offset)),
+            location: event.location,
+            description: event.description,
+            completed: event.completed,
+            order_pid: event.order_pid,
+            cnj_number: event.cnj_number,
+            user_pid: event.user_pid,
+            user_name: event.user_name,
+            value: None,
+        }
+    }
+}
+
+impl From<&AgingItem> for UpcomingItem {
+    fn from(instalment: &AgingItem) -> Self {
+        Self {
+            kind: "instalment".to_string(),
+            pid: instalment.payment_pid,
+            title: format!(
+                "Instalment of {} - {}",
+                instalment.process, instalment.client
+            ),
+            date: instalment.effective_due_date,
+            starts_at: None,
+            ends_at: None,
+            location: None,
+            description: None,
+            completed: false,
+            order_pid: Some(instalment.order_pid),
+            cnj_number: None,
+            user_pid: None,
+            user_name: None,
+            value: Some(instalment.amount),
+        }
+    }
+}
+
+impl UpcomingItem {
+    /// Merges the events with the open instalments falling due from `from`
+    /// up to `until`, in the order they happen at the given offset, the
+    /// events of a day before its instalments
+    #[must_use]
+    pub fn merge(
+        events: Vec<CalendarEventView>,
+        instalments: &[AgingItem],
+        from: chrono::NaiveDate,
+        until: Option<chrono::NaiveDate>,
+        offset: chrono::FixedOffset,
+    ) -> Vec<Self> {
+        let mut items = events
+            .into_iter()
+            .map(|event| Self::from_event(event, offset))
+            .collect::<Vec<_>>();
+        items.extend(
+            instalments
+                .iter()
+                .filter(|instalment| {
+                    instalment.effective_due_date >= from
+                        && until.is_none_or(|until| instalment.effective_due_date <= until)
+                })
+                .map(Self::from),
+        );
+        items.sort_by_key(|item| (item.date, item.starts_at.is_none(), item.starts_at));
+        items
+    }
+
+    /// Finds the events from `from` up to `until`, days being taken at the
+    /// given offset, of the given user only when one is given, with the
+    /// instalments falling due on the business days of the calendar if asked
+    /// to
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find(
+        db: &DatabaseConnection,
+        from: chrono::NaiveDate,
+        until: Option<chrono::NaiveDate>,
+        user_id: Option<i32>,
+        instalments: bool,
+        offset: chrono::FixedOffset,
+        calendar: &BusinessCalendar,
+    ) -> ModelResult<Vec<Self>> {
+        let events = calendar_events::Model::find_between(db, from, until, user_id, offset).await?;
+        let (orders, lawsuits, users) = calendar_events::Model::find_links(db, &events).await?;
+        let events = CalendarEventView::from_model(events, &orders, &lawsuits, &users);
+        let instalments = if instalments {
+            aging::aging_items(db, from, calendar).await?
+        } else {
+            vec![]
+        };
+        Ok(Self::merge(events, &instalments, from, until, offset))
+    }
+
+    /// the item as an event of an iCalendar feed
+    #[must_use]
+    pub fn to_ics(&self, stamp: chrono::DateTime<chrono::Utc>) -> IcsEvent {
+        let description = [
+            self.description.clone(),
+            self.cnj_number
+                .as_ref()
+                .map(|cnj_number| format!("Lawsuit {cnj_number}")),
+            self.user_name
+                .as_ref()
+                .map(|user_name| format!("Responsible: {user_name}")),
+            self.value.map(|value| format!("Owed: {value:.2}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n");
+        IcsEvent {
+            uid: format!("{}-{}@johandler", self.kind, self.pid),
+            stamp,
+            start: self.starts_at.map_or(IcsTime::Day(self.date), |starts_at| {
+                IcsTime::At(starts_at.to_utc())
+            }),
+            end: self.ends_at.map(|ends_at| IcsTime::At(ends_at.to_utc())),
+            summary: self.title.clone(),
+            location: self.location.clone(),
+            description: (!description.is_empty()).then_some(description),
+            category: Some(self.kind.to_uppercase()),
+            completed: self.completed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CalendarFeedView {
+    pub pid: Uuid,
+    pub firm: bool,
+    pub url: String,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl CalendarFeedView {
+    /// the feed with its full url on the given server
+    #[must_use]
+    pub fn new(feed: &calendar_feeds::Model, server_url: &str) -> Self {
+        Self {
+            pid: feed.pid,
+            firm: feed.firm,
+            url: format!("{}{}", server_url.trim_end_matches('/'), feed.path()),
+            created_at: feed.created_at,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::models::_entities::{client_merges, clients};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClientMergeView {
+    pub pid: Uuid,
+    pub primary_client_pid: Option<Uuid>,
+    pub secondary_client_pid: Uuid,
+    pub secondary_name: String,
+    pub moved_orders: i32,
+    /// the duplicate as it was before the merge
+    pub snapshot: serde_json::Value,
+    pub merged_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl ClientMergeView {
+    #[must_use]
+    pub fn from_model(model: Vec<client_merges::Model>, clients: &[clients::Model]) -> Vec<Self> {
+        model
+            .into_iter()
+            .map(|model| Self::from(model, clients))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from(model: client_merges::Model, clients: &[clients::Model]) -> Self {
+        Self {
+            pid: model.pid,
+            primary_client_pid: clients
+                .iter()
+                .find(|client| client.id == model.primary_client_id)
+                .map(|client| client.pid),
+            secondary_client_pid: model.secondary_client_pid,
+            secondary_name: model.secondary_name,
+            moved_orders: model.moved_orders,
+            snapshot: serde_json::from_str(&model.snapshot).unwrap_or_default(),
+            merged_at: model.created_at,
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::models::_entities::{clients, orders, partners, processes, sellers};
+use crate::models::_entities::{
+    client_addresses, client_contacts, clients, orders, partners, processes, sellers,
+};
+use crate::models::client_addresses::format_cep;
+use crate::views::orders::GetOrderReturn;
 use crate::views::partners::PartnerView;
 use crate::views::sellers::SellerView;
 use loco_rs::model::ModelResult;
@@ -22,14 +26,66 @@ pub struct ClientOrdersView {
     pub partner_fee: Option<f32>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClientContactView {
+    pub pid: Uuid,
+    pub kind: String,
+    pub value: String,
+    pub primary: bool,
+    pub whatsapp: bool,
+    pub notes: Option<String>,
+}
+
+impl From<client_contacts::Model> for ClientContactView {
+    fn from(contact: client_contacts::Model) -> Self {
+        Self {
+            pid: contact.pid,
+            kind: contact.kind,
+            value: contact.value,
+            primary: contact.primary,
+            whatsapp: contact.whatsapp,
+            notes: contact.notes,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClientAddressView {
+    pub pid: Uuid,
+    pub cep: String,
+    pub street: String,
+    pub number: String,
+    pub complement: Option<String>,
+    pub district: Option<String>,
+    pub city: String,
+    pub state: String,
+    pub primary: bool,
+}
+
+impl From<client_addresses::Model> for ClientAddressView {
+    fn from(address: client_addresses::Model) -> Self {
+        Self {
+            pid: address.pid,
+            cep: format_cep(&address.cep),
+            street: address.street,
+            number: address.number,
+            complement: address.complement,
+            district: address.district,
+            city: address.city,
+            state: address.state,
+            primary: address.primary,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ClientViewResponse {
     pub pid: Uuid,
     pub name: String,
     pub contact: String,
-    pub phone: Option<String>,
-    pub phone2: Option<String>,
-    pub email: Option<String>,
+    pub contacts: Vec<ClientContactView>,
+    pub addresses: Vec<ClientAddressView>,
+    pub document: Option<String>,
     pub partner: Option<PartnerView>,
     pub orders: Vec<ClientOrdersView>,
 }
@@ -68,16 +124,234 @@ impl ClientViewResponse {
             }
             None => None,
         };
+        let contacts = client_contacts::Model::find_by_client_id(db, client.id).await?;
+        let addresses = client_addresses::Model::find_by_client_id(db, client.id).await?;
 
         Ok(Self {
             pid: client.pid,
             name: client.name,
             contact: client.contact,
-            phone: Some(client.phone),
-            phone2: client.phone2,
-            email: Some(client.email),
+            contacts: contacts.into_iter().map(ClientContactView::from).collect(),
+            addresses: addresses.into_iter().map(ClientAddressView::from).collect(),
+            document: client.document,
             partner,
             orders: client_orders,
         })
     }
 }
+
+/// What moved the balance of a client account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementMovementKind {
+    Instalment,
+    Refund,
+    Payment,
+    CreditNote,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StatementMovementView {
+    pub date: chrono::NaiveDate,
+    pub order_pid: Uuid,
+    pub kind: StatementMovementKind,
+    pub description: String,
+    pub debit: f32,
+    pub credit: f32,
+    /// what the client owes after the movement
+    pub balance: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StatementInstalmentView {
+    pub pid: Option<Uuid>,
+    pub due_date: chrono::NaiveDate,
+    pub postponed_dates: Vec<chrono::NaiveDate>,
+    /// the due date after the postponements
+    pub effective_due_date: chrono::NaiveDate,
+    pub value: f32,
+    pub credited: f32,
+    pub payment_date: Option<chrono::NaiveDate>,
+    /// whether it was still unpaid on the date of the statement
+    pub open: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StatementOrderView {
+    pub pid: Uuid,
+    pub case_type: String,
+    pub fee: f32,
+    pub instalments: Vec<StatementInstalmentView>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct StatementTotals {
+    pub billed: f32,
+    pub received: f32,
+    pub credited: f32,
+    pub refunded: f32,
+    /// what the client owes on the date of the statement
+    pub balance: f32,
+    /// the open instalments that fall due after the date of the statement
+    pub not_yet_due: f32,
+}
+
+/// The account of a client across all their orders as of a date.
+///
+/// Instalments are charged on the date they fall due after their
+/// postponements, payments received and credit notes are credited on their
+/// own dates, and refunds are charged back on theirs. Chargebacks reopen their
+/// instalment, so they do not move the balance themselves.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ClientStatementView {
+    pub client_pid: Uuid,
+    pub client_name: String,
+    pub client_document: Option<String>,
+    pub as_of: chrono::NaiveDate,
+    pub orders: Vec<StatementOrderView>,
+    pub movements: Vec<StatementMovementView>,
+    pub totals: StatementTotals,
+}
+
+impl ClientStatementView {
+    #[must_use]
+    pub fn new(
+        client: &clients::Model,
+        orders: &[GetOrderReturn],
+        as_of: chrono::NaiveDate,
+    ) -> Self {
+        let mut movements = vec![];
+        let mut statement_orders = vec![];
+        let mut totals = StatementTotals::default();
+        for order in orders {
+            let mut instalments = vec![];
+            for payment in &order.payments {
+                let postponed_dates = payment.postponed_dates.clone().unwrap_or_default();
+                let effective_due_date = postponed_dates
+                    .iter()
+                    .copied()
+                    .max()
+                    .unwrap_or(payment.due_date);
+                let payment_date = payment
+                    .payment_date
+                    .filter(|payment_date| !payment.open && *payment_date <= as_of);
+                let credited = order
+                    .credit_notes
+                    .iter()
+                    .filter(|credit_note| {
+                        payment.pid.is_some() && credit_note.payment_pid == payment.pid
+                    })
+                    .filter(|credit_note| credit_note.credit_date <= as_of)
+                    .fold(0.0, |credited, credit_note| credited + credit_note.value);
+                if effective_due_date <= as_of {
+                    movements.push(StatementMovementView {
+                        date: effective_due_date,
+                        order_pid: order.pid,
+                        kind: StatementMovementKind::Instalment,
+                        description: format!("Instalment of {}", order.process.case_type),
+                        debit: payment.value,
+                        credit: 0.0,
+                        balance: 0.0,
+                    });
+                    totals.billed += payment.value;
+                } else if payment_date.is_none() {
+                    totals.not_yet_due += payment.value;
+                }
+                if let Some(payment_date) = payment_date {
+                    movements.push(StatementMovementView {
+                        date: payment_date,
+                        order_pid: order.pid,
+                        kind: StatementMovementKind::Payment,
+                        description: payment.payment_method.clone().map_or_else(
+                            || "Payment received".to_string(),
+                            |method| format!("Payment received ({method})"),
+                        ),
+                        debit: 0.0,
+                        credit: payment.value,
+                        balance: 0.0,
+                    });
+                    totals.received += payment.value;
+                }
+                instalments.push(StatementInstalmentView {
+                    pid: payment.pid,
+                    due_date: payment.due_date,
+                    postponed_dates,
+                    effective_due_date,
+                    value: payment.value,
+                    credited,
+                    payment_date,
+                    open: payment_date.is_none(),
+                });
+            }
+            for credit_note in &order.credit_notes {
+                if credit_note.credit_date <= as_of {
+                    movements.push(StatementMovementView {
+                        date: credit_note.credit_date,
+                        order_pid: order.pid,
+                        kind: StatementMovementKind::CreditNote,
+                        description: format!("Credit note: {}", credit_note.reason),
+                        debit: 0.0,
+                        credit: credit_note.value,
+                        balance: 0.0,
+                    });
+                    totals.credited += credit_note.value;
+                }
+            }
+            for refund in &order.refunds {
+                if !refund.chargeback && refund.refund_date <= as_of {
+                    movements.push(StatementMovementView {
+                        date: refund.refund_date,
+                        order_pid: order.pid,
+                        kind: StatementMovementKind::Refund,
+                        description: format!("Refund: {}", refund.reason),
+                        debit: refund.value,
+                        credit: 0.0,
+                        balance: 0.0,
+                    });
+                    totals.refunded += refund.value;
+                }
+            }
+            instalments.sort_by_key(|instalment| instalment.effective_due_date);
+            statement_orders.push(StatementOrderView {
+                pid: order.pid,
+                case_type: order.process.case_type.clone(),
+                fee: order.fee,
+                instalments,
+            });
+        }
+
+        movements.sort_by_key(|movement| (movement.date, movement.kind));
+        let mut balance = 0.0;
+        for movement in &mut movements {
+            balance += movement.debit - movement.credit;
+            movement.balance = balance;
+        }
+        totals.balance = balance;
+        Self {
+            client_pid: client.pid,
+            client_name: client.name.clone(),
+            client_document: client.document.clone(),
+            as_of,
+            orders: statement_orders,
+            movements,
+            totals,
+        }
+    }
+
+    /// Builds the statement of a client from all of their orders
+    ///
+    /// # Errors
+    ///
+    /// When could not find the orders of the client or DB query error
+    pub async fn from_model(
+        db: &DatabaseConnection,
+        client: &clients::Model,
+        as_of: chrono::NaiveDate,
+    ) -> ModelResult<Self> {
+        let mut orders = vec![];
+        for order in orders::Model::find_by_client_id(db, client.id).await? {
+            orders.push(orders::Model::find_by_pid(db, order.pid).await?);
+        }
+        Ok(Self::new(client, &orders, as_of))
+    }
+}
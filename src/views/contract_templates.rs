@@ -0,0 +1,42 @@
+use crate::models::_entities::{contract_templates, processes};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ContractTemplateView {
+    pub pid: Uuid,
+    pub process_pid: Option<Uuid>,
+    pub case_type: Option<String>,
+    pub name: String,
+    pub body: String,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl ContractTemplateView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<contract_templates::Model>,
+        processes: &[processes::Model],
+    ) -> Vec<Self> {
+        model
+            .into_iter()
+            .map(|m| Self::from(m, processes))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from(model: contract_templates::Model, processes: &[processes::Model]) -> Self {
+        let process = processes
+            .iter()
+            .find(|process| process.id == model.process_id);
+        Self {
+            pid: model.pid,
+            process_pid: process.map(|process| process.pid),
+            case_type: process.map(|process| process.case_type.clone()),
+            name: model.name,
+            body: model.body,
+            updated_at: model.updated_at,
+        }
+    }
+}
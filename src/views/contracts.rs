@@ -0,0 +1,53 @@
+use crate::models::_entities::contracts;
+use crate::models::contracts::ContractLinks;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ContractView {
+    pub pid: Uuid,
+    pub version: i32,
+    /// whether the version is written from what the order and its template
+    /// are now
+    pub current: bool,
+    /// the template of the process, none when written from the default one
+    pub template_name: Option<String>,
+    pub pdf_attachment_pid: Option<Uuid>,
+    pub docx_attachment_pid: Option<Uuid>,
+    pub author_pid: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl ContractView {
+    #[must_use]
+    pub fn from(model: contracts::Model, links: &ContractLinks, current: bool) -> Self {
+        let attachment_pid = |id: Option<i32>| {
+            id.and_then(|id| {
+                links
+                    .attachments
+                    .iter()
+                    .find(|attachment| attachment.id == id)
+            })
+            .map(|attachment| attachment.pid)
+        };
+        let author = model
+            .user_id
+            .and_then(|id| links.users.iter().find(|user| user.id == id));
+        Self {
+            pid: model.pid,
+            version: model.version,
+            current,
+            template_name: model
+                .template_id
+                .and_then(|id| links.templates.iter().find(|template| template.id == id))
+                .map(|template| template.name.clone()),
+            pdf_attachment_pid: attachment_pid(model.pdf_attachment_id),
+            docx_attachment_pid: attachment_pid(model.docx_attachment_id),
+            author_pid: author.map(|user| user.pid),
+            author_name: author.map(|user| user.name.clone()),
+            created_at: model.created_at,
+        }
+    }
+}
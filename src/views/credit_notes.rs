@@ -0,0 +1,53 @@
+use crate::models::_entities::{credit_notes, order_fees, payments};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreditNoteView {
+    pub pid: Uuid,
+    pub order_fee_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub value: f32,
+    pub reason: String,
+    pub credit_date: chrono::NaiveDate,
+}
+
+impl CreditNoteView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<credit_notes::Model>,
+        order_fees: &[order_fees::Model],
+        payments: &[payments::Model],
+    ) -> Vec<Self> {
+        model
+            .into_iter()
+            .map(|m| Self::from(m, order_fees, payments))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from(
+        model: credit_notes::Model,
+        order_fees: &[order_fees::Model],
+        payments: &[payments::Model],
+    ) -> Self {
+        Self {
+            pid: model.pid,
+            order_fee_pid: model.order_fee_id.and_then(|id| {
+                order_fees
+                    .iter()
+                    .find(|order_fee| order_fee.id == id)
+                    .map(|order_fee| order_fee.pid)
+            }),
+            payment_pid: model.payment_id.and_then(|id| {
+                payments
+                    .iter()
+                    .find(|payment| payment.id == id)
+                    .map(|payment| payment.pid)
+            }),
+            value: model.value,
+            reason: model.reason,
+            credit_date: model.credit_date,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::models::_entities::{expenses, payments};
+use crate::models::expenses::ExpenseStatus;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExpenseView {
+    pub pid: Uuid,
+    pub category: String,
+    pub value: f32,
+    pub expense_date: chrono::NaiveDate,
+    pub description: Option<String>,
+    pub receipt: Option<String>,
+    pub reimbursable: bool,
+    pub payment_pid: Option<Uuid>,
+    pub status: ExpenseStatus,
+}
+
+impl ExpenseView {
+    #[must_use]
+    pub fn from_model(model: Vec<expenses::Model>, payments: &[payments::Model]) -> Vec<Self> {
+        model.into_iter().map(|m| Self::from(m, payments)).collect()
+    }
+
+    #[must_use]
+    pub fn from(model: expenses::Model, payments: &[payments::Model]) -> Self {
+        let status = model.status(payments);
+        Self {
+            pid: model.pid,
+            category: model.category,
+            value: model.value,
+            expense_date: model.expense_date,
+            description: model.description,
+            receipt: model.receipt,
+            reimbursable: model.reimbursable,
+            payment_pid: model.payment_id.and_then(|id| {
+                payments
+                    .iter()
+                    .find(|payment| payment.id == id)
+                    .map(|payment| payment.pid)
+            }),
+            status,
+        }
+    }
+}
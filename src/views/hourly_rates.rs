@@ -0,0 +1,46 @@
+use crate::models::_entities::{hourly_rates, orders, users};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HourlyRateView {
+    pub pid: Uuid,
+    pub user_pid: Option<Uuid>,
+    pub order_pid: Option<Uuid>,
+    pub value: f32,
+}
+
+impl HourlyRateView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<hourly_rates::Model>,
+        users: &[users::Model],
+        orders: &[orders::Model],
+    ) -> Vec<Self> {
+        model
+            .into_iter()
+            .map(|m| Self::from(m, users, orders))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from(
+        model: hourly_rates::Model,
+        users: &[users::Model],
+        orders: &[orders::Model],
+    ) -> Self {
+        Self {
+            pid: model.pid,
+            user_pid: model
+                .user_id
+                .and_then(|id| users.iter().find(|user| user.id == id).map(|user| user.pid)),
+            order_pid: model.order_id.and_then(|id| {
+                orders
+                    .iter()
+                    .find(|order| order.id == id)
+                    .map(|order| order.pid)
+            }),
+            value: model.value,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::models::_entities::lawsuits;
+use crate::models::lawsuits::{format_cnj, CnjNumber, JusticeBranch};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What a CNJ number tells about the case
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CnjView {
+    pub number: String,
+    pub year: u16,
+    pub branch: Option<JusticeBranch>,
+    pub court: Option<String>,
+    pub state: Option<String>,
+    /// the code of the court unit the case started in, the `OOOO`
+    pub origin: String,
+}
+
+impl From<CnjNumber> for CnjView {
+    fn from(cnj: CnjNumber) -> Self {
+        Self {
+            number: format_cnj(&cnj.digits()),
+            year: cnj.year,
+            branch: cnj.branch(),
+            court: cnj.court_name(),
+            state: cnj.state().map(ToString::to_string),
+            origin: format!("{:04}", cnj.origin),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LawsuitView {
+    pub pid: Uuid,
+    pub cnj_number: String,
+    pub cnj: Option<CnjView>,
+    pub court: Option<String>,
+    pub district: Option<String>,
+    pub opposing_party: Option<String>,
+    pub filing_date: Option<chrono::NaiveDate>,
+    pub status: String,
+}
+
+impl From<lawsuits::Model> for LawsuitView {
+    fn from(lawsuit: lawsuits::Model) -> Self {
+        Self {
+            pid: lawsuit.pid,
+            cnj: lawsuit.cnj().map(CnjView::from),
+            cnj_number: format_cnj(&lawsuit.cnj_number),
+            court: lawsuit.court,
+            district: lawsuit.district,
+            opposing_party: lawsuit.opposing_party,
+            filing_date: lawsuit.filing_date,
+            status: lawsuit.status,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::models::journal_entries::{cents, AccountBalance};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AccountBalanceView {
+    pub code: String,
+    pub name: String,
+    pub kind: String,
+    pub debit: f32,
+    pub credit: f32,
+    pub balance: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrialBalanceView {
+    pub accounts: Vec<AccountBalanceView>,
+    pub total_debit: f32,
+    pub total_credit: f32,
+    pub balanced: bool,
+}
+
+impl TrialBalanceView {
+    #[must_use]
+    pub fn from(balances: Vec<AccountBalance>) -> Self {
+        let total_debit: i64 = balances.iter().map(|balance| cents(balance.debit)).sum();
+        let total_credit: i64 = balances.iter().map(|balance| cents(balance.credit)).sum();
+        #[allow(clippy::cast_precision_loss)]
+        Self {
+            accounts: balances
+                .into_iter()
+                .map(|balance| AccountBalanceView {
+                    balance: balance.balance(),
+                    code: balance.account.code,
+                    name: balance.account.name,
+                    kind: balance.account.kind,
+                    debit: balance.debit,
+                    credit: balance.credit,
+                })
+                .collect(),
+            total_debit: total_debit as f32 / 100.0,
+            total_credit: total_credit as f32 / 100.0,
+            balanced: total_debit == total_credit,
+        }
+    }
+}
@@ -1,8 +1,27 @@
+pub mod accounting_periods;
+pub mod attachments;
 pub mod auth;
+pub mod bank_transactions;
+pub mod billing_plans;
+pub mod boletos;
+pub mod calendar_events;
+pub mod client_merges;
 pub mod clients;
+pub mod contract_templates;
+pub mod contracts;
+pub mod credit_notes;
+pub mod expenses;
 pub mod fees;
+pub mod hourly_rates;
+pub mod lawsuits;
+pub mod ledger;
+pub mod notes;
 pub mod orders;
 pub mod partners;
+pub mod payment_charges;
+pub mod pix;
 pub mod postponed_payments;
 pub mod processes;
+pub mod refunds;
 pub mod sellers;
+pub mod time_entries;
@@ -0,0 +1,326 @@
+use crate::models::_entities::{
+    clients, note_revisions, notes, orders, payments, postponed_payments, status_changes,
+};
+use crate::models::notes::NoteLinks;
+use loco_rs::model::{self, ModelResult};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NoteView {
+    pub pid: Uuid,
+    pub body: String,
+    pub pinned: bool,
+    pub author_pid: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub order_pid: Option<Uuid>,
+    pub client_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub created_at: DateTimeWithTimeZone,
+    /// when the body was last changed
+    pub edited_at: Option<DateTimeWithTimeZone>,
+    /// how many times the body was changed
+    pub revisions: usize,
+}
+
+impl NoteView {
+    #[must_use]
+    pub fn from_model(model: Vec<notes::Model>, links: &NoteLinks) -> Vec<Self> {
+        model.into_iter().map(|m| Self::from(m, links)).collect()
+    }
+
+    #[must_use]
+    pub fn from(model: notes::Model, links: &NoteLinks) -> Self {
+        let author = model
+            .user_id
+            .and_then(|id| links.users.iter().find(|user| user.id == id));
+        let revisions = links
+            .revisions
+            .iter()
+            .filter(|revision| revision.note_id == model.id)
+            .collect::<Vec<_>>();
+        Self {
+            pid: model.pid,
+            body: model.body,
+            pinned: model.pinned,
+            author_pid: author.map(|user| user.pid),
+            author_name: author.map(|user| user.name.clone()),
+            order_pid: model
+                .order_id
+                .and_then(|id| links.orders.iter().find(|order| order.id == id))
+                .map(|order| order.pid),
+            client_pid: model
+                .client_id
+                .and_then(|id| links.clients.iter().find(|client| client.id == id))
+                .map(|client| client.pid),
+            payment_pid: model
+                .payment_id
+                .and_then(|id| links.payments.iter().find(|payment| payment.id == id))
+                .map(|payment| payment.pid),
+            created_at: model.created_at,
+            edited_at: revisions.iter().map(|revision| revision.created_at).max(),
+            revisions: revisions.len(),
+        }
+    }
+}
+
+/// A body a note had before it was edited
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NoteRevisionView {
+    pub pid: Uuid,
+    pub body: String,
+    /// who replaced the body
+    pub editor_pid: Option<Uuid>,
+    pub editor_name: Option<String>,
+    pub edited_at: DateTimeWithTimeZone,
+}
+
+impl NoteRevisionView {
+    #[must_use]
+    pub fn from(model: &note_revisions::Model, links: &NoteLinks) -> Self {
+        let editor = model
+            .user_id
+            .and_then(|id| links.users.iter().find(|user| user.id == id));
+        Self {
+            pid: model.pid,
+            body: model.body.clone(),
+            editor_pid: editor.map(|user| user.pid),
+            editor_name: editor.map(|user| user.name.clone()),
+            edited_at: model.created_at,
+        }
+    }
+}
+
+/// A note with the bodies it had before, oldest first
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NoteHistoryView {
+    pub note: NoteView,
+    pub revisions: Vec<NoteRevisionView>,
+}
+
+impl NoteHistoryView {
+    #[must_use]
+    pub fn new(note: notes::Model, links: &NoteLinks) -> Self {
+        let revisions = links
+            .revisions
+            .iter()
+            .filter(|revision| revision.note_id == note.id)
+            .map(|revision| NoteRevisionView::from(revision, links))
+            .collect();
+        Self {
+            note: NoteView::from(note, links),
+            revisions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Note,
+    ClientCreated,
+    OrderCreated,
+    PaymentSettled,
+    PostponementAdded,
+    StatusChanged,
+}
+
+/// Something that happened to a client or an order
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TimelineItem {
+    pub kind: TimelineKind,
+    pub at: DateTimeWithTimeZone,
+    pub description: String,
+    pub order_pid: Option<Uuid>,
+    pub payment_pid: Option<Uuid>,
+    pub note: Option<NoteView>,
+}
+
+/// The notes and the events of a client or an order in the order they
+/// happened, with the pinned notes apart.
+///
+/// Payments are only known to be settled on a date, so they are placed at
+/// the start of that day.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Timeline {
+    pub pinned: Vec<NoteView>,
+    pub items: Vec<TimelineItem>,
+}
+
+impl Timeline {
+    /// merges the events of the client, when given, of its orders and of
+    /// their payments with the notes on them
+    #[must_use]
+    pub fn new(
+        client: Option<&clients::Model>,
+        orders: &[orders::Model],
+        payments: &[payments::Model],
+        postponed_payments: &[postponed_payments::Model],
+        status_changes: &[status_changes::Model],
+        notes: Vec<NoteView>,
+    ) -> Self {
+        let order_pid = |order_id: i32| {
+            orders
+                .iter()
+                .find(|order| order.id == order_id)
+                .map(|order| order.pid)
+        };
+        let mut items = vec![];
+        if let Some(client) = client {
+            items.push(TimelineItem {
+                kind: TimelineKind::ClientCreated,
+                at: client.created_at,
+                description: format!("Client {} created", client.name),
+                order_pid: None,
+                payment_pid: None,
+                note: None,
+            });
+        }
+        items.extend(orders.iter().map(|order| TimelineItem {
+            kind: TimelineKind::OrderCreated,
+            at: order.created_at,
+            description: format!("Order created with a fee of {:.2}", order.fee),
+            order_pid: Some(order.pid),
+            payment_pid: None,
+            note: None,
+        }));
+        items.extend(
+            payments
+                .iter()
+                .filter(|payment| !payment.open)
+                .filter_map(|payment| {
+                    let payment_date = payment.payment_date?;
+                    Some(TimelineItem {
+                        kind: TimelineKind::PaymentSettled,
+                        at: payment_date
+                            .and_time(chrono::NaiveTime::MIN)
+                            .and_utc()
+                            .fixed_offset(),
+                        description: payment.payment_method.as_ref().map_or_else(
+                            || {
+                                format!(
+                                    "Instalment of {:.2} due {} settled",
+                                    payment.value, payment.due_date
+                                )
+                            },
+                            |method| {
+                                format!(
+                                    "Instalment of {:.2} due {} settled ({method})",
+                                    payment.value, payment.due_date
+                                )
+                            },
+                        ),
+                        order_pid: order_pid(payment.order_id),
+                        payment_pid: Some(payment.pid),
+                        note: None,
+                    })
+                }),
+        );
+        items.extend(postponed_payments.iter().filter_map(|postponed| {
+            let payment = payments
+                .iter()
+                .find(|payment| payment.id == postponed.payment_id)?;
+            Some(TimelineItem {
+                kind: TimelineKind::PostponementAdded,
+                at: postponed.created_at,
+                description: format!(
+                    "Instalment due {} postponed to {}",
+                    payment.due_date, postponed.postponed_date
+                ),
+                order_pid: order_pid(payment.order_id),
+                payment_pid: Some(payment.pid),
+                note: None,
+            })
+        }));
+        items.extend(status_changes.iter().map(|status_change| {
+            let mut subject = status_change.subject.clone();
+            if let Some(first) = subject.get_mut(..1) {
+                first.make_ascii_uppercase();
+            }
+            TimelineItem {
+                kind: TimelineKind::StatusChanged,
+                at: status_change.created_at,
+                description: format!(
+                    "{subject} status changed from {} to {}",
+                    status_change.from_status, status_change.to_status
+                ),
+                order_pid: order_pid(status_change.order_id),
+                payment_pid: None,
+                note: None,
+            }
+        }));
+        let pinned = notes.iter().filter(|note| note.pinned).cloned().collect();
+        items.extend(notes.into_iter().map(|note| {
+            TimelineItem {
+                kind: TimelineKind::Note,
+                at: note.created_at,
+                description: note
+                    .author_name
+                    .as_ref()
+                    .map_or_else(|| "Note".to_string(), |name| format!("Note by {name}")),
+                order_pid: note.order_pid,
+                payment_pid: note.payment_pid,
+                note: Some(note),
+            }
+        }));
+        items.sort_by_key(|item| item.at);
+        Self { pinned, items }
+    }
+
+    /// finds the timeline of a client, with the orders and payments of the
+    /// client when given, or of the given orders alone
+    ///
+    /// # Errors
+    ///
+    /// When DB query error
+    pub async fn find(
+        db: &DatabaseConnection,
+        client: Option<&clients::Model>,
+        orders: &[orders::Model],
+    ) -> ModelResult<Self> {
+        let order_ids = orders.iter().map(|order| order.id).collect::<Vec<_>>();
+        let payments = payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(payments::Column::OrderId, order_ids.iter().copied())
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let payment_ids = payments
+            .iter()
+            .map(|payment| payment.id)
+            .collect::<Vec<_>>();
+        let postponed_payments = postponed_payments::Entity::find()
+            .filter(
+                model::query::condition()
+                    .is_in(
+                        postponed_payments::Column::PaymentId,
+                        payment_ids.iter().copied(),
+                    )
+                    .build(),
+            )
+            .all(db)
+            .await?;
+        let status_changes =
+            status_changes::Model::find_by_order_ids(db, order_ids.iter().copied()).await?;
+        let notes = notes::Model::find_by_orders(
+            db,
+            client.map(|client| client.id),
+            &order_ids,
+            &payment_ids,
+        )
+        .await?;
+        let links = notes::Model::find_links(db, &notes).await?;
+        Ok(Self::new(
+            client,
+            orders,
+            &payments,
+            &postponed_payments,
+            &status_changes,
+            NoteView::from_model(notes, &links),
+        ))
+    }
+}
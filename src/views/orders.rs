@@ -1,6 +1,14 @@
 use crate::controllers::orders::JsonOrderFeesToCreate;
+use crate::models::_entities::{client_contacts, clients};
+use crate::models::client_contacts::{contact_value, ContactKind};
+use crate::views::billing_plans::BillingPlanView;
+use crate::views::credit_notes::CreditNoteView;
+use crate::views::expenses::ExpenseView;
+use crate::views::lawsuits::LawsuitView;
 use crate::views::partners::PartnerView;
+use crate::views::refunds::RefundView;
 use crate::views::sellers::SellerView;
+use crate::views::time_entries::TimeEntryView;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -49,9 +57,32 @@ pub struct ClientOrderReturn {
     pub phone: Option<String>,
     pub phone2: Option<String>,
     pub email: Option<String>,
+    pub document: Option<String>,
     pub partner: Option<PartnerView>,
 }
 
+impl ClientOrderReturn {
+    /// the client of an order with its primary phone and email, and a second
+    /// phone if it has one
+    #[must_use]
+    pub fn from_model(
+        client: clients::Model,
+        contacts: &[client_contacts::Model],
+        partner: Option<PartnerView>,
+    ) -> Self {
+        Self {
+            pid: client.pid,
+            name: client.name,
+            contact: client.contact,
+            phone: contact_value(contacts, ContactKind::Phone, true),
+            phone2: contact_value(contacts, ContactKind::Phone, false),
+            email: contact_value(contacts, ContactKind::Email, true),
+            document: client.document,
+            partner,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ClientProcessReturn {
     pub pid: Uuid,
@@ -69,7 +100,15 @@ pub struct GetOrderReturn {
     pub seller: SellerView,
     pub client: ClientOrderReturn,
     pub process: ClientProcessReturn,
+    pub lawsuit: Option<LawsuitView>,
     pub payments: Vec<OrderPayments>,
+    pub credit_notes: Vec<CreditNoteView>,
+    pub outstanding_balance: f32,
+    pub refunds: Vec<RefundView>,
+    pub net_received: f32,
+    pub expenses: Vec<ExpenseView>,
+    pub billing_plans: Vec<BillingPlanView>,
+    pub time_entries: Vec<TimeEntryView>,
 }
 
 impl GetOrderReturn {
@@ -84,12 +123,20 @@ impl GetOrderReturn {
                 .map(OrderPayments::from)
                 .collect(),
             process: order.process,
+            lawsuit: order.lawsuit,
             seller: order.seller,
             open: order.open,
             fee: order.fee,
             fees: order.fees.into_iter().collect(),
             payout: order.payout,
             partner_fee: order.partner_fee,
+            credit_notes: order.credit_notes,
+            outstanding_balance: order.outstanding_balance,
+            refunds: order.refunds,
+            net_received: order.net_received,
+            expenses: order.expenses,
+            billing_plans: order.billing_plans,
+            time_entries: order.time_entries,
         }
     }
 }
@@ -0,0 +1,42 @@
+use crate::models::_entities::{payment_charges, payments};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PaymentChargeView {
+    pub pid: Uuid,
+    pub payment_pid: Option<Uuid>,
+    pub provider: String,
+    pub external_id: String,
+    pub checkout_url: Option<String>,
+    pub value: f32,
+    pub status: String,
+    pub paid_on: Option<chrono::NaiveDate>,
+}
+
+impl PaymentChargeView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<payment_charges::Model>,
+        payments: &[payments::Model],
+    ) -> Vec<Self> {
+        model.into_iter().map(|m| Self::from(m, payments)).collect()
+    }
+
+    #[must_use]
+    pub fn from(model: payment_charges::Model, payments: &[payments::Model]) -> Self {
+        Self {
+            pid: model.pid,
+            payment_pid: payments
+                .iter()
+                .find(|payment| payment.id == model.payment_id)
+                .map(|payment| payment.pid),
+            provider: model.provider,
+            external_id: model.external_id,
+            checkout_url: model.checkout_url,
+            value: model.value,
+            status: model.status,
+            paid_on: model.paid_on,
+        }
+    }
+}
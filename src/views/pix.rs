@@ -0,0 +1,34 @@
+use crate::documents::pix::{self, PixSettings};
+use crate::documents::qrcode::QrCode;
+use crate::models::_entities::payments;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PixView {
+    pub payment_pid: Uuid,
+    pub value: f32,
+    pub due_date: chrono::NaiveDate,
+    pub txid: String,
+    pub payload: String,
+    pub qr_code: String,
+}
+
+impl PixView {
+    /// builds the PIX charge of an instalment, or `None` when its payload does
+    /// not fit in a QR code
+    #[must_use]
+    pub fn from(payment: &payments::Model, settings: &PixSettings) -> Option<Self> {
+        let txid = pix::txid(payment.pid);
+        let payload = pix::payload(settings, payment.value, &txid);
+        let qr_code = QrCode::encode(payload.as_bytes())?.to_svg();
+        Some(Self {
+            payment_pid: payment.pid,
+            value: payment.value,
+            due_date: payment.due_date,
+            txid,
+            payload,
+            qr_code,
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use crate::models::_entities::{payments, refunds};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RefundView {
+    pub pid: Uuid,
+    pub payment_pid: Option<Uuid>,
+    pub value: f32,
+    pub refund_date: chrono::NaiveDate,
+    pub refund_method: Option<String>,
+    pub reason: String,
+    pub chargeback: bool,
+}
+
+impl RefundView {
+    #[must_use]
+    pub fn from_model(model: Vec<refunds::Model>, payments: &[payments::Model]) -> Vec<Self> {
+        model.into_iter().map(|m| Self::from(m, payments)).collect()
+    }
+
+    #[must_use]
+    pub fn from(model: refunds::Model, payments: &[payments::Model]) -> Self {
+        Self {
+            pid: model.pid,
+            payment_pid: model.payment_id.and_then(|id| {
+                payments
+                    .iter()
+                    .find(|payment| payment.id == id)
+                    .map(|payment| payment.pid)
+            }),
+            value: model.value,
+            refund_date: model.refund_date,
+            refund_method: model.refund_method,
+            reason: model.reason,
+            chargeback: model.chargeback,
+        }
+    }
+}
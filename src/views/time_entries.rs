@@ -0,0 +1,58 @@
+use crate::models::_entities::{payments, time_entries, users};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TimeEntryView {
+    pub pid: Uuid,
+    pub user_pid: Option<Uuid>,
+    pub user_name: Option<String>,
+    pub entry_date: chrono::NaiveDate,
+    pub minutes: i32,
+    pub description: String,
+    pub hourly_rate: f32,
+    pub amount: f32,
+    pub billed: bool,
+    pub payment_pid: Option<Uuid>,
+}
+
+impl TimeEntryView {
+    #[must_use]
+    pub fn from_model(
+        model: Vec<time_entries::Model>,
+        users: &[users::Model],
+        payments: &[payments::Model],
+    ) -> Vec<Self> {
+        model
+            .into_iter()
+            .map(|m| Self::from(m, users, payments))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn from(
+        model: time_entries::Model,
+        users: &[users::Model],
+        payments: &[payments::Model],
+    ) -> Self {
+        let amount = model.amount();
+        let user = users.iter().find(|user| user.id == model.user_id);
+        Self {
+            pid: model.pid,
+            user_pid: user.map(|user| user.pid),
+            user_name: user.map(|user| user.name.clone()),
+            entry_date: model.entry_date,
+            minutes: model.minutes,
+            description: model.description,
+            hourly_rate: model.hourly_rate,
+            amount,
+            billed: model.billed,
+            payment_pid: model.payment_id.and_then(|id| {
+                payments
+                    .iter()
+                    .find(|payment| payment.id == id)
+                    .map(|payment| payment.pid)
+            }),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use super::fixtures;
+use johandler::app::App;
+use johandler::file_storage::StorageSettings;
+use johandler::models::_entities::{attachments, orders, payments};
+use johandler::models::attachments::{
+    checksum, clean_file_name, AttachmentCategory, AttachmentRules, AttachmentTarget, Upload,
+};
+use loco_rs::storage::{drivers, Storage};
+use loco_rs::testing;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ColumnTrait, ConnectionTrait, Database, EntityTrait, QueryFilter};
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn upload(content_type: &str, content: &[u8]) -> Upload {
+    Upload {
+        category: AttachmentCategory::Contract,
+        file_name: "Contrato de honorários.pdf".to_string(),
+        content_type: content_type.to_string(),
+        content: bytes::Bytes::copy_from_slice(content),
+        checksum: None,
+    }
+}
+
+#[test]
+fn uploads_are_checked_against_the_rules() {
+    let rules = AttachmentRules {
+        max_size: 16,
+        ..AttachmentRules::default()
+    };
+    assert!(rules.check(&upload("application/pdf", b"%PDF-1.7")).is_ok());
+    assert!(rules
+        .check(&upload("Application/PDF; charset=binary", b"%PDF-1.7"))
+        .is_ok());
+    assert!(rules.check(&upload("text/plain", b"receipt")).is_ok());
+    // empty, too large, of a type not accepted or not what it claims to be
+    assert!(rules.check(&upload("application/pdf", b"")).is_err());
+    assert!(rules
+        .check(&upload("application/pdf", b"%PDF-1.7 and much more"))
+        .is_err());
+    assert!(rules
+        .check(&upload("application/x-msdownload", b"MZ"))
+        .is_err());
+    assert!(rules.check(&upload("application/pdf", b"MZ")).is_err());
+    let mut nameless = upload("application/pdf", b"%PDF-1.7");
+    nameless.file_name = "C:\\".to_string();
+    assert!(rules.check(&nameless).is_err());
+
+    assert_eq!(
+        clean_file_name("C:\\Users\\ana\\procuração.pdf"),
+        "procuração.pdf"
+    );
+    assert_eq!(clean_file_name("../../etc/passwd"), "passwd");
+    assert_eq!(
+        checksum(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn storage_is_on_the_local_disk_unless_configured() {
+    assert_eq!(
+        StorageSettings::from_settings(None),
+        StorageSettings::Local {
+            root: "storage".to_string()
+        }
+    );
+    let settings = serde_json::json!({
+        "storage": {
+            "driver": "s3",
+            "bucket": "johandler",
+            "endpoint": "http://localhost:9000",
+            "access_key_id": "minioadmin",
+            "secret_access_key": "minioadmin",
+            "allow_http": true,
+        }
+    });
+    let storage = StorageSettings::from_settings(Some(&settings));
+    assert!(matches!(
+        &storage,
+        StorageSettings::S3 { region, allow_http: true, .. } if region == "us-east-1"
+    ));
+    assert!(storage.driver().is_ok());
+}
+
+#[tokio::test]
+async fn attachments_are_kept_in_the_storage() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    db.execute_unprepared(
+        "INSERT INTO clients (created_at, updated_at, pid, name, contact) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'0f8fad5bd9cb469fa16570867728950e', 'João da Silva', 'x')",
+    )
+    .await
+    .unwrap();
+    let client_pid = uuid::Uuid::parse_str("0f8fad5b-d9cb-469f-a165-70867728950e").unwrap();
+    let storage = Storage::single(drivers::mem::new());
+    let rules = AttachmentRules::default();
+
+    assert!(AttachmentTarget::find(&db, None, None, None, None)
+        .await
+        .is_err());
+    let target = AttachmentTarget::find(&db, Some(client_pid), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(target, AttachmentTarget::Client(1));
+
+    let mut wrong_checksum = upload("application/pdf", b"%PDF-1.7 procuracao");
+    wrong_checksum.checksum = Some(checksum(b"something else"));
+    assert!(
+        attachments::Model::create(&db, &storage, &rules, None, target, wrong_checksum)
+            .await
+            .is_err()
+    );
+
+    let mut proof = upload("application/pdf", b"%PDF-1.7 procuracao");
+    proof.checksum = Some(checksum(b"%PDF-1.7 procuracao").to_uppercase());
+    let attachment = attachments::Model::create(&db, &storage, &rules, None, target, proof)
+        .await
+        .unwrap();
+    assert_eq!(attachment.category, "contract");
+    assert_eq!(attachment.size, 19);
+    assert_eq!(attachment.checksum, checksum(b"%PDF-1.7 procuracao"));
+    assert_eq!(
+        attachments::Model::find_by_target(&db, target)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let file = attachment.open(&storage).await.unwrap();
+    assert_eq!(file.bytes().await.unwrap().as_ref(), b"%PDF-1.7 procuracao");
+
+    attachments::Model::delete(&db, &storage, attachment.pid)
+        .await
+        .unwrap();
+    assert!(attachment.open(&storage).await.is_err());
+    assert!(attachments::Model::find_by_target(&db, target)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn records_with_attachments_are_not_deleted() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let storage = Storage::single(drivers::mem::new());
+    let order = fixtures::order(db).await;
+    let payment_pid = order.payments[0].pid.unwrap();
+    let payment = payments::Entity::find()
+        .filter(payments::Column::Pid.eq(payment_pid))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut proof = upload("application/pdf", b"%PDF-1.7 comprovante");
+    proof.category = AttachmentCategory::PaymentProof;
+    let attachment = attachments::Model::create(
+        db,
+        &storage,
+        &AttachmentRules::default(),
+        None,
+        AttachmentTarget::Payment(payment.id),
+        proof,
+    )
+    .await
+    .unwrap();
+
+    assert!(payments::Model::delete(db, payment_pid).await.is_err());
+    assert!(orders::Model::delete(db, order.pid).await.is_err());
+    assert!(attachment.open(&storage).await.is_ok());
+
+    attachments::Model::delete(db, &storage, attachment.pid)
+        .await
+        .unwrap();
+    payments::Model::delete(db, payment_pid).await.unwrap();
+}
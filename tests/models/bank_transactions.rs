@@ -0,0 +1,267 @@
+use super::fixtures::{self, date};
+use chrono::NaiveDate;
+use johandler::app::App;
+use johandler::bank_statements::{self, StatementFormat, StatementLine};
+use johandler::documents::pix;
+use johandler::models::_entities::{bank_transactions, payments};
+use johandler::models::bank_transactions::{propose_match, MatchRule, TransactionStatus};
+use loco_rs::testing;
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, QueryFilter};
+use serial_test::serial;
+use std::collections::HashSet;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+/// a fixed width record with the given fields, placed at positions counted
+/// from 1 as in the layout manuals
+fn record(width: usize, fields: &[(usize, &str)]) -> String {
+    let mut record = vec![' '; width];
+    for (start, value) in fields {
+        for (offset, c) in value.chars().enumerate() {
+            record[start - 1 + offset] = c;
+        }
+    }
+    record.into_iter().collect()
+}
+
+fn payment(
+    id: i32,
+    value: f32,
+    due_date: NaiveDate,
+    nosso_numero: Option<&str>,
+) -> payments::Model {
+    payments::Model {
+        due_date,
+        nosso_numero: nosso_numero.map(ToString::to_string),
+        ..fixtures::payment(id, value, true)
+    }
+}
+
+fn line(value: f32, date: NaiveDate, description: &str) -> StatementLine {
+    StatementLine {
+        external_id: "1".to_string(),
+        date,
+        value,
+        description: description.to_string(),
+        nosso_numero: None,
+    }
+}
+
+#[test]
+fn ofx_credits_are_read() {
+    let content = "OFXHEADER:100\nDATA:OFXSGML\n<OFX>\n<BANKTRANLIST>\n\
+        <STMTTRN>\n<TRNTYPE>CREDIT\n<DTPOSTED>20250110120000[-3:BRT]\n<TRNAMT>500,00\n\
+        <FITID>202501100001\n<MEMO>PIX RECEBIDO JOAO\n</STMTTRN>\n\
+        <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20250111\n<TRNAMT>-80.00\n<FITID>202501110002\n\
+        <MEMO>TARIFA\n</STMTTRN>\n</BANKTRANLIST>\n</OFX>\n";
+    let (format, lines) = bank_statements::parse(content).unwrap();
+    assert_eq!(format, StatementFormat::Ofx);
+    assert_eq!(
+        lines,
+        vec![StatementLine {
+            external_id: "202501100001".to_string(),
+            date: date(2025, 1, 10),
+            value: 500.0,
+            description: "PIX RECEBIDO JOAO".to_string(),
+            nosso_numero: None,
+        }]
+    );
+}
+
+#[test]
+fn cnab_400_settlements_are_read() {
+    let content = [
+        record(400, &[(1, "02RETORNO")]),
+        record(
+            400,
+            &[
+                (1, "7"),
+                (64, "12345670000000042"),
+                (109, "06"),
+                (111, "120325"),
+                (254, "0000000150050"),
+            ],
+        ),
+        record(
+            400,
+            &[
+                (1, "7"),
+                (64, "12345670000000043"),
+                (109, "02"),
+                (111, "120325"),
+            ],
+        ),
+        record(400, &[(1, "9")]),
+    ]
+    .join("\r\n");
+    let (format, lines) = bank_statements::parse(&content).unwrap();
+    assert_eq!(format, StatementFormat::Cnab400);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].nosso_numero.as_deref(), Some("12345670000000042"));
+    assert_eq!(lines[0].date, date(2025, 3, 12));
+    assert!((lines[0].value - 1500.5).abs() < f32::EPSILON);
+    assert_eq!(lines[0].external_id, "12345670000000042-20250312");
+}
+
+#[test]
+fn cnab_240_settlements_pair_segments_t_and_u() {
+    let content = [
+        record(240, &[(1, "00100000")]),
+        record(
+            240,
+            &[(8, "3"), (14, "T"), (16, "06"), (38, "12345670000000042")],
+        ),
+        record(
+            240,
+            &[
+                (8, "3"),
+                (14, "U"),
+                (16, "06"),
+                (78, "000000000050000"),
+                (138, "10022025"),
+            ],
+        ),
+        record(240, &[(8, "9")]),
+    ]
+    .join("\n");
+    let (format, lines) = bank_statements::parse(&content).unwrap();
+    assert_eq!(format, StatementFormat::Cnab240);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].nosso_numero.as_deref(), Some("12345670000000042"));
+    assert_eq!(lines[0].date, date(2025, 2, 10));
+    assert!((lines[0].value - 500.0).abs() < f32::EPSILON);
+}
+
+#[test]
+fn unknown_files_are_rejected() {
+    assert!(bank_statements::parse("date;value\n2025-01-10;500").is_none());
+}
+
+#[test]
+fn matches_prefer_nosso_numero_then_txid_then_amount() {
+    let by_boleto = payment(1, 500.0, date(2025, 1, 10), Some("12345670000000001"));
+    let by_pix = payment(2, 500.0, date(2025, 1, 10), None);
+    let by_amount = payment(3, 320.0, date(2025, 1, 15), None);
+    let payments = [by_boleto.clone(), by_pix.clone(), by_amount];
+    let taken = HashSet::new();
+
+    let mut boleto = line(500.0, date(2025, 1, 10), "Liquidação do boleto");
+    boleto.nosso_numero = Some("12345670000000001".to_string());
+    assert_eq!(
+        propose_match(&boleto, &payments, &taken),
+        Some((1, MatchRule::NossoNumero))
+    );
+
+    let pix = line(
+        500.0,
+        date(2025, 1, 10),
+        &format!("Pix recebido {}", pix::txid(by_pix.pid).to_lowercase()),
+    );
+    assert_eq!(
+        propose_match(&pix, &payments, &taken),
+        Some((2, MatchRule::Txid))
+    );
+
+    let transfer = line(320.0, date(2025, 1, 18), "TED recebida");
+    assert_eq!(
+        propose_match(&transfer, &payments, &taken),
+        Some((3, MatchRule::Amount))
+    );
+    let late = line(320.0, date(2025, 1, 25), "TED recebida");
+    assert_eq!(propose_match(&late, &payments, &taken), None);
+}
+
+#[test]
+fn ambiguous_amounts_are_left_for_review() {
+    let payments = [
+        payment(1, 500.0, date(2025, 1, 8), None),
+        payment(2, 500.0, date(2025, 1, 12), None),
+    ];
+    let transfer = line(500.0, date(2025, 1, 10), "TED recebida");
+    assert_eq!(propose_match(&transfer, &payments, &HashSet::new()), None);
+
+    let taken = HashSet::from([2]);
+    assert_eq!(
+        propose_match(&transfer, &payments, &taken),
+        Some((1, MatchRule::Amount))
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn only_a_credit_of_the_instalment_value_settles_it() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let payment_pid = order.payments[0].pid.unwrap();
+    let mut transactions = vec![];
+    for value in [-1000.0, 10.0] {
+        let transaction = bank_transactions::ActiveModel {
+            source: ActiveValue::Set("ofx".to_string()),
+            external_id: ActiveValue::Set(uuid::Uuid::new_v4().to_string()),
+            transaction_date: ActiveValue::Set(date(2025, 4, 10)),
+            value: ActiveValue::Set(value),
+            description: ActiveValue::Set("PIX RECEBIDO".to_string()),
+            status: ActiveValue::Set(TransactionStatus::Unmatched.as_str().to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        transactions.push(transaction);
+    }
+    let is_open = || async {
+        payments::Entity::find()
+            .filter(payments::Column::Pid.eq(payment_pid))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+            .open
+    };
+
+    let debit = &transactions[0];
+    assert!(
+        bank_transactions::Model::confirm(db, debit.pid, Some(payment_pid), true)
+            .await
+            .is_err()
+    );
+    let short_credit = &transactions[1];
+    assert!(
+        bank_transactions::Model::confirm(db, short_credit.pid, Some(payment_pid), false)
+            .await
+            .is_err()
+    );
+    assert!(is_open().await);
+
+    bank_transactions::Model::confirm(db, short_credit.pid, Some(payment_pid), true)
+        .await
+        .unwrap();
+    assert!(!is_open().await);
+}
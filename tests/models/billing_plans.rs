@@ -0,0 +1,170 @@
+use super::fixtures::{self, date};
+use chrono::NaiveDate;
+use johandler::app::App;
+use johandler::business_days::BusinessCalendar;
+use johandler::models::_entities::{billing_plan_prices, billing_plans, payments};
+use johandler::models::billing_plans::{ChangeBillingPlanPrice, CreateNewBillingPlan};
+use loco_rs::testing;
+use sea_orm::EntityTrait;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn billing_plan(billed_until: Option<NaiveDate>) -> billing_plans::Model {
+    let now = chrono::Utc::now().into();
+    billing_plans::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        order_id: 1,
+        value: 1500.0,
+        day_of_month: 31,
+        start_date: date(2025, 1, 10),
+        end_date: Some(date(2025, 6, 30)),
+        paused: false,
+        cancelled_at: None,
+        billed_until,
+    }
+}
+
+fn price(id: i32, value: f32, effective_date: NaiveDate) -> billing_plan_prices::Model {
+    let now = chrono::Utc::now().into();
+    billing_plan_prices::Model {
+        created_at: now,
+        updated_at: now,
+        id,
+        pid: uuid::Uuid::new_v4(),
+        billing_plan_id: 1,
+        value,
+        effective_date,
+    }
+}
+
+#[test]
+fn due_dates_fall_back_to_the_end_of_short_months() {
+    assert_eq!(
+        billing_plan(None).due_dates(date(2025, 4, 15)),
+        vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 31)]
+    );
+}
+
+#[test]
+fn due_dates_continue_after_what_was_billed_and_stop_at_the_end_date() {
+    let mut plan = billing_plan(Some(date(2025, 3, 31)));
+    assert_eq!(
+        plan.due_dates(date(2025, 12, 31)),
+        vec![date(2025, 4, 30), date(2025, 5, 31), date(2025, 6, 30)]
+    );
+
+    plan.cancelled_at = Some(date(2025, 5, 15));
+    assert_eq!(plan.due_dates(date(2025, 12, 31)), vec![date(2025, 4, 30)]);
+
+    plan.paused = true;
+    assert!(plan.due_dates(date(2025, 12, 31)).is_empty());
+}
+
+#[test]
+fn value_on_uses_the_latest_price_in_effect() {
+    let plan = billing_plan(None);
+    let prices = vec![
+        price(1, 1800.0, date(2025, 3, 1)),
+        price(2, 2000.0, date(2025, 5, 1)),
+    ];
+    assert_eq!(plan.value_on(&prices, date(2025, 2, 28)), 1500.0);
+    assert_eq!(plan.value_on(&prices, date(2025, 3, 31)), 1800.0);
+    assert_eq!(plan.value_on(&prices, date(2025, 5, 31)), 2000.0);
+}
+
+#[tokio::test]
+#[serial]
+async fn a_price_change_reprices_instalments_by_the_date_they_bill_for() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let billing_plan = billing_plans::Model::create(
+        db,
+        CreateNewBillingPlan {
+            order_pid: order.pid,
+            value: 1000.0,
+            day_of_month: 31,
+            start_date: date(2025, 5, 1),
+            end_date: Some(date(2025, 5, 31)),
+        },
+    )
+    .await
+    .unwrap();
+    // 2025-05-31 is a Saturday, so May's instalment is due in June
+    let payment = billing_plans::Model::generate_payments(
+        db,
+        date(2025, 5, 31),
+        &BusinessCalendar::default(),
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|payment| payment.billing_plan_id == Some(billing_plan.id))
+    .unwrap();
+    assert_eq!(payment.due_date, date(2025, 6, 2));
+    assert_eq!(payment.period_date, Some(date(2025, 5, 31)));
+
+    billing_plans::Model::change_price(
+        db,
+        billing_plan.pid,
+        ChangeBillingPlanPrice {
+            value: 2000.0,
+            effective_date: date(2025, 6, 1),
+        },
+    )
+    .await
+    .unwrap();
+    let payment = payments::Entity::find_by_id(payment.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(payment.value, 1000.0);
+
+    billing_plans::Model::change_price(
+        db,
+        billing_plan.pid,
+        ChangeBillingPlanPrice {
+            value: 1500.0,
+            effective_date: date(2025, 5, 15),
+        },
+    )
+    .await
+    .unwrap();
+    let payment = payments::Entity::find_by_id(payment.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(payment.value, 1500.0);
+}
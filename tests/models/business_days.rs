@@ -0,0 +1,146 @@
+use super::fixtures::date;
+use johandler::app::App;
+use johandler::business_days::{easter, national_holidays, BusinessCalendar, LocalHoliday};
+use loco_rs::testing;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+#[test]
+fn easter_and_the_holidays_that_move_with_it() {
+    assert_eq!(easter(2019), Some(date(2019, 4, 21)));
+    assert_eq!(easter(2024), Some(date(2024, 3, 31)));
+    assert_eq!(easter(2025), Some(date(2025, 4, 20)));
+    assert_eq!(easter(2026), Some(date(2026, 4, 5)));
+
+    let holidays = national_holidays(2025);
+    let named = |name: &str| {
+        holidays
+            .iter()
+            .filter(|holiday| holiday.name == name)
+            .map(|holiday| holiday.date)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(named("Carnaval"), vec![date(2025, 3, 3), date(2025, 3, 4)]);
+    assert_eq!(named("Paixão de Cristo"), vec![date(2025, 4, 18)]);
+    assert_eq!(named("Corpus Christi"), vec![date(2025, 6, 19)]);
+    assert_eq!(holidays.len(), 13);
+    assert!(holidays.windows(2).all(|pair| pair[0].date <= pair[1].date));
+}
+
+#[test]
+fn consciencia_negra_is_national_from_2024() {
+    let calendar = BusinessCalendar::default();
+    assert!(calendar.is_business_day(date(2023, 11, 20)));
+    assert!(!calendar.is_business_day(date(2024, 11, 20)));
+    assert_eq!(national_holidays(2023).len(), 12);
+}
+
+#[test]
+fn due_dates_move_to_the_next_business_day() {
+    let calendar = BusinessCalendar::default();
+    // a weekday
+    assert_eq!(
+        calendar.next_business_day(date(2025, 3, 10)),
+        date(2025, 3, 10)
+    );
+    // a Saturday before Carnival
+    assert_eq!(
+        calendar.next_business_day(date(2025, 3, 1)),
+        date(2025, 3, 5)
+    );
+    // Good Friday, then the weekend and Tiradentes
+    assert_eq!(
+        calendar.next_business_day(date(2025, 4, 18)),
+        date(2025, 4, 22)
+    );
+    // Christmas and the new year
+    assert_eq!(
+        calendar.next_business_day(date(2026, 12, 25)),
+        date(2026, 12, 28)
+    );
+}
+
+#[test]
+fn local_holidays_come_from_the_settings() {
+    let settings = serde_json::json!({
+        "business_days": {
+            "holidays": [
+                { "date": "07-09", "name": "Revolução Constitucionalista" },
+                { "date": "2025-11-21", "name": "Ponte" },
+                { "date": "not a date", "name": "Ignored" },
+            ]
+        }
+    });
+    let calendar = BusinessCalendar::from_settings(Some(&settings));
+    assert_eq!(
+        calendar.holidays,
+        vec![
+            LocalHoliday {
+                date: "07-09".to_string(),
+                name: "Revolução Constitucionalista".to_string(),
+            },
+            LocalHoliday {
+                date: "2025-11-21".to_string(),
+                name: "Ponte".to_string(),
+            },
+            LocalHoliday {
+                date: "not a date".to_string(),
+                name: "Ignored".to_string(),
+            },
+        ]
+    );
+
+    assert_eq!(
+        calendar.next_business_day(date(2025, 7, 9)),
+        date(2025, 7, 10)
+    );
+    assert_eq!(
+        calendar.next_business_day(date(2026, 7, 9)),
+        date(2026, 7, 10)
+    );
+    // the one-off holiday runs into the weekend
+    assert_eq!(
+        calendar.next_business_day(date(2025, 11, 21)),
+        date(2025, 11, 24)
+    );
+    assert!(!calendar.is_business_day(date(2026, 11, 20)));
+    assert!(calendar.is_business_day(date(2026, 11, 23)));
+
+    let holiday = calendar.holiday(date(2025, 7, 9)).unwrap();
+    assert_eq!(holiday.name, "Revolução Constitucionalista");
+    assert!(!holiday.national);
+    assert_eq!(calendar.holidays(2025).len(), 15);
+    assert_eq!(calendar.holidays(2026).len(), 14);
+
+    assert_eq!(
+        BusinessCalendar::from_settings(None),
+        BusinessCalendar::default()
+    );
+}
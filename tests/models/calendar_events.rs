@@ -0,0 +1,191 @@
+use johandler::app::App;
+use johandler::documents::ics::{self, IcsEvent, IcsTime};
+use johandler::models::calendar_events::firm_offset;
+use johandler::reports::aging::{AgingBucket, AgingItem};
+use johandler::views::calendar_events::{CalendarEventView, UpcomingItem};
+use loco_rs::testing;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn event(pid: u128, kind: &str, starts_at: &str) -> CalendarEventView {
+    CalendarEventView {
+        pid: uuid::Uuid::from_u128(pid),
+        kind: kind.to_string(),
+        title: format!("{kind} {pid}"),
+        starts_at: chrono::DateTime::parse_from_rfc3339(starts_at).unwrap(),
+        ends_at: None,
+        location: None,
+        description: None,
+        completed: false,
+        order_pid: None,
+        lawsuit_pid: None,
+        cnj_number: None,
+        user_pid: None,
+        user_name: None,
+    }
+}
+
+fn instalment(pid: u128, due_date: &str) -> AgingItem {
+    let due_date = due_date.parse().unwrap();
+    AgingItem {
+        payment_pid: uuid::Uuid::from_u128(pid),
+        order_pid: uuid::Uuid::from_u128(100),
+        due_date,
+        effective_due_date: due_date,
+        days_late: 0,
+        bucket: AgingBucket::Current,
+        amount: 1500.0,
+        client_pid: uuid::Uuid::from_u128(200),
+        client: "Maria".to_string(),
+        client_document: None,
+        seller_pid: uuid::Uuid::from_u128(300),
+        seller: "João".to_string(),
+        partner_pid: None,
+        partner: None,
+        process_pid: uuid::Uuid::from_u128(400),
+        process: "Revisional".to_string(),
+    }
+}
+
+#[test]
+fn the_firm_offset_comes_from_the_settings() {
+    assert_eq!(firm_offset(None).local_minus_utc(), -3 * 3600);
+    let settings = serde_json::json!({ "calendar": { "utc_offset": -4 } });
+    assert_eq!(firm_offset(Some(&settings)).local_minus_utc(), -4 * 3600);
+    let settings = serde_json::json!({ "calendar": { "utc_offset": 5.5 } });
+    assert_eq!(firm_offset(Some(&settings)).local_minus_utc(), 19800);
+}
+
+#[test]
+fn upcoming_items_are_merged_in_the_order_they_happen() {
+    let offset = firm_offset(None);
+    let events = vec![
+        event(2, "meeting", "2025-03-11T14:00:00-03:00"),
+        event(1, "hearing", "2025-03-10T13:00:00Z"),
+        // late at night in Brasília, the next day in UTC
+        event(3, "deadline", "2025-03-10T23:59:00-03:00"),
+    ];
+    let instalments = vec![
+        instalment(10, "2025-03-10"),
+        instalment(11, "2025-03-09"),
+        instalment(12, "2025-03-20"),
+    ];
+    let from = "2025-03-10".parse().unwrap();
+    let until = Some("2025-03-15".parse().unwrap());
+    let items = UpcomingItem::merge(events, &instalments, from, until, offset);
+
+    assert_eq!(
+        items
+            .iter()
+            .map(|item| (
+                item.kind.as_str(),
+                item.pid.as_u128(),
+                item.date.to_string()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("hearing", 1, "2025-03-10".to_string()),
+            ("deadline", 3, "2025-03-10".to_string()),
+            ("instalment", 10, "2025-03-10".to_string()),
+            ("meeting", 2, "2025-03-11".to_string()),
+        ]
+    );
+    assert_eq!(
+        items[0].starts_at.unwrap().to_rfc3339(),
+        "2025-03-10T10:00:00-03:00"
+    );
+    assert_eq!(items[2].title, "Instalment of Revisional - Maria");
+    assert_eq!(items[2].value, Some(1500.0));
+}
+
+#[test]
+fn ics_text_is_escaped_and_folded() {
+    assert_eq!(ics::escape("a,b;c\\d\r\ne"), r"a\,b\;c\\d\ne");
+
+    let folded = ics::fold(&format!("SUMMARY:{}", "é".repeat(40)));
+    let lines = folded
+        .trim_end_matches("\r\n")
+        .split("\r\n")
+        .collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|line| line.len() <= 75));
+    assert!(lines[1].starts_with(' '));
+    assert_eq!(
+        lines.concat().replace(" é", "é"),
+        format!("SUMMARY:{}", "é".repeat(40))
+    );
+}
+
+#[test]
+fn ics_calendars_have_timed_and_whole_day_events() {
+    let stamp = chrono::DateTime::parse_from_rfc3339("2025-03-01T12:00:00Z")
+        .unwrap()
+        .to_utc();
+    let events = vec![
+        IcsEvent {
+            uid: "hearing-1@johandler".to_string(),
+            stamp,
+            start: IcsTime::At(
+                chrono::DateTime::parse_from_rfc3339("2025-03-10T10:00:00-03:00")
+                    .unwrap()
+                    .to_utc(),
+            ),
+            end: None,
+            summary: "Hearing, room 2".to_string(),
+            location: Some("Fórum João Mendes".to_string()),
+            description: None,
+            category: Some("HEARING".to_string()),
+            completed: true,
+        },
+        IcsEvent {
+            uid: "instalment-2@johandler".to_string(),
+            stamp,
+            start: IcsTime::Day("2025-03-12".parse().unwrap()),
+            end: None,
+            summary: "Instalment".to_string(),
+            location: None,
+            description: Some("Owed: 1500.00".to_string()),
+            category: None,
+            completed: false,
+        },
+    ];
+    let calendar = ics::calendar("Johandler", &events);
+
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+    assert!(calendar.contains("DTSTAMP:20250301T120000Z\r\n"));
+    assert!(calendar.contains("DTSTART:20250310T130000Z\r\n"));
+    assert!(calendar.contains("SUMMARY:Hearing\\, room 2\r\n"));
+    assert!(calendar.contains("LOCATION:Fórum João Mendes\r\n"));
+    assert!(calendar.contains("TRANSP:TRANSPARENT\r\n"));
+    assert!(calendar.contains("DTSTART;VALUE=DATE:20250312\r\n"));
+    assert!(calendar.contains("DESCRIPTION:Owed: 1500.00\r\n"));
+}
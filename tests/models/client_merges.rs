@@ -0,0 +1,100 @@
+use johandler::app::App;
+use johandler::models::client_merges::{
+    duplicate_score, find_candidates, normalize_name, phone_key, ClientKeys,
+};
+use loco_rs::testing;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn keys(pid: u128, name: &str, emails: &[&str], phones: &[&str]) -> ClientKeys {
+    ClientKeys {
+        pid: uuid::Uuid::from_u128(pid),
+        name: normalize_name(name),
+        document: None,
+        emails: emails.iter().map(ToString::to_string).collect(),
+        phones: phones.iter().filter_map(|phone| phone_key(phone)).collect(),
+    }
+}
+
+#[test]
+fn names_compare_without_accents_or_punctuation() {
+    assert_eq!(
+        normalize_name("  José  da Conceição. "),
+        "jose da conceicao"
+    );
+    assert_eq!(normalize_name("JOÃO D'ÁVILA"), "joao d avila");
+    assert_eq!(
+        phone_key("+55 (11) 99999-0000").as_deref(),
+        Some("99990000")
+    );
+    assert_eq!(phone_key("99990000").as_deref(), Some("99990000"));
+    assert_eq!(phone_key("1234"), None);
+}
+
+#[test]
+fn duplicates_are_scored_by_what_they_share() {
+    let maria = keys(1, "Maria Souza", &["maria@example.com"], &["11999990000"]);
+    let same_email = keys(2, "Maria S.", &["maria@example.com"], &[]);
+    let same_phone_and_name = keys(3, "MARIA SOUZA", &[], &["+55 11 99999-0000"]);
+    let middle_name = keys(4, "Maria Aparecida Souza", &[], &[]);
+    let stranger = keys(5, "Pedro Lima", &["pedro@example.com"], &["1133330000"]);
+
+    assert_eq!(duplicate_score(&maria, &same_email).0, 40);
+    assert_eq!(
+        duplicate_score(&maria, &same_phone_and_name),
+        (70, vec!["phone".to_string(), "name".to_string()])
+    );
+    assert_eq!(
+        duplicate_score(&maria, &middle_name),
+        (20, vec!["similar name".to_string()])
+    );
+    assert_eq!(duplicate_score(&maria, &stranger).0, 0);
+
+    let mut same_document = stranger.clone();
+    same_document.document = Some("52998224725".to_string());
+    let mut other = keys(6, "Pedro de Lima", &[], &[]);
+    other.document = Some("52998224725".to_string());
+    assert_eq!(duplicate_score(&same_document, &other).0, 100);
+}
+
+#[test]
+fn candidates_keep_the_first_registered_client() {
+    let clients = [
+        keys(1, "Maria Souza", &["maria@example.com"], &[]),
+        keys(2, "Pedro Lima", &[], &[]),
+        keys(3, "Maria Souza", &["maria@example.com"], &[]),
+    ];
+    let candidates = find_candidates(&clients, 50);
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].primary_pid, uuid::Uuid::from_u128(1));
+    assert_eq!(candidates[0].secondary_pid, uuid::Uuid::from_u128(3));
+    assert_eq!(candidates[0].score, 80);
+}
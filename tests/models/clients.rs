@@ -1,5 +1,16 @@
+use super::fixtures::{self, date};
 use johandler::app::App;
+use johandler::models::_entities::{client_addresses, client_contacts, clients};
+use johandler::models::client_addresses::{check_addresses, format_cep, normalize_cep, NewAddress};
+use johandler::models::client_contacts::{
+    check_contacts, normalize_contact, ContactKind, NewContact,
+};
+use johandler::models::clients::{format_document, normalize_document, CreateNewClient, TaxIdKind};
+use johandler::views::clients::{ClientStatementView, StatementMovementKind};
+use johandler::views::orders::GetOrderReturn;
 use loco_rs::testing;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ColumnTrait, ConnectionTrait, Database, EntityTrait, QueryFilter, QueryOrder};
 use serial_test::serial;
 
 macro_rules! configure_insta {
@@ -29,3 +40,365 @@ async fn test_model() {
     // snapshot the result:
     // assert_debug_snapshot!(item);
 }
+
+fn client() -> clients::Model {
+    let now = chrono::Utc::now().into();
+    clients::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        name: "Maria".to_string(),
+        contact: "Maria".to_string(),
+        partner_id: None,
+        document: None,
+    }
+}
+
+fn order() -> GetOrderReturn {
+    let payment = |pid: u128, due_date: &str, payment_date: Option<&str>, postponed: &[&str]| {
+        serde_json::json!({
+            "pid": uuid::Uuid::from_u128(pid),
+            "value": 500.0,
+            "payment_date": payment_date,
+            "due_date": due_date,
+            "payment_method": payment_date.map(|_| "PIX"),
+            "currency": null,
+            "postponed_payment": !postponed.is_empty(),
+            "open": payment_date.is_none(),
+            "postponed_dates": postponed,
+        })
+    };
+    serde_json::from_value(serde_json::json!({
+        "pid": uuid::Uuid::from_u128(9),
+        "open": true,
+        "fee": 1500.0,
+        "fees": [],
+        "payout": null,
+        "partner_fee": null,
+        "seller": { "pid": uuid::Uuid::from_u128(8), "name": "João" },
+        "client": {
+            "pid": uuid::Uuid::from_u128(7),
+            "name": "Maria",
+            "contact": "Maria",
+            "phone": null,
+            "phone2": null,
+            "email": null,
+            "partner": null,
+        },
+        "process": { "pid": uuid::Uuid::from_u128(6), "case_type": "Trabalhista" },
+        "payments": [
+            payment(1, "2025-01-10", Some("2025-01-08"), &[]),
+            payment(2, "2025-02-10", None, &["2025-03-20"]),
+            payment(3, "2025-03-10", None, &[]),
+        ],
+        "credit_notes": [{
+            "pid": uuid::Uuid::from_u128(5),
+            "order_fee_pid": null,
+            "payment_pid": uuid::Uuid::from_u128(3),
+            "value": 100.0,
+            "reason": "Desconto",
+            "credit_date": "2025-03-01",
+        }],
+        "outstanding_balance": 900.0,
+        "refunds": [],
+        "net_received": 500.0,
+        "expenses": [],
+        "billing_plans": [],
+        "time_entries": [],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn statement_runs_the_balance_as_of_a_date() {
+    let statement = ClientStatementView::new(&client(), &[order()], date(2025, 3, 15));
+
+    let kinds = statement
+        .movements
+        .iter()
+        .map(|movement| (movement.date, movement.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (date(2025, 1, 8), StatementMovementKind::Payment),
+            (date(2025, 1, 10), StatementMovementKind::Instalment),
+            (date(2025, 3, 1), StatementMovementKind::CreditNote),
+            (date(2025, 3, 10), StatementMovementKind::Instalment),
+        ]
+    );
+    let balances = statement
+        .movements
+        .iter()
+        .map(|movement| movement.balance)
+        .collect::<Vec<_>>();
+    assert_eq!(balances, vec![-500.0, 0.0, -100.0, 400.0]);
+    // the postponed instalment is not due yet
+    assert!((statement.totals.balance - 400.0).abs() < 0.01);
+    assert!((statement.totals.not_yet_due - 500.0).abs() < 0.01);
+    assert_eq!(
+        statement.orders[0].instalments[2].effective_due_date,
+        date(2025, 3, 20)
+    );
+    assert!((statement.orders[0].instalments[1].credited - 100.0).abs() < 0.01);
+}
+
+#[test]
+fn statement_leaves_out_later_payments() {
+    let statement = ClientStatementView::new(&client(), &[order()], date(2025, 1, 5));
+
+    assert!(statement.movements.is_empty());
+    assert!(statement.orders[0].instalments.iter().all(|i| i.open));
+    assert!((statement.totals.not_yet_due - 1500.0).abs() < 0.01);
+}
+
+#[test]
+fn documents_are_checked_and_stripped_of_their_mask() {
+    assert_eq!(
+        normalize_document("529.982.247-25").as_deref(),
+        Some("52998224725")
+    );
+    assert_eq!(
+        normalize_document("11.222.333/0001-81").as_deref(),
+        Some("11222333000181")
+    );
+    assert_eq!(
+        normalize_document(" 11144477735 ").as_deref(),
+        Some("11144477735")
+    );
+    // wrong check digits
+    assert_eq!(normalize_document("529.982.247-24"), None);
+    assert_eq!(normalize_document("11.222.333/0001-80"), None);
+    // a single repeated digit, letters and wrong lengths
+    assert_eq!(normalize_document("111.111.111-11"), None);
+    assert_eq!(normalize_document("5299822472a"), None);
+    assert_eq!(normalize_document("5299822472"), None);
+}
+
+#[test]
+fn documents_are_masked_by_kind() {
+    assert_eq!(TaxIdKind::of("52998224725"), Some(TaxIdKind::Cpf));
+    assert_eq!(TaxIdKind::of("11222333000181"), Some(TaxIdKind::Cnpj));
+    assert_eq!(format_document("52998224725"), "529.982.247-25");
+    assert_eq!(format_document("11222333000181"), "11.222.333/0001-81");
+    assert_eq!(format_document("123"), "123");
+}
+
+#[test]
+fn contacts_are_normalized_by_kind() {
+    assert_eq!(
+        normalize_contact(ContactKind::Phone, "+55 (11) 99999-0000").as_deref(),
+        Some("5511999990000")
+    );
+    assert_eq!(
+        normalize_contact(ContactKind::Email, " Maria@Example.com ").as_deref(),
+        Some("maria@example.com")
+    );
+    assert_eq!(normalize_contact(ContactKind::Phone, "1234"), None);
+    assert_eq!(normalize_contact(ContactKind::Phone, "11 9999a0000"), None);
+    assert_eq!(normalize_contact(ContactKind::Email, "maria@example"), None);
+    assert_eq!(normalize_contact(ContactKind::Email, "@example.com"), None);
+}
+
+#[test]
+fn first_contact_of_each_kind_becomes_primary() {
+    let contact = |kind: ContactKind, value: &str, primary: bool| NewContact {
+        kind,
+        value: value.to_string(),
+        primary,
+        whatsapp: false,
+        notes: None,
+    };
+    let contacts = check_contacts(vec![
+        contact(ContactKind::Phone, "11 99999-0000", false),
+        contact(ContactKind::Phone, "11 3333-0000", true),
+        contact(ContactKind::Email, "maria@example.com", false),
+    ])
+    .unwrap();
+    let primaries = contacts
+        .iter()
+        .map(|contact| contact.primary)
+        .collect::<Vec<_>>();
+    assert_eq!(primaries, vec![false, true, true]);
+
+    assert!(check_contacts(vec![
+        contact(ContactKind::Email, "maria@example.com", true),
+        contact(ContactKind::Email, "maria@example.org", true),
+    ])
+    .is_err());
+}
+
+#[test]
+fn addresses_need_a_valid_cep_and_state() {
+    assert_eq!(normalize_cep("01310-100").as_deref(), Some("01310100"));
+    assert_eq!(normalize_cep("01310100").as_deref(), Some("01310100"));
+    assert_eq!(normalize_cep("0131-0100"), None);
+    assert_eq!(normalize_cep("1310100"), None);
+    assert_eq!(normalize_cep("00000-000"), None);
+    assert_eq!(format_cep("01310100"), "01310-100");
+
+    let address = |cep: &str, state: &str| NewAddress {
+        cep: cep.to_string(),
+        street: "Avenida Paulista".to_string(),
+        number: "1000".to_string(),
+        complement: None,
+        district: Some("Bela Vista".to_string()),
+        city: "São Paulo".to_string(),
+        state: state.to_string(),
+        primary: false,
+    };
+    let addresses = check_addresses(vec![address("01310-100", "sp")]).unwrap();
+    assert_eq!(addresses[0].cep, "01310100");
+    assert_eq!(addresses[0].state, "SP");
+    assert!(addresses[0].primary);
+    assert!(check_addresses(vec![address("01310-10", "SP")]).is_err());
+    assert!(check_addresses(vec![address("01310-100", "XX")]).is_err());
+}
+
+fn client_edit(document: Option<&str>) -> CreateNewClient {
+    CreateNewClient {
+        name: "João da Silva".to_string(),
+        contact: "x".to_string(),
+        partner_pid: None,
+        document: document.map(ToString::to_string),
+        contacts: None,
+        addresses: None,
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn editing_a_client_without_its_document_keeps_it() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let pid = fixtures::client(db).await.pid;
+
+    let client = clients::Model::update(db, pid, client_edit(Some("529.982.247-25")))
+        .await
+        .unwrap();
+    assert_eq!(client.document.as_deref(), Some("52998224725"));
+
+    let client = clients::Model::update(db, pid, client_edit(None))
+        .await
+        .unwrap();
+    assert_eq!(client.document.as_deref(), Some("52998224725"));
+
+    let client = clients::Model::update(db, pid, client_edit(Some("")))
+        .await
+        .unwrap();
+    assert_eq!(client.document, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn editing_a_client_without_contacts_or_addresses_keeps_them() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let pid = fixtures::client(db).await.pid;
+    let edit = CreateNewClient {
+        contacts: Some(vec![NewContact {
+            kind: ContactKind::Email,
+            value: "maria@example.com".to_string(),
+            primary: true,
+            whatsapp: false,
+            notes: None,
+        }]),
+        addresses: Some(vec![NewAddress {
+            cep: "01310-100".to_string(),
+            street: "Avenida Paulista".to_string(),
+            number: "1000".to_string(),
+            complement: None,
+            district: None,
+            city: "São Paulo".to_string(),
+            state: "SP".to_string(),
+            primary: true,
+        }]),
+        ..client_edit(None)
+    };
+    let client = clients::Model::update(db, pid, edit).await.unwrap();
+
+    let client = clients::Model::update(db, client.pid, client_edit(None))
+        .await
+        .unwrap();
+    let contacts = client_contacts::Model::find_by_client_id(db, client.id)
+        .await
+        .unwrap();
+    let addresses = client_addresses::Model::find_by_client_id(db, client.id)
+        .await
+        .unwrap();
+    assert_eq!(contacts.len(), 1);
+    assert_eq!(addresses.len(), 1);
+
+    let edit = CreateNewClient {
+        contacts: Some(vec![]),
+        addresses: Some(vec![]),
+        ..client_edit(None)
+    };
+    let client = clients::Model::update(db, client.pid, edit).await.unwrap();
+    assert!(client_contacts::Model::find_by_client_id(db, client.id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(client_addresses::Model::find_by_client_id(db, client.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn legacy_contacts_are_moved_the_way_new_ones_are_stored() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    // every migration before the one moving the contacts
+    Migrator::up(&db, Some(34)).await.unwrap();
+    db.execute_unprepared(
+        "INSERT INTO clients (created_at, updated_at, pid, name, contact, phone, phone2, email) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'0f8fad5bd9cb469fa16570867728950e', 'João da Silva', 'João', \
+         'ramal 12', '(11) 98765-4321', ' Joao@Example.COM '), \
+         ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'1f8fad5bd9cb469fa16570867728950e', 'Maria Souza', 'Maria', \
+         'falar com a filha', NULL, '')",
+    )
+    .await
+    .unwrap();
+    Migrator::up(&db, Some(1)).await.unwrap();
+
+    let contacts = client_contacts::Entity::find()
+        .filter(client_contacts::Column::ClientId.eq(1))
+        .order_by_asc(client_contacts::Column::Id)
+        .all(&db)
+        .await
+        .unwrap();
+    let moved = contacts
+        .iter()
+        .map(|contact| {
+            (
+                contact.kind.as_str(),
+                contact.value.as_str(),
+                contact.primary,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        moved,
+        vec![
+            ("phone", "11987654321", true),
+            ("email", "joao@example.com", true),
+        ]
+    );
+    assert_eq!(
+        contacts[0].notes.as_deref(),
+        Some("Not recognised when moved: phone: ramal 12")
+    );
+
+    let without_contacts = clients::Entity::find_by_id(2)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        without_contacts.contact,
+        "Maria (Not recognised when moved: phone: falar com a filha)"
+    );
+}
@@ -0,0 +1,186 @@
+use johandler::documents::contract::{self, Block, ContractData, TableKind, DEFAULT_TEMPLATE};
+use johandler::documents::docx::crc32;
+use johandler::documents::Letterhead;
+use johandler::models::_entities::{attachments, contract_templates, contracts};
+use johandler::models::attachments::AttachmentTarget;
+use johandler::models::contract_templates::{CreateNewContractTemplate, UpdateContractTemplate};
+use loco_rs::storage::{drivers, Storage};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, Database};
+
+#[test]
+fn templates_are_checked() {
+    assert!(contract::check(DEFAULT_TEMPLATE).is_ok());
+    assert!(contract::check("Cliente: {{ client.name }}\n{{ fees }}").is_ok());
+    assert!(contract::check("Cliente: {{ client.nickname }}").is_err());
+    assert!(contract::check("Cliente: {{ client.name").is_err());
+}
+
+#[test]
+fn templates_are_rendered_with_the_order_data() {
+    let mut data = ContractData::default();
+    data.values
+        .insert("client.name".to_string(), "João da Silva".to_string());
+    data.fees = vec![vec![
+        "Êxito".to_string(),
+        "percentual".to_string(),
+        "R$ 1.000,00".to_string(),
+        String::new(),
+    ]];
+    let issued_on = chrono::NaiveDate::from_ymd_opt(2025, 8, 11).unwrap();
+    let contract = contract::render(
+        "# Contrato\n\nCONTRATANTE: {{ client.name }}, em {{ date }}.\n{{ fees }}",
+        &data,
+        issued_on,
+    )
+    .unwrap();
+    assert_eq!(contract.blocks[0], Block::Heading("Contrato".to_string()));
+    assert_eq!(
+        contract.blocks[1],
+        Block::Paragraph("CONTRATANTE: João da Silva, em 11/08/2025.".to_string())
+    );
+    assert_eq!(
+        contract.blocks[2],
+        Block::Table {
+            kind: TableKind::Fees,
+            rows: data.fees.clone(),
+        }
+    );
+
+    let pdf = contract.to_pdf(&Letterhead::default());
+    assert!(pdf.starts_with(b"%PDF-"));
+    let docx = contract.to_docx();
+    assert!(docx.starts_with(b"PK\x03\x04"));
+    let docx = String::from_utf8_lossy(&docx);
+    assert!(docx.contains("word/document.xml"));
+    assert!(docx.contains("CONTRATANTE: João da Silva"));
+}
+
+#[test]
+fn zip_entries_are_checked_with_crc32() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b""), 0);
+}
+
+#[tokio::test]
+async fn contracts_get_a_new_version_when_the_order_changes() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    for statement in [
+        "INSERT INTO clients (created_at, updated_at, pid, name, contact) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'0f8fad5bd9cb469fa16570867728950e', 'João da Silva', 'x')",
+        "INSERT INTO sellers (created_at, updated_at, pid, name) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'1f8fad5bd9cb469fa16570867728950e', 'Maria')",
+        "INSERT INTO processes (created_at, updated_at, pid, case_type) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'2f8fad5bd9cb469fa16570867728950e', 'Trabalhista')",
+        "INSERT INTO orders (created_at, updated_at, pid, client_id, process_id, open, \
+         payout, fee, seller_id) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'3f8fad5bd9cb469fa16570867728950e', 1, 1, true, 0, 1000, 1)",
+    ] {
+        db.execute_unprepared(statement).await.unwrap();
+    }
+    let process_pid = uuid::Uuid::parse_str("2f8fad5b-d9cb-469f-a165-70867728950e").unwrap();
+    let order_pid = uuid::Uuid::parse_str("3f8fad5b-d9cb-469f-a165-70867728950e").unwrap();
+    let storage = Storage::single(drivers::mem::new());
+    let letterhead = Letterhead::default();
+    let issued_on = chrono::NaiveDate::from_ymd_opt(2025, 8, 11).unwrap();
+
+    let first = contracts::Model::generate(&db, &storage, &letterhead, None, order_pid, issued_on)
+        .await
+        .unwrap();
+    assert_eq!(first.version, 1);
+    assert_eq!(first.template_id, None);
+
+    // nothing changed, even a day later
+    let again = contracts::Model::generate(
+        &db,
+        &storage,
+        &letterhead,
+        None,
+        order_pid,
+        issued_on.succ_opt().unwrap(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(again.pid, first.pid);
+
+    assert!(contract_templates::Model::create(
+        &db,
+        CreateNewContractTemplate {
+            process_pid,
+            name: "Trabalhista".to_string(),
+            body: "Cliente: {{ client.nickname }}".to_string(),
+        },
+    )
+    .await
+    .is_err());
+    let template = contract_templates::Model::create(
+        &db,
+        CreateNewContractTemplate {
+            process_pid,
+            name: "Trabalhista".to_string(),
+            body: "# Contrato\n\nCliente: {{ client.name }}\n{{ fees }}".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    let second = contracts::Model::generate(&db, &storage, &letterhead, None, order_pid, issued_on)
+        .await
+        .unwrap();
+    assert_eq!(second.version, 2);
+    assert_eq!(second.template_id, Some(template.id));
+
+    // the fee of the order changes, so the nightly refresh writes version 3
+    db.execute_unprepared("UPDATE orders SET fee = 1500 WHERE id = 1")
+        .await
+        .unwrap();
+    let refreshed = contracts::Model::refresh_all(&db, &storage, &letterhead, issued_on)
+        .await
+        .unwrap();
+    assert_eq!(refreshed.len(), 1);
+    assert_eq!(refreshed[0].version, 3);
+    assert!(
+        contracts::Model::refresh_all(&db, &storage, &letterhead, issued_on)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    contract_templates::Model::update(
+        &db,
+        template.pid,
+        UpdateContractTemplate {
+            name: "Trabalhista".to_string(),
+            body: "Cliente: {{ client.name }}\n{{ instalments }}".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_ne!(
+        contracts::Model::current_fingerprint(&db, &letterhead, 1)
+            .await
+            .unwrap(),
+        refreshed[0].fingerprint
+    );
+
+    let versions = contracts::Model::find_by_order_id(&db, 1).await.unwrap();
+    assert_eq!(
+        versions
+            .iter()
+            .map(|contract| contract.version)
+            .collect::<Vec<_>>(),
+        vec![3, 2, 1]
+    );
+    let files = attachments::Model::find_by_target(&db, AttachmentTarget::Order(1))
+        .await
+        .unwrap();
+    assert_eq!(files.len(), 6);
+    assert!(files.iter().all(|file| file.category == "contract"));
+    assert!(files
+        .iter()
+        .any(|file| file.file_name == "Contrato - Trabalhista - João da Silva - v3.docx"));
+}
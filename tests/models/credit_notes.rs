@@ -0,0 +1,104 @@
+use super::fixtures::{self, date, payment};
+use johandler::app::App;
+use johandler::models::_entities::{credit_notes, orders, payments};
+use johandler::models::credit_notes::{outstanding_balance, CreateNewCreditNote};
+use loco_rs::testing;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn credit_note(value: f32, payment_id: Option<i32>) -> credit_notes::Model {
+    let now = chrono::Utc::now().into();
+    credit_notes::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        order_id: 1,
+        order_fee_id: None,
+        payment_id,
+        value,
+        reason: "discount".to_string(),
+        credit_date: chrono::NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+    }
+}
+
+#[test]
+fn credit_notes_reduce_outstanding_balance() {
+    let payments = vec![
+        payment(1, 100.0, true),
+        payment(2, 100.0, true),
+        payment(3, 100.0, false),
+    ];
+    assert!((outstanding_balance(&payments, &[]) - 200.0).abs() < f32::EPSILON);
+
+    let credit_notes = vec![credit_note(30.0, Some(1)), credit_note(20.0, None)];
+    assert!((outstanding_balance(&payments, &credit_notes) - 150.0).abs() < f32::EPSILON);
+
+    // credits allocated to settled instalments do not change what is owed
+    let credit_notes = vec![credit_note(50.0, Some(3))];
+    assert!((outstanding_balance(&payments, &credit_notes) - 200.0).abs() < f32::EPSILON);
+
+    // an instalment cannot be credited below zero
+    let credit_notes = vec![credit_note(150.0, Some(1))];
+    assert!((outstanding_balance(&payments, &credit_notes) - 100.0).abs() < f32::EPSILON);
+}
+
+#[tokio::test]
+#[serial]
+async fn credit_notes_outlive_their_payment_but_keep_the_order() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let payment_pid = order.payments[0].pid.unwrap();
+    let credit_note = credit_notes::Model::create(
+        db,
+        CreateNewCreditNote {
+            order_pid: order.pid,
+            order_fee_pid: None,
+            payment_pid: Some(payment_pid),
+            value: 100.0,
+            reason: "discount".to_string(),
+            credit_date: date(2025, 4, 1),
+        },
+    )
+    .await
+    .unwrap();
+
+    payments::Model::delete(db, payment_pid).await.unwrap();
+    let credit_note = credit_notes::Model::find_by_pid(db, credit_note.pid)
+        .await
+        .unwrap();
+    assert_eq!(credit_note.payment_id, None);
+
+    assert!(orders::Model::delete(db, order.pid).await.is_err());
+    assert!(credit_notes::Model::find_by_pid(db, credit_note.pid)
+        .await
+        .is_ok());
+}
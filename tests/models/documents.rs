@@ -0,0 +1,233 @@
+use super::fixtures;
+use johandler::app::App;
+use johandler::documents::format_money;
+use johandler::documents::journal::{self, ChartOfAccounts, JournalRow};
+use johandler::documents::pdf::{Font, Pdf};
+use johandler::documents::pix::{self, PixSettings};
+use johandler::documents::qrcode::QrCode;
+use johandler::models::_entities::{documents, orders, payments};
+use johandler::models::documents::DocumentKind;
+use loco_rs::testing;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+#[test]
+fn document_code_is_prefixed_by_kind() {
+    let now = chrono::Utc::now().into();
+    let mut document = documents::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        kind: DocumentKind::Receipt.as_str().to_string(),
+        number: 42,
+        order_id: 1,
+        payment_id: Some(1),
+    };
+    assert_eq!(document.code(), "REC-000042");
+    document.kind = DocumentKind::Statement.as_str().to_string();
+    assert_eq!(document.code(), "EXT-000042");
+}
+
+#[tokio::test]
+#[serial]
+async fn receipts_are_issued_once_and_keep_their_order_and_payment() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let payment_pid = order.payments[0].pid.unwrap();
+    let payment = payments::Entity::find()
+        .filter(payments::Column::Pid.eq(payment_pid))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let receipt = documents::Model::issue(
+        db,
+        DocumentKind::Receipt,
+        payment.order_id,
+        Some(payment.id),
+    )
+    .await
+    .unwrap();
+    let downloaded_again = documents::Model::issue(
+        db,
+        DocumentKind::Receipt,
+        payment.order_id,
+        Some(payment.id),
+    )
+    .await
+    .unwrap();
+    assert_eq!(downloaded_again.id, receipt.id);
+    assert_eq!(downloaded_again.number, receipt.number);
+
+    let statement = documents::Model::issue(db, DocumentKind::Statement, payment.order_id, None)
+        .await
+        .unwrap();
+    let next_statement =
+        documents::Model::issue(db, DocumentKind::Statement, payment.order_id, None)
+            .await
+            .unwrap();
+    assert_eq!(next_statement.number, statement.number + 1);
+
+    assert!(payments::Model::delete(db, payment_pid).await.is_err());
+    assert!(orders::Model::delete(db, order.pid).await.is_err());
+    assert!(documents::Model::find_receipt(db, payment.id)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn money_is_formatted_in_reais() {
+    assert_eq!(format_money(0.5), "R$ 0,50");
+    assert_eq!(format_money(1234.56), "R$ 1.234,56");
+    assert_eq!(format_money(1_500_000.0), "R$ 1.500.000,00");
+    assert_eq!(format_money(-80.0), "-R$ 80,00");
+}
+
+#[test]
+fn pdf_is_a_complete_file() {
+    let mut pdf = Pdf::new();
+    pdf.line(12.0, Font::Bold, "Recibo (cópia)");
+    for _ in 0..80 {
+        pdf.row(
+            9.0,
+            Font::Regular,
+            &[(0.0, "10/01/2025"), (90.0, "R$ 500,00")],
+        );
+    }
+    let file = pdf.finish();
+    let text = String::from_utf8_lossy(&file);
+    assert!(file.starts_with(b"%PDF-1.4"));
+    assert!(text.ends_with("%%EOF\n"));
+    assert!(text.contains("/Count 2"));
+    assert!(text.contains("(Recibo \\(c\u{fffd}pia\\)) Tj"));
+}
+
+#[test]
+fn pix_checksum_is_crc16_ccitt() {
+    assert_eq!(pix::crc16(b"123456789"), 0x29B1);
+    let reference = "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000\
+                     5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***6304";
+    assert_eq!(format!("{:04X}", pix::crc16(reference.as_bytes())), "1D3D");
+}
+
+#[test]
+fn pix_payload_charges_the_instalment() {
+    let settings = PixSettings {
+        key: "contato@johandler.com.br".to_string(),
+        merchant_name: "Johandler Advocacia e Consultoria".to_string(),
+        merchant_city: "São Paulo".to_string(),
+    };
+    let pid = uuid::Uuid::parse_str("0f8fad5b-d9cb-469f-a165-70867728950e").unwrap();
+    let txid = pix::txid(pid);
+    assert_eq!(txid, "0F8FAD5BD9CB469FA16570867");
+
+    let payload = pix::payload(&settings, 1500.5, &txid);
+    assert!(payload.starts_with(
+        "000201\
+         26460014br.gov.bcb.pix0124contato@johandler.com.br\
+         52040000530398654071500.505802BR\
+         5925JOHANDLER ADVOCACIA E CON6009SAO PAULO\
+         622905250F8FAD5BD9CB469FA16570867\
+         6304"
+    ));
+    let (content, checksum) = payload.split_at(payload.len() - 4);
+    assert_eq!(checksum, format!("{:04X}", pix::crc16(content.as_bytes())));
+}
+
+#[test]
+fn qr_code_fits_the_smallest_version() {
+    let qr = QrCode::encode(b"hello").unwrap();
+    assert_eq!(qr.size(), 21);
+    // finder pattern in the top left corner
+    assert!((0..7).all(|i| qr.module(i, 0) && qr.module(0, i)));
+    assert!(!qr.module(7, 0) && !qr.module(1, 1));
+
+    let qr = QrCode::encode(&[b'x'; 250]).unwrap();
+    assert_eq!(qr.size(), 61);
+    assert!(QrCode::encode(&[b'x'; 2400]).is_none());
+    assert!(qr.to_svg().contains("viewBox=\"0 0 69 69\""));
+}
+
+#[test]
+fn chart_of_accounts_maps_ledger_accounts() {
+    let settings = serde_json::json!({
+        "accounting": {
+            "accounts": {
+                "receivables": "1.1.2.01",
+                "revenue": "3.1.1.01",
+                "revenue:retainer": "3.1.1.02"
+            },
+            "fee_types": {"success": "3.1.1.06"}
+        }
+    });
+    let chart = ChartOfAccounts::from_settings(Some(&settings));
+    let client_pid = uuid::Uuid::new_v4();
+    assert_eq!(
+        chart.account_code(&format!("receivables:{client_pid}")),
+        "1.1.2.01"
+    );
+    assert_eq!(chart.account_code("revenue:success"), "3.1.1.06");
+    assert_eq!(chart.account_code("revenue:retainer"), "3.1.1.02");
+    assert_eq!(chart.account_code("revenue:consulting"), "3.1.1.01");
+    assert_eq!(chart.account_code("cash"), "cash");
+}
+
+#[test]
+fn journal_months_and_csv() {
+    let (starts_on, ends_on) = journal::month_bounds("2024-02").unwrap();
+    assert_eq!(starts_on.to_string(), "2024-02-01");
+    assert_eq!(ends_on.to_string(), "2024-02-29");
+    assert!(journal::month_bounds("2024-13").is_none());
+
+    let row = JournalRow {
+        entry_date: starts_on,
+        entry_pid: uuid::Uuid::nil(),
+        ledger_code: "cash".to_string(),
+        ledger_name: "Cash".to_string(),
+        debit: 1234.5,
+        credit: 0.0,
+        description: "Refund: paid \"twice\", by PIX".to_string(),
+    };
+    let csv = journal::to_csv(&[row], &ChartOfAccounts::default());
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        "date,entry,account,ledger_account,debit,credit,description"
+    );
+    assert_eq!(
+        lines[1],
+        "2024-02-01,00000000-0000-0000-0000-000000000000,cash,Cash,1234.50,0.00,\"Refund: paid \"\"twice\"\", by PIX\""
+    );
+}
@@ -0,0 +1,120 @@
+use super::fixtures::{self, date, payment};
+use johandler::app::App;
+use johandler::business_days::BusinessCalendar;
+use johandler::models::_entities::expenses;
+use johandler::models::expenses::{BillExpenses, CreateNewExpense, ExpenseStatus};
+use loco_rs::testing;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn expense(reimbursable: bool, payment_id: Option<i32>) -> expenses::Model {
+    let now = chrono::Utc::now().into();
+    expenses::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        order_id: 1,
+        category: "court_costs".to_string(),
+        value: 250.0,
+        expense_date: chrono::NaiveDate::from_ymd_opt(2025, 4, 14).unwrap(),
+        description: None,
+        receipt: None,
+        reimbursable,
+        payment_id,
+    }
+}
+
+#[test]
+fn expense_status_follows_billing_instalment() {
+    let payments = vec![payment(1, 250.0, true), payment(2, 250.0, false)];
+    assert_eq!(
+        expense(false, None).status(&payments),
+        ExpenseStatus::NotReimbursable
+    );
+    assert_eq!(
+        expense(true, None).status(&payments),
+        ExpenseStatus::Pending
+    );
+    assert_eq!(
+        expense(true, Some(1)).status(&payments),
+        ExpenseStatus::Billed
+    );
+    assert_eq!(
+        expense(true, Some(2)).status(&payments),
+        ExpenseStatus::Reimbursed
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn expenses_sent_twice_are_billed_once() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let mut expense_pids = vec![];
+    for value in [250.0, 100.0] {
+        let expense = expenses::Model::create(
+            db,
+            CreateNewExpense {
+                order_pid: order.pid,
+                category: "court_costs".to_string(),
+                value,
+                expense_date: date(2025, 4, 14),
+                description: None,
+                receipt: None,
+                reimbursable: true,
+            },
+        )
+        .await
+        .unwrap();
+        expense_pids.push(expense.pid);
+    }
+
+    let payment = expenses::Model::bill(
+        db,
+        BillExpenses {
+            order_pid: order.pid,
+            expense_pids: vec![expense_pids[0], expense_pids[0], expense_pids[1]],
+            payment_pid: None,
+            due_date: Some(date(2025, 5, 10)),
+        },
+        &BusinessCalendar::default(),
+    )
+    .await
+    .unwrap();
+    assert!((payment.value - 350.0).abs() < f32::EPSILON);
+    // the 10th is a Saturday
+    assert_eq!(payment.due_date, date(2025, 5, 12));
+    for pid in expense_pids {
+        let expense = expenses::Model::find_by_pid(db, pid).await.unwrap();
+        assert_eq!(expense.payment_id, Some(payment.id));
+    }
+}
@@ -0,0 +1,106 @@
+//! Records shared by the model tests, built in memory, and a client and an
+//! order created through the models for the tests that need them in the
+//! database.
+
+use chrono::NaiveDate;
+use johandler::business_days::BusinessCalendar;
+use johandler::controllers::orders::{JsonOrderToCreate, OrderPaymentsRequest};
+use johandler::models::_entities::{clients, orders, payments, processes, sellers};
+use johandler::models::clients::CreateNewClient;
+use johandler::models::processes::CreateNewProcess;
+use johandler::models::sellers::CreateNewSeller;
+use johandler::views::orders::GetOrderReturn;
+use sea_orm::DatabaseConnection;
+
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// an instalment of order 1 due on 2025-04-10, not paid yet when open
+pub fn payment(id: i32, value: f32, open: bool) -> payments::Model {
+    let now = chrono::Utc::now().into();
+    payments::Model {
+        created_at: now,
+        updated_at: now,
+        id,
+        pid: uuid::Uuid::new_v4(),
+        value,
+        payment_date: None,
+        due_date: date(2025, 4, 10),
+        payment_method: None,
+        currency: None,
+        postponed_payment: None,
+        order_id: 1,
+        open,
+        billing_plan_id: None,
+        period_date: None,
+        nosso_numero: None,
+    }
+}
+
+/// a new client without a document, contacts or addresses
+pub async fn client(db: &DatabaseConnection) -> clients::Model {
+    clients::Model::create(
+        db,
+        CreateNewClient {
+            name: format!("João da Silva {}", uuid::Uuid::new_v4()),
+            contact: "x".to_string(),
+            partner_pid: None,
+            document: None,
+            contacts: None,
+            addresses: None,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+/// an open order of R$ 1.000,00 for a new client, sold by a new seller in a
+/// new process, with its open instalment due on 2025-04-10
+pub async fn order(db: &DatabaseConnection) -> GetOrderReturn {
+    let name = uuid::Uuid::new_v4().to_string();
+    let client = client(db).await;
+    let seller = sellers::Model::create(db, CreateNewSeller { name: name.clone() })
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|seller| seller.name == name)
+        .unwrap();
+    let process = processes::Model::create(
+        db,
+        CreateNewProcess {
+            case_type: name.clone(),
+        },
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|process| process.case_type == name)
+    .unwrap();
+    orders::Model::create(
+        db,
+        &JsonOrderToCreate {
+            process_pid: process.pid,
+            client_pid: client.pid,
+            open: true,
+            fee: 1000.0,
+            fees: vec![],
+            payout: None,
+            partner_fee: None,
+            seller_pid: seller.pid,
+            payments: vec![OrderPaymentsRequest {
+                value: 1000.0,
+                payment_date: None,
+                due_date: date(2025, 4, 10),
+                payment_method: None,
+                currency: None,
+                postponed_payment: None,
+                open: true,
+                postponed_dates: None,
+            }],
+        },
+        &BusinessCalendar::default(),
+    )
+    .await
+    .unwrap()
+}
@@ -0,0 +1,356 @@
+use super::fixtures::{self, date};
+use johandler::app::App;
+use johandler::business_days::BusinessCalendar;
+use johandler::models::_entities::{
+    billing_plans, clients, credit_notes, expenses, fees, journal_entries, ledger_accounts,
+    order_fees, orders, payments, refunds, sellers,
+};
+use johandler::models::billing_plans::CreateNewBillingPlan;
+use johandler::models::expenses::{BillExpenses, CreateNewExpense};
+use johandler::models::journal_entries::{
+    cents, is_balanced, postings_for_order, OrderActivity, Posting,
+};
+use johandler::models::ledger_accounts::Account;
+use loco_rs::testing;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn order(payout: f32) -> orders::Model {
+    let now = chrono::Utc::now().into();
+    orders::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        client_id: 1,
+        process_id: 1,
+        seller_id: 1,
+        open: true,
+        fee: 1000.0,
+        payout,
+        partner_fee: None,
+    }
+}
+
+fn client() -> clients::Model {
+    let now = chrono::Utc::now().into();
+    clients::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        name: "Maria".to_string(),
+        contact: "Maria".to_string(),
+        partner_id: None,
+        document: None,
+    }
+}
+
+fn seller() -> sellers::Model {
+    let now = chrono::Utc::now().into();
+    sellers::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        name: "João".to_string(),
+    }
+}
+
+fn order_fee(id: i32, value: f32, fee_type: Option<&str>) -> (order_fees::Model, fees::Model) {
+    let now = chrono::Utc::now().into();
+    (
+        order_fees::Model {
+            created_at: now,
+            updated_at: now,
+            id,
+            pid: uuid::Uuid::new_v4(),
+            fee_id: id,
+            order_id: 1,
+            open: true,
+            value,
+            info: None,
+        },
+        fees::Model {
+            created_at: now,
+            updated_at: now,
+            id,
+            pid: uuid::Uuid::new_v4(),
+            fee: format!("fee {id}"),
+            r#type: fee_type.map(ToString::to_string),
+        },
+    )
+}
+
+fn payment(id: i32, value: f32, open: bool) -> payments::Model {
+    payments::Model {
+        payment_date: (!open).then(|| date(2025, 3, 12)),
+        due_date: date(2025, 3, 10),
+        ..fixtures::payment(id, value, open)
+    }
+}
+
+fn refund(id: i32, value: f32, chargeback: bool) -> refunds::Model {
+    let now = chrono::Utc::now().into();
+    refunds::Model {
+        created_at: now,
+        updated_at: now,
+        id,
+        pid: uuid::Uuid::new_v4(),
+        order_id: 1,
+        payment_id: Some(1),
+        value,
+        refund_date: date(2025, 3, 20),
+        refund_method: None,
+        reason: "overpaid".to_string(),
+        chargeback,
+    }
+}
+
+fn credit_note(id: i32, value: f32) -> credit_notes::Model {
+    let now = chrono::Utc::now().into();
+    credit_notes::Model {
+        created_at: now,
+        updated_at: now,
+        id,
+        pid: uuid::Uuid::new_v4(),
+        order_id: 1,
+        order_fee_id: None,
+        payment_id: None,
+        value,
+        reason: "discount".to_string(),
+        credit_date: date(2025, 3, 15),
+    }
+}
+
+/// the balance of an account over the postings, debits minus credits, in cents
+fn balance(postings: &[Posting], account: &Account) -> i64 {
+    postings
+        .iter()
+        .flat_map(|posting| &posting.lines)
+        .filter(|line| line.account.code == account.code)
+        .map(|line| cents(line.debit) - cents(line.credit))
+        .sum()
+}
+
+#[test]
+fn lines_balance_to_the_cent() {
+    assert!(is_balanced([(0.1, 0.0), (0.2, 0.0), (0.0, 0.3)]));
+    assert!(!is_balanced([(100.0, 0.0), (0.0, 99.99)]));
+}
+
+#[test]
+fn order_activity_posts_balanced_entries() {
+    let order = order(150.0);
+    let client = client();
+    let seller = seller();
+    let order_fees = vec![
+        order_fee(1, 600.0, Some("Contractual")),
+        order_fee(2, 400.0, None),
+    ];
+    let payments = vec![payment(1, 500.0, false), payment(2, 500.0, true)];
+    let refunds = vec![refund(1, 50.0, false), refund(2, 500.0, true)];
+    let credit_notes = vec![credit_note(1, 100.0)];
+    let postings = postings_for_order(&OrderActivity {
+        order: &order,
+        client: &client,
+        seller: &seller,
+        partner: None,
+        order_fees: &order_fees,
+        payments: &payments,
+        refunds: &refunds,
+        credit_notes: &credit_notes,
+        time_entries: &[],
+        expenses: &[],
+    });
+
+    assert!(postings.iter().all(Posting::is_balanced));
+    let sources = postings
+        .iter()
+        .map(|posting| posting.source.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sources,
+        vec![
+            "order_fee:1",
+            "order_fee:2",
+            "settlement:1",
+            "refund:1",
+            "credit_note:1",
+            "commission:1",
+            "commission_refund:1"
+        ]
+    );
+
+    let receivables = Account::receivables(client.pid, &client.name);
+    assert_eq!(balance(&postings, &receivables), 100_000 - 50_000 - 10_000);
+    assert_eq!(balance(&postings, &Account::cash()), 50_000 - 5_000);
+    assert_eq!(
+        balance(&postings, &Account::fee_revenue("contractual")),
+        -60_000
+    );
+    assert_eq!(balance(&postings, &Account::fee_revenue("fee 2")), -40_000);
+    assert_eq!(
+        balance(
+            &postings,
+            &Account::commission_payable(seller.pid, &seller.name)
+        ),
+        -15_000 + 750
+    );
+}
+
+/// what the journal entries posted for a source credited to an account, net
+/// of their reversals, in cents
+async fn credited(db: &DatabaseConnection, source: &str, account: &Account) -> i64 {
+    let ledger_account = ledger_accounts::Model::find_or_open(db, account)
+        .await
+        .unwrap();
+    let journal_entry_ids = journal_entries::Entity::find()
+        .filter(journal_entries::Column::Source.eq(source))
+        .all(db)
+        .await
+        .unwrap()
+        .iter()
+        .map(|journal_entry| journal_entry.id)
+        .collect();
+    journal_entries::Model::find_lines(db, journal_entry_ids)
+        .await
+        .unwrap()
+        .iter()
+        .filter(|line| line.ledger_account_id == ledger_account.id)
+        .map(|line| cents(line.credit) - cents(line.debit))
+        .sum()
+}
+
+#[tokio::test]
+#[serial]
+async fn expenses_billed_into_a_retainer_instalment_are_not_retainer_revenue() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let billing_plan = billing_plans::Model::create(
+        db,
+        CreateNewBillingPlan {
+            order_pid: order.pid,
+            value: 1000.0,
+            day_of_month: 10,
+            start_date: date(2025, 6, 1),
+            end_date: Some(date(2025, 6, 30)),
+        },
+    )
+    .await
+    .unwrap();
+    let payment = billing_plans::Model::generate_payments(
+        db,
+        date(2025, 6, 30),
+        &BusinessCalendar::default(),
+    )
+    .await
+    .unwrap()
+    .into_iter()
+    .find(|payment| payment.billing_plan_id == Some(billing_plan.id))
+    .unwrap();
+    let expense = expenses::Model::create(
+        db,
+        CreateNewExpense {
+            order_pid: order.pid,
+            category: "court_costs".to_string(),
+            value: 250.0,
+            expense_date: date(2025, 6, 2),
+            description: None,
+            receipt: None,
+            reimbursable: true,
+        },
+    )
+    .await
+    .unwrap();
+
+    let payment = expenses::Model::bill(
+        db,
+        BillExpenses {
+            order_pid: order.pid,
+            expense_pids: vec![expense.pid],
+            payment_pid: Some(payment.pid),
+            due_date: None,
+        },
+        &BusinessCalendar::default(),
+    )
+    .await
+    .unwrap();
+    assert!((payment.value - 1250.0).abs() < f32::EPSILON);
+    assert_eq!(
+        credited(
+            db,
+            &format!("retainer:{}", payment.id),
+            &Account::retainer_revenue()
+        )
+        .await,
+        100_000
+    );
+    assert_eq!(
+        credited(
+            db,
+            &format!("expenses:{}", payment.id),
+            &Account::reimbursed_expenses()
+        )
+        .await,
+        25_000
+    );
+}
+
+#[test]
+fn settlements_leave_out_the_credit_notes_of_their_instalment() {
+    let order = order(0.0);
+    let client = client();
+    let seller = seller();
+    let order_fees = vec![order_fee(1, 1000.0, None)];
+    let payments = vec![payment(1, 500.0, false), payment(2, 500.0, true)];
+    let credit_notes = vec![credit_notes::Model {
+        payment_id: Some(1),
+        ..credit_note(1, 100.0)
+    }];
+    let postings = postings_for_order(&OrderActivity {
+        order: &order,
+        client: &client,
+        seller: &seller,
+        partner: None,
+        order_fees: &order_fees,
+        payments: &payments,
+        refunds: &[],
+        credit_notes: &credit_notes,
+        time_entries: &[],
+        expenses: &[],
+    });
+
+    let receivables = Account::receivables(client.pid, &client.name);
+    assert_eq!(balance(&postings, &receivables), 100_000 - 10_000 - 40_000);
+    assert_eq!(balance(&postings, &Account::cash()), 40_000);
+}
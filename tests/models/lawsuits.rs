@@ -0,0 +1,117 @@
+use johandler::app::App;
+use johandler::models::_entities::lawsuits;
+use johandler::models::lawsuits::{self as lawsuit, CnjNumber, JusticeBranch};
+use johandler::views::lawsuits::LawsuitView;
+use loco_rs::testing;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+#[test]
+fn cnj_numbers_are_checked_by_mod_97() {
+    let cnj = CnjNumber::parse("0001234-71.2024.8.26.0100").unwrap();
+    assert_eq!(
+        cnj,
+        CnjNumber {
+            sequence: 1234,
+            check_digits: 71,
+            year: 2024,
+            segment: 8,
+            court: 26,
+            origin: 100,
+        }
+    );
+    assert_eq!(cnj.digits(), "00012347120248260100");
+    assert_eq!(CnjNumber::parse("00012347120248260100"), Some(cnj));
+    assert_eq!(
+        lawsuit::format_cnj(&cnj.digits()),
+        "0001234-71.2024.8.26.0100"
+    );
+
+    // wrong check digits, a digit swapped, a broken mask, too short
+    assert!(CnjNumber::parse("0001234-70.2024.8.26.0100").is_none());
+    assert!(CnjNumber::parse("0001234-71.2024.8.26.1000").is_none());
+    assert!(CnjNumber::parse("0001234.71-2024.8.26.0100").is_none());
+    assert!(CnjNumber::parse("0001234712024826010").is_none());
+}
+
+#[test]
+fn cnj_numbers_tell_their_court() {
+    let state = CnjNumber::parse("0001234-71.2024.8.26.0100").unwrap();
+    assert_eq!(state.branch(), Some(JusticeBranch::State));
+    assert_eq!(state.state(), Some("SP"));
+    assert_eq!(state.court_name().as_deref(), Some("TJSP"));
+
+    let labour = CnjNumber::parse("1000123-42.2019.5.02.0031").unwrap();
+    assert_eq!(labour.branch(), Some(JusticeBranch::Labour));
+    assert_eq!(labour.state(), None);
+    assert_eq!(labour.court_name().as_deref(), Some("TRT2"));
+
+    let federal = CnjNumber::parse("0800456-22.2023.4.03.6100").unwrap();
+    assert_eq!(federal.court_name().as_deref(), Some("TRF3"));
+
+    // a state court numbered 28 does not exist
+    let mut unknown = state;
+    unknown.court = 28;
+    unknown.check_digits = unknown.expected_check_digits();
+    assert_eq!(CnjNumber::parse(&unknown.digits()), Some(unknown));
+    assert_eq!(unknown.court_name(), None);
+    assert!(lawsuit::check_cnj_number(&unknown.digits(), None).is_err());
+}
+
+#[test]
+fn cnj_year_must_be_the_filing_year() {
+    let filed = chrono::NaiveDate::from_ymd_opt(2024, 3, 5);
+    assert!(lawsuit::check_cnj_number("0001234-71.2024.8.26.0100", filed).is_ok());
+    let filed = chrono::NaiveDate::from_ymd_opt(2023, 12, 30);
+    assert!(lawsuit::check_cnj_number("0001234-71.2024.8.26.0100", filed).is_err());
+}
+
+#[test]
+fn lawsuit_view_masks_and_decodes_the_number() {
+    let now = chrono::Utc::now().into();
+    let view = LawsuitView::from(lawsuits::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::from_u128(1),
+        order_id: 1,
+        cnj_number: "10001234220195020031".to_string(),
+        court: Some("TRT2".to_string()),
+        district: Some("São Paulo".to_string()),
+        opposing_party: Some("Empresa Ltda".to_string()),
+        filing_date: None,
+        status: "active".to_string(),
+    });
+    assert_eq!(view.cnj_number, "1000123-42.2019.5.02.0031");
+    let cnj = view.cnj.unwrap();
+    assert_eq!(cnj.court.as_deref(), Some("TRT2"));
+    assert_eq!(cnj.origin, "0031");
+    assert_eq!(cnj.year, 2019);
+}
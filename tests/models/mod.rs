@@ -1,3 +1,5 @@
+mod fixtures;
+
 mod users;
 
 mod clients;
@@ -13,3 +15,37 @@ mod fees;
 mod order_fees;
 
 mod processes_fees;
+
+mod credit_notes;
+
+mod refunds;
+
+mod expenses;
+
+mod billing_plans;
+
+mod time_entries;
+
+mod documents;
+
+mod bank_transactions;
+
+mod payment_charges;
+
+mod journal_entries;
+
+mod client_merges;
+
+mod search;
+
+mod lawsuits;
+
+mod calendar_events;
+
+mod business_days;
+
+mod notes;
+
+mod attachments;
+
+mod contracts;
@@ -0,0 +1,262 @@
+use super::fixtures;
+use johandler::app::App;
+use johandler::models::_entities::{
+    clients, note_revisions, notes, orders, payments, postponed_payments, status_changes,
+};
+use johandler::models::notes::{CreateNewNote, NoteTarget};
+use johandler::views::notes::{NoteHistoryView, NoteView, Timeline, TimelineKind};
+use loco_rs::testing;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, Database, EntityTrait};
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn at(at: &str) -> sea_orm::prelude::DateTimeWithTimeZone {
+    chrono::DateTime::parse_from_rfc3339(at).unwrap()
+}
+
+fn order(id: i32, created_at: &str) -> orders::Model {
+    orders::Model {
+        created_at: at(created_at),
+        updated_at: at(created_at),
+        id,
+        pid: uuid::Uuid::from_u128(100 + id as u128),
+        client_id: 1,
+        process_id: 1,
+        open: true,
+        payout: 0.0,
+        fee: 3000.0,
+        partner_fee: None,
+        seller_id: 1,
+    }
+}
+
+fn payment(id: i32, payment_date: Option<&str>) -> payments::Model {
+    payments::Model {
+        created_at: at("2025-01-10T12:00:00Z"),
+        updated_at: at("2025-01-10T12:00:00Z"),
+        pid: uuid::Uuid::from_u128(200 + id as u128),
+        payment_date: payment_date.map(|date| date.parse().unwrap()),
+        due_date: "2025-02-10".parse().unwrap(),
+        payment_method: payment_date.map(|_| "pix".to_string()),
+        ..fixtures::payment(id, 1000.0, payment_date.is_none())
+    }
+}
+
+fn note_view(pid: u128, created_at: &str, pinned: bool) -> NoteView {
+    NoteView {
+        pid: uuid::Uuid::from_u128(pid),
+        body: format!("note {pid}"),
+        pinned,
+        author_pid: None,
+        author_name: Some("Ana".to_string()),
+        order_pid: Some(uuid::Uuid::from_u128(101)),
+        client_pid: None,
+        payment_pid: None,
+        created_at: at(created_at),
+        edited_at: None,
+        revisions: 0,
+    }
+}
+
+#[test]
+fn timelines_merge_notes_and_events_in_order() {
+    let client = clients::Model {
+        created_at: at("2025-01-02T09:00:00Z"),
+        updated_at: at("2025-01-02T09:00:00Z"),
+        id: 1,
+        pid: uuid::Uuid::from_u128(1),
+        name: "Maria Souza".to_string(),
+        contact: "x".to_string(),
+        partner_id: None,
+        document: None,
+    };
+    let postponed = postponed_payments::Model {
+        created_at: at("2025-02-08T15:00:00Z"),
+        updated_at: at("2025-02-08T15:00:00Z"),
+        id: 1,
+        pid: uuid::Uuid::from_u128(300),
+        payment_id: 2,
+        postponed_date: "2025-03-10".parse().unwrap(),
+    };
+    let status_change = status_changes::Model {
+        created_at: at("2025-03-20T10:00:00Z"),
+        updated_at: at("2025-03-20T10:00:00Z"),
+        id: 1,
+        pid: uuid::Uuid::from_u128(400),
+        order_id: 1,
+        subject: "order".to_string(),
+        from_status: "open".to_string(),
+        to_status: "closed".to_string(),
+    };
+    let timeline = Timeline::new(
+        Some(&client),
+        &[order(1, "2025-01-10T11:00:00Z")],
+        &[payment(1, Some("2025-02-10")), payment(2, None)],
+        &[postponed],
+        &[status_change],
+        vec![
+            note_view(1, "2025-01-09T18:00:00Z", true),
+            note_view(2, "2025-02-10T14:00:00Z", false),
+        ],
+    );
+
+    assert_eq!(
+        timeline
+            .items
+            .iter()
+            .map(|item| (item.kind, item.description.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (TimelineKind::ClientCreated, "Client Maria Souza created"),
+            (TimelineKind::Note, "Note by Ana"),
+            (
+                TimelineKind::OrderCreated,
+                "Order created with a fee of 3000.00"
+            ),
+            (
+                TimelineKind::PostponementAdded,
+                "Instalment due 2025-02-10 postponed to 2025-03-10"
+            ),
+            (
+                TimelineKind::PaymentSettled,
+                "Instalment of 1000.00 due 2025-02-10 settled (pix)"
+            ),
+            (TimelineKind::Note, "Note by Ana"),
+            (
+                TimelineKind::StatusChanged,
+                "Order status changed from open to closed"
+            ),
+        ]
+    );
+    assert_eq!(
+        timeline.items[4].payment_pid,
+        Some(uuid::Uuid::from_u128(201))
+    );
+    assert_eq!(
+        timeline.items[4].order_pid,
+        Some(uuid::Uuid::from_u128(101))
+    );
+    assert_eq!(timeline.pinned.len(), 1);
+    assert_eq!(timeline.pinned[0].pid, uuid::Uuid::from_u128(1));
+}
+
+#[tokio::test]
+async fn notes_keep_their_earlier_bodies() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    db.execute_unprepared(
+        "INSERT INTO clients (created_at, updated_at, pid, name, contact) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'0f8fad5bd9cb469fa16570867728950e', 'João da Silva', 'x')",
+    )
+    .await
+    .unwrap();
+    let client_pid = uuid::Uuid::parse_str("0f8fad5b-d9cb-469f-a165-70867728950e").unwrap();
+
+    let note = |order_pid, client_pid| CreateNewNote {
+        body: "Asked for a discount on the second instalment".to_string(),
+        pinned: false,
+        order_pid,
+        client_pid,
+        payment_pid: None,
+    };
+    assert!(notes::Model::create(&db, None, note(None, None))
+        .await
+        .is_err());
+    assert!(notes::Model::create(
+        &db,
+        None,
+        note(Some(uuid::Uuid::from_u128(1)), Some(client_pid))
+    )
+    .await
+    .is_err());
+    let mut empty = note(None, Some(client_pid));
+    empty.body = "  ".to_string();
+    assert!(notes::Model::create(&db, None, empty).await.is_err());
+
+    let created = notes::Model::create(&db, None, note(None, Some(client_pid)))
+        .await
+        .unwrap();
+    let edited = notes::Model::update(&db, created.pid, None, "Agreed on 10% off".to_string())
+        .await
+        .unwrap();
+    assert_eq!(edited.body, "Agreed on 10% off");
+    // saving the same body again is not an edit
+    notes::Model::update(&db, created.pid, None, "Agreed on 10% off".to_string())
+        .await
+        .unwrap();
+    let pinned = notes::Model::pin(&db, created.pid, true).await.unwrap();
+    assert!(pinned.pinned);
+
+    let links = notes::Model::find_links(&db, std::slice::from_ref(&pinned))
+        .await
+        .unwrap();
+    let history = NoteHistoryView::new(pinned, &links);
+    assert_eq!(history.note.client_pid, Some(client_pid));
+    assert_eq!(history.note.revisions, 1);
+    assert!(history.note.edited_at.is_some());
+    assert_eq!(history.revisions.len(), 1);
+    assert_eq!(
+        history.revisions[0].body,
+        "Asked for a discount on the second instalment"
+    );
+
+    let target = NoteTarget::find(&db, None, Some(client_pid), None)
+        .await
+        .unwrap();
+    assert_eq!(target, NoteTarget::Client(1));
+    assert_eq!(
+        notes::Model::find_by_target(&db, target)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // a deleted note is no longer found but is kept with its revisions
+    let deleted = notes::Model::delete(&db, created.pid).await.unwrap();
+    assert!(deleted.deleted_at.is_some());
+    assert!(notes::Model::find_by_pid(&db, created.pid).await.is_err());
+    assert!(notes::Model::find_by_target(&db, target)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(
+        notes::Model::update(&db, created.pid, None, "Gone".to_string())
+            .await
+            .is_err()
+    );
+    assert_eq!(notes::Entity::find().all(&db).await.unwrap().len(), 1);
+    assert_eq!(
+        note_revisions::Entity::find().all(&db).await.unwrap().len(),
+        1
+    );
+}
@@ -1,4 +1,9 @@
+use super::fixtures::{self, date};
 use johandler::app::App;
+use johandler::business_days::BusinessCalendar;
+use johandler::controllers::orders::{JsonOrderToCreate, OrderPaymentsRequest};
+use johandler::documents::journal::ChartOfAccounts;
+use johandler::models::_entities::{accounting_periods, clients, orders};
 use loco_rs::testing;
 use serial_test::serial;
 
@@ -29,3 +34,84 @@ async fn test_model() {
     // snapshot the result:
     // assert_debug_snapshot!(item);
 }
+
+#[tokio::test]
+#[serial]
+async fn orders_the_books_refuse_are_not_saved() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    accounting_periods::Model::export(db, "2019-01", &ChartOfAccounts::default())
+        .await
+        .unwrap();
+
+    let settled_in_a_locked_month = JsonOrderToCreate {
+        process_pid: order.process.pid,
+        client_pid: order.client.pid,
+        open: true,
+        fee: 1000.0,
+        fees: vec![],
+        payout: None,
+        partner_fee: None,
+        seller_pid: order.seller.pid,
+        payments: vec![OrderPaymentsRequest {
+            value: 1000.0,
+            payment_date: Some(date(2019, 1, 15)),
+            due_date: date(2019, 1, 15),
+            payment_method: Some("pix".to_string()),
+            currency: None,
+            postponed_payment: None,
+            open: false,
+            postponed_dates: None,
+        }],
+    };
+    assert!(
+        orders::Model::create(db, &settled_in_a_locked_month, &BusinessCalendar::default())
+            .await
+            .is_err()
+    );
+    let client = clients::Model::find_by_pid(db, order.client.pid)
+        .await
+        .unwrap();
+    assert_eq!(
+        orders::Model::find_by_client_id(db, client.id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn instalments_due_on_a_holiday_are_due_on_the_next_business_day() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+
+    // Paixão de Cristo, followed by a weekend and Tiradentes
+    let due_on_good_friday = JsonOrderToCreate {
+        process_pid: order.process.pid,
+        client_pid: order.client.pid,
+        open: true,
+        fee: 1000.0,
+        fees: vec![],
+        payout: None,
+        partner_fee: None,
+        seller_pid: order.seller.pid,
+        payments: vec![OrderPaymentsRequest {
+            value: 1000.0,
+            payment_date: None,
+            due_date: date(2025, 4, 18),
+            payment_method: None,
+            currency: None,
+            postponed_payment: None,
+            open: true,
+            postponed_dates: None,
+        }],
+    };
+    let created = orders::Model::create(db, &due_on_good_friday, &BusinessCalendar::default())
+        .await
+        .unwrap();
+    assert_eq!(created.payments[0].due_date, date(2025, 4, 22));
+}
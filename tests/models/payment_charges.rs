@@ -0,0 +1,181 @@
+use super::fixtures::{self, date};
+use johandler::app::App;
+use johandler::models::_entities::{payment_charges, payments, provider_events};
+use johandler::payment_providers::mock::MockProvider;
+use johandler::payment_providers::{
+    self, ChargeStatus, PaymentProvider, ProviderError, WebhookEvent,
+};
+use loco_rs::testing;
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, QueryFilter};
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn payment() -> payments::Model {
+    payments::Model {
+        pid: uuid::Uuid::parse_str("0f8fad5b-d9cb-469f-a165-70867728950e").unwrap(),
+        due_date: date(2025, 2, 10),
+        ..fixtures::payment(1, 500.0, true)
+    }
+}
+
+#[test]
+fn signatures_are_hmac_sha256() {
+    // RFC 4231, test case 2
+    assert_eq!(
+        payment_providers::sign("Jefe", b"what do ya want for nothing?"),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    let signature = payment_providers::sign("secret", b"{}");
+    assert!(payment_providers::verify_signature(
+        "secret", b"{}", &signature
+    ));
+    assert!(!payment_providers::verify_signature(
+        "other", b"{}", &signature
+    ));
+    assert!(!payment_providers::verify_signature(
+        "secret", b"{}", "not hex"
+    ));
+}
+
+#[tokio::test]
+async fn mock_provider_charges_and_signs_its_webhooks() {
+    let provider = MockProvider::new("secret".to_string());
+    let charge = provider.create_charge(&payment()).await.unwrap();
+    assert_eq!(charge.external_id, "mock_0f8fad5bd9cb469fa16570867728950e");
+
+    let event = WebhookEvent {
+        event_id: "evt_1".to_string(),
+        charge_id: charge.external_id,
+        status: ChargeStatus::Paid,
+        paid_on: chrono::NaiveDate::from_ymd_opt(2025, 2, 12),
+    };
+    let (body, signature) = provider.webhook(&event);
+    assert_eq!(
+        provider.verify_webhook(&signature, body.as_bytes()),
+        Ok(event)
+    );
+
+    let tampered = body.replace("2025-02-12", "2025-02-01");
+    assert_eq!(
+        provider.verify_webhook(&signature, tampered.as_bytes()),
+        Err(ProviderError::InvalidSignature)
+    );
+    let not_an_event = "{}";
+    let signature = payment_providers::sign("secret", not_an_event.as_bytes());
+    assert!(matches!(
+        provider.verify_webhook(&signature, not_an_event.as_bytes()),
+        Err(ProviderError::InvalidPayload(_))
+    ));
+}
+
+#[test]
+fn provider_is_read_from_settings() {
+    let settings = serde_json::json!({
+        "payment_provider": { "name": "mock", "webhook_secret": "secret" }
+    });
+    let provider = payment_providers::from_settings(Some(&settings)).unwrap();
+    assert_eq!(provider.name(), "mock");
+
+    let unknown = serde_json::json!({
+        "payment_provider": { "name": "other", "webhook_secret": "secret" }
+    });
+    assert!(payment_providers::from_settings(Some(&unknown)).is_none());
+    assert!(payment_providers::from_settings(None).is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn only_a_charge_paid_for_the_instalment_value_settles_it_and_only_once() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let provider = MockProvider::new("secret".to_string());
+    let order = fixtures::order(db).await;
+    let payment_pid = order.payments[0].pid.unwrap();
+    let charge = payment_charges::Model::create(db, &provider, payment_pid)
+        .await
+        .unwrap();
+    let payment = payments::Entity::find()
+        .filter(payments::Column::Pid.eq(payment_pid))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the instalment was changed after it was charged
+    let mut changed_payment: payments::ActiveModel = payment.clone().into();
+    changed_payment.value = ActiveValue::Set(1200.0);
+    changed_payment.update(db).await.unwrap();
+    let paid = || WebhookEvent {
+        event_id: uuid::Uuid::new_v4().to_string(),
+        charge_id: charge.external_id.clone(),
+        status: ChargeStatus::Paid,
+        paid_on: Some(date(2025, 4, 9)),
+    };
+    assert!(provider_events::Model::record(db, provider.name(), paid())
+        .await
+        .unwrap()
+        .is_some());
+    let payment = payments::Entity::find_by_id(payment.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(payment.open);
+
+    let order = fixtures::order(db).await;
+    let payment_pid = order.payments[0].pid.unwrap();
+    let charge = payment_charges::Model::create(db, &provider, payment_pid)
+        .await
+        .unwrap();
+    let paid_again = WebhookEvent {
+        charge_id: charge.external_id,
+        ..paid()
+    };
+    assert!(
+        provider_events::Model::record(db, provider.name(), paid_again.clone())
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        provider_events::Model::record(db, provider.name(), paid_again)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let payment = payments::Entity::find()
+        .filter(payments::Column::Pid.eq(payment_pid))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!payment.open);
+    assert_eq!(payment.payment_date, Some(date(2025, 4, 9)));
+}
@@ -1,4 +1,15 @@
+use super::fixtures::{date, payment};
+use chrono::NaiveDate;
 use johandler::app::App;
+use johandler::documents::boleto::{self, Boleto, BoletoSettings};
+use johandler::documents::itf::Itf;
+use johandler::models::_entities::{payments, postponed_payments};
+use johandler::reports::aging::{
+    effective_due_date, AgingBucket, AgingGroupBy, AgingItem, AgingReport,
+};
+use johandler::reports::forecast::{
+    on_time_rates, ForecastLine, ForecastPeriod, ForecastReport, RateBy,
+};
 use loco_rs::testing;
 use serial_test::serial;
 
@@ -29,3 +40,211 @@ async fn test_model() {
     // snapshot the result:
     // assert_debug_snapshot!(item);
 }
+
+fn settings() -> BoletoSettings {
+    BoletoSettings {
+        bank_code: "001".to_string(),
+        agreement: "1234567".to_string(),
+        wallet: "17".to_string(),
+        beneficiary: "Johandler Advocacia".to_string(),
+    }
+}
+
+#[test]
+fn due_date_factor_restarts_after_9999() {
+    assert_eq!(boleto::due_date_factor(date(2007, 12, 31)), Some(3737));
+    assert_eq!(boleto::due_date_factor(date(2025, 2, 21)), Some(9999));
+    assert_eq!(boleto::due_date_factor(date(2025, 2, 22)), Some(1000));
+    assert_eq!(boleto::due_date_factor(date(1997, 10, 7)), None);
+}
+
+#[test]
+fn digitable_line_follows_febraban() {
+    let barcode = "00193373700000001000500940144816060680935031";
+    assert_eq!(
+        boleto::barcode_check_digit("0019373700000001000500940144816060680935031"),
+        3
+    );
+    assert_eq!(
+        boleto::digitable_line(barcode),
+        "00190.50095 40144.816069 06809.350314 3 37370000000100"
+    );
+}
+
+#[test]
+fn boleto_carries_the_nosso_numero_in_the_free_field() {
+    let settings = settings();
+    let nosso_numero = settings.nosso_numero(42);
+    assert_eq!(nosso_numero, "12345670000000042");
+
+    let boleto = Boleto::new(&settings, &nosso_numero, 1500.5, date(2025, 3, 10)).unwrap();
+    assert_eq!(boleto.due_date_factor, 1016);
+    assert_eq!(boleto.barcode.len(), 44);
+    assert_eq!(&boleto.barcode[..4], "0019");
+    assert_eq!(&boleto.barcode[5..19], "10160000150050");
+    assert_eq!(&boleto.barcode[19..], "0000001234567000000004217");
+    let check_digit =
+        boleto::barcode_check_digit(&format!("{}{}", &boleto.barcode[..4], &boleto.barcode[5..]));
+    assert_eq!(boleto.barcode[4..5], check_digit.to_string());
+    assert_eq!(
+        boleto.digitable_line,
+        boleto::digitable_line(&boleto.barcode)
+    );
+
+    assert!(Boleto::new(&settings, &nosso_numero, 100_000_000.0, date(2025, 3, 10)).is_none());
+}
+
+#[test]
+fn itf_encodes_digit_pairs() {
+    let itf = Itf::encode("00193373700000001000500940144816060680935031").unwrap();
+    // start, 44 digits of 2 wide and 3 narrow elements, and stop
+    assert_eq!(itf.modules().len(), 4 + 44 * 9 + 5);
+    assert_eq!(&itf.modules()[..4], &[true, false, true, false]);
+    assert!(Itf::encode("123").is_none());
+    assert!(Itf::encode("12a4").is_none());
+}
+
+fn open_payment(id: i32, due_date: NaiveDate) -> payments::Model {
+    payments::Model {
+        due_date,
+        ..payment(id, 100.0, true)
+    }
+}
+
+fn postponed(payment_id: i32, postponed_date: NaiveDate) -> postponed_payments::Model {
+    let now = chrono::Utc::now().into();
+    postponed_payments::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        payment_id,
+        postponed_date,
+    }
+}
+
+fn aging_item(client: (u128, &str), days_late: i64, amount: f32) -> AgingItem {
+    let due_date = date(2025, 6, 30) - chrono::Duration::days(days_late);
+    AgingItem {
+        payment_pid: uuid::Uuid::new_v4(),
+        order_pid: uuid::Uuid::new_v4(),
+        due_date,
+        effective_due_date: due_date,
+        days_late,
+        bucket: AgingBucket::from_days_late(days_late),
+        amount,
+        client_pid: uuid::Uuid::from_u128(client.0),
+        client: client.1.to_string(),
+        client_document: None,
+        seller_pid: uuid::Uuid::nil(),
+        seller: "Ana".to_string(),
+        partner_pid: None,
+        partner: None,
+        process_pid: uuid::Uuid::nil(),
+        process: "Trabalhista".to_string(),
+    }
+}
+
+#[test]
+fn instalments_age_from_their_last_postponement() {
+    let payment = open_payment(1, date(2025, 3, 10));
+    assert_eq!(effective_due_date(&payment, &[]), date(2025, 3, 10));
+    let postponements = vec![
+        postponed(1, date(2025, 4, 10)),
+        postponed(1, date(2025, 5, 10)),
+        postponed(2, date(2025, 8, 10)),
+    ];
+    assert_eq!(
+        effective_due_date(&payment, &postponements),
+        date(2025, 5, 10)
+    );
+
+    assert_eq!(AgingBucket::from_days_late(-3), AgingBucket::Current);
+    assert_eq!(AgingBucket::from_days_late(0), AgingBucket::Current);
+    assert_eq!(AgingBucket::from_days_late(30), AgingBucket::Days1To30);
+    assert_eq!(AgingBucket::from_days_late(31), AgingBucket::Days31To60);
+    assert_eq!(AgingBucket::from_days_late(90), AgingBucket::Days61To90);
+    assert_eq!(AgingBucket::from_days_late(91), AgingBucket::Over90);
+}
+
+#[test]
+fn aging_report_totals_buckets_per_group() {
+    let items = vec![
+        aging_item((1, "Maria"), 5, 100.0),
+        aging_item((1, "Maria"), 120, 50.0),
+        aging_item((2, "José"), -10, 300.0),
+    ];
+    let report = AgingReport::new(date(2025, 6, 30), AgingGroupBy::Client, items);
+
+    assert_eq!(report.groups.len(), 2);
+    assert_eq!(report.groups[0].name, "José");
+    let maria = &report.groups[1];
+    assert!((maria.totals.days_1_30 - 100.0).abs() < f32::EPSILON);
+    assert!((maria.totals.over_90 - 50.0).abs() < f32::EPSILON);
+    assert!((maria.totals.total - 150.0).abs() < f32::EPSILON);
+    assert_eq!(maria.items[0].days_late, 120);
+    assert!((report.totals.current - 300.0).abs() < f32::EPSILON);
+    assert!((report.totals.total - 450.0).abs() < f32::EPSILON);
+
+    let csv = report.to_csv();
+    assert_eq!(csv.lines().count(), 4);
+    assert!(csv.lines().nth(2).unwrap().ends_with(",120,90+,50.00"));
+}
+
+#[test]
+fn on_time_rate_counts_instalments_paid_by_their_due_date() {
+    let settled = [
+        (1, date(2025, 1, 10), date(2025, 1, 10)),
+        (1, date(2025, 2, 10), date(2025, 2, 20)),
+        (2, date(2025, 1, 10), date(2025, 1, 5)),
+    ];
+    let rates = on_time_rates(&settled);
+
+    assert!((rates[&1] - 0.5).abs() < 0.001);
+    assert!((rates[&2] - 1.0).abs() < 0.001);
+    assert!(!rates.contains_key(&3));
+}
+
+#[test]
+fn forecast_weighs_instalments_by_period() {
+    let line = |date: NaiveDate, retainer: bool| ForecastLine {
+        date,
+        scheduled: 1000.0,
+        on_time_rate: 0.5,
+        deduction_rate: 0.2,
+        retainer,
+    };
+    let lines = [
+        line(date(2025, 2, 20), false),
+        line(date(2025, 3, 5), false),
+        line(date(2025, 4, 10), true),
+        line(date(2025, 9, 1), true),
+    ];
+    let report = ForecastReport::new(
+        date(2025, 3, 1),
+        date(2025, 4, 30),
+        ForecastPeriod::Month,
+        RateBy::Client,
+        &lines,
+    );
+
+    assert_eq!(report.periods.len(), 2);
+    assert_eq!(report.periods[0].starts_on, date(2025, 3, 1));
+    assert_eq!(report.periods[0].ends_on, date(2025, 3, 31));
+    // the late instalment is still expected, in the first period
+    assert_eq!(report.periods[0].totals.instalments, 2);
+    assert!((report.periods[0].totals.scheduled - 2000.0).abs() < 0.01);
+    assert!((report.periods[0].totals.expected - 1000.0).abs() < 0.01);
+    assert!((report.periods[0].totals.net_expected - 800.0).abs() < 0.01);
+    assert!((report.periods[1].totals.retainers - 1000.0).abs() < 0.01);
+    assert_eq!(report.totals.instalments, 3);
+    assert!((report.totals.net_scheduled - 2400.0).abs() < 0.01);
+}
+
+#[test]
+fn forecast_weeks_start_on_monday() {
+    let starts_on = ForecastPeriod::Week.start_of(date(2025, 3, 13));
+
+    assert_eq!(starts_on, date(2025, 3, 10));
+    assert_eq!(ForecastPeriod::Week.end_of(starts_on), date(2025, 3, 16));
+}
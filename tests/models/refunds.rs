@@ -0,0 +1,108 @@
+use super::fixtures::{self, date, payment};
+use johandler::app::App;
+use johandler::models::_entities::{payments, refunds};
+use johandler::models::refunds::{net_received, CreateNewRefund};
+use loco_rs::testing;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+};
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn refund(value: f32, payment_id: Option<i32>, chargeback: bool) -> refunds::Model {
+    let now = chrono::Utc::now().into();
+    refunds::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        order_id: 1,
+        payment_id,
+        value,
+        refund_date: chrono::NaiveDate::from_ymd_opt(2025, 4, 20).unwrap(),
+        refund_method: Some("pix".to_string()),
+        reason: "overpaid".to_string(),
+        chargeback,
+    }
+}
+
+#[test]
+fn refunds_are_negative_amounts_in_net_received() {
+    let payments = vec![payment(1, 100.0, false), payment(2, 100.0, true)];
+    assert!((net_received(&payments, &[]) - 100.0).abs() < f32::EPSILON);
+
+    let refunds = vec![refund(40.0, Some(1), false)];
+    assert!((refunds[0].signed_value() + 40.0).abs() < f32::EPSILON);
+    assert!((net_received(&payments, &refunds) - 60.0).abs() < f32::EPSILON);
+
+    // a chargeback reopens its payment, so it is not subtracted a second time
+    let payments = vec![payment(1, 100.0, true), payment(2, 100.0, true)];
+    let refunds = vec![refund(100.0, Some(1), true)];
+    assert!(net_received(&payments, &refunds).abs() < f32::EPSILON);
+}
+
+#[tokio::test]
+#[serial]
+async fn chargebacks_take_back_the_whole_payment() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let payment_pid = order.payments[0].pid.unwrap();
+    let payment = payments::Entity::find()
+        .filter(payments::Column::Pid.eq(payment_pid))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut settled_payment = payment.clone().into_active_model();
+    settled_payment.open = ActiveValue::Set(false);
+    settled_payment.payment_date = ActiveValue::Set(Some(date(2025, 4, 10)));
+    settled_payment.update(db).await.unwrap();
+    let chargeback = |value| CreateNewRefund {
+        order_pid: order.pid,
+        payment_pid: Some(payment_pid),
+        value,
+        refund_date: date(2025, 4, 20),
+        refund_method: None,
+        reason: "chargeback".to_string(),
+        chargeback: true,
+    };
+
+    assert!(refunds::Model::create(db, chargeback(400.0)).await.is_err());
+    refunds::Model::create(db, chargeback(1000.0))
+        .await
+        .unwrap();
+    let payment = payments::Entity::find_by_id(payment.id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(payment.open);
+}
@@ -0,0 +1,122 @@
+use johandler::app::App;
+use johandler::search::{self, SearchHit, SearchKind};
+use loco_rs::testing;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ConnectionTrait, Database};
+use serial_test::serial;
+use uuid::Uuid;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+#[test]
+fn search_terms_fold_case_and_accents() {
+    assert_eq!(
+        search::search_terms("  JOÃO  da Conceição! "),
+        vec!["joao", "da", "conceicao"]
+    );
+    assert_eq!(
+        search::search_terms("João.Silva@Exemplo.com.br."),
+        vec!["joao.silva@exemplo.com.br"]
+    );
+    assert_eq!(search::search_terms("(11) 99999-0000"), vec!["11999990000"]);
+    assert_eq!(search::search_terms("+55 11 9999"), vec!["55119999"]);
+    assert!(search::search_terms(" - & | ").is_empty());
+    assert!(search::search_terms("").is_empty());
+}
+
+#[test]
+fn hits_are_merged_by_result_and_ranked() {
+    let client = Uuid::from_u128(1);
+    let order = Uuid::from_u128(2);
+    let hit = |kind, pid, field: &str, rank| SearchHit {
+        kind,
+        pid,
+        field: field.to_string(),
+        rank,
+    };
+    let ranked = search::rank_hits(vec![
+        hit(SearchKind::Client, client, "name", 0.1),
+        hit(SearchKind::Order, order, "fee_info", 0.2),
+        hit(SearchKind::Client, client, "email", 0.3),
+        hit(SearchKind::Client, client, "email", 0.05),
+    ]);
+    assert_eq!(
+        ranked,
+        vec![
+            (
+                SearchKind::Client,
+                client,
+                vec!["name".to_string(), "email".to_string()],
+                0.3
+            ),
+            (SearchKind::Order, order, vec!["fee_info".to_string()], 0.2),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn sqlite_index_follows_the_tables() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    db.execute_unprepared(
+        "INSERT INTO clients (created_at, updated_at, pid, name, contact) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'0f8fad5bd9cb469fa16570867728950e', 'João da Silva', 'x')",
+    )
+    .await
+    .unwrap();
+    db.execute_unprepared(
+        "INSERT INTO client_contacts \
+         (created_at, updated_at, pid, client_id, kind, value, \"primary\", whatsapp) \
+         VALUES ('2025-01-01T00:00:00+00:00', '2025-01-01T00:00:00+00:00', \
+         X'1f8fad5bd9cb469fa16570867728950e', 1, 'email', 'joao@exemplo.com', 1, 0)",
+    )
+    .await
+    .unwrap();
+
+    let results = search::search(&db, "JOÃO silv", 20).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].kind, SearchKind::Client);
+    assert_eq!(results[0].title, "João da Silva");
+    assert_eq!(results[0].matched, vec!["name"]);
+    let results = search::search(&db, "joao@exemplo.com", 20).await.unwrap();
+    assert_eq!(results[0].matched, vec!["email"]);
+
+    db.execute_unprepared("UPDATE clients SET name = 'Maria Souza' WHERE id = 1")
+        .await
+        .unwrap();
+    assert!(search::search(&db, "silva", 20).await.unwrap().is_empty());
+    assert_eq!(search::search(&db, "mária", 20).await.unwrap().len(), 1);
+
+    db.execute_unprepared("DELETE FROM client_contacts")
+        .await
+        .unwrap();
+    assert!(search::search(&db, "exemplo", 20).await.unwrap().is_empty());
+    Migrator::down(&db, Some(1)).await.unwrap();
+}
@@ -0,0 +1,147 @@
+use super::fixtures::{self, date};
+use johandler::app::App;
+use johandler::business_days::BusinessCalendar;
+use johandler::models::_entities::{hourly_rates, payments, time_entries, users};
+use johandler::models::hourly_rates::resolve_hourly_rate;
+use johandler::models::time_entries::{CreateNewTimeEntry, InvoiceTimeEntries};
+use loco_rs::testing;
+use sea_orm::EntityTrait;
+use serial_test::serial;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[tokio::test]
+#[serial]
+async fn test_model() {
+    configure_insta!();
+
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+
+    // query your model, e.g.:
+    //
+    // let item = models::posts::Model::find_by_pid(
+    //     &boot.app_context.db,
+    //     "11111111-1111-1111-1111-111111111111",
+    // )
+    // .await;
+
+    // snapshot the result:
+    // assert_debug_snapshot!(item);
+}
+
+fn hourly_rate(
+    id: i32,
+    user_id: Option<i32>,
+    order_id: Option<i32>,
+    value: f32,
+) -> hourly_rates::Model {
+    let now = chrono::Utc::now().into();
+    hourly_rates::Model {
+        created_at: now,
+        updated_at: now,
+        id,
+        pid: uuid::Uuid::new_v4(),
+        user_id,
+        order_id,
+        value,
+    }
+}
+
+fn time_entry(minutes: i32, hourly_rate: f32) -> time_entries::Model {
+    let now = chrono::Utc::now().into();
+    time_entries::Model {
+        created_at: now,
+        updated_at: now,
+        id: 1,
+        pid: uuid::Uuid::new_v4(),
+        order_id: 1,
+        user_id: 1,
+        entry_date: chrono::NaiveDate::from_ymd_opt(2025, 4, 28).unwrap(),
+        minutes,
+        description: "hearing preparation".to_string(),
+        hourly_rate,
+        billed: false,
+        payment_id: None,
+    }
+}
+
+#[test]
+fn hourly_rate_prefers_the_most_specific_rate() {
+    let rates = vec![
+        hourly_rate(1, Some(1), None, 300.0),
+        hourly_rate(2, None, Some(1), 350.0),
+        hourly_rate(3, Some(1), Some(2), 400.0),
+    ];
+    assert_eq!(resolve_hourly_rate(&rates, 1, 2), Some(400.0));
+    assert_eq!(resolve_hourly_rate(&rates, 1, 1), Some(350.0));
+    assert_eq!(resolve_hourly_rate(&rates, 1, 3), Some(300.0));
+    assert_eq!(resolve_hourly_rate(&rates, 2, 3), None);
+}
+
+#[test]
+fn amount_bills_the_duration_at_the_hourly_rate() {
+    assert_eq!(time_entry(90, 300.0).amount(), 450.0);
+    assert_eq!(time_entry(20, 250.0).amount(), 83.33);
+}
+
+#[tokio::test]
+#[serial]
+async fn time_of_a_deleted_invoice_can_be_invoiced_again() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+    testing::seed::<App>(&boot.app_context.db).await.unwrap();
+    let db = &boot.app_context.db;
+    let order = fixtures::order(db).await;
+    let user = users::Entity::find_by_id(1).one(db).await.unwrap().unwrap();
+    let time_entry = time_entries::Model::create(
+        db,
+        &user,
+        CreateNewTimeEntry {
+            order_pid: order.pid,
+            user_pid: None,
+            entry_date: date(2025, 4, 14),
+            minutes: 90,
+            description: "Audiência".to_string(),
+            hourly_rate: Some(200.0),
+        },
+    )
+    .await
+    .unwrap();
+    let invoice = InvoiceTimeEntries {
+        order_pid: order.pid,
+        due_date: date(2025, 5, 10),
+        until: None,
+    };
+
+    let payment = time_entries::Model::invoice(db, invoice, &BusinessCalendar::default())
+        .await
+        .unwrap();
+    let billed = time_entries::Model::find_by_pid(db, time_entry.pid)
+        .await
+        .unwrap();
+    assert!(billed.billed);
+    assert_eq!(billed.payment_id, Some(payment.id));
+
+    payments::Model::delete(db, payment.pid).await.unwrap();
+    let released = time_entries::Model::find_by_pid(db, time_entry.pid)
+        .await
+        .unwrap();
+    assert!(!released.billed);
+    assert_eq!(released.payment_id, None);
+
+    let invoice = InvoiceTimeEntries {
+        order_pid: order.pid,
+        due_date: date(2025, 5, 10),
+        until: None,
+    };
+    let payment = time_entries::Model::invoice(db, invoice, &BusinessCalendar::default())
+        .await
+        .unwrap();
+    assert!((payment.value - 300.0).abs() < f32::EPSILON);
+}
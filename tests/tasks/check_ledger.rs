@@ -0,0 +1,17 @@
+use johandler::app::App;
+use loco_rs::{boot::run_task, task, testing};
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn test_can_check_ledger() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+
+    assert!(run_task::<App>(
+        &boot.app_context,
+        Some(&"check_ledger".to_string()),
+        &task::Vars::default()
+    )
+    .await
+    .is_ok());
+}
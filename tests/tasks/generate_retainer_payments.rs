@@ -0,0 +1,17 @@
+use johandler::app::App;
+use loco_rs::{boot::run_task, task, testing};
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn test_can_generate_retainer_payments() {
+    let boot = testing::boot_test::<App>().await.unwrap();
+
+    assert!(run_task::<App>(
+        &boot.app_context,
+        Some(&"generate_retainer_payments".to_string()),
+        &task::Vars::default()
+    )
+    .await
+    .is_ok());
+}
@@ -1 +1,4 @@
+pub mod check_ledger;
+pub mod find_duplicate_clients;
+pub mod generate_retainer_payments;
 pub mod seed;